@@ -6,21 +6,28 @@ Usage:
     agentsh --version           Show version
     agentsh --config <path>     Use custom config file
     agentsh --login             Run as login shell
+    agentsh -x "<request>"      Run one AI request and exit with its status
     agentsh config show         Show current configuration
     agentsh status              Check system health
+    agentsh doctor              Show an environment report for troubleshooting
     agentsh completions bash    Output bash completion script
+    agentsh --init-shell bash   Output shell integration snippet for ~/.bashrc
     agentsh --mcp-server        Run as MCP server (for remote LLM integration)
 """
 
 import argparse
+import os
 import sys
 from pathlib import Path
-from typing import Optional
+from typing import TYPE_CHECKING, Optional
 
 from agentsh import __version__
-from agentsh.config.loader import load_config
+from agentsh.config.loader import format_config, load_config, load_config_with_origins
 from agentsh.telemetry.logger import setup_logging, get_logger
 
+if TYPE_CHECKING:
+    from agentsh.config.schemas import AgentSHConfig
+
 
 def create_parser() -> argparse.ArgumentParser:
     """Create the argument parser for the CLI."""
@@ -32,8 +39,11 @@ def create_parser() -> argparse.ArgumentParser:
 Examples:
   agentsh                    Start interactive shell
   agentsh --config ~/my.yaml Use custom configuration
+  agentsh -x "clean build" --export plan.sh
+                             Save the AI's plan as a script for review
   agentsh config show        Display current settings
   agentsh status             Check health of all components
+  agentsh doctor             Report config, provider and environment details
 
 For more information, visit: https://github.com/agentsh/agentsh
         """,
@@ -59,6 +69,32 @@ For more information, visit: https://github.com/agentsh/agentsh
         help="Override log level",
     )
 
+    parser.add_argument(
+        "--debug",
+        action="store_true",
+        help="Log at DEBUG level, including LLM request and response bodies (keys redacted)",
+    )
+
+    parser.add_argument(
+        "-x", "--execute",
+        metavar="REQUEST",
+        help="Run a single AI request non-interactively and exit with its status",
+    )
+
+    parser.add_argument(
+        "--export",
+        type=Path,
+        metavar="FILE",
+        help="With -x, write the AI's plan to FILE as a bash script instead of running it",
+    )
+
+    parser.add_argument(
+        "--init-shell",
+        choices=["bash", "zsh", "fish"],
+        metavar="SHELL",
+        help="Print the shell integration snippet for SHELL (bash, zsh, fish)",
+    )
+
     parser.add_argument(
         "--mcp-server",
         action="store_true",
@@ -103,12 +139,22 @@ For more information, visit: https://github.com/agentsh/agentsh
     # config subcommand
     config_parser = subparsers.add_parser("config", help="Configuration management")
     config_subparsers = config_parser.add_subparsers(dest="config_command")
-    config_subparsers.add_parser("show", help="Show current configuration")
+    config_show = config_subparsers.add_parser(
+        "show", help="Show the effective configuration after merging all sources"
+    )
+    config_show.add_argument(
+        "--origin",
+        action="store_true",
+        help="Annotate each setting with the file or environment variable that set it",
+    )
     config_subparsers.add_parser("init", help="Initialize default configuration")
 
     # status subcommand
     subparsers.add_parser("status", help="Check system health")
 
+    # doctor subcommand
+    subparsers.add_parser("doctor", help="Show an environment report for troubleshooting")
+
     # completions subcommand
     completions_parser = subparsers.add_parser(
         "completions",
@@ -137,13 +183,11 @@ For more information, visit: https://github.com/agentsh/agentsh
     return parser
 
 
-def cmd_config_show(config_path: Optional[Path]) -> int:
-    """Show current configuration."""
+def cmd_config_show(config_path: Optional[Path], show_origin: bool = False) -> int:
+    """Show the effective configuration, optionally with each setting's source."""
     try:
-        config = load_config(config_path)
-        print("Current AgentSH Configuration:")
-        print("=" * 50)
-        print(config.model_dump_json(indent=2))
+        config, origins = load_config_with_origins(config_path)
+        print(format_config(config, origins if show_origin else None), end="")
         return 0
     except Exception as e:
         print(f"Error loading configuration: {e}", file=sys.stderr)
@@ -186,6 +230,23 @@ def cmd_status() -> int:
     return 0 if overall else 1
 
 
+def cmd_doctor(config_path: Optional[Path]) -> int:
+    """Print an environment report; nonzero if the AI would be unavailable."""
+    from agentsh.config.loader import get_config_paths
+    from agentsh.telemetry.doctor import build_report, format_report
+
+    try:
+        config = load_config(config_path)
+    except Exception as e:
+        print(f"Error loading configuration: {e}", file=sys.stderr)
+        return 1
+
+    config_paths = get_config_paths() + ([config_path] if config_path else [])
+    report = build_report(config, config_paths)
+    print(format_report(report))
+    return 0 if report.ai_available else 1
+
+
 def cmd_mcp_server(config_path: Optional[Path]) -> int:
     """Run as MCP server."""
     import asyncio
@@ -365,6 +426,162 @@ def cmd_completions(args: argparse.Namespace) -> int:
         return 0
 
 
+def cmd_init_shell(shell: str) -> int:
+    """Print the shell integration snippet for an rc file."""
+    from agentsh.shell.init_scripts import get_init_script
+
+    print(get_init_script(shell))
+    return 0
+
+
+def cmd_execute(
+    config_path: Optional[Path],
+    request: str,
+    log_level: Optional[str] = None,
+) -> int:
+    """Run a single AI request and return its aggregate exit status.
+
+    Content piped on stdin is sent with the request, up to
+    llm.max_context_size characters.
+    Returns 0 if every executed step succeeded, 1 if a step failed, and
+    130 if a step was cancelled (blocked or declined).
+    """
+    try:
+        config = load_config(config_path)
+
+        if log_level:
+            config.log_level = log_level
+
+        setup_logging(config.log_level, config.telemetry.log_file)
+
+        from agentsh.agent.factory import create_ai_handler
+
+        # Piped content (e.g. `cat error.log | agentsh -x "fix this"`) is context
+        piped_input = _read_piped_input(config.llm.max_context_size)
+
+        handler = create_ai_handler(config)
+        print(handler(request, piped_input=piped_input))
+        return getattr(handler, "last_status", 0)
+
+    except Exception as e:
+        print(f"Error: {e}", file=sys.stderr)
+        return 1
+
+
+def cmd_export(
+    config_path: Optional[Path],
+    request: str,
+    output: Path,
+    log_level: Optional[str] = None,
+) -> int:
+    """Write the AI's plan for a request to a bash script without running it."""
+    import asyncio
+
+    try:
+        config = load_config(config_path)
+
+        if log_level:
+            config.log_level = log_level
+
+        setup_logging(config.log_level, config.telemetry.log_file)
+
+        from agentsh.agent.agent_loop import AgentContext
+        from agentsh.agent.factory import create_agent_loop
+        from agentsh.agent.plan_export import export_script, steps_from_tool_calls
+
+        piped_input = _read_piped_input(config.llm.max_context_size)
+        context = AgentContext(cwd=str(Path.cwd()), piped_input=piped_input)
+
+        agent = create_agent_loop(config)
+        response = asyncio.run(agent.plan(request, context))
+
+        steps = steps_from_tool_calls(response.tool_calls)
+        if not steps:
+            print(response.content)
+            print("No commands to export.", file=sys.stderr)
+            return 1
+
+        output.write_text(export_script(steps, request))
+        output.chmod(0o755)
+        print(f"Wrote {len(steps)} step(s) to {output}")
+        return 0
+
+    except Exception as e:
+        print(f"Error: {e}", file=sys.stderr)
+        return 1
+
+
+def _read_piped_input(limit: int) -> str:
+    """Read content piped on stdin without reading past what can be sent.
+
+    The pipe is then closed and stdin replaced by /dev/null, so the rest of
+    the piped data can never answer an approval prompt.
+
+    Args:
+        limit: Maximum characters sent with a request
+
+    Returns:
+        Up to limit + 1 characters, so an overlong input is still seen as
+        truncated; empty when stdin is a terminal
+    """
+    if sys.stdin is None or sys.stdin.isatty():
+        return ""
+    piped_input = sys.stdin.read(limit + 1)
+    sys.stdin.close()
+    sys.stdin = open(os.devnull)
+    return piped_input
+
+
+def _check_model(config: "AgentSHConfig") -> Optional[str]:
+    """Check llm.model against the provider's model list.
+
+    Args:
+        config: AgentSH configuration
+
+    Returns:
+        Warning text, or None if the model is listed or cannot be checked
+    """
+    import asyncio
+
+    from agentsh.agent.factory import create_llm_client
+    from agentsh.agent.model_check import check_model
+    from agentsh.utils.ux import Spinner, SpinnerStyle
+
+    spinner = Spinner(
+        "Checking model...",
+        style=SpinnerStyle(config.shell.spinner.value),
+        use_color=config.shell.color,
+    ).start()
+    try:
+        return asyncio.run(check_model(create_llm_client(config)))
+    except Exception as e:
+        get_logger(__name__).debug("Model validation failed", error=str(e))
+        return None
+    finally:
+        spinner.stop()
+
+
+def _child_ps1(config: "AgentSHConfig") -> Optional[str]:
+    """Build the PS1 exported to shells started from AgentSH.
+
+    Args:
+        config: AgentSH configuration
+
+    Returns:
+        PS1 from ``shell.prompt``, or None when unset or unsupported
+    """
+    from agentsh.shell.prompt import template_to_ps1
+
+    if not config.shell.prompt:
+        return None
+    mode = config.security.mode.value
+    return template_to_ps1(
+        config.shell.prompt,
+        config.shell.backend,
+        config.shell.mode_indicators.get(mode, mode),
+    )
+
+
 def cmd_interactive_shell(
     config_path: Optional[Path],
     log_level: Optional[str],
@@ -376,11 +593,19 @@ def cmd_interactive_shell(
 ) -> int:
     """Start the interactive shell."""
     import time
+    from agentsh.shell.cleanup import (
+        install_crash_hook,
+        reap_children,
+        restore_terminal,
+        save_terminal,
+    )
     from agentsh.shell.wrapper import ShellWrapper
     from agentsh.shell.login import LoginShellManager
     from agentsh.shell.session import get_session, cleanup_session
 
     start_time = time.time() if profile_startup else None
+    # Saved before anything can switch the terminal to raw mode
+    terminal = save_terminal()
 
     try:
         config = load_config(config_path)
@@ -393,6 +618,7 @@ def cmd_interactive_shell(
 
         # Set up login shell if needed
         login_manager = None
+        ps1 = _child_ps1(config)
         if login or config.shell.login_shell:
             login_manager = LoginShellManager(
                 force_login=login,
@@ -400,9 +626,11 @@ def cmd_interactive_shell(
                 skip_rc=norc,
                 custom_rc=rcfile or config.shell.rc_file,
             )
-            login_manager.setup_environment()
+            login_manager.setup_environment(ps1=ps1)
             login_manager.source_profiles(config.shell.backend)
             login_manager.source_rc_files(config.shell.backend)
+        elif ps1 is not None:
+            os.environ["PS1"] = ps1
 
         # Initialize session
         session = get_session()
@@ -412,18 +640,18 @@ def cmd_interactive_shell(
 
         logger.info("Starting AgentSH", version=__version__)
 
-        shell = ShellWrapper(config)
+        shell = ShellWrapper(config, config_path=config_path)
 
         if profile_startup:
             print(f"[startup] Shell initialized: {(time.time() - start_time)*1000:.1f}ms")
 
         # Set up AI handler if API key is configured
-        if config.llm.api_key:
+        if os.environ.get(config.llm.api_key_env):
             try:
                 from agentsh.agent.factory import create_ai_handler
 
                 ai_handler = create_ai_handler(config)
-                shell.set_ai_handler(ai_handler)
+                shell.set_ai_handler(ai_handler, factory=create_ai_handler)
                 logger.info(
                     "AI handler configured",
                     provider=config.llm.provider.value,
@@ -433,12 +661,18 @@ def cmd_interactive_shell(
                 logger.warning("Failed to configure AI handler", error=str(e))
                 # Continue without AI - will show placeholder
 
+            if config.llm.validate_model:
+                warning = _check_model(config)
+                if warning:
+                    print(f"Warning: {warning}", file=sys.stderr)
+
         if profile_startup:
             total_ms = (time.time() - start_time) * 1000
             print(f"[startup] Ready: {total_ms:.1f}ms")
             if total_ms > 200:
                 print(f"[startup] Warning: startup time exceeds 200ms target")
 
+        install_crash_hook()
         shell.run()
 
         # Cleanup
@@ -455,17 +689,23 @@ def cmd_interactive_shell(
     except Exception as e:
         print(f"Error starting AgentSH: {e}", file=sys.stderr)
         return 1
+    finally:
+        # A command's PTY may still be in raw mode or alive after Ctrl-C
+        restore_terminal(terminal)
+        reap_children()
 
 
 def main() -> int:
     """Main entry point for the CLI."""
     parser = create_parser()
     args = parser.parse_args()
+    if args.debug:
+        args.log_level = "DEBUG"
 
     # Handle subcommands
     if args.command == "config":
         if args.config_command == "show":
-            return cmd_config_show(args.config)
+            return cmd_config_show(args.config, args.origin)
         elif args.config_command == "init":
             return cmd_config_init(args.config)
         else:
@@ -475,15 +715,27 @@ def main() -> int:
     elif args.command == "status":
         return cmd_status()
 
+    elif args.command == "doctor":
+        return cmd_doctor(args.config)
+
     elif args.command == "completions":
         return cmd_completions(args)
 
     elif args.command == "devices":
         return cmd_devices(args)
 
+    elif args.init_shell:
+        return cmd_init_shell(args.init_shell)
+
     elif args.mcp_server:
         return cmd_mcp_server(args.config)
 
+    elif args.execute and args.export:
+        return cmd_export(args.config, args.execute, args.export, args.log_level)
+
+    elif args.execute:
+        return cmd_execute(args.config, args.execute, args.log_level)
+
     else:
         # Default: start interactive shell
         return cmd_interactive_shell(
@@ -5,7 +5,9 @@ from agentsh.agent.agent_loop import (
     AgentContext,
     AgentLoop,
     AgentResult,
+    StepOutcome,
     StreamingAgentLoop,
+    aggregate_exit_status,
 )
 from agentsh.agent.cache import (
     CacheConfig,
@@ -18,7 +20,13 @@ from agentsh.agent.cache import (
 )
 from agentsh.agent.llm_client import (
     LLMClient,
+    LLMAPIError,
+    LLMError,
+    LLMNetworkError,
+    LLMParseError,
+    LLMTruncatedError,
     LLMResponse,
+    LLMTimeoutError,
     Message,
     MessageRole,
     StopReason,
@@ -50,10 +58,18 @@ __all__ = [
     "AgentContext",
     "AgentLoop",
     "AgentResult",
+    "StepOutcome",
     "StreamingAgentLoop",
+    "aggregate_exit_status",
     # LLM Client
     "LLMClient",
+    "LLMAPIError",
+    "LLMParseError",
+    "LLMTruncatedError",
+    "LLMError",
+    "LLMNetworkError",
     "LLMResponse",
+    "LLMTimeoutError",
     "Message",
     "MessageRole",
     "StopReason",
@@ -2,32 +2,146 @@
 
 import asyncio
 import json
-from dataclasses import dataclass, field
-from typing import Any, Callable, Optional
-
+import os
+import time
+from dataclasses import dataclass, field, replace
+from datetime import datetime, timezone
+from enum import Enum
+from typing import Any, Callable, Optional, Union
+
+from agentsh.agent.alternatives import (
+    ALTERNATIVES_TOOL,
+    Alternative,
+    alternative_steps,
+    alternatives_definition,
+    parse_alternatives,
+)
+from agentsh.agent.budget import ExecutionBudget
+from agentsh.agent.chat_export import ChatTurn
+from agentsh.agent.cost import estimate_cost, format_usage
+from agentsh.agent.explain import build_explain_request
+from agentsh.agent.file_context import MAX_CONTEXT_FILE_LINES, read_file_context
+from agentsh.agent.history import WINDOW_MESSAGES, summarize_step_results, trim_history
+from agentsh.agent.http_client import BODY_SNIPPET_CHARS
 from agentsh.agent.llm_client import (
     LLMClient,
+    LLMError,
     LLMResponse,
+    LLMTruncatedError,
     Message,
     StopReason,
     ToolCall,
     ToolDefinition,
+    json_truncated,
+)
+from agentsh.agent.plan_display import format_plan
+from agentsh.agent.plan_export import SHELL_TOOLS
+from agentsh.agent.project_notes import MAX_NOTES_CHARS, read_notes
+from agentsh.agent.prompts import build_system_prompt, build_user_message, is_fix_request
+from agentsh.agent.replan import FailureAction, build_replan_message, describe_step
+from agentsh.agent.scheduling import (
+    DEPENDENCY_PARAMETERS,
+    extract_dependencies,
+    schedule_batches,
+)
+from agentsh.security.approval import (
+    ApprovalFlow,
+    ApprovalKind,
+    ApprovalRequest,
+    ApprovalResult,
+)
+from agentsh.security.classifier import (
+    CommandRiskAssessment,
+    RiskClassifier,
+    RiskLevel,
+    cd_outside_workspace,
+    outside_workspace,
 )
-from agentsh.agent.prompts import build_system_prompt
-from agentsh.security.classifier import RiskLevel
 from agentsh.security.controller import (
     SecurityContext,
     SecurityController,
     SecurityDecision,
     ValidationResult,
 )
+from agentsh.security.pre_exec import HookAction, HookVerdict, run_pre_exec_hook
 from agentsh.security.rbac import Role, User
-from agentsh.telemetry.logger import get_logger, LoggerMixin
-from agentsh.tools.base import Tool, ToolResult
+from agentsh.telemetry.logger import get_logger, LoggerMixin, request_context, truncate_preview
+from agentsh.tools.base import Tool, ToolResult, decode_output
 from agentsh.tools.registry import ToolRegistry
+from agentsh.utils.sysinfo import SystemContext, collect_system_context
 
 logger = get_logger(__name__)
 
+# Built-in tool the LLM uses to request read-only context before answering
+NEED_INFO_TOOL = "need_info"
+
+# Argument the AI sets on a shell step it considers destructive
+DESTRUCTIVE_FLAG = "is_destructive"
+
+# Maximum bytes of output returned per information probe (head and tail)
+MAX_INFO_OUTPUT_BYTES = 4000
+
+# Exit statuses reported for an AI request
+EXIT_SUCCESS = 0
+EXIT_FAILED = 1
+EXIT_CANCELLED = 130
+
+# Errors of requests whose plan stopped before all steps ran
+INCOMPLETE_ERRORS = ("Plan stopped", "Plan timeout")
+
+
+class StepOutcome(str, Enum):
+    """Outcome of a single executed tool step."""
+
+    SUCCESS = "success"
+    FAILED = "failed"
+    CANCELLED = "cancelled"  # Blocked or declined before running
+
+
+@dataclass
+class StepRecord:
+    """A step that ran during a request, with its result.
+
+    Attributes:
+        step: The command, or the tool name and arguments
+        tool: Name of the tool that ran
+        outcome: How the step ended
+        output: The step's output as sent back to the AI
+    """
+
+    step: str
+    tool: str
+    outcome: StepOutcome
+    output: str = ""
+
+    def to_dict(self) -> dict[str, str]:
+        """Convert to a dictionary for logging, with the output shortened."""
+        return {
+            "step": self.step,
+            "tool": self.tool,
+            "outcome": self.outcome.value,
+            "output": truncate_preview(self.output),
+        }
+
+
+def aggregate_exit_status(outcomes: list[StepOutcome]) -> int:
+    """Map step outcomes to a single shell-style exit status.
+
+    A cancelled step takes precedence over a failed one, since the plan
+    was stopped rather than attempted.
+
+    Args:
+        outcomes: Outcomes of the executed steps, in order
+
+    Returns:
+        EXIT_CANCELLED, EXIT_FAILED, or EXIT_SUCCESS
+    """
+    if StepOutcome.CANCELLED in outcomes:
+        return EXIT_CANCELLED
+    if StepOutcome.FAILED in outcomes:
+        return EXIT_FAILED
+    return EXIT_SUCCESS
+
 
 @dataclass
 class AgentConfig:
@@ -38,12 +152,79 @@ class AgentConfig:
         temperature: LLM sampling temperature
         max_tokens: Maximum tokens per LLM response
         timeout: Timeout per tool execution in seconds
+        max_info_rounds: Maximum rounds of read-only need_info probes per
+            request (0 disables the need_info tool)
+        max_tool_calls: Maximum tool calls accepted from a single LLM response
+        reject_excess_tool_calls: Fail the request instead of truncating when
+            a response exceeds max_tool_calls
+        domain_hint: Free-form domain description added to the system prompt
+        max_piped_input_chars: Maximum characters of piped stdin sent with a request
+        min_request_interval_ms: Minimum time between AI requests (0 disables)
+        reject_rapid_requests: Reject requests arriving sooner than
+            min_request_interval_ms instead of delaying them
+        total_timeout: Wall-clock seconds allowed for all steps of a
+            request (0 disables)
+        max_notes_chars: Maximum characters of project notes added to the
+            system prompt (0 disables)
+        max_replans: Maximum times one request's plan can be revised after
+            the user asks to adjust it
+        strict_output: Replace probe output that is not valid UTF-8 with a
+            placeholder instead of lossy text
+        history_window: Earlier conversation messages sent with each
+            request, or estimated tokens in tokens mode (0 disables)
+        history_window_mode: "messages" or "tokens"
+        history_results_chars: Maximum characters of the step results
+            summary remembered with an answer (0 disables)
+        parallel: Run steps whose dependencies have finished concurrently
+        max_parallel: Maximum steps running at the same time when parallel
+        context_files: Files added to the system prompt, relative to cwd
+        context_file_extensions: Only read context files with these
+            extensions (None reads any text file)
+        context_file_max_lines: Maximum lines included per context file
+        show_plan_cost: Report the tokens used by a response proposing
+            steps through on_step, before the steps run
+        pricing: USD per million (prompt, completion) tokens of the model,
+            adding a cost estimate to that report
+        plan_verbosity: Detail of the plan shown through on_step before
+            its steps run: "minimal", "normal" or "verbose" (None shows
+            no plan)
+        workspace_root: Directory steps should stay in; a shell step that
+            cd's outside it must be confirmed (None disables)
+        pre_exec_hook: Command asked to allow, deny or modify each step
+            before it runs (see agentsh.security.pre_exec)
+        pre_exec_hook_timeout: Seconds the hook may take before the step
+            is denied
     """
 
     max_steps: int = 10
     temperature: float = 0.0
     max_tokens: int = 4096
     timeout: float = 30.0
+    max_info_rounds: int = 3
+    max_tool_calls: int = 20
+    reject_excess_tool_calls: bool = False
+    domain_hint: Optional[str] = None
+    max_piped_input_chars: int = 20000
+    min_request_interval_ms: int = 0
+    reject_rapid_requests: bool = False
+    total_timeout: float = 0.0
+    max_notes_chars: int = MAX_NOTES_CHARS
+    max_replans: int = 2
+    strict_output: bool = False
+    history_window: int = 10
+    history_window_mode: str = WINDOW_MESSAGES
+    history_results_chars: int = 2000
+    parallel: bool = False
+    max_parallel: int = 4
+    context_files: list[str] = field(default_factory=list)
+    context_file_extensions: Optional[list[str]] = None
+    context_file_max_lines: int = MAX_CONTEXT_FILE_LINES
+    show_plan_cost: bool = False
+    pricing: Optional[tuple[float, float]] = None
+    plan_verbosity: Optional[str] = None
+    workspace_root: Optional[str] = None
+    pre_exec_hook: Optional[str] = None
+    pre_exec_hook_timeout: float = 5.0
 
 
 @dataclass
@@ -55,12 +236,26 @@ class AgentContext:
         env: Environment variables
         history: Recent command history
         user_id: User identifier for audit
+        piped_input: Content piped to agentsh on stdin, sent with the request
+        last_exit_code: Exit status of the user's last shell command, if known
+        hostname: Machine the commands run on (this machine's when empty)
+        read_only: Refuse every step that could change something (ai ask)
+        recent_output: Tail of the shell's terminal output, sent with fix
+            requests so the AI sees the error on screen
+        interactive: Whether someone can answer approval prompts; steps
+            needing approval are denied when False
     """
 
     cwd: str = ""
     env: dict[str, str] = field(default_factory=dict)
     history: list[str] = field(default_factory=list)
     user_id: str = ""
+    piped_input: str = ""
+    last_exit_code: Optional[int] = None
+    hostname: str = ""
+    read_only: bool = False
+    recent_output: str = ""
+    interactive: bool = True
 
 
 @dataclass
@@ -75,6 +270,9 @@ class AgentResult:
         output_tokens: Total output tokens generated
         success: Whether the agent completed successfully
         error: Error message if failed
+        step_outcomes: Outcome of each executed tool call
+        steps: The steps that ran, with their output, also when the plan
+            stopped part-way
     """
 
     response: str
@@ -84,6 +282,46 @@ class AgentResult:
     output_tokens: int = 0
     success: bool = True
     error: Optional[str] = None
+    step_outcomes: list[StepOutcome] = field(default_factory=list)
+    steps: list[StepRecord] = field(default_factory=list)
+
+    @property
+    def exit_status(self) -> int:
+        """Get the aggregate exit status for this request."""
+        status = aggregate_exit_status(self.step_outcomes)
+        if status == EXIT_SUCCESS and not self.success:
+            return EXIT_FAILED
+        return status
+
+
+@dataclass
+class Exchange:
+    """What was sent to the LLM for a request and what came back.
+
+    Attributes:
+        system_prompt: Assembled system prompt
+        request: User message
+        responses: Raw LLM replies in order, one per loop step
+    """
+
+    system_prompt: str
+    request: str
+    responses: list[str] = field(default_factory=list)
+
+
+def format_raw_response(response: LLMResponse) -> str:
+    """Render an LLM response as text, including requested tool calls.
+
+    Args:
+        response: LLM response
+
+    Returns:
+        Response content followed by one line per tool call
+    """
+    lines = [response.content] if response.content else []
+    for call in response.tool_calls:
+        lines.append(f"[tool call] {call.name} {json.dumps(call.arguments, default=str)}")
+    return "\n".join(lines) or "(empty response)"
 
 
 class AgentLoop(LoggerMixin):
@@ -114,6 +352,8 @@ class AgentLoop(LoggerMixin):
         tool_registry: ToolRegistry,
         config: Optional[AgentConfig] = None,
         security_controller: Optional[SecurityController] = None,
+        clock: Callable[[], float] = time.monotonic,
+        approval_flow: Optional[ApprovalFlow] = None,
     ) -> None:
         """Initialize the agent loop.
 
@@ -122,11 +362,48 @@ class AgentLoop(LoggerMixin):
             tool_registry: Registry of available tools
             config: Agent configuration
             security_controller: Optional security controller for command validation
+            clock: Monotonic clock in seconds, used for request rate limiting
+                and the total time budget
+            approval_flow: Asks the user about steps that leave the
+                workspace when there is no security controller
         """
         self.llm_client = llm_client
         self.tool_registry = tool_registry
         self.config = config or AgentConfig()
         self.security_controller = security_controller
+        self.approval_flow = approval_flow or ApprovalFlow()
+        self._clock = clock
+        self._last_request_at: Optional[float] = None
+
+        # Most recent prompt/response pair, kept for :why
+        self.last_exchange: Optional[Exchange] = None
+        # Answered requests as user/assistant pairs, oldest first
+        self.conversation: list[Message] = []
+        # Every request of the session with the steps it ran, for export-chat
+        self.turns: list[ChatTurn] = []
+        # Steps run by the most recent request that ran any, for redo, and
+        # whether that request was a read-only query
+        self.last_plan: list[ToolCall] = []
+        self._last_plan_read_only = False
+        self._current_plan: list[ToolCall] = []
+        self._current_steps: list[StepRecord] = []
+        # Steps of the current turn in execution order, for editing the
+        # rest of the plan on approval, and ids of those the user removed
+        self._turn_steps: list[ToolCall] = []
+        self._removed_steps: set[str] = set()
+        # Host facts for the system prompt, collected on the first request
+        self._system: Optional[SystemContext] = None
+        self._probe_classifier = (
+            security_controller.classifier if security_controller else RiskClassifier()
+        )
+
+        # Attribute audited actions to this loop's model unless configured otherwise
+        if security_controller is not None:
+            audit = security_controller.audit
+            if audit.model is None:
+                audit.model = llm_client.model
+            if audit.provider is None:
+                audit.provider = llm_client.provider
 
         self.logger.info(
             "AgentLoop initialized",
@@ -140,36 +417,118 @@ class AgentLoop(LoggerMixin):
         self,
         request: str,
         context: Optional[AgentContext] = None,
+        on_step: Optional[Callable[[str], None]] = None,
+        on_failure: Optional[Callable[[str, str], FailureAction]] = None,
+        on_choose: Optional[Callable[[list[Alternative]], Optional[int]]] = None,
     ) -> AgentResult:
         """Invoke the agent with a user request.
 
+        When config.total_timeout is set, the remaining steps are abandoned
+        once the budget is used up; the step running at that moment is
+        allowed to finish.
+
+        Each request gets a new request id, sent to the provider as
+        X-Request-Id and recorded in logs and audit entries.
+
         Args:
             request: User's natural language request
             context: Execution context
+            on_step: Callback receiving progress notes between steps, such
+                as the remaining time budget
+            on_failure: Callback asked what to do when a step fails, given
+                the step and its output. Without it the plan continues.
+            on_choose: Callback picking one of several alternative plans
+                offered by the AI, returning its index or None to run none.
+                Without it the AI is not offered alternatives.
 
         Returns:
             AgentResult with response and metadata
         """
+        self._current_plan = []
+        self._current_steps = []
         context = context or AgentContext()
+        with request_context():
+            result = await self._invoke(request, context, on_step, on_failure, on_choose)
+            result.steps = list(self._current_steps)
+            if result.error in INCOMPLETE_ERRORS:
+                self._audit_incomplete(request, result)
+                if result.steps:
+                    ran = "\n".join(f"- {s.step} [{s.outcome.value}]" for s in result.steps)
+                    result.response += f"\n\nSteps run before stopping:\n{ran}"
+        if self._current_plan:
+            self.last_plan = self._current_plan
+            self._last_plan_read_only = context.read_only
+        # Failed runs are remembered too, for "why is it failing?" follow-ups
+        if result.steps or (result.success and result.response):
+            answer = result.response
+            if not result.success:
+                answer = f"Error: {result.error}\n\n{answer}".rstrip()
+            results = summarize_step_results(result.steps, self.config.history_results_chars)
+            if results:
+                answer += f"\n\n{results}"
+            self.conversation.extend([Message.user(request), Message.assistant(answer)])
+        self.turns.append(ChatTurn(request, result.response, result.steps))
+        return result
+
+    async def _invoke(
+        self,
+        request: str,
+        context: AgentContext,
+        on_step: Optional[Callable[[str], None]],
+        on_failure: Optional[Callable[[str, str], FailureAction]] = None,
+        on_choose: Optional[Callable[[list[Alternative]], Optional[int]]] = None,
+    ) -> AgentResult:
+        """Run the agent loop for one request (see :meth:`invoke`)."""
+
+        rejected = await self._throttle()
+        if rejected is not None:
+            return rejected
+
+        budget = ExecutionBudget(self.config.total_timeout, clock=self._clock)
 
         # Build tool definitions for LLM
         tool_defs = self._build_tool_definitions()
 
         # Build initial messages
+        system = await self._system_context()
         system_prompt = build_system_prompt(
             available_tools=[f"{t.name}: {t.description}" for t in self.tool_registry.list_tools()],
             cwd=context.cwd,
+            os_info=system.os_info(),
+            hostname=context.hostname or system.known_hostname(),
             recent_history=context.history,
+            domain_hint=self.config.domain_hint,
+            last_exit_code=context.last_exit_code,
+            recent_output=context.recent_output if is_fix_request(request) else None,
+            project_notes=read_notes(context.cwd or os.getcwd(), self.config.max_notes_chars),
+            file_context=self._read_file_context(context),
+            read_only=context.read_only,
+            offer_alternatives=on_choose is not None,
         )
 
+        user_message = build_user_message(
+            request, context.piped_input, self.config.max_piped_input_chars
+        )
+        history = trim_history(
+            self.conversation,
+            self.config.history_window,
+            self.config.history_window_mode,
+            self.llm_client.count_tokens,
+        )
         messages = [
             Message.system(system_prompt),
-            Message.user(request),
+            *history,
+            Message.user(user_message),
         ]
+        exchange = Exchange(system_prompt=system_prompt, request=user_message)
+        self.last_exchange = exchange
 
         total_input_tokens = 0
         total_output_tokens = 0
         tool_calls_made: list[str] = []
+        step_outcomes: list[StepOutcome] = []
+        info_rounds = 0
+        replans = 0
         step = 0
 
         self.logger.info("Starting agent loop", request=request[:100])
@@ -177,15 +536,23 @@ class AgentLoop(LoggerMixin):
         while step < self.config.max_steps:
             step += 1
 
+            # Offer need_info only while probe rounds remain
+            call_tools = list(tool_defs)
+            if info_rounds < self.config.max_info_rounds:
+                call_tools.append(self._need_info_definition())
+            if on_choose is not None:
+                call_tools.append(alternatives_definition())
+
             try:
                 # Call LLM
                 response = await self.llm_client.invoke(
                     messages=messages,
-                    tools=tool_defs if tool_defs else None,
+                    tools=call_tools if call_tools else None,
                     temperature=self.config.temperature,
                     max_tokens=self.config.max_tokens,
                 )
 
+                exchange.responses.append(format_raw_response(response))
                 total_input_tokens += response.input_tokens
                 total_output_tokens += response.output_tokens
 
@@ -196,25 +563,113 @@ class AgentLoop(LoggerMixin):
                     stop_reason=response.stop_reason.value,
                 )
 
+                tool_calls = self._sanitize_tool_calls(response.tool_calls)
+
+                if len(tool_calls) > self.config.max_tool_calls:
+                    if self.config.reject_excess_tool_calls:
+                        self.logger.warning(
+                            "Rejected response with too many tool calls",
+                            count=len(tool_calls),
+                            limit=self.config.max_tool_calls,
+                        )
+                        return AgentResult(
+                            response=(
+                                f"The AI proposed {len(tool_calls)} steps, more than the "
+                                f"limit of {self.config.max_tool_calls}. Nothing was run."
+                            ),
+                            tool_calls_made=tool_calls_made,
+                            total_steps=step,
+                            input_tokens=total_input_tokens,
+                            output_tokens=total_output_tokens,
+                            success=False,
+                            error="Too many tool calls",
+                            step_outcomes=step_outcomes,
+                        )
+
+                    self.logger.warning(
+                        "Truncating tool calls",
+                        count=len(tool_calls),
+                        limit=self.config.max_tool_calls,
+                    )
+                    tool_calls = tool_calls[: self.config.max_tool_calls]
+
                 # If no tool calls, we're done
-                if not response.has_tool_calls:
+                if not tool_calls:
                     return AgentResult(
                         response=response.content,
                         tool_calls_made=tool_calls_made,
                         total_steps=step,
                         input_tokens=total_input_tokens,
                         output_tokens=total_output_tokens,
+                        step_outcomes=step_outcomes,
+                    )
+
+                if on_step and self.config.show_plan_cost:
+                    self._report_plan_cost(response, on_step)
+
+                proposed = [tc for tc in tool_calls if tc.name != NEED_INFO_TOOL]
+                if on_step and self.config.plan_verbosity and proposed:
+                    on_step(
+                        format_plan(
+                            proposed,
+                            self.config.plan_verbosity,
+                            self._probe_classifier,
+                            context.cwd or None,
+                        )
                     )
 
                 # Execute tool calls
-                messages.append(
-                    Message.assistant(response.content, response.tool_calls)
-                )
+                messages.append(Message.assistant(response.content, tool_calls))
+
+                if any(tc.name == NEED_INFO_TOOL for tc in tool_calls):
+                    info_rounds += 1
+
+                batches = [[tc] for tc in tool_calls]
+                if self.config.parallel:
+                    batches = schedule_batches(tool_calls)
+                    tool_calls = [tc for batch in batches for tc in batch]
+                self._turn_steps = tool_calls
+                self._removed_steps = set()
+                # Results of steps already run concurrently with an earlier step of their batch
+                prefetched: dict[str, tuple[str, StepOutcome]] = {}
+                # Steps of the batches run so far, as copied before they ran
+                planned: dict[str, ToolCall] = {}
+
+                for index, tool_call in enumerate(tool_calls):
+                    if budget.exceeded:
+                        return self._budget_exceeded(
+                            budget,
+                            tool_calls_made,
+                            step,
+                            total_input_tokens,
+                            total_output_tokens,
+                            step_outcomes,
+                        )
 
-                for tool_call in response.tool_calls:
                     tool_calls_made.append(tool_call.name)
 
-                    result = await self._execute_tool(tool_call, context)
+                    outcome: Optional[StepOutcome] = None
+                    if tool_call.name == NEED_INFO_TOOL:
+                        result = await self._gather_info(
+                            tool_call,
+                            context,
+                            allowed=info_rounds <= self.config.max_info_rounds,
+                        )
+                    elif tool_call.name == ALTERNATIVES_TOOL:
+                        result, ran = await self._run_alternative(tool_call, context, on_choose)
+                        tool_calls_made.extend(step.name for step, _ in ran)
+                        step_outcomes.extend(step_outcome for _, step_outcome in ran)
+                        if ran:
+                            outcome = ran[-1][1]
+                    else:
+                        if tool_call.id not in prefetched:
+                            batch = next(b for b in batches if tool_call in b)
+                            planned.update((tc.id, self._copy_step(tc)) for tc in batch)
+                            prefetched.update(await self._execute_batch(batch, context))
+                        result, outcome = prefetched.pop(tool_call.id)
+                        step_outcomes.append(outcome)
+                        self._record_step(planned.pop(tool_call.id), outcome)
+                        self._record_result(tool_call, outcome, result)
 
                     messages.append(
                         Message.tool_result(
@@ -224,16 +679,89 @@ class AgentLoop(LoggerMixin):
                         )
                     )
 
+                    action = FailureAction.CONTINUE
+                    if on_failure and outcome == StepOutcome.FAILED:
+                        action = on_failure(describe_step(tool_call), result)
+                    stopped = "Stopped after a failed step."
+                    if action == FailureAction.ADJUST and replans >= self.config.max_replans:
+                        self.logger.warning("Replan limit reached", limit=self.config.max_replans)
+                        action = FailureAction.ABORT
+                        stopped = (
+                            f"Stopped: the plan was already revised {replans} time(s), "
+                            "the llm.max_replans limit."
+                        )
+
+                    remaining = [tc for tc in tool_calls[index + 1 :] if tc.id not in prefetched]
+                    if action in (FailureAction.ABORT, FailureAction.ADJUST):
+                        # Later steps of the batch already ran alongside this one
+                        for ran in tool_calls[index + 1 :]:
+                            if ran.id in prefetched:
+                                ran_result, ran_outcome = prefetched[ran.id]
+                                tool_calls_made.append(ran.name)
+                                step_outcomes.append(ran_outcome)
+                                self._record_step(planned.pop(ran.id), ran_outcome)
+                                self._record_result(ran, ran_outcome, ran_result)
+                    if action == FailureAction.ABORT:
+                        return AgentResult(
+                            response=f"{stopped} {len(remaining)} remaining step(s) were not run.",
+                            tool_calls_made=tool_calls_made,
+                            total_steps=step,
+                            input_tokens=total_input_tokens,
+                            output_tokens=total_output_tokens,
+                            success=False,
+                            error="Plan stopped",
+                            step_outcomes=step_outcomes,
+                        )
+                    if action == FailureAction.ADJUST:
+                        replans += 1
+                        self.logger.info("Replanning", replan=replans, skipped=len(remaining))
+                        # Every tool call needs a result before the next turn
+                        for skipped in tool_calls[index + 1 :]:
+                            content = "Not run: the user asked for a revised plan."
+                            if skipped.id in prefetched:
+                                content = prefetched.pop(skipped.id)[0]
+                            messages.append(
+                                Message.tool_result(
+                                    tool_call_id=skipped.id,
+                                    name=skipped.name,
+                                    content=content,
+                                )
+                            )
+                        messages.append(
+                            Message.user(build_replan_message(tool_call, result, remaining))
+                        )
+                        break
+
+                    if budget.limited:
+                        self.logger.info("Plan budget", remaining=budget.remaining())
+                        if on_step:
+                            on_step(budget.describe())
+
+                if budget.exceeded:
+                    return self._budget_exceeded(
+                        budget,
+                        tool_calls_made,
+                        step,
+                        total_input_tokens,
+                        total_output_tokens,
+                        step_outcomes,
+                    )
+
             except Exception as e:
                 self.logger.error("Agent loop error", step=step, error=str(e))
+                if isinstance(e, LLMError):
+                    message = e.user_message
+                else:
+                    message = f"Error during execution: {str(e)}"
                 return AgentResult(
-                    response=f"Error during execution: {str(e)}",
+                    response=message,
                     tool_calls_made=tool_calls_made,
                     total_steps=step,
                     input_tokens=total_input_tokens,
                     output_tokens=total_output_tokens,
                     success=False,
                     error=str(e),
+                    step_outcomes=step_outcomes,
                 )
 
         # Hit max steps
@@ -247,8 +775,226 @@ class AgentLoop(LoggerMixin):
             output_tokens=total_output_tokens,
             success=False,
             error="Max steps reached",
+            step_outcomes=step_outcomes,
+        )
+
+    def _budget_exceeded(
+        self,
+        budget: ExecutionBudget,
+        tool_calls_made: list[str],
+        step: int,
+        input_tokens: int,
+        output_tokens: int,
+        step_outcomes: list[StepOutcome],
+    ) -> AgentResult:
+        """Build the result for a request stopped by its time budget.
+
+        Args:
+            budget: The exhausted budget
+            tool_calls_made: Tools called so far
+            step: Current loop step
+            input_tokens: Input tokens used so far
+            output_tokens: Output tokens used so far
+            step_outcomes: Outcomes of the steps that ran
+
+        Returns:
+            Failed AgentResult with error "Plan timeout"
+        """
+        self.logger.warning(
+            "Plan timeout",
+            elapsed=budget.elapsed(),
+            limit=budget.total_seconds,
+            steps_run=len(step_outcomes),
+        )
+        return AgentResult(
+            response=(
+                f"Stopped after {len(tool_calls_made)} steps: the plan exceeded its "
+                f"{budget.total_seconds:g}s time budget. Remaining steps were not run."
+            ),
+            tool_calls_made=tool_calls_made,
+            total_steps=step,
+            input_tokens=input_tokens,
+            output_tokens=output_tokens,
+            success=False,
+            error="Plan timeout",
+            step_outcomes=step_outcomes,
         )
 
+    async def plan(
+        self,
+        request: str,
+        context: Optional[AgentContext] = None,
+    ) -> LLMResponse:
+        """Ask the LLM for its proposed tool calls without executing them.
+
+        Used to export a plan for review instead of running it.
+
+        Args:
+            request: User's natural language request
+            context: Execution context
+
+        Returns:
+            The LLM response, with tool calls sanitized and capped at
+            max_tool_calls
+
+        Raises:
+            LLMError: If the request fails or is rate limited
+        """
+        context = context or AgentContext()
+        rejected = await self._throttle()
+        if rejected is not None:
+            raise LLMError(rejected.response)
+
+        system = await self._system_context()
+        system_prompt = build_system_prompt(
+            available_tools=[f"{t.name}: {t.description}" for t in self.tool_registry.list_tools()],
+            cwd=context.cwd,
+            os_info=system.os_info(),
+            hostname=context.hostname or system.known_hostname(),
+            recent_history=context.history,
+            domain_hint=self.config.domain_hint,
+            last_exit_code=context.last_exit_code,
+            recent_output=context.recent_output if is_fix_request(request) else None,
+            project_notes=read_notes(context.cwd or os.getcwd(), self.config.max_notes_chars),
+            file_context=self._read_file_context(context),
+            read_only=context.read_only,
+        )
+        tool_defs = self._build_tool_definitions()
+
+        with request_context():
+            response = await self.llm_client.invoke(
+                messages=[
+                    Message.system(system_prompt),
+                    Message.user(
+                        build_user_message(
+                            request, context.piped_input, self.config.max_piped_input_chars
+                        )
+                    ),
+                ],
+                tools=tool_defs or None,
+                temperature=self.config.temperature,
+                max_tokens=self.config.max_tokens,
+            )
+        response.tool_calls = self._sanitize_tool_calls(response.tool_calls)[
+            : self.config.max_tool_calls
+        ]
+        return response
+
+    def _report_plan_cost(
+        self, response: LLMResponse, on_step: Callable[[str], None]
+    ) -> None:
+        """Report the tokens, and cost if priced, of a response proposing steps.
+
+        Args:
+            response: LLM response with the plan
+            on_step: Callback shown to the user
+        """
+        if not response.input_tokens and not response.output_tokens:
+            return
+        cost = estimate_cost(response.input_tokens, response.output_tokens, self.config.pricing)
+        on_step(f"Plan used {format_usage(response.input_tokens, response.output_tokens, cost)}")
+
+    def request_wait(self) -> float:
+        """Get how long the next request must wait to respect the interval.
+
+        Returns:
+            Seconds remaining until min_request_interval_ms has elapsed
+            since the previous request (0.0 if it may start now)
+        """
+        if self.config.min_request_interval_ms <= 0 or self._last_request_at is None:
+            return 0.0
+        elapsed = self._clock() - self._last_request_at
+        return max(0.0, self.config.min_request_interval_ms / 1000 - elapsed)
+
+    async def _system_context(self) -> SystemContext:
+        """Get host facts for the system prompt.
+
+        Collected concurrently under a short timeout on the first request
+        and kept afterwards; a partial result is collected again next time.
+
+        Returns:
+            SystemContext, with "unknown" for facts that timed out
+        """
+        if self._system is None or self._system.timed_out:
+            self._system = await collect_system_context()
+        return self._system
+
+    async def _throttle(self) -> Optional[AgentResult]:
+        """Enforce the minimum interval between requests.
+
+        Delays the request until the interval has elapsed, or rejects it
+        when reject_rapid_requests is set.
+
+        Returns:
+            A failed AgentResult if the request was rejected, else None
+        """
+        wait = self.request_wait()
+        if wait > 0:
+            wait_ms = int(wait * 1000 + 0.5)
+            if self.config.reject_rapid_requests:
+                self.logger.warning("Rejecting rapid AI request", wait_ms=wait_ms)
+                return AgentResult(
+                    response=(
+                        f"Requests are limited to one every "
+                        f"{self.config.min_request_interval_ms} ms. "
+                        f"Try again in {wait_ms} ms."
+                    ),
+                    success=False,
+                    error="Rate limited",
+                )
+            self.logger.info("Delaying AI request", wait_ms=wait_ms)
+            await asyncio.sleep(wait)
+
+        self._last_request_at = self._clock()
+        return None
+
+    def _read_file_context(self, context: AgentContext) -> Optional[str]:
+        """Read the configured context files for the system prompt.
+
+        Args:
+            context: Execution context, whose cwd relative paths resolve against
+
+        Returns:
+            File contents, or None if no files are configured or readable
+        """
+        if not self.config.context_files:
+            return None
+        return read_file_context(
+            context.cwd or os.getcwd(),
+            self.config.context_files,
+            self.config.context_file_extensions,
+            self.config.context_file_max_lines,
+        )
+
+    def _sanitize_tool_calls(self, tool_calls: list[ToolCall]) -> list[ToolCall]:
+        """Drop empty and duplicate tool calls from an LLM response.
+
+        Args:
+            tool_calls: Tool calls as returned by the LLM
+
+        Returns:
+            Tool calls with unnamed calls and exact repeats removed, in
+            order, and dependency arguments moved out of their arguments
+        """
+        seen: set[str] = set()
+        kept: list[ToolCall] = []
+
+        for tool_call in tool_calls:
+            if not tool_call.name.strip():
+                self.logger.debug("Dropping empty tool call", id=tool_call.id)
+                continue
+            extract_dependencies(tool_call)
+
+            key = tool_call.name + json.dumps(tool_call.arguments, sort_keys=True, default=str)
+            if key in seen:
+                self.logger.debug("Dropping duplicate tool call", tool=tool_call.name)
+                continue
+
+            seen.add(key)
+            kept.append(tool_call)
+
+        return kept
+
     def _build_security_context(self, context: AgentContext) -> SecurityContext:
         """Build a SecurityContext from AgentContext.
 
@@ -269,41 +1015,295 @@ class AgentLoop(LoggerMixin):
             user=user,
             cwd=context.cwd or None,
             env=context.env or None,
-            interactive=True,
+            interactive=context.interactive,
         )
 
     def _check_command_security(
         self,
-        command: str,
+        tool_call: ToolCall,
         context: AgentContext,
-    ) -> tuple[bool, str]:
-        """Check if a command is allowed by security policy.
+    ) -> tuple[bool, str, Optional[ApprovalKind]]:
+        """Check if a shell step's command is allowed by security policy.
+
+        The shell steps from this one on are offered for editing together;
+        a command or plan the user edited replaces the planned ones.
 
         Args:
-            command: Command to check
+            tool_call: Shell step to check
             context: Execution context
 
         Returns:
-            Tuple of (allowed, message)
+            Tuple of (allowed, message, how an allowed command was authorized)
         """
         if not self.security_controller:
-            return True, "Security checks disabled"
+            return True, "Security checks disabled", None
 
         security_context = self._build_security_context(context)
-        decision = self.security_controller.validate_and_approve(command, security_context)
+        plan = self._remaining_shell_steps(tool_call)
+        decision = self.security_controller.validate_and_approve(
+            tool_call.arguments["command"],
+            security_context,
+            plan=[step.arguments["command"] for step in plan],
+        )
 
         if decision.result == ValidationResult.ALLOW:
-            return True, decision.reason
+            if decision.approval == ApprovalKind.EDITED:
+                self._apply_edited_plan(plan or [tool_call], decision.plan or [decision.command])
+            return True, decision.reason, decision.approval
         elif decision.result == ValidationResult.BLOCKED:
-            return False, f"Command blocked: {decision.reason}"
+            return False, f"Command blocked: {decision.reason}", None
         else:  # NEED_APPROVAL but we already ran validate_and_approve
-            return False, f"Approval required: {decision.reason}"
+            return False, f"Approval required: {decision.reason}", None
+
+    def _remaining_shell_steps(self, tool_call: ToolCall) -> list[ToolCall]:
+        """Get the shell steps of the current turn from a step on.
+
+        Args:
+            tool_call: Step about to run
+
+        Returns:
+            The step and the shell steps with a command after it, or an
+            empty list if the step is not part of the current turn
+        """
+        position = next(
+            (i for i, step in enumerate(self._turn_steps) if step is tool_call), None
+        )
+        if position is None:
+            return []
+        return [
+            step
+            for step in self._turn_steps[position:]
+            if step.name in SHELL_TOOLS
+            and step.id not in self._removed_steps
+            and isinstance(step.arguments.get("command"), str)
+        ]
+
+    def _apply_edited_plan(self, steps: list[ToolCall], commands: list[str]) -> None:
+        """Replace the commands of planned steps with the user's edits.
+
+        Steps beyond the edited commands were deleted by the user and are
+        not run.
+
+        Args:
+            steps: Shell steps offered for editing, in order
+            commands: Their commands as edited, at most one per step
+        """
+        for step, command in zip(steps, commands):
+            step.arguments["command"] = command
+        for step in steps[len(commands) :]:
+            self._removed_steps.add(step.id)
+        self.logger.info("Plan edited", steps=len(commands), removed=len(steps) - len(commands))
+
+    @staticmethod
+    def _copy_step(tool_call: ToolCall) -> ToolCall:
+        """Copy a step before it runs, as the AI asked for it.
+
+        Running the tool can add to its arguments, and the pre-exec hook
+        can rewrite its command; a redo replays the step as planned.
+
+        Args:
+            tool_call: Step about to run
+
+        Returns:
+            Copy of the step with its own arguments
+        """
+        return ToolCall(id=tool_call.id, name=tool_call.name, arguments=dict(tool_call.arguments))
+
+    def _record_step(self, planned: ToolCall, outcome: StepOutcome) -> None:
+        """Remember a step of the current request for :meth:`redo`.
+
+        Only steps that ran, successfully or not, are remembered; steps
+        that were declined, refused or never started are not redone.
+
+        Args:
+            planned: The step as copied before it ran
+            outcome: How it ended
+        """
+        if outcome in (StepOutcome.SUCCESS, StepOutcome.FAILED):
+            self._current_plan.append(planned)
+
+    def _record_result(self, tool_call: ToolCall, outcome: StepOutcome, output: str) -> None:
+        """Remember how a step of the current request ended.
+
+        Args:
+            tool_call: Step that ran
+            outcome: Its outcome
+            output: Its output
+        """
+        self._current_steps.append(
+            StepRecord(
+                step=describe_step(tool_call),
+                tool=tool_call.name,
+                outcome=outcome,
+                output=output,
+            )
+        )
+
+    def _audit_incomplete(self, request: str, result: AgentResult) -> None:
+        """Record a plan that stopped part-way, with the steps that ran.
+
+        Args:
+            request: The user's request
+            result: Result of the stopped request
+        """
+        self.logger.warning(
+            "Plan incomplete",
+            reason=result.error,
+            steps_run=len(result.steps),
+        )
+        if self.security_controller is None:
+            return
+        self.security_controller.audit.log_plan_incomplete(
+            request,
+            reason=result.error or "",
+            steps=[step.to_dict() for step in result.steps],
+        )
+
+    async def explain(self, command: str) -> str:
+        """Ask the AI to explain a command (``ai explain``).
+
+        The AI is offered no tools, so nothing runs, and the exchange is
+        not added to the conversation history.
+
+        Args:
+            command: Command to explain
+
+        Returns:
+            The AI's raw reply, for agentsh.agent.explain.parse_explanation
+
+        Raises:
+            LLMError: If the request fails or is rate limited
+        """
+        rejected = await self._throttle()
+        if rejected is not None:
+            raise LLMError(rejected.response)
+
+        with request_context():
+            response = await self.llm_client.invoke(
+                messages=[Message.user(build_explain_request(command))],
+                tools=None,
+                temperature=self.config.temperature,
+                max_tokens=self.config.max_tokens,
+            )
+        return response.content or ""
+
+    async def redo(self, context: Optional[AgentContext] = None) -> AgentResult:
+        """Run the steps of the last executed plan again without asking the AI.
+
+        Every step goes through the security checks and confirmation again,
+        so without a security controller nothing is redone. A plan run by
+        a read-only query is redone as a read-only query. The redo stops at
+        the first step that fails or is declined.
+
+        Args:
+            context: Execution context
+
+        Returns:
+            AgentResult listing each step run and its output
+        """
+        if not self.last_plan:
+            return AgentResult(
+                response="No plan has been run yet, so there is nothing to redo.",
+                success=False,
+                error="Nothing to redo",
+            )
+        if self.security_controller is None:
+            return AgentResult(
+                response="Redo needs the security checks to confirm each step again; "
+                "they are not set up, so nothing was run.",
+                success=False,
+                error="Redo unavailable",
+            )
+
+        context = context or AgentContext()
+        if self._last_plan_read_only:
+            context = replace(context, read_only=True)
+        lines: list[str] = []
+        tool_calls_made: list[str] = []
+        step_outcomes: list[StepOutcome] = []
+
+        with request_context():
+            for number, step in enumerate(self.last_plan, start=1):
+                tool_call = ToolCall(id=step.id, name=step.name, arguments=dict(step.arguments))
+                tool_calls_made.append(tool_call.name)
+                output, outcome = await self._execute_tool(tool_call, context)
+                step_outcomes.append(outcome)
+                lines.append(f"Step {number}: {describe_step(step)} [{outcome.value}]")
+                lines.append(output)
+                if outcome != StepOutcome.SUCCESS:
+                    remaining = len(self.last_plan) - number
+                    if remaining:
+                        lines.append(f"Stopped. {remaining} remaining step(s) were not run.")
+                    break
+
+        success = all(outcome == StepOutcome.SUCCESS for outcome in step_outcomes)
+        return AgentResult(
+            response="\n".join(lines),
+            tool_calls_made=tool_calls_made,
+            total_steps=len(step_outcomes),
+            success=success,
+            error=None if success else "Redo stopped",
+            step_outcomes=step_outcomes,
+        )
+
+    async def _execute_batch(
+        self,
+        batch: list[ToolCall],
+        context: AgentContext,
+    ) -> dict[str, tuple[str, StepOutcome]]:
+        """Execute a batch of independent tool calls concurrently.
+
+        At most config.max_parallel steps run at the same time; a batch of
+        one step simply runs it. Confirmation prompts block on input, so
+        every step is checked and confirmed in plan order before any of
+        them starts, and results are reported in plan order.
+
+        Args:
+            batch: Tool calls with no dependencies on each other
+            context: Execution context
+
+        Returns:
+            Result and outcome of each tool call, by tool call id
+        """
+        steps = [tc for tc in batch if tc.name not in (NEED_INFO_TOOL, ALTERNATIVES_TOOL)]
+        if len(steps) <= 1:
+            return {tc.id: await self._execute_tool(tc, context) for tc in steps}
+
+        results: dict[str, tuple[str, StepOutcome]] = {}
+        authorized: list[tuple[Tool, ToolCall, ApprovalKind]] = []
+        for tool_call in steps:
+            tool = self.tool_registry.get_tool(tool_call.name)
+            if not tool:
+                results[tool_call.id] = (
+                    f"Error: Unknown tool '{tool_call.name}'",
+                    StepOutcome.FAILED,
+                )
+                continue
+            checked = await self._authorize_tool(tool, tool_call, context)
+            if isinstance(checked, ApprovalKind):
+                authorized.append((tool, tool_call, checked))
+            else:
+                results[tool_call.id] = checked
+
+        self.logger.info("Running steps in parallel", steps=len(authorized))
+        semaphore = asyncio.Semaphore(max(1, self.config.max_parallel))
+
+        async def run(
+            tool: Tool, tool_call: ToolCall, approval: ApprovalKind
+        ) -> tuple[str, StepOutcome]:
+            async with semaphore:
+                return await self._run_authorized(tool, tool_call, context, approval)
+
+        outputs = await asyncio.gather(*(run(*step) for step in authorized))
+        for (_, tool_call, _), output in zip(authorized, outputs):
+            results[tool_call.id] = output
+        return {tc.id: results[tc.id] for tc in steps}
 
     async def _execute_tool(
         self,
         tool_call: ToolCall,
         context: AgentContext,
-    ) -> str:
+    ) -> tuple[str, StepOutcome]:
         """Execute a single tool call.
 
         Args:
@@ -311,12 +1311,53 @@ class AgentLoop(LoggerMixin):
             context: Execution context
 
         Returns:
-            Tool result as string
+            Tuple of (tool result as string, step outcome)
         """
         tool = self.tool_registry.get_tool(tool_call.name)
 
         if not tool:
-            return f"Error: Unknown tool '{tool_call.name}'"
+            return f"Error: Unknown tool '{tool_call.name}'", StepOutcome.FAILED
+
+        checked = await self._authorize_tool(tool, tool_call, context)
+        if not isinstance(checked, ApprovalKind):
+            return checked
+        return await self._run_authorized(tool, tool_call, context, checked)
+
+    async def _authorize_tool(
+        self,
+        tool: Tool,
+        tool_call: ToolCall,
+        context: AgentContext,
+    ) -> Union[ApprovalKind, tuple[str, StepOutcome]]:
+        """Run the pre-exec hook, security checks and confirmation for a step.
+
+        Args:
+            tool: The tool the step runs
+            tool_call: Tool call to authorize; the hook may rewrite its command
+            context: Execution context
+
+        Returns:
+            How the step was authorized, or the refusal and its outcome
+        """
+        if tool_call.id in self._removed_steps:
+            return "Not run: the user removed it from the plan", StepOutcome.CANCELLED
+
+        # The team's own policy engine sees the step before anything else
+        if self.config.pre_exec_hook:
+            refusal = await self._apply_pre_exec_hook(tool_call, context)
+            if refusal is not None:
+                return refusal, StepOutcome.CANCELLED
+
+        # Read-only queries refuse anything that could change the system,
+        # judged on the command as the hook left it and before the user is
+        # asked to confirm it
+        if context.read_only and not self._is_read_only_step(tool, tool_call):
+            self.logger.warning("Step refused in read-only query", tool=tool_call.name)
+            return (
+                f"Refused: {describe_step(tool_call)} could change the system, and this "
+                "is a read-only query",
+                StepOutcome.CANCELLED,
+            )
 
         self.logger.info(
             "Executing tool",
@@ -324,11 +1365,15 @@ class AgentLoop(LoggerMixin):
             arguments=list(tool_call.arguments.keys()),
         )
 
+        # Steps that are neither checked nor confirmed run on policy alone
+        approval = ApprovalKind.AUTO_APPROVED
+
         # Check security for shell/command execution tools
-        if self.security_controller and tool.name in ("shell", "bash", "execute", "run_command"):
+        if self.security_controller and tool.name in SHELL_TOOLS:
             command = tool_call.arguments.get("command", "")
             if command:
-                allowed, message = self._check_command_security(command, context)
+                allowed, message, checked = self._check_command_security(tool_call, context)
+                approval = checked or approval
                 if not allowed:
                     self.logger.warning(
                         "Tool execution blocked by security",
@@ -336,24 +1381,430 @@ class AgentLoop(LoggerMixin):
                         command=command[:100],
                         reason=message,
                     )
-                    return f"Security: {message}"
+                    return f"Security: {message}", StepOutcome.CANCELLED
+
+        destructive = self._destructive_risk(tool_call)
+        leaves_workspace = self._leaves_workspace(tool_call, context)
+        # A command the user just approved in the security check is not
+        # confirmed a second time for the same concerns; with confirm_all,
+        # other tools that could change something are confirmed too
+        confirm_all = bool(self.security_controller and self.security_controller.confirm_all)
+        needs_confirmation = approval == ApprovalKind.AUTO_APPROVED and (
+            self.tool_registry.requires_confirmation(tool.name)
+            or destructive is not None
+            or (
+                confirm_all
+                and tool_call.name not in SHELL_TOOLS
+                and not self._is_read_only_step(tool, tool_call)
+            )
+        )
+        if self.security_controller and (needs_confirmation or leaves_workspace):
+            decision = self.security_controller.confirm_tool(
+                describe_step(tool_call),
+                destructive.risk_level if destructive else RiskLevel[tool.risk_level.name],
+                self._build_security_context(context),
+                reasons=[leaves_workspace] if leaves_workspace else None,
+            )
+            if decision.result != ValidationResult.ALLOW:
+                self.logger.warning("Tool declined", tool=tool_call.name, reason=decision.reason)
+                return f"Security: {decision.reason}", StepOutcome.CANCELLED
+            approval = decision.approval or ApprovalKind.CONFIRMED
+        elif leaves_workspace:
+            # Without a controller the warning still needs an explicit yes
+            response = self.approval_flow.request_approval(
+                ApprovalRequest(
+                    command=describe_step(tool_call),
+                    risk_level=destructive.risk_level if destructive else RiskLevel.MEDIUM,
+                    reasons=[leaves_workspace],
+                    context={"cwd": context.cwd or os.getcwd()},
+                )
+            )
+            if response.result != ApprovalResult.APPROVED:
+                self.logger.warning("Tool declined", tool=tool_call.name, reason=leaves_workspace)
+                reason = "Step leaves the workspace and was not confirmed"
+                return f"Security: {reason}", StepOutcome.CANCELLED
+            approval = ApprovalKind.CONFIRMED
+
+        return approval
+
+    async def _run_authorized(
+        self,
+        tool: Tool,
+        tool_call: ToolCall,
+        context: AgentContext,
+        approval: ApprovalKind,
+    ) -> tuple[str, StepOutcome]:
+        """Run a step that passed its checks, and audit it.
+
+        Args:
+            tool: The tool the step runs
+            tool_call: Tool call to run
+            context: Execution context
+            approval: How the step was authorized
 
+        Returns:
+            Tuple of (tool result as string, step outcome)
+        """
+        started_at = datetime.now(timezone.utc)
+        start = time.monotonic()
         try:
             # Execute with timeout
             result = await asyncio.wait_for(
                 self._run_tool(tool, tool_call.arguments, context),
                 timeout=self.config.timeout,
             )
+            result.started_at = started_at
+            result.duration_ms = int((time.monotonic() - start) * 1000)
+            self._audit_executed(tool_call, result, approval)
 
             if result.success:
-                return result.output or "Tool executed successfully (no output)."
+                output = result.output or "Tool executed successfully (no output)."
+                return output, StepOutcome.SUCCESS
             else:
-                return f"Tool error: {result.error}"
+                return f"Tool error: {result.error}", StepOutcome.FAILED
 
         except asyncio.TimeoutError:
-            return f"Tool '{tool_call.name}' timed out after {self.config.timeout}s"
+            return (
+                f"Tool '{tool_call.name}' timed out after {self.config.timeout}s",
+                StepOutcome.FAILED,
+            )
         except Exception as e:
-            return f"Tool execution error: {str(e)}"
+            return f"Tool execution error: {str(e)}", StepOutcome.FAILED
+
+    async def _apply_pre_exec_hook(
+        self,
+        tool_call: ToolCall,
+        context: AgentContext,
+    ) -> Optional[str]:
+        """Run the pre-execution hook on a step and apply its verdict.
+
+        A modified command replaces the step's command, and then goes
+        through the usual security checks and confirmation.
+
+        Args:
+            tool_call: Step about to run; its command may be replaced
+            context: Execution context
+
+        Returns:
+            Message for the AI if the step is refused, None if it may run
+        """
+        command = tool_call.arguments.get("command")
+        step = {
+            "tool": tool_call.name,
+            "arguments": {k: v for k, v in tool_call.arguments.items() if k != "context"},
+            "command": command if isinstance(command, str) else None,
+            "cwd": context.cwd,
+        }
+        verdict = await asyncio.to_thread(
+            run_pre_exec_hook,
+            self.config.pre_exec_hook,
+            step,
+            self.config.pre_exec_hook_timeout,
+            context.cwd or None,
+        )
+
+        if verdict.action == HookAction.MODIFY:
+            if not isinstance(command, str):
+                verdict = HookVerdict(
+                    HookAction.DENY, reason="pre-exec hook modified a step without a command"
+                )
+            else:
+                self.logger.info(
+                    "Pre-exec hook modified step",
+                    original=command[:100],
+                    command=verdict.command[:100],
+                )
+                tool_call.arguments["command"] = verdict.command
+                return None
+
+        if verdict.action == HookAction.DENY:
+            self.logger.warning("Step denied by pre-exec hook", reason=verdict.reason)
+            reason = f": {verdict.reason}" if verdict.reason else ""
+            return f"Denied by pre-exec hook{reason}"
+        return None
+
+    def _audit_executed(
+        self,
+        tool_call: ToolCall,
+        result: ToolResult,
+        approval: Optional[ApprovalKind] = None,
+    ) -> None:
+        """Record an executed shell step in the audit log with its timing and safety report.
+
+        Args:
+            tool_call: Tool call that ran
+            result: Its result, with started_at and duration_ms set
+            approval: How the step was authorized
+        """
+        command = tool_call.arguments.get("command")
+        if not self.security_controller or tool_call.name not in SHELL_TOOLS:
+            return
+        if not isinstance(command, str):
+            return
+        exit_code = result.exit_code
+        if exit_code is None and not result.success:
+            exit_code = 1
+        assessment = self._probe_classifier.classify(command)
+        self.security_controller.audit.log_command_executed(
+            command,
+            risk_level=assessment.risk_level,
+            exit_code=exit_code,
+            started_at=result.started_at,
+            duration_ms=result.duration_ms,
+            safety_flags=assessment.safety_flags(),
+            warnings=assessment.reasons if assessment.risk_level > RiskLevel.SAFE else None,
+            approval=approval,
+        )
+
+    def _is_read_only_step(self, tool: Tool, tool_call: ToolCall) -> bool:
+        """Check whether a step only inspects the system.
+
+        Shell commands must be known read-only commands, optionally piped
+        into each other; other tools must be SAFE and need no confirmation.
+
+        Args:
+            tool: The tool the step runs
+            tool_call: The step
+
+        Returns:
+            True if the step may run in a read-only query
+        """
+        if tool_call.name in SHELL_TOOLS:
+            command = tool_call.arguments.get("command")
+            return isinstance(command, str) and self._probe_classifier.is_read_only_pipeline(
+                command
+            )
+        return RiskLevel[tool.risk_level.name] == RiskLevel.SAFE and not tool.requires_confirmation
+
+    def _leaves_workspace(self, tool_call: ToolCall, context: AgentContext) -> Optional[str]:
+        """Warn about a shell step that runs or cd's outside config.workspace_root.
+
+        The step starts in its own ``cwd`` argument if it has one, else in
+        the session's working directory; a ``cwd`` argument outside the
+        workspace is warned about by itself.
+
+        Args:
+            tool_call: Tool call to check
+            context: Execution context
+
+        Returns:
+            The warning shown when confirming the step, or None if it stays
+            in the workspace (or no workspace is configured)
+        """
+        command = tool_call.arguments.get("command")
+        root = self.config.workspace_root
+        if not root or tool_call.name not in SHELL_TOOLS or not isinstance(command, str):
+            return None
+
+        cwd = context.cwd or os.getcwd()
+        step_cwd = tool_call.arguments.get("cwd")
+        if isinstance(step_cwd, str) and step_cwd:
+            cwd = os.path.normpath(os.path.join(cwd, os.path.expanduser(step_cwd)))
+            if outside_workspace(cwd, root):
+                self.logger.warning("Step runs outside the workspace", cwd=cwd, workspace_root=root)
+                return f"WARNING: the step runs in {cwd}, outside the workspace ({root})"
+
+        targets = cd_outside_workspace(command, cwd, root)
+        if not targets:
+            return None
+
+        self.logger.warning(
+            "Step leaves the workspace",
+            command=truncate_preview(command),
+            targets=targets,
+            workspace_root=root,
+        )
+        return (
+            f"WARNING: cd {', '.join(targets)} leaves the workspace ({root}); "
+            "relative paths in later commands act outside it"
+        )
+
+    def _destructive_risk(self, tool_call: ToolCall) -> Optional[CommandRiskAssessment]:
+        """Find a shell step that must be confirmed as destructive.
+
+        A step is destructive when the AI marked it ``is_destructive``, or
+        when the classifier, independently of the AI, rates it high-risk
+        (recursive or forced delete, sudo, stopping a critical service,
+        ...). A high-risk command marked safe or not marked at all gets the
+        flag set.
+
+        Args:
+            tool_call: Tool call to check; its arguments are updated when
+                the AI under-reported the risk
+
+        Returns:
+            The classifier's assessment if the step must be confirmed, else None
+        """
+        command = tool_call.arguments.get("command")
+        if tool_call.name not in SHELL_TOOLS or not isinstance(command, str):
+            return None
+
+        assessment = self._probe_classifier.classify(command)
+        if tool_call.arguments.get(DESTRUCTIVE_FLAG):
+            return assessment
+        if assessment.risk_level < RiskLevel.HIGH and not assessment.affects_critical_service:
+            return None
+
+        tool_call.arguments[DESTRUCTIVE_FLAG] = True
+        self.logger.warning(
+            "AI under-reported risk",
+            tool=tool_call.name,
+            command=truncate_preview(command),
+            risk_level=assessment.risk_level.name,
+            reasons=assessment.reasons,
+        )
+        return assessment
+
+    async def _run_alternative(
+        self,
+        tool_call: ToolCall,
+        context: AgentContext,
+        on_choose: Optional[Callable[[list[Alternative]], Optional[int]]],
+    ) -> tuple[str, list[tuple[ToolCall, StepOutcome]]]:
+        """Let the user pick one of the offered alternatives and run it.
+
+        The chosen alternative's commands run as shell steps through the
+        usual security checks and confirmation, stopping at the first step
+        that does not succeed.
+
+        Args:
+            tool_call: The offer_alternatives tool call
+            context: Execution context
+            on_choose: Callback picking an alternative
+
+        Returns:
+            Tuple of (result to feed back to the LLM, steps run with their
+            outcomes)
+        """
+        alternatives = parse_alternatives(tool_call)
+        if not alternatives:
+            return "Error: offer_alternatives requires alternatives with a name and commands", []
+
+        choice = on_choose(alternatives) if on_choose else None
+        if choice is None:
+            self.logger.info("No alternative chosen", offered=len(alternatives))
+            return "The user did not pick any of the alternatives. Nothing was run.", []
+
+        chosen = alternatives[choice]
+        self.logger.info("Alternative chosen", name=chosen.name, offered=len(alternatives))
+        steps = alternative_steps(tool_call, chosen)
+        lines = [f"The user chose: {chosen.name}"]
+        ran: list[tuple[ToolCall, StepOutcome]] = []
+        for number, step in enumerate(steps, start=1):
+            planned = self._copy_step(step)
+            output, outcome = await self._execute_tool(step, context)
+            self._record_step(planned, outcome)
+            ran.append((step, outcome))
+            self._record_result(step, outcome, output)
+            lines.append(f"$ {describe_step(step)} [{outcome.value}]")
+            lines.append(output)
+            if outcome != StepOutcome.SUCCESS:
+                remaining = len(steps) - number
+                if remaining:
+                    lines.append(f"Stopped. {remaining} remaining step(s) were not run.")
+                break
+        return "\n".join(lines), ran
+
+    def _need_info_definition(self) -> ToolDefinition:
+        """Build the definition for the built-in need_info tool.
+
+        Returns:
+            ToolDefinition for need_info
+        """
+        return ToolDefinition(
+            name=NEED_INFO_TOOL,
+            description=(
+                "Run read-only commands (e.g. ls, cat, git status) to gather "
+                "information before answering. Commands that could modify the "
+                "system are refused. Their output is returned to you."
+            ),
+            parameters={
+                "commands": {
+                    "type": "array",
+                    "items": {"type": "string"},
+                    "description": "Read-only shell commands to run",
+                },
+            },
+            required=["commands"],
+        )
+
+    async def _gather_info(
+        self,
+        tool_call: ToolCall,
+        context: AgentContext,
+        allowed: bool = True,
+    ) -> str:
+        """Run the read-only probes requested by a need_info call.
+
+        Probes skip the approval flow, so each command must first pass
+        RiskClassifier.is_read_only.
+
+        Args:
+            tool_call: The need_info tool call
+            context: Execution context
+            allowed: False once the need_info round limit has been exceeded
+
+        Returns:
+            Combined probe output to feed back to the LLM
+        """
+        if not allowed:
+            return (
+                f"Information limit reached ({self.config.max_info_rounds} rounds). "
+                "Answer with the information you already have."
+            )
+
+        commands = tool_call.arguments.get("commands") or []
+        if isinstance(commands, str):
+            commands = [commands]
+
+        if not commands:
+            return "Error: need_info requires at least one command"
+
+        sections = []
+        for command in commands:
+            if not self._probe_classifier.is_read_only(command):
+                self.logger.warning("need_info command refused", command=command[:100])
+                sections.append(f"$ {command}\nRefused: not a read-only command")
+                continue
+
+            output = await self._run_probe(command, context)
+            sections.append(f"$ {command}\n{output}")
+
+        return "\n\n".join(sections)
+
+    async def _run_probe(self, command: str, context: AgentContext) -> str:
+        """Run a single read-only probe command.
+
+        Args:
+            command: Command to run
+            context: Execution context
+
+        Returns:
+            Command output (truncated), or an error description
+        """
+        try:
+            proc = await asyncio.create_subprocess_shell(
+                command,
+                stdout=asyncio.subprocess.PIPE,
+                stderr=asyncio.subprocess.STDOUT,
+                cwd=context.cwd or None,
+            )
+            try:
+                stdout, _ = await asyncio.wait_for(
+                    proc.communicate(), timeout=self.config.timeout
+                )
+            except asyncio.TimeoutError:
+                proc.kill()
+                await proc.wait()
+                return f"Timed out after {self.config.timeout}s"
+        except Exception as e:
+            return f"Error: {str(e)}"
+
+        output = decode_output(stdout, self.config.strict_output).strip()
+        output = truncate_preview(output, MAX_INFO_OUTPUT_BYTES)
+        if proc.returncode != 0:
+            output = f"{output}\n(exit code {proc.returncode})".strip()
+        return output or "(no output)"
 
     async def _run_tool(
         self,
@@ -404,11 +1855,14 @@ class AgentLoop(LoggerMixin):
         definitions = []
 
         for tool in self.tool_registry.list_tools():
+            parameters = tool.parameters.get("properties", {})
+            if self.config.parallel:
+                parameters = {**parameters, **DEPENDENCY_PARAMETERS}
             definitions.append(
                 ToolDefinition(
                     name=tool.name,
                     description=tool.description,
-                    parameters=tool.parameters.get("properties", {}),
+                    parameters=parameters,
                     required=tool.parameters.get("required", []),
                 )
             )
@@ -441,18 +1895,37 @@ class StreamingAgentLoop(AgentLoop):
             Final AgentResult
         """
         context = context or AgentContext()
+
+        rejected = await self._throttle()
+        if rejected is not None:
+            return rejected
+
         tool_defs = self._build_tool_definitions()
 
+        system = await self._system_context()
         system_prompt = build_system_prompt(
             available_tools=[f"{t.name}: {t.description}" for t in self.tool_registry.list_tools()],
             cwd=context.cwd,
+            os_info=system.os_info(),
+            hostname=context.hostname or system.known_hostname(),
             recent_history=context.history,
+            domain_hint=self.config.domain_hint,
+            last_exit_code=context.last_exit_code,
+            recent_output=context.recent_output if is_fix_request(request) else None,
+            project_notes=read_notes(context.cwd or os.getcwd(), self.config.max_notes_chars),
+            file_context=self._read_file_context(context),
+            read_only=context.read_only,
         )
 
+        user_message = build_user_message(
+            request, context.piped_input, self.config.max_piped_input_chars
+        )
         messages = [
             Message.system(system_prompt),
-            Message.user(request),
+            Message.user(user_message),
         ]
+        exchange = Exchange(system_prompt=system_prompt, request=user_message)
+        self.last_exchange = exchange
 
         collected_response = ""
         tool_calls_made: list[str] = []
@@ -477,8 +1950,52 @@ class StreamingAgentLoop(AgentLoop):
             # For now, just return the streamed response
             break
 
+        if json_truncated(collected_response):
+            try:
+                collected_response = await self._refetch_truncated(messages, tool_defs)
+            except LLMError as e:
+                self.logger.error("Streamed response cut off", error=str(e))
+                exchange.responses.append(collected_response)
+                return AgentResult(
+                    response=e.user_message,
+                    tool_calls_made=tool_calls_made,
+                    total_steps=step,
+                    success=False,
+                    error=str(e),
+                )
+
+        exchange.responses.append(collected_response)
+
         return AgentResult(
             response=collected_response,
             tool_calls_made=tool_calls_made,
             total_steps=step,
         )
+
+    async def _refetch_truncated(
+        self, messages: list[Message], tool_defs: list[ToolDefinition]
+    ) -> str:
+        """Request a response again without streaming after a cut-off stream.
+
+        Args:
+            messages: Messages the stream was requested with
+            tool_defs: Tools offered to the stream
+
+        Returns:
+            The complete response text
+
+        Raises:
+            LLMTruncatedError: If the retried response is cut off as well
+            LLMError: If the retry fails
+        """
+        self.logger.warning("Streamed response cut off mid-JSON, retrying without streaming")
+        response = await self.llm_client.invoke(
+            messages=messages,
+            tools=tool_defs if tool_defs else None,
+            temperature=self.config.temperature,
+            max_tokens=self.config.max_tokens,
+        )
+        content = response.content or ""
+        if json_truncated(content):
+            raise LLMTruncatedError(content[-BODY_SNIPPET_CHARS:])
+        return content
@@ -0,0 +1,178 @@
+"""Alternatives - Let the user pick one of several approaches the AI offers.
+
+For open-ended requests the AI may answer with a few named candidate
+plans instead of a single one, by calling the built-in
+``offer_alternatives`` tool. The user picks one from a numbered list and
+its commands then run as ordinary ``shell.run`` steps, each going through
+the usual security checks and confirmation.
+"""
+
+from dataclasses import dataclass, field
+from typing import Callable, Optional
+
+from agentsh.agent.llm_client import ToolCall, ToolDefinition
+from agentsh.telemetry.logger import get_logger
+
+logger = get_logger(__name__)
+
+ALTERNATIVES_TOOL = "offer_alternatives"
+
+# Tool the chosen alternative's commands run as
+ALTERNATIVE_STEP_TOOL = "shell.run"
+
+
+@dataclass
+class Alternative:
+    """One candidate plan offered by the AI.
+
+    Attributes:
+        name: Short name shown in the picker
+        description: What the approach does and its trade-offs
+        commands: Shell commands making up the plan, in order
+    """
+
+    name: str
+    description: str = ""
+    commands: list[str] = field(default_factory=list)
+
+
+def alternatives_definition() -> ToolDefinition:
+    """Build the definition for the built-in offer_alternatives tool.
+
+    Returns:
+        ToolDefinition for offer_alternatives
+    """
+    return ToolDefinition(
+        name=ALTERNATIVES_TOOL,
+        description=(
+            "Offer the user two to four different approaches to an open-ended "
+            "request instead of running one. The user picks one and its commands "
+            "are run, each confirmed as usual."
+        ),
+        parameters={
+            "alternatives": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "name": {"type": "string", "description": "Short name"},
+                        "description": {
+                            "type": "string",
+                            "description": "What this approach does and its trade-offs",
+                        },
+                        "commands": {
+                            "type": "array",
+                            "items": {"type": "string"},
+                            "description": "Shell commands to run, in order",
+                        },
+                    },
+                    "required": ["name", "commands"],
+                },
+            },
+        },
+        required=["alternatives"],
+    )
+
+
+def parse_alternatives(tool_call: ToolCall) -> list[Alternative]:
+    """Read the candidate plans from an offer_alternatives call.
+
+    Entries without a name or without any command are skipped.
+
+    Args:
+        tool_call: The offer_alternatives tool call
+
+    Returns:
+        The valid alternatives, in the order offered
+    """
+    entries = tool_call.arguments.get("alternatives")
+    if not isinstance(entries, list):
+        return []
+
+    alternatives = []
+    for entry in entries:
+        if not isinstance(entry, dict):
+            continue
+        name = str(entry.get("name") or "").strip()
+        commands = entry.get("commands")
+        if isinstance(commands, str):
+            commands = [commands]
+        if not isinstance(commands, list):
+            commands = []
+        commands = [str(command).strip() for command in commands if str(command).strip()]
+        if not name or not commands:
+            logger.debug("Skipping malformed alternative", entry=str(entry)[:100])
+            continue
+        alternatives.append(
+            Alternative(
+                name=name,
+                description=str(entry.get("description") or "").strip(),
+                commands=commands,
+            )
+        )
+    return alternatives
+
+
+def alternative_steps(tool_call: ToolCall, alternative: Alternative) -> list[ToolCall]:
+    """Turn the chosen alternative into shell steps.
+
+    Args:
+        tool_call: The offer_alternatives call the alternative came from
+        alternative: The chosen alternative
+
+    Returns:
+        One shell.run tool call per command
+    """
+    return [
+        ToolCall(
+            id=f"{tool_call.id}-{number}",
+            name=ALTERNATIVE_STEP_TOOL,
+            arguments={"command": command},
+        )
+        for number, command in enumerate(alternative.commands, start=1)
+    ]
+
+
+def format_alternatives(alternatives: list[Alternative]) -> str:
+    """Format alternatives as a numbered list for the picker.
+
+    Args:
+        alternatives: Alternatives to show
+
+    Returns:
+        One numbered entry per alternative with its description and commands
+    """
+    lines = []
+    for number, alternative in enumerate(alternatives, start=1):
+        lines.append(f"{number}. {alternative.name}")
+        if alternative.description:
+            lines.append(f"   {alternative.description}")
+        lines.extend(f"   $ {command}" for command in alternative.commands)
+    return "\n".join(lines)
+
+
+def prompt_alternative(
+    alternatives: list[Alternative],
+    input_fn: Callable[[str], str] = input,
+) -> Optional[int]:
+    """Ask the user which alternative to run.
+
+    Args:
+        alternatives: Alternatives offered by the AI
+        input_fn: Function reading a line of input
+
+    Returns:
+        Index of the chosen alternative, or None if the user picked none
+        (empty answer, EOF or Ctrl-C)
+    """
+    print(f"\nThe AI offers {len(alternatives)} approaches:\n{format_alternatives(alternatives)}")
+    while True:
+        try:
+            choice = input_fn(f"Run which? [1-{len(alternatives)}, Enter to cancel] > ").strip()
+        except (EOFError, KeyboardInterrupt):
+            return None
+        if not choice:
+            return None
+        if choice.isdigit() and 1 <= int(choice) <= len(alternatives):
+            return int(choice) - 1
+        print(f"Please answer a number from 1 to {len(alternatives)}.")
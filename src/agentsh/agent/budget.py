@@ -0,0 +1,70 @@
+"""Execution budget - Wall-clock limit across all steps of a request.
+
+Per-tool timeouts bound a single step, but a long multi-step plan can
+still run for a long time. ExecutionBudget tracks the time spent on a
+whole request so the agent loop can stop between steps once it is used
+up. The clock is injectable, so the budget can be tested without
+sleeping.
+"""
+
+import time
+from typing import Callable, Optional
+
+
+class ExecutionBudget:
+    """Track elapsed time against a total time limit.
+
+    Example:
+        budget = ExecutionBudget(60.0)
+        for step in steps:
+            if budget.exceeded:
+                break
+            run(step)
+            print(budget.describe())
+    """
+
+    def __init__(
+        self,
+        total_seconds: float,
+        clock: Callable[[], float] = time.monotonic,
+    ) -> None:
+        """Start the budget.
+
+        Args:
+            total_seconds: Time allowed for the whole request (0 or less
+                means unlimited)
+            clock: Monotonic clock returning seconds
+        """
+        self.total_seconds = total_seconds
+        self._clock = clock
+        self._started_at = clock()
+
+    @property
+    def limited(self) -> bool:
+        """Whether a time limit is set."""
+        return self.total_seconds > 0
+
+    def elapsed(self) -> float:
+        """Get the seconds spent since the budget started."""
+        return self._clock() - self._started_at
+
+    def remaining(self) -> Optional[float]:
+        """Get the seconds left, or None when unlimited."""
+        if not self.limited:
+            return None
+        return max(0.0, self.total_seconds - self.elapsed())
+
+    @property
+    def exceeded(self) -> bool:
+        """Whether the time limit has been used up."""
+        return self.limited and self.elapsed() >= self.total_seconds
+
+    def describe(self) -> str:
+        """Describe the budget's state for display.
+
+        Returns:
+            e.g. "42.5s of 60s plan budget left"
+        """
+        if not self.limited:
+            return f"{self.elapsed():.1f}s elapsed (no plan budget)"
+        return f"{self.remaining():.1f}s of {self.total_seconds:g}s plan budget left"
@@ -0,0 +1,93 @@
+"""Chat export - Save an AI session as a Markdown transcript.
+
+``ai export-chat <file>`` writes every request of the session, the
+commands run for it with their (shortened) output, and the AI's answer
+to a Markdown file that can be shared. API keys, tokens and passwords
+are redacted before anything is written.
+"""
+
+import re
+from dataclasses import dataclass, field
+from datetime import datetime
+from pathlib import Path
+from typing import TYPE_CHECKING, Optional
+
+from agentsh.telemetry.logger import truncate_preview
+from agentsh.utils.validators import redact_secrets
+
+if TYPE_CHECKING:
+    from agentsh.agent.agent_loop import StepRecord
+
+
+@dataclass
+class ChatTurn:
+    """One request of an AI session.
+
+    Attributes:
+        request: What the user asked
+        response: The AI's final answer
+        steps: Steps run for the request, in order
+    """
+
+    request: str
+    response: str
+    steps: list["StepRecord"] = field(default_factory=list)
+
+
+def _fenced(text: str, info: str = "") -> str:
+    """Wrap text in a code fence longer than any backtick run inside it."""
+    longest = max((len(run) for run in re.findall(r"`+", text)), default=0)
+    fence = "`" * max(3, longest + 1)
+    return f"{fence}{info}\n{text}\n{fence}"
+
+
+def render_chat_markdown(turns: list[ChatTurn], exported_at: Optional[datetime] = None) -> str:
+    """Render an AI session as Markdown.
+
+    Args:
+        turns: Requests of the session, oldest first
+        exported_at: Time shown in the header (defaults to now)
+
+    Returns:
+        Markdown transcript with secrets redacted
+    """
+    exported_at = exported_at or datetime.now()
+    lines = [
+        "# AgentSH conversation",
+        "",
+        f"_Exported {exported_at.strftime('%Y-%m-%d %H:%M')}_",
+    ]
+
+    for turn in turns:
+        lines += ["", "## You", "", turn.request.strip()]
+        if turn.steps:
+            lines += ["", "### Commands run", ""]
+            for step in turn.steps:
+                output = truncate_preview(step.output.strip())
+                lines.append(_fenced(f"$ {step.step}\n{output}".rstrip(), "console"))
+                if step.outcome.value != "success":
+                    lines.append(f"_{step.outcome.value}_")
+                lines.append("")
+            lines.pop()
+        lines += ["", "## AgentSH", "", turn.response.strip() or "_(no response)_"]
+
+    return redact_secrets("\n".join(lines) + "\n")
+
+
+def export_chat(turns: list[ChatTurn], path: Path) -> Path:
+    """Write an AI session to a Markdown file.
+
+    Args:
+        turns: Requests of the session, oldest first
+        path: File to write (~ is expanded, parent directories created)
+
+    Returns:
+        The path written
+
+    Raises:
+        OSError: If the file cannot be written
+    """
+    path = path.expanduser()
+    path.parent.mkdir(parents=True, exist_ok=True)
+    path.write_text(render_chat_markdown(turns), encoding="utf-8")
+    return path
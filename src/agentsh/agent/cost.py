@@ -0,0 +1,81 @@
+"""Cost estimates - Token usage and price of the request behind a plan.
+
+Providers report how many prompt and completion tokens a request used.
+Before a plan's steps are confirmed the usage is shown, and when the
+model has an entry in ``llm.pricing`` an approximate dollar cost too, so
+users on metered APIs can see what each request costs.
+"""
+
+from typing import Any, Optional
+
+# Token counts in the usage blocks of the supported APIs:
+# OpenAI-compatible, Anthropic and Gemini
+USAGE_KEYS = (
+    ("prompt_tokens", "completion_tokens"),
+    ("input_tokens", "output_tokens"),
+    ("promptTokenCount", "candidatesTokenCount"),
+)
+
+
+def _count(value: Any) -> int:
+    """Read a token count, treating missing or malformed values as 0."""
+    if isinstance(value, bool) or not isinstance(value, (int, float)):
+        return 0
+    return max(0, int(value))
+
+
+def parse_usage(usage: Any) -> tuple[int, int]:
+    """Read the token counts from a provider's usage block.
+
+    Args:
+        usage: The response's usage object (e.g. OpenAI's ``usage`` with
+            ``prompt_tokens`` and ``completion_tokens``)
+
+    Returns:
+        (prompt tokens, completion tokens), 0 for counts not reported
+    """
+    if not isinstance(usage, dict):
+        return 0, 0
+    for prompt_key, completion_key in USAGE_KEYS:
+        if prompt_key in usage or completion_key in usage:
+            return _count(usage.get(prompt_key)), _count(usage.get(completion_key))
+    return 0, 0
+
+
+def estimate_cost(
+    input_tokens: int,
+    output_tokens: int,
+    pricing: Optional[tuple[float, float]],
+) -> Optional[float]:
+    """Estimate the dollar cost of a request.
+
+    Args:
+        input_tokens: Prompt tokens used
+        output_tokens: Completion tokens used
+        pricing: USD per million (prompt, completion) tokens, or None if
+            the model has no configured price
+
+    Returns:
+        Estimated cost in USD, or None without pricing
+    """
+    if pricing is None:
+        return None
+    input_price, output_price = pricing
+    return (input_tokens * input_price + output_tokens * output_price) / 1_000_000
+
+
+def format_usage(input_tokens: int, output_tokens: int, cost: Optional[float] = None) -> str:
+    """Describe a request's token usage for the user.
+
+    Args:
+        input_tokens: Prompt tokens used
+        output_tokens: Completion tokens used
+        cost: Estimated cost in USD, if known
+
+    Returns:
+        Text such as "~1,200 prompt + 80 completion tokens, about $0.0048"
+    """
+    text = f"~{input_tokens:,} prompt + {output_tokens:,} completion tokens"
+    if cost is not None:
+        text += f", about ${cost:.4f}" if cost < 0.01 else f", about ${cost:.2f}"
+    return text
@@ -0,0 +1,131 @@
+"""Command explanations - Structured output for ``ai explain '<command>'``.
+
+The model is asked for a small JSON object (a one-line summary, a
+breakdown of each flag and a risk note), which is rendered as separate
+terminal sections. Models that answer in prose instead are shown as a
+plain summary.
+"""
+
+import json
+import re
+from dataclasses import dataclass, field
+from typing import Any, Optional
+
+from agentsh.agent.prompts import EXPLAIN_PROMPT
+from agentsh.utils.ux import Color, colorize
+
+# JSON object in a reply, optionally inside a ```json fence
+JSON_OBJECT = re.compile(r"\{.*\}", re.DOTALL)
+
+
+@dataclass
+class Explanation:
+    """Explanation of a shell command.
+
+    Attributes:
+        summary: What the command does (the whole reply when unstructured)
+        flags: (flag, meaning) pairs in command order
+        risk: What could go wrong, if the model named anything
+        structured: Whether the reply followed the JSON shape
+    """
+
+    summary: str
+    flags: list[tuple[str, str]] = field(default_factory=list)
+    risk: Optional[str] = None
+    structured: bool = False
+
+
+def build_explain_request(command: str) -> str:
+    """Build the request asking the model to explain a command.
+
+    Args:
+        command: Command to explain
+
+    Returns:
+        Request text sent to the AI
+    """
+    return EXPLAIN_PROMPT.format(command=command)
+
+
+def _parse_flags(value: Any) -> Optional[list[tuple[str, str]]]:
+    """Read the flags list, or None if it has the wrong shape."""
+    if not isinstance(value, list):
+        return None
+    flags = []
+    for item in value:
+        if not isinstance(item, dict):
+            return None
+        flag, meaning = item.get("flag"), item.get("meaning")
+        if not isinstance(flag, str) or not isinstance(meaning, str):
+            return None
+        flags.append((flag.strip(), meaning.strip()))
+    return flags
+
+
+def parse_explanation(response: str) -> Explanation:
+    """Parse a model's explanation.
+
+    Args:
+        response: Raw reply to :func:`build_explain_request`
+
+    Returns:
+        Structured explanation, or the reply as a plain summary if it does
+        not contain the expected JSON
+    """
+    fallback = Explanation(summary=response.strip())
+    match = JSON_OBJECT.search(response)
+    if not match:
+        return fallback
+    try:
+        data = json.loads(match.group(0))
+    except json.JSONDecodeError:
+        return fallback
+    if not isinstance(data, dict):
+        return fallback
+
+    summary = data.get("summary")
+    flags = _parse_flags(data.get("flags", []))
+    risk = data.get("risk")
+    if not isinstance(summary, str) or not summary.strip() or flags is None:
+        return fallback
+    if not isinstance(risk, str) or risk.strip().lower() in ("", "none"):
+        risk = None
+
+    return Explanation(
+        summary=summary.strip(),
+        flags=flags,
+        risk=risk.strip() if risk else None,
+        structured=True,
+    )
+
+
+def format_explanation(explanation: Explanation, use_color: bool = True) -> str:
+    """Render an explanation as terminal sections.
+
+    Args:
+        explanation: Result of :func:`parse_explanation`
+        use_color: Whether to use ANSI colors
+
+    Returns:
+        Text ready to print
+    """
+    if not explanation.structured:
+        return explanation.summary
+
+    def heading(text: str) -> str:
+        return colorize(text, Color.BOLD, use_color=use_color)
+
+    lines = [heading("What it does"), f"  {explanation.summary}"]
+    if explanation.flags:
+        width = max(len(flag) for flag, _ in explanation.flags)
+        lines.extend(["", heading("Flags")])
+        lines.extend(
+            f"  {colorize(flag.ljust(width), Color.CYAN, use_color=use_color)}  {meaning}"
+            for flag, meaning in explanation.flags
+        )
+    lines.extend(["", heading("Risk")])
+    if explanation.risk:
+        lines.append(f"  {colorize(explanation.risk, Color.YELLOW, use_color=use_color)}")
+    else:
+        lines.append("  None noted")
+    return "\n".join(lines)
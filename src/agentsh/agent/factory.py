@@ -1,15 +1,40 @@
 """Agent Factory - Creates and configures agent instances."""
 
 import asyncio
+import os
+import sys
 from typing import Any, Callable, Optional
 
-from agentsh.agent.agent_loop import AgentConfig, AgentContext, AgentLoop
+from agentsh.agent.agent_loop import (
+    EXIT_FAILED,
+    EXIT_SUCCESS,
+    AgentConfig,
+    AgentContext,
+    AgentLoop,
+)
+from agentsh.agent.alternatives import prompt_alternative
+from agentsh.agent.chat_export import export_chat
+from agentsh.agent.http_client import network_options
 from agentsh.agent.llm_client import LLMClient
 from agentsh.agent.providers.anthropic import AnthropicClient
+from agentsh.agent.providers.azure import AzureOpenAIClient
+from agentsh.agent.providers.gemini import GeminiClient
+from agentsh.agent.providers.mock import MockClient
+from agentsh.agent.providers.ollama import OllamaClient
 from agentsh.agent.providers.openai import OpenAIClient
-from agentsh.config.schemas import AgentSHConfig, LLMProvider
+from agentsh.agent.replan import prompt_failure_action
+from agentsh.agent.resilient import FailoverLLMClient
+from agentsh.config.schemas import AgentSHConfig, LLMProvider, OutputEncoding
 from agentsh.memory.manager import MemoryManager
-from agentsh.security.controller import SecurityController
+from agentsh.plugins.base import ToolsetRegistry
+from agentsh.plugins.loader import load_plugins
+from agentsh.security.approval import ApprovalFlow
+from agentsh.security.audit import AuditLogger
+from agentsh.security.check import policy_for_config
+from agentsh.security.classifier import RiskClassifier, RiskLevel
+from agentsh.security.controller import SecurityContext, SecurityController, ValidationResult
+from agentsh.security.policies import PolicyManager
+from agentsh.security.rbac import Role, User
 from agentsh.telemetry.logger import get_logger
 from agentsh.tools.registry import ToolRegistry
 from agentsh.workflows.executor import WorkflowExecutor
@@ -17,6 +42,19 @@ from agentsh.workflows.executor import WorkflowExecutor
 logger = get_logger(__name__)
 
 
+def _api_key(config: AgentSHConfig) -> Optional[str]:
+    """Read the API key from the variable named by llm.api_key_env.
+
+    Args:
+        config: AgentSH configuration
+
+    Returns:
+        The key, or None if the variable is unset so the client falls back
+        to its provider's usual variable (such as AZURE_OPENAI_API_KEY)
+    """
+    return os.environ.get(config.llm.api_key_env) or None
+
+
 def create_llm_client(config: AgentSHConfig) -> LLMClient:
     """Create an LLM client based on configuration.
 
@@ -29,22 +67,77 @@ def create_llm_client(config: AgentSHConfig) -> LLMClient:
     Raises:
         ValueError: If provider is not supported
     """
+    if config.llm.fallback is not None:
+        # Requests the primary cannot serve go to llm.fallback
+        primary_llm = config.llm.model_copy(update={"fallback": None})
+        primary = config.model_copy(update={"llm": primary_llm})
+        fallback = config.model_copy(update={"llm": config.llm.fallback})
+        return FailoverLLMClient(
+            create_llm_client(primary),
+            create_llm_client(fallback),
+            on_switch=lambda note: print(f"[{note}]", file=sys.stderr),
+        )
+
+    client_options = network_options(config.llm.socks_proxy, config.llm.ca_cert_path)
+
     if config.llm.provider == LLMProvider.ANTHROPIC:
         return AnthropicClient(
-            api_key=config.llm.api_key,
+            api_key=_api_key(config),
             model=config.llm.model,
-            timeout=config.llm.timeout,
+            timeout=config.llm.timeout_seconds,
+            client_options=client_options,
         )
     elif config.llm.provider == LLMProvider.OPENAI:
         return OpenAIClient(
-            api_key=config.llm.api_key,
+            api_key=_api_key(config),
+            model=config.llm.model,
+            timeout=config.llm.timeout_seconds,
+            client_options=client_options,
+        )
+    elif config.llm.provider == LLMProvider.AZURE:
+        return AzureOpenAIClient(
+            api_key=_api_key(config),
+            endpoint=config.llm.azure_endpoint,
+            deployment=config.llm.azure_deployment,
+            api_version=config.llm.azure_api_version,
+            model=config.llm.model,
+            timeout=config.llm.timeout_seconds,
+            client_options=client_options,
+        )
+    elif config.llm.provider == LLMProvider.GEMINI:
+        return GeminiClient(
+            api_key=_api_key(config),
             model=config.llm.model,
-            timeout=config.llm.timeout,
+            timeout=config.llm.timeout_seconds,
+            client_options=client_options,
         )
+    elif config.llm.provider == LLMProvider.OLLAMA:
+        # Local server from OLLAMA_HOST; local inference needs no API key
+        return OllamaClient(
+            model=config.llm.model,
+            timeout=config.llm.timeout_seconds,
+        )
+    elif config.llm.provider == LLMProvider.MOCK:
+        return MockClient(rules_path=config.llm.mock_responses_path)
     else:
         raise ValueError(f"Unsupported LLM provider: {config.llm.provider}")
 
 
+def _model_pricing(config: AgentSHConfig) -> Optional[tuple[float, float]]:
+    """Look up llm.model in llm.pricing.
+
+    Args:
+        config: AgentSH configuration
+
+    Returns:
+        USD per million (prompt, completion) tokens, or None if not priced
+    """
+    price = config.llm.pricing.get(config.llm.model)
+    if price is None:
+        return None
+    return price.input, price.output
+
+
 def create_agent_loop(
     config: AgentSHConfig,
     tool_registry: Optional[ToolRegistry] = None,
@@ -53,56 +146,162 @@ def create_agent_loop(
 
     Args:
         config: AgentSH configuration
-        tool_registry: Optional pre-configured tool registry
+        tool_registry: Optional pre-configured tool registry (default: the
+            tools of the enabled plugins)
 
     Returns:
         Configured AgentLoop
     """
     llm_client = create_llm_client(config)
+    security_controller = create_security_controller(config)
 
     if tool_registry is None:
         tool_registry = ToolRegistry()
-        # Register default tools (Phase 4)
-        # For now, create empty registry
+        load_plugins(
+            config,
+            tool_registry,
+            registry=ToolsetRegistry(),
+            confirm_cwd=_protected_cwd_confirmation(security_controller),
+        )
 
     agent_config = AgentConfig(
         max_steps=10,
         temperature=config.llm.temperature,
         max_tokens=config.llm.max_tokens,
         timeout=30.0,
+        max_info_rounds=config.llm.max_info_rounds,
+        max_tool_calls=config.llm.max_steps,
+        reject_excess_tool_calls=config.llm.reject_excess_steps,
+        domain_hint=config.llm.domain_hint,
+        max_piped_input_chars=config.llm.max_context_size,
+        min_request_interval_ms=config.llm.min_request_interval_ms,
+        reject_rapid_requests=config.llm.reject_rapid_requests,
+        total_timeout=float(config.llm.total_timeout_seconds),
+        max_replans=config.llm.max_replans,
+        strict_output=config.shell.output_encoding == OutputEncoding.STRICT,
+        history_window=config.llm.history_window,
+        history_window_mode=config.llm.history_window_mode.value,
+        history_results_chars=config.llm.history_results_chars,
+        parallel=config.shell.parallel,
+        max_parallel=config.shell.max_parallel,
+        context_files=config.llm.context_files,
+        context_file_extensions=config.llm.context_file_extensions,
+        context_file_max_lines=config.llm.context_file_max_lines,
+        show_plan_cost=config.llm.show_plan_cost,
+        pricing=_model_pricing(config),
+        plan_verbosity=config.shell.plan_verbosity.value,
+        workspace_root=(
+            str(config.security.workspace_root.expanduser())
+            if config.security.workspace_root
+            else None
+        ),
+        pre_exec_hook=config.security.pre_exec_hook,
+        pre_exec_hook_timeout=config.security.pre_exec_hook_timeout_seconds,
     )
 
     return AgentLoop(
         llm_client=llm_client,
         tool_registry=tool_registry,
         config=agent_config,
+        security_controller=security_controller,
     )
 
 
+def _protected_cwd_confirmation(controller: SecurityController) -> Callable[[str], bool]:
+    """Build the question asked before a shell step runs in a protected directory.
+
+    Args:
+        controller: Controller asking the user and auditing the answer
+
+    Returns:
+        Function taking the directory and returning whether to run there
+    """
+
+    def confirm(path: str) -> bool:
+        decision = controller.confirm_tool(
+            f"Run a command in {path}",
+            RiskLevel.HIGH,
+            SecurityContext(
+                user=User(id="agent", name="agent", role=Role.OPERATOR),
+                cwd=path,
+                interactive=sys.stdin is not None and sys.stdin.isatty(),
+            ),
+            reasons=[f"{path} is a protected system directory"],
+        )
+        return decision.result == ValidationResult.ALLOW
+
+    return confirm
+
+
+def _print_note(note: str) -> None:
+    """Show a progress note on stderr, bracketed unless it spans lines (a plan)."""
+    print(note if "\n" in note else f"[{note}]", file=sys.stderr)
+
+
 def create_ai_handler(config: AgentSHConfig) -> callable:
     """Create an AI handler function for the shell wrapper.
 
     This creates a synchronous handler that can be used with ShellWrapper.
+    The exit status of the most recent request is exposed as the handler's
+    ``last_status`` attribute (0 success, 1 failed, 130 cancelled), and the
+    prompt/response pair as ``last_exchange``. ``handler.redo()`` runs the
+    last executed plan again without calling the AI (``ai redo``),
+    ``handler.explain(command)`` asks the AI to explain a command without
+    tools or history (``ai explain``), ``handler.conversation`` holds
+    the agent's conversation history, and ``handler.session_id`` is the
+    audit log's session id.
+    With ``read_only=True`` (``ai ask``) every step that could change the
+    system is refused. ``cwd`` is the user's shell directory when it is
+    tracked (see agentsh.shell.hooks); it defaults to shell.cwd.
+    ``recent_output`` is the tail of the terminal output, shown to the AI
+    for ``ai fix`` requests, and ``last_exit_code`` the status of the
+    user's last shell command.
 
     Args:
         config: AgentSH configuration
 
     Returns:
-        Handler function that takes request string and returns response
+        Handler function that takes a request string (and optionally piped
+        stdin content, read_only, cwd, recent_output and last_exit_code) and
+        returns the response
     """
     agent = create_agent_loop(config)
 
-    def handler(request: str) -> str:
+    def handler(
+        request: str,
+        piped_input: str = "",
+        read_only: bool = False,
+        cwd: str = "",
+        recent_output: str = "",
+        last_exit_code: Optional[int] = None,
+    ) -> str:
         """Handle an AI request synchronously."""
         # Run the async agent in a new event loop
         try:
             loop = asyncio.new_event_loop()
             asyncio.set_event_loop(loop)
             try:
+                # Ask the user to decide only when someone can answer
+                interactive = sys.stdin is not None and sys.stdin.isatty()
                 context = AgentContext(
-                    cwd=str(config.shell.cwd) if hasattr(config.shell, 'cwd') else "",
+                    cwd=cwd or (str(config.shell.cwd) if hasattr(config.shell, 'cwd') else ""),
+                    piped_input=piped_input,
+                    read_only=read_only,
+                    recent_output=recent_output,
+                    last_exit_code=last_exit_code,
+                    interactive=interactive,
+                )
+                result = loop.run_until_complete(
+                    agent.invoke(
+                        request,
+                        context,
+                        on_step=_print_note,
+                        on_failure=prompt_failure_action if interactive else None,
+                        on_choose=prompt_alternative if interactive else None,
+                    )
                 )
-                result = loop.run_until_complete(agent.invoke(request, context))
+                handler.last_status = result.exit_status
+                handler.last_exchange = agent.last_exchange
 
                 if result.success:
                     return result.response
@@ -112,8 +311,45 @@ def create_ai_handler(config: AgentSHConfig) -> callable:
                 loop.close()
         except Exception as e:
             logger.error("AI handler error", error=str(e))
+            handler.last_status = EXIT_FAILED
             return f"AI Error: {str(e)}"
 
+    def redo() -> str:
+        """Run the last executed plan again, confirming each step anew."""
+        loop = asyncio.new_event_loop()
+        asyncio.set_event_loop(loop)
+        try:
+            context = AgentContext(
+                cwd=str(config.shell.cwd) if hasattr(config.shell, 'cwd') else "",
+            )
+            result = loop.run_until_complete(agent.redo(context))
+        finally:
+            loop.close()
+
+        handler.last_status = result.exit_status
+        if result.success:
+            return result.response
+        return f"Error: {result.error}\n\n{result.response}"
+
+    def explain(command: str) -> str:
+        """Ask the AI to explain a command; nothing runs and nothing is remembered."""
+        loop = asyncio.new_event_loop()
+        asyncio.set_event_loop(loop)
+        try:
+            return loop.run_until_complete(agent.explain(command))
+        finally:
+            loop.close()
+
+    handler.last_status = EXIT_SUCCESS
+    handler.last_exchange = None
+    handler.redo = redo
+    handler.explain = explain
+    handler.conversation = agent.conversation
+    handler.turns = agent.turns
+    handler.session_id = (
+        agent.security_controller.audit.session_id if agent.security_controller else None
+    )
+    handler.export_chat = lambda path: export_chat(agent.turns, path)
     return handler
 
 
@@ -141,6 +377,53 @@ async def create_async_ai_handler(config: AgentSHConfig) -> Callable:
     return handler
 
 
+def create_security_controller(config: AgentSHConfig) -> SecurityController:
+    """Create a security controller whose prompts follow the UI settings.
+
+    Args:
+        config: AgentSH configuration
+
+    Returns:
+        SecurityController protecting security.critical_services, with an
+        approval flow using shell.color and shell.redact_display, an audit log
+        following the security.audit_* and redaction settings,
+        security.auto_run_allowlist applied when security.allow_autonomous
+        is set, retyping for critical commands when
+        security.require_retype_for_critical is set, and every command that
+        is not read-only confirmed when security.confirm_all is set; commands
+        are matched against security.rules and deny_patterns, and policy
+        follows security.mode
+    """
+    security = config.security
+    return SecurityController(
+        policy_manager=PolicyManager(default_policy=policy_for_config(security)),
+        classifier=RiskClassifier(
+            critical_services=security.critical_services,
+            max_command_length=security.max_command_length,
+            reject_long_commands=security.reject_long_commands,
+            block_root_shells=security.block_root_shells,
+        ),
+        approval_flow=ApprovalFlow(
+            use_color=config.shell.color,
+            redact_display=config.shell.redact_display,
+        ),
+        audit_logger=AuditLogger(
+            log_path=security.audit_log_path.expanduser() if security.audit_log_path else None,
+            logged_events=security.logged_events,
+            redact_network=security.redact_network_identifiers,
+            redact_private_only=security.redact_private_only,
+        ),
+        auto_run_allowlist=(
+            security.auto_run_allowlist
+            if security.allow_autonomous and security.auto_run_allowlist
+            else None
+        ),
+        require_retype_for_critical=security.require_retype_for_critical,
+        confirm_all=security.confirm_all,
+        rules=security.effective_rules(),
+    )
+
+
 def create_memory_manager(
     config: AgentSHConfig,
     db_path: Optional[str] = None,
@@ -0,0 +1,263 @@
+"""File context - Project files included with every AI request.
+
+Files listed in ``llm.context_files`` (such as a README or a Makefile)
+are read relative to the working directory and added to the system
+prompt. Binary files are skipped, ``llm.context_file_extensions`` can
+restrict which file types are read, and each file is capped at
+``llm.context_file_max_lines`` lines so one large file cannot crowd out
+the rest of the prompt.
+
+A ``.agentshignore`` file at the project root (gitignore syntax) lists
+files that are never read into the AI's context, such as ``.env`` or
+``secrets.yaml``, even when they appear in ``llm.context_files``.
+"""
+
+import re
+from pathlib import Path
+from typing import Optional
+
+from agentsh.agent.project_notes import find_project_root
+from agentsh.plugins.builtin.filesystem import BINARY_SNIFF_BYTES, looks_binary
+from agentsh.telemetry.logger import get_logger
+
+logger = get_logger(__name__)
+
+# Default maximum lines included per file
+MAX_CONTEXT_FILE_LINES = 200
+
+# Lines longer than this (minified code, data) are shortened
+MAX_CONTEXT_LINE_CHARS = 500
+
+# Bytes read per file; the rest is never loaded
+MAX_CONTEXT_FILE_BYTES = 256 * 1024
+
+# Patterns of files kept out of the AI's context, at the project root
+IGNORE_FILE = ".agentshignore"
+
+
+def _glob_regex(glob: str) -> str:
+    """Translate a gitignore glob into a regex for "/"-separated paths."""
+    parts = []
+    i = 0
+    while i < len(glob):
+        if glob.startswith("**/", i):
+            parts.append("(?:.*/)?")
+            i += 3
+        elif glob.startswith("**", i):
+            parts.append(".*")
+            i += 2
+        elif glob[i] == "*":
+            parts.append("[^/]*")
+            i += 1
+        elif glob[i] == "?":
+            parts.append("[^/]")
+            i += 1
+        elif glob[i] == "[" and "]" in glob[i + 2 :]:
+            end = glob.index("]", i + 2)
+            body = glob[i + 1 : end]
+            if body.startswith("!"):
+                body = "^" + body[1:]
+            parts.append(f"[{body}]")
+            i = end + 1
+        elif glob[i] == "\\" and i + 1 < len(glob):
+            parts.append(re.escape(glob[i + 1]))
+            i += 2
+        else:
+            parts.append(re.escape(glob[i]))
+            i += 1
+    return "".join(parts)
+
+
+class IgnoreRules:
+    """Parsed ``.agentshignore`` patterns.
+
+    Follows gitignore rules: later patterns override earlier ones, ``!``
+    re-includes, a trailing ``/`` only matches directories, and a pattern
+    containing ``/`` is relative to the project root while one without
+    matches at any depth.
+
+    Example:
+        rules = IgnoreRules([".env", "secrets/", "!secrets/README.md"])
+        rules.ignored("config/.env")  # True
+    """
+
+    def __init__(self, patterns: list[str]) -> None:
+        """Parse patterns.
+
+        Args:
+            patterns: Lines of an ignore file (blank lines and # comments
+                are skipped)
+        """
+        self._rules: list[tuple[re.Pattern[str], bool, bool]] = []
+        for line in patterns:
+            pattern = line.rstrip()
+            if not pattern or pattern.startswith("#"):
+                continue
+            negate = pattern.startswith("!")
+            if negate:
+                pattern = pattern[1:]
+            dir_only = pattern.endswith("/")
+            pattern = pattern.rstrip("/")
+            anchored = "/" in pattern
+            pattern = pattern.lstrip("/")
+            if not pattern:
+                continue
+            prefix = "" if anchored else "(?:.*/)?"
+            self._rules.append((re.compile(prefix + _glob_regex(pattern)), negate, dir_only))
+
+    def __bool__(self) -> bool:
+        """Check whether any pattern was given."""
+        return bool(self._rules)
+
+    def _matches(self, path: str, is_dir: bool) -> bool:
+        """Apply the rules to one path, the last matching rule winning."""
+        ignored = False
+        for regex, negate, dir_only in self._rules:
+            if dir_only and not is_dir:
+                continue
+            if regex.fullmatch(path):
+                ignored = not negate
+        return ignored
+
+    def ignored(self, relative_path: str) -> bool:
+        """Check whether a file is ignored.
+
+        A file inside an ignored directory is ignored, as with git.
+
+        Args:
+            relative_path: File path relative to the project root
+
+        Returns:
+            True if the file must not be read into context
+        """
+        parts = Path(relative_path).as_posix().split("/")
+        for depth in range(1, len(parts)):
+            if self._matches("/".join(parts[:depth]), is_dir=True):
+                return True
+        return self._matches("/".join(parts), is_dir=False)
+
+
+def load_ignore_rules(root: Path) -> IgnoreRules:
+    """Read the project's ``.agentshignore``.
+
+    Args:
+        root: Project root directory
+
+    Returns:
+        Parsed rules (empty if the file is missing or unreadable)
+    """
+    try:
+        text = (root / IGNORE_FILE).read_text(encoding="utf-8", errors="replace")
+    except OSError:
+        return IgnoreRules([])
+    return IgnoreRules(text.splitlines())
+
+
+def extension_allowed(path: Path, allowed_extensions: Optional[list[str]]) -> bool:
+    """Check a file against the extension allowlist.
+
+    Args:
+        path: File to check
+        allowed_extensions: Extensions such as ".md" or "py" (None allows all)
+
+    Returns:
+        True if the file may be read
+    """
+    if allowed_extensions is None:
+        return True
+    allowed = {f".{ext.lower().lstrip('.')}" for ext in allowed_extensions}
+    return path.suffix.lower() in allowed
+
+
+def read_context_file(path: Path, max_lines: int = MAX_CONTEXT_FILE_LINES) -> Optional[str]:
+    """Read one context file as text.
+
+    Args:
+        path: File to read
+        max_lines: Keep at most this many lines
+
+    Returns:
+        The file's text, with a note when lines were dropped or shortened,
+        or None if the file is binary
+
+    Raises:
+        OSError: If the file cannot be read
+    """
+    with open(path, "rb") as f:
+        raw = f.read(MAX_CONTEXT_FILE_BYTES + 1)
+
+    if looks_binary(raw[:BINARY_SNIFF_BYTES]):
+        logger.debug("Skipping binary context file", path=str(path))
+        return None
+
+    partial = len(raw) > MAX_CONTEXT_FILE_BYTES
+    lines = raw[:MAX_CONTEXT_FILE_BYTES].decode("utf-8", errors="replace").splitlines()
+    if partial and lines:
+        # The last line was cut at the byte limit
+        lines.pop()
+
+    kept = [
+        line if len(line) <= MAX_CONTEXT_LINE_CHARS else f"{line[:MAX_CONTEXT_LINE_CHARS]}..."
+        for line in lines[:max_lines]
+    ]
+    text = "\n".join(kept)
+    if len(lines) > max_lines or partial:
+        text += f"\n(truncated to the first {len(kept)} lines)"
+    return text
+
+
+def _ignored(path: Path, root: Path, rules: IgnoreRules) -> bool:
+    """Check a file against the project's ignore rules.
+
+    Files outside the project root are not covered by its ignore file.
+    """
+    try:
+        relative = path.resolve().relative_to(root)
+    except ValueError:
+        return False
+    return rules.ignored(relative.as_posix())
+
+
+def read_file_context(
+    cwd: str,
+    paths: list[str],
+    allowed_extensions: Optional[list[str]] = None,
+    max_lines: int = MAX_CONTEXT_FILE_LINES,
+) -> Optional[str]:
+    """Read the configured context files for the system prompt.
+
+    Files matched by the project's ``.agentshignore`` are left out.
+
+    Args:
+        cwd: Directory relative paths are resolved against
+        paths: Files to include
+        allowed_extensions: Only read files with these extensions (None
+            reads any text file)
+        max_lines: Maximum lines included per file
+
+    Returns:
+        The files' contents under a header each, or None if none were read
+    """
+    root = find_project_root(cwd)
+    rules = load_ignore_rules(root)
+    sections = []
+    for name in paths:
+        path = Path(cwd, name).expanduser()
+        if not path.is_file():
+            continue
+        if rules and _ignored(path, root, rules):
+            logger.debug("Context file excluded by ignore file", path=str(path))
+            continue
+        if not extension_allowed(path, allowed_extensions):
+            logger.debug("Context file type not allowed", path=str(path))
+            continue
+        try:
+            text = read_context_file(path, max_lines)
+        except OSError as e:
+            logger.debug("Context file not readable", path=str(path), error=str(e))
+            continue
+        if text is None:
+            sections.append(f"--- {name} ---\n(binary file, not included)")
+            continue
+        sections.append(f"--- {name} ---\n{text}")
+    return "\n\n".join(sections) or None
@@ -0,0 +1,107 @@
+"""Conversation history - Earlier exchanges sent along with a new request.
+
+The agent remembers each answered request as a user/assistant message
+pair so follow-ups like "now do the same for staging" have context. Only
+a window of the most recent messages is sent, either a fixed number of
+messages (``llm.history_window``) or as many as fit in an estimated
+token budget (``llm.history_window_mode: tokens``). System messages are
+never part of the history; the system prompt is rebuilt for every request.
+
+When a request ran steps, a short, redacted summary of their results is
+added to the remembered answer (``llm.history_results_chars``), so a
+follow-up like "now why is it failing?" can refer to what the steps printed.
+"""
+
+from typing import TYPE_CHECKING, Callable, Optional
+
+from agentsh.agent.llm_client import Message, MessageRole
+from agentsh.utils.validators import redact_secrets
+
+if TYPE_CHECKING:
+    from agentsh.agent.agent_loop import StepRecord
+
+# Window modes
+WINDOW_MESSAGES = "messages"
+WINDOW_TOKENS = "tokens"
+
+# Last lines of each step's output kept in the results summary
+RESULT_TAIL_LINES = 5
+
+# Heading of the results summary added to a remembered answer
+RESULTS_HEADING = "Results of the steps run:"
+
+
+def estimate_tokens(text: str) -> int:
+    """Roughly estimate the tokens in a text (about 4 characters each)."""
+    return len(text) // 4 + 1
+
+
+def trim_history(
+    history: list[Message],
+    window: int,
+    mode: str = WINDOW_MESSAGES,
+    count_tokens: Optional[Callable[[str], int]] = None,
+) -> list[Message]:
+    """Select the most recent history that fits the window.
+
+    The result always starts with a user message, so an answer is never
+    sent without the question it answers.
+
+    Args:
+        history: Earlier messages, oldest first
+        window: Maximum messages, or estimated tokens in tokens mode
+            (0 or less sends no history)
+        mode: "messages" or "tokens"
+        count_tokens: Token counter for tokens mode (defaults to
+            :func:`estimate_tokens`)
+
+    Returns:
+        The newest messages within the window, oldest first
+    """
+    messages = [msg for msg in history if msg.role != MessageRole.SYSTEM]
+    if window <= 0:
+        return []
+
+    if mode == WINDOW_TOKENS:
+        count = count_tokens or estimate_tokens
+        kept = 0
+        used = 0
+        for msg in reversed(messages):
+            used += count(msg.content)
+            if used > window:
+                break
+            kept += 1
+        selected = messages[len(messages) - kept :] if kept else []
+    else:
+        selected = messages[-window:]
+
+    while selected and selected[0].role != MessageRole.USER:
+        selected = selected[1:]
+    return selected
+
+
+def summarize_step_results(steps: list["StepRecord"], max_chars: int) -> str:
+    """Summarize the results of a request's steps for the conversation history.
+
+    Each step is listed with its outcome and the last lines of its output.
+    Secrets are redacted, and the summary is cut to max_chars.
+
+    Args:
+        steps: Steps that ran, in order
+        max_chars: Maximum characters of the summary (0 or less disables)
+
+    Returns:
+        The summary, or an empty string if there is nothing to add
+    """
+    if not steps or max_chars <= 0:
+        return ""
+
+    lines = [RESULTS_HEADING]
+    for record in steps:
+        lines.append(f"- {record.step} [{record.outcome.value}]")
+        tail = record.output.strip().splitlines()[-RESULT_TAIL_LINES:]
+        lines.extend(f"    {line}" for line in tail)
+    summary = redact_secrets("\n".join(lines))
+    if len(summary) > max_chars:
+        summary = summary[: max(max_chars - 4, 0)].rstrip() + "\n..."
+    return summary
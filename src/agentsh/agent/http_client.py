@@ -5,15 +5,223 @@ timeouts, and retry configuration for efficient API communication.
 """
 
 import asyncio
+import json
+import logging
+import re
+from collections.abc import Iterable, Mapping
 from dataclasses import dataclass, field
+from pathlib import Path
 from typing import Any, Optional
 
 import httpx
 
-from agentsh.telemetry.logger import get_logger
+from agentsh.agent.llm_client import LLMNetworkError, LLMParseError, LLMTimeoutError
+from agentsh.telemetry.logger import current_request_id, get_logger
+from agentsh.utils.validators import load_ca_bundle, redact_secrets
 
 logger = get_logger(__name__)
 
+REDACTED = "***REDACTED***"
+
+# Headers that carry credentials, compared case-insensitively
+SENSITIVE_HEADERS = frozenset(
+    {"authorization", "proxy-authorization", "api-key", "x-api-key", "x-goog-api-key"}
+)
+
+BEARER_TOKEN = re.compile(r"(?i)\b(bearer\s+)[^\s\"',]+")
+
+# Characters of a non-JSON response body quoted in the error
+BODY_SNIPPET_CHARS = 200
+
+# Header carrying the AI request id, for matching audit entries to traces
+REQUEST_ID_HEADER = "X-Request-Id"
+
+
+def request_id_headers() -> dict[str, str]:
+    """Get the headers identifying the AI request being handled.
+
+    Returns:
+        ``{"X-Request-Id": id}`` while a request id is bound, else empty
+    """
+    request_id = current_request_id()
+    return {REQUEST_ID_HEADER: request_id} if request_id else {}
+
+
+def network_options(
+    socks_proxy: Optional[str] = None,
+    ca_cert_path: Optional[Path] = None,
+) -> dict[str, Any]:
+    """Build httpx client options for a proxy and a custom CA bundle.
+
+    Args:
+        socks_proxy: SOCKS5 proxy URL (llm.socks_proxy)
+        ca_cert_path: PEM bundle of trusted roots (llm.ca_cert_path)
+
+    Returns:
+        Keyword arguments for httpx.AsyncClient (empty when neither is set)
+
+    Raises:
+        ValueError: If the CA bundle cannot be loaded
+    """
+    options: dict[str, Any] = {}
+    if socks_proxy:
+        options["proxy"] = socks_proxy
+    if ca_cert_path:
+        options["verify"] = load_ca_bundle(ca_cert_path)
+    return options
+
+
+def redact_log_text(text: str, secrets: Iterable[Optional[str]] = ()) -> str:
+    """Remove credentials from text about to be logged.
+
+    Args:
+        text: Request or response text
+        secrets: Known secrets (e.g. the client's API key) to mask verbatim
+
+    Returns:
+        Text with the secrets, bearer tokens and other likely secrets masked
+    """
+    for secret in secrets:
+        if secret:
+            text = text.replace(secret, REDACTED)
+    text = BEARER_TOKEN.sub(rf"\g<1>{REDACTED}", text)
+    return redact_secrets(text, REDACTED)
+
+
+def redact_headers(
+    headers: Mapping[str, str],
+    secrets: Iterable[Optional[str]] = (),
+) -> dict[str, str]:
+    """Mask credential headers for logging.
+
+    Args:
+        headers: Request headers
+        secrets: Known secrets to mask in other headers
+
+    Returns:
+        Copy of the headers with credential values replaced
+    """
+    secrets = list(secrets)
+    return {
+        name: REDACTED if name.lower() in SENSITIVE_HEADERS else redact_log_text(value, secrets)
+        for name, value in headers.items()
+    }
+
+
+def trace_llm_request(
+    provider: str,
+    body: Any,
+    headers: Optional[Mapping[str, str]] = None,
+    secrets: Iterable[Optional[str]] = (),
+) -> None:
+    """Log the exact body sent to an LLM provider at DEBUG level.
+
+    Nothing is serialized unless DEBUG logging (``--debug``) is on.
+
+    Args:
+        provider: Provider name
+        body: Request body
+        headers: Request headers
+        secrets: Known secrets to mask
+    """
+    if not logging.getLogger(__name__).isEnabledFor(logging.DEBUG):
+        return
+    secrets = list(secrets)
+    logger.debug(
+        "LLM request",
+        provider=provider,
+        headers=redact_headers(headers or {}, secrets),
+        body=redact_log_text(json.dumps(body, default=str), secrets),
+    )
+
+
+def trace_llm_response(
+    provider: str,
+    body: Any,
+    secrets: Iterable[Optional[str]] = (),
+) -> None:
+    """Log the raw body received from an LLM provider at DEBUG level.
+
+    Args:
+        provider: Provider name
+        body: Raw response text, or an SDK response model
+        secrets: Known secrets to mask
+    """
+    if not logging.getLogger(__name__).isEnabledFor(logging.DEBUG):
+        return
+    if not isinstance(body, str):
+        body = body.model_dump_json() if hasattr(body, "model_dump_json") else str(body)
+    logger.debug("LLM response", provider=provider, body=redact_log_text(body, secrets))
+
+
+def classify_http_error(error: httpx.HTTPError) -> Exception:
+    """Map an httpx error to the matching LLM error type.
+
+    Timeouts become LLMTimeoutError and other transport failures become
+    LLMNetworkError. HTTP status errors are returned unchanged, since the
+    provider did respond.
+
+    Args:
+        error: Error raised by httpx
+
+    Returns:
+        Exception to raise in place of the httpx error
+    """
+    if isinstance(error, httpx.TimeoutException):
+        return LLMTimeoutError(str(error) or "Request timed out")
+    if isinstance(error, httpx.TransportError):
+        return LLMNetworkError(str(error) or "Network error")
+    return error
+
+
+def body_snippet(text: str, limit: int = BODY_SNIPPET_CHARS) -> str:
+    """Shorten a response body for an error message.
+
+    Whitespace runs are collapsed and secrets redacted.
+
+    Args:
+        text: Response body
+        limit: Maximum characters kept
+
+    Returns:
+        The start of the body, with "..." appended if it was cut
+    """
+    text = redact_log_text(" ".join(text.split()))
+    return text if len(text) <= limit else text[:limit] + "..."
+
+
+def parse_json_response(response: httpx.Response, provider: str = "AI provider") -> Any:
+    """Decode a provider response as JSON.
+
+    Args:
+        response: Successful HTTP response
+        provider: Provider name used in the error message
+
+    Returns:
+        The decoded JSON body
+
+    Raises:
+        LLMParseError: If the body is empty or not JSON (for example a
+            proxy login page), quoting the start of the body
+    """
+    try:
+        return response.json()
+    except ValueError:
+        text = response.text or ""
+
+    snippet = body_snippet(text)
+    if not snippet:
+        message = f"The {provider} endpoint returned an empty response instead of JSON."
+    elif re.match(r"(?i)<(?:!doctype\s+html|html|head|body)\b", snippet):
+        message = (
+            f"The {provider} endpoint returned an HTML page instead of JSON "
+            f"(a proxy login or error page?): {snippet}"
+        )
+    else:
+        message = f"The {provider} endpoint returned a response that is not JSON: {snippet}"
+    logger.error("LLM response is not JSON", provider=provider, body=snippet)
+    raise LLMParseError(message, snippet)
+
 
 @dataclass
 class HTTPClientConfig:
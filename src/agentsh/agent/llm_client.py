@@ -1,14 +1,22 @@
 """LLM Client Abstraction - Unified interface for LLM providers."""
 
 from abc import ABC, abstractmethod
+from contextlib import contextmanager
+from contextvars import ContextVar
 from dataclasses import dataclass, field
 from enum import Enum
-from typing import Any, AsyncIterator, Optional
+from typing import Any, AsyncIterator, Iterator, Optional
 
 from agentsh.telemetry.logger import get_logger
 
 logger = get_logger(__name__)
 
+# Request body field constraining OpenAI-compatible models to a JSON object
+JSON_RESPONSE_FORMAT = {"type": "json_object"}
+
+# Whether the request being handled expects a JSON object as the answer
+_json_response: ContextVar[bool] = ContextVar("json_response", default=False)
+
 
 class MessageRole(Enum):
     """Role of a message in the conversation."""
@@ -27,11 +35,20 @@ class ToolCall:
         id: Unique identifier for this tool call
         name: Name of the tool to call
         arguments: Tool arguments as a dictionary
+        step_id: Id the AI gave this step, referenced by other steps
+        depends_on: Ids of steps that must finish before this one
     """
 
     id: str
     name: str
     arguments: dict[str, Any]
+    step_id: Optional[str] = None
+    depends_on: list[str] = field(default_factory=list)
+
+    @property
+    def key(self) -> str:
+        """Id used to reference this step in depends_on."""
+        return self.step_id or self.id
 
 
 @dataclass
@@ -101,6 +118,48 @@ class Message:
         )
 
 
+def split_system_prompt(messages: list[Message]) -> tuple[str, list[Message]]:
+    """Separate system messages from the rest of a conversation.
+
+    Providers take the system prompt either as a top-level field
+    (Anthropic, Gemini) or as the first message (OpenAI-style APIs); in
+    neither case may it appear later in the conversation. All system
+    messages are joined so none is dropped.
+
+    Args:
+        messages: Conversation in order
+
+    Returns:
+        Tuple of (combined system prompt, other messages in order)
+    """
+    system = [msg.content for msg in messages if msg.role == MessageRole.SYSTEM]
+    rest = [msg for msg in messages if msg.role != MessageRole.SYSTEM]
+    return "\n\n".join(part for part in system if part), rest
+
+
+@contextmanager
+def json_response(enabled: bool = True) -> Iterator[None]:
+    """Ask for JSON answers from requests made in this block.
+
+    Providers with a JSON mode (OpenAI-compatible APIs and Ollama) then
+    constrain the model to emit a single valid JSON object; the others
+    ignore it, so callers must still parse leniently.
+
+    Args:
+        enabled: Whether to request JSON (llm.json_mode)
+    """
+    token = _json_response.set(enabled)
+    try:
+        yield
+    finally:
+        _json_response.reset(token)
+
+
+def json_response_requested() -> bool:
+    """Check whether the current request should be answered in JSON mode."""
+    return _json_response.get()
+
+
 @dataclass
 class ToolDefinition:
     """Definition of a tool for LLM consumption.
@@ -144,6 +203,18 @@ class ToolDefinition:
             },
         }
 
+    def to_gemini_format(self) -> dict[str, Any]:
+        """Convert to Gemini function declaration format."""
+        return {
+            "name": self.name,
+            "description": self.description,
+            "parameters": {
+                "type": "object",
+                "properties": self.parameters,
+                "required": self.required,
+            },
+        }
+
 
 class StopReason(Enum):
     """Reason why the LLM stopped generating."""
@@ -186,6 +257,120 @@ class LLMResponse:
         return self.input_tokens + self.output_tokens
 
 
+class LLMError(Exception):
+    """Base error for failed LLM provider requests.
+
+    Attributes:
+        user_message: Short explanation suitable for showing to the user
+    """
+
+    user_message = "The AI request failed."
+
+
+class LLMTimeoutError(LLMError, TimeoutError):
+    """The provider did not respond within the configured timeout."""
+
+    user_message = (
+        "The AI provider took too long to respond. Try again, or increase "
+        "llm.timeout_seconds in your config."
+    )
+
+
+class LLMAPIError(LLMError):
+    """The provider answered but reported an error or returned no result."""
+
+    def __init__(self, message: str) -> None:
+        """Initialize with the provider's explanation.
+
+        Args:
+            message: Explanation suitable for showing to the user
+        """
+        super().__init__(message)
+        self.user_message = message
+
+
+class LLMParseError(LLMError):
+    """The provider answered with a body that is not valid JSON.
+
+    Attributes:
+        snippet: Start of the body that was received
+    """
+
+    def __init__(self, message: str, snippet: str = "") -> None:
+        """Initialize with an explanation and the start of the body.
+
+        Args:
+            message: Explanation suitable for showing to the user
+            snippet: Start of the body that was received
+        """
+        super().__init__(message)
+        self.user_message = message
+        self.snippet = snippet
+
+
+class LLMTruncatedError(LLMParseError):
+    """A response ended in the middle of a JSON object."""
+
+    user_message = "The AI response was cut off before it finished. Try again."
+
+    def __init__(self, snippet: str = "") -> None:
+        """Initialize with the end of the cut-off response.
+
+        Args:
+            snippet: Last part of the response that was received
+        """
+        super().__init__(self.user_message, snippet)
+
+
+class LLMNetworkError(LLMError, ConnectionError):
+    """The provider could not be reached."""
+
+    user_message = (
+        "Could not reach the AI provider. Check your network connection "
+        "and endpoint settings."
+    )
+
+
+def json_truncated(text: str) -> bool:
+    """Check whether a JSON reply stops before its object is closed.
+
+    Only replies that start with a JSON object (optionally inside a code
+    fence) are checked, so prose mentioning braces is never flagged.
+
+    Args:
+        text: Complete response text
+
+    Returns:
+        True if the reply opens a JSON object that never closes
+    """
+    stripped = text.lstrip()
+    if not stripped.startswith(("{", "```")):
+        return False
+    start = stripped.find("{")
+    if start < 0:
+        return False
+
+    depth = 0
+    in_string = escaped = False
+    for char in stripped[start:]:
+        if in_string:
+            if escaped:
+                escaped = False
+            elif char == "\\":
+                escaped = True
+            elif char == '"':
+                in_string = False
+        elif char == '"':
+            in_string = True
+        elif char in "{[":
+            depth += 1
+        elif char in "}]":
+            depth -= 1
+            if depth == 0:
+                return False
+    return True
+
+
 class LLMClient(ABC):
     """Abstract base class for LLM clients.
 
@@ -253,6 +438,17 @@ class LLMClient(ABC):
         """
         ...
 
+    async def list_models(self) -> list[Any]:
+        """List the models available from the provider.
+
+        Providers without a models endpoint keep this default.
+
+        Returns:
+            Model names or model info dictionaries (with an "id" or "name"
+            key), or an empty list if listing is unsupported or failed
+        """
+        return []
+
     def count_tokens(self, text: str) -> int:
         """Estimate token count for text.
 
@@ -0,0 +1,98 @@
+"""Model check - Warn at startup when the configured model looks wrong.
+
+A typo in ``llm.model`` (``gpt4`` instead of ``gpt-4``) otherwise only
+shows up as a confusing 404 on the first request. With
+``llm.validate_model`` enabled, AgentSH asks the provider for its model
+list at startup and warns if the configured model is not in it. Providers
+that cannot list models, and failures such as having no network, skip
+the check silently.
+"""
+
+import asyncio
+import difflib
+from typing import Any, Optional
+
+from agentsh.agent.llm_client import LLMClient
+from agentsh.telemetry.logger import get_logger
+
+logger = get_logger(__name__)
+
+# Seconds to wait for the models endpoint before giving up
+LIST_MODELS_TIMEOUT = 5.0
+
+
+def model_ids(models: list[Any]) -> list[str]:
+    """Extract model identifiers from a models-list response.
+
+    Args:
+        models: Model names, or info dictionaries keyed by "id" (OpenAI
+            style) or "name"
+
+    Returns:
+        Identifiers in response order
+    """
+    ids = []
+    for model in models:
+        model_id = (model.get("id") or model.get("name")) if isinstance(model, dict) else model
+        if model_id:
+            ids.append(str(model_id))
+    return ids
+
+
+def is_known_model(model: str, ids: list[str]) -> bool:
+    """Check whether a model is in a provider's model list.
+
+    A name without a tag also matches its ``:latest`` variant, as Ollama
+    resolves it that way.
+
+    Args:
+        model: Configured model name
+        ids: Identifiers from the provider
+
+    Returns:
+        True if the model is listed
+    """
+    return model in ids or f"{model}:latest" in ids
+
+
+def model_warning(model: str, ids: list[str]) -> Optional[str]:
+    """Build a warning for a model missing from the provider's list.
+
+    Args:
+        model: Configured model name
+        ids: Identifiers from the provider
+
+    Returns:
+        Warning text, or None if the model is listed or the list is empty
+    """
+    if not ids or is_known_model(model, ids):
+        return None
+
+    warning = f"Model '{model}' is not offered by the provider"
+    suggestions = difflib.get_close_matches(model, ids, n=3)
+    if suggestions:
+        warning += f" (did you mean {', '.join(suggestions)}?)"
+    return warning
+
+
+async def check_model(
+    client: LLMClient,
+    timeout: float = LIST_MODELS_TIMEOUT,
+) -> Optional[str]:
+    """Check the client's model against the provider's model list.
+
+    Args:
+        client: LLM client to check
+        timeout: Seconds to wait for the model list
+
+    Returns:
+        Warning text, or None if the model is listed or the check could
+        not be done
+    """
+    try:
+        models = await asyncio.wait_for(client.list_models(), timeout=timeout)
+    except Exception as e:
+        logger.debug("Model validation skipped", provider=client.provider, error=str(e))
+        return None
+
+    return model_warning(client.model, model_ids(models))
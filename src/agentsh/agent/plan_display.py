@@ -0,0 +1,76 @@
+"""Plan display - Show the steps the AI proposes before they run.
+
+How much is shown per step follows ``shell.plan_verbosity``:
+
+- ``minimal``: the numbered commands only
+- ``normal``: each step's description and safety flags as well
+- ``verbose``: the risk warnings and the previewed effects as well
+
+Secrets in commands are always masked; the commands that run are not
+changed.
+"""
+
+from typing import Optional
+
+from agentsh.agent.llm_client import ToolCall
+from agentsh.agent.replan import describe_step
+from agentsh.security.classifier import RiskClassifier
+from agentsh.security.effects import preview_effects
+from agentsh.utils.validators import redact_secrets
+
+# Verbosity levels
+VERBOSITY_MINIMAL = "minimal"
+VERBOSITY_NORMAL = "normal"
+VERBOSITY_VERBOSE = "verbose"
+
+# Reason given by the classifier when nothing matched, not worth showing
+NO_RISK_REASON = "No known risk patterns"
+
+
+def format_plan(
+    tool_calls: list[ToolCall],
+    verbosity: str = VERBOSITY_NORMAL,
+    classifier: Optional[RiskClassifier] = None,
+    cwd: Optional[str] = None,
+) -> str:
+    """Format the proposed steps of a plan for the user.
+
+    Args:
+        tool_calls: Proposed steps, in order
+        verbosity: "minimal", "normal" or "verbose"
+        classifier: Classifier used for the flags and warnings of
+            shell steps
+        cwd: Directory the steps will run in, for the effects preview
+
+    Returns:
+        Multi-line plan, one numbered entry per step
+    """
+    classifier = classifier or RiskClassifier()
+    lines = ["Plan:"]
+
+    for number, call in enumerate(tool_calls, start=1):
+        lines.append(f"  {number}. {redact_secrets(describe_step(call))}")
+        if verbosity == VERBOSITY_MINIMAL:
+            continue
+
+        indent = " " * (len(str(number)) + 4)
+        description = call.arguments.get("description")
+        if isinstance(description, str) and description.strip():
+            lines.append(f"{indent}{description.strip()}")
+
+        command = call.arguments.get("command")
+        if not isinstance(command, str) or not command:
+            continue
+        assessment = classifier.classify(command)
+        flags = assessment.safety_flags()
+        if flags:
+            lines.append(f"{indent}Flags: {', '.join(flags)}")
+
+        if verbosity == VERBOSITY_VERBOSE:
+            for reason in assessment.reasons:
+                if reason != NO_RISK_REASON:
+                    lines.append(f"{indent}Warning: {reason}")
+            for effect in preview_effects(command, cwd):
+                lines.append(f"{indent}Effect: {effect}")
+
+    return "\n".join(lines)
@@ -0,0 +1,124 @@
+"""Plan export - Write the AI's proposed commands as a reviewable script.
+
+Instead of running a plan, ``agentsh -x REQUEST --export plan.sh`` asks the
+AI for its tool calls and writes the shell steps to a bash script. Each
+step is preceded by its description; high-risk steps are wrapped in a
+``read`` prompt so running the script never performs them silently.
+"""
+
+import shlex
+from dataclasses import dataclass
+from datetime import datetime
+from typing import Optional
+
+from agentsh import __version__
+from agentsh.agent.llm_client import ToolCall
+from agentsh.security.classifier import RiskClassifier, RiskLevel
+
+# Tools whose "command" argument is a shell command line
+SHELL_TOOLS = ("shell.run",)
+
+
+@dataclass
+class PlanStep:
+    """A single exported plan step.
+
+    Attributes:
+        command: Shell command, or None for a step that is not a command
+        description: What the step does
+        guarded: Whether the script should ask before running it
+    """
+
+    command: Optional[str]
+    description: str
+    guarded: bool = False
+
+
+def steps_from_tool_calls(
+    tool_calls: list[ToolCall],
+    classifier: Optional[RiskClassifier] = None,
+) -> list[PlanStep]:
+    """Convert proposed tool calls to plan steps.
+
+    Args:
+        tool_calls: Tool calls from the AI, in order
+        classifier: Risk classifier used to decide which steps to guard
+
+    Returns:
+        Plan steps. Non-shell tools become comment-only steps.
+    """
+    classifier = classifier or RiskClassifier()
+    steps: list[PlanStep] = []
+
+    for call in tool_calls:
+        command = call.arguments.get("command") if call.name in SHELL_TOOLS else None
+        if not command:
+            args = ", ".join(f"{k}={v!r}" for k, v in call.arguments.items())
+            steps.append(
+                PlanStep(
+                    command=None,
+                    description=f"{call.name}({args}) is not a shell command; skipped",
+                )
+            )
+            continue
+
+        cwd = call.arguments.get("cwd")
+        if cwd:
+            command = f"(cd {shlex.quote(cwd)} && {command})"
+
+        assessment = classifier.classify(command)
+        steps.append(
+            PlanStep(
+                command=command,
+                description=call.arguments.get("description") or f"Run {call.name}",
+                guarded=(
+                    assessment.risk_level >= RiskLevel.HIGH
+                    or bool(call.arguments.get("is_destructive"))
+                ),
+            )
+        )
+
+    return steps
+
+
+def export_script(steps: list[PlanStep], request: str) -> str:
+    """Render plan steps as a bash script.
+
+    Args:
+        steps: Steps to export
+        request: The request the plan answers, recorded in the header
+
+    Returns:
+        Script text
+    """
+    lines = [
+        "#!/usr/bin/env bash",
+        f"# Generated by AgentSH {__version__} on {datetime.now().strftime('%Y-%m-%d %H:%M')}",
+    ]
+    lines.extend(f"# Request: {line}" for line in request.splitlines() or [""])
+    lines.extend(["#", "# Review every step before running this script.", ""])
+    lines.extend(["set -euo pipefail", ""])
+
+    for number, step in enumerate(steps, start=1):
+        for i, line in enumerate(step.description.splitlines() or [""]):
+            prefix = f"# Step {number}: " if i == 0 else "#   "
+            lines.append(f"{prefix}{line}")
+
+        if step.command is None:
+            lines.append("")
+            continue
+
+        if step.guarded:
+            question = shlex.quote(f"Run high-risk step {number}? [y/N] ")
+            lines.append(f"read -r -p {question} reply")
+            lines.append('if [[ "$reply" =~ ^[Yy]$ ]]; then')
+            # Multi-line commands are left unindented so heredocs still work
+            lines.append(step.command if "\n" in step.command else f"    {step.command}")
+            lines.append("else")
+            lines.append(f'    echo "Skipped step {number}"')
+            lines.append("fi")
+        else:
+            lines.append(step.command)
+        lines.append("")
+
+    return "\n".join(lines)
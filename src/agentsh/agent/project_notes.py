@@ -0,0 +1,100 @@
+"""Project notes - Facts about a project that persist across sessions.
+
+Notes live in ``.agentsh/notes.md`` at the project root, next to the
+optional ``.agentsh.yaml`` project config. Users can edit the file by
+hand, and the AI records what it learns with the ``notes.append`` tool.
+The newest part of the file is included in the system prompt, bounded so
+a long file cannot crowd out the request.
+"""
+
+from datetime import date
+from pathlib import Path
+from typing import Optional
+
+# Notes file, relative to the project root
+NOTES_PATH = Path(".agentsh") / "notes.md"
+
+# Entries marking a directory as a project root
+PROJECT_MARKERS = (".agentsh", ".agentsh.yaml", ".git")
+
+# Default maximum characters of notes included in the system prompt
+MAX_NOTES_CHARS = 4000
+
+
+def find_project_root(cwd: str) -> Path:
+    """Find the root of the project containing a directory.
+
+    The search stops below the home directory: ``~/.agentsh`` holds the
+    user configuration, and a project inside $HOME must not fall back to
+    $HOME as its root and share its notes and ignore file.
+
+    Args:
+        cwd: Directory to start from
+
+    Returns:
+        Nearest ancestor below $HOME holding .agentsh, .agentsh.yaml or
+        .git, else cwd
+    """
+    start = Path(cwd).resolve()
+    home = Path.home().resolve()
+    for directory in (start, *start.parents):
+        if directory == home:
+            break
+        if any((directory / marker).exists() for marker in PROJECT_MARKERS):
+            return directory
+    return start
+
+
+def notes_file(cwd: str) -> Path:
+    """Get the notes file for the project containing a directory."""
+    return find_project_root(cwd) / NOTES_PATH
+
+
+def read_notes(cwd: str, max_chars: int = MAX_NOTES_CHARS) -> Optional[str]:
+    """Read the project notes for the system prompt.
+
+    Args:
+        cwd: Directory inside the project
+        max_chars: Keep at most this many characters, from the end of the
+            file where the newest notes are (0 disables notes)
+
+    Returns:
+        Notes text, or None if there are none
+    """
+    if max_chars <= 0:
+        return None
+    try:
+        text = notes_file(cwd).read_text(encoding="utf-8", errors="replace").strip()
+    except OSError:
+        return None
+    if not text:
+        return None
+    if len(text) > max_chars:
+        text = f"(older notes omitted)\n{text[-max_chars:].lstrip()}"
+    return text
+
+
+def append_note(cwd: str, note: str) -> Path:
+    """Append a note to the project's notes file.
+
+    Args:
+        cwd: Directory inside the project
+        note: Fact to remember (one line)
+
+    Returns:
+        Path of the notes file
+
+    Raises:
+        ValueError: If the note is empty
+    """
+    note = " ".join(note.split())
+    if not note:
+        raise ValueError("Empty note")
+
+    path = notes_file(cwd)
+    path.parent.mkdir(parents=True, exist_ok=True)
+    with path.open("a", encoding="utf-8") as f:
+        if path.stat().st_size == 0:
+            f.write("# Project notes\n\n")
+        f.write(f"- {note} ({date.today().isoformat()})\n")
+    return path
@@ -2,14 +2,26 @@
 
 import os
 import platform
+import re
+import socket
 from datetime import datetime
 from pathlib import Path
 from typing import Optional
 
 from agentsh.telemetry.logger import get_logger
+from agentsh.utils.validators import redact_secrets
 
 logger = get_logger(__name__)
 
+# Host names that say nothing about which machine this is
+UNINFORMATIVE_HOSTNAMES = frozenset({"localhost", "localhost.localdomain", "localhost6"})
+
+# Longest host name DNS allows
+MAX_HOSTNAME_LENGTH = 253
+
+# Requests that get the recent terminal output as context ("ai fix ...")
+FIX_REQUEST = re.compile(r"\s*fix\b", re.IGNORECASE)
+
 
 # Core system prompt template
 SYSTEM_PROMPT_TEMPLATE = """You are AgentSH, an AI-powered shell assistant that helps users accomplish tasks in their terminal.
@@ -72,6 +84,31 @@ When using tools:
 """
 
 
+# Files whose presence in the working directory hints at the project type
+PROJECT_MARKERS: list[tuple[str, str]] = [
+    ("Cargo.toml", "Rust project"),
+    ("package.json", "Node.js"),
+    ("docker-compose.yml", "Docker"),
+    ("docker-compose.yaml", "Docker"),
+]
+
+
+def detect_domain_hints(cwd: str) -> list[str]:
+    """Detect project types from marker files in a directory.
+
+    Args:
+        cwd: Directory to inspect
+
+    Returns:
+        Unique hints in marker order, e.g. ["Rust project", "Docker"]
+    """
+    hints: list[str] = []
+    for marker, hint in PROJECT_MARKERS:
+        if hint not in hints and (Path(cwd) / marker).is_file():
+            hints.append(hint)
+    return hints
+
+
 # Few-shot examples for better task understanding
 FEW_SHOT_EXAMPLES = [
     {
@@ -119,12 +156,56 @@ Would you like me to install it for you?""",
 ]
 
 
+def is_fix_request(request: str) -> bool:
+    """Check whether a request asks to fix something (``ai fix ...``).
+
+    Args:
+        request: User's request
+
+    Returns:
+        True if the request starts with "fix"
+    """
+    return bool(FIX_REQUEST.match(request))
+
+
+def prompt_hostname(hostname: Optional[str] = None) -> Optional[str]:
+    """Get the host name to tell the AI about, if it is meaningful.
+
+    Characters not valid in a host name are dropped, so a crafted name
+    cannot inject text into the prompt.
+
+    Args:
+        hostname: Host name (defaults to this machine's)
+
+    Returns:
+        The sanitized host name, or None if it is empty or just localhost
+    """
+    if hostname is None:
+        try:
+            hostname = socket.gethostname()
+        except OSError:
+            return None
+    name = re.sub(r"[^A-Za-z0-9._-]", "", hostname)[:MAX_HOSTNAME_LENGTH].strip(".")
+    if not name or name.lower() in UNINFORMATIVE_HOSTNAMES:
+        return None
+    return name
+
+
 def build_system_prompt(
     available_tools: list[str],
     cwd: Optional[str] = None,
     os_info: Optional[str] = None,
     shell: Optional[str] = None,
+    hostname: Optional[str] = None,
     recent_history: Optional[list[str]] = None,
+    domain_hint: Optional[str] = None,
+    detect_domain: bool = True,
+    last_exit_code: Optional[int] = None,
+    recent_output: Optional[str] = None,
+    project_notes: Optional[str] = None,
+    file_context: Optional[str] = None,
+    offer_alternatives: bool = False,
+    read_only: bool = False,
 ) -> str:
     """Build the complete system prompt with context.
 
@@ -133,7 +214,19 @@ def build_system_prompt(
         cwd: Current working directory
         os_info: Operating system information
         shell: User's shell (bash, zsh, etc.)
+        hostname: Machine the commands run on (defaults to this machine's;
+            left out when it is just localhost)
         recent_history: Recent command history
+        domain_hint: Free-form description of the user's domain
+        detect_domain: Append hints detected from project files in cwd
+        last_exit_code: Exit status of the user's last shell command
+        recent_output: Tail of the terminal output, for fix requests
+            (secrets are redacted)
+        project_notes: Contents of the project's .agentsh/notes.md
+        file_context: Contents of the files listed in llm.context_files
+        offer_alternatives: Tell the AI it may offer several approaches
+            with the offer_alternatives tool
+        read_only: Tell the AI to use only commands that change nothing
 
     Returns:
         Complete system prompt string
@@ -154,23 +247,87 @@ def build_system_prompt(
     shell = shell or os.environ.get("SHELL", "unknown")
     context_parts.append(f"Shell: {Path(shell).name if shell else 'unknown'}")
 
+    # Host, so multi-host workflows know which machine this is
+    host = prompt_hostname(hostname)
+    if host:
+        context_parts.append(f"Host: {host}")
+
+    # Domain hints (explicit first, then auto-detected)
+    hints = [domain_hint.strip()] if domain_hint and domain_hint.strip() else []
+    if detect_domain:
+        hints.extend(h for h in detect_domain_hints(cwd) if h not in hints)
+    if hints:
+        context_parts.append(f"Domain: {'; '.join(hints)}")
+
     # Date/time
     context_parts.append(f"Current time: {datetime.now().strftime('%Y-%m-%d %H:%M')}")
 
+    # Last command status, so "fix" requests know something failed
+    if last_exit_code is not None:
+        context_parts.append(f"Last command exit status: {last_exit_code}")
+
+    # What the user saw on screen, so "fix" requests can see the error
+    if recent_output and recent_output.strip():
+        context_parts.append(
+            f"Recent terminal output:\n```\n{redact_secrets(recent_output.strip())}\n```"
+        )
+
     # Recent history
     if recent_history:
         history_str = "\n".join(f"  - {cmd}" for cmd in recent_history[-5:])
         context_parts.append(f"Recent commands:\n{history_str}")
 
+    # Facts recorded for this project (notes.append)
+    if project_notes:
+        context_parts.append(f"Project notes:\n{project_notes}")
+
+    # Files the user asked to include with every request
+    if file_context:
+        context_parts.append(f"Project files:\n{file_context}")
+
     context = "\n".join(context_parts)
 
     # Build tools section
     tools_str = "\n".join(f"- {tool}" for tool in available_tools) if available_tools else "No tools available."
 
-    return SYSTEM_PROMPT_TEMPLATE.format(
+    prompt = SYSTEM_PROMPT_TEMPLATE.format(
         context=context,
         available_tools=tools_str,
     )
+    if offer_alternatives:
+        prompt += ALTERNATIVES_PROMPT
+    if read_only:
+        prompt += READ_ONLY_PROMPT
+    return prompt
+
+
+def build_user_message(
+    request: str,
+    piped_input: Optional[str] = None,
+    max_piped_chars: Optional[int] = None,
+) -> str:
+    """Build the user message for a request.
+
+    Args:
+        request: User's request
+        piped_input: Content piped to agentsh on stdin, if any
+        max_piped_chars: Keep at most this many characters of piped input
+
+    Returns:
+        The request, followed by the piped input in a fenced block, with
+        secrets in the input redacted
+    """
+    if not piped_input or not piped_input.strip():
+        return request
+
+    text = piped_input
+    note = ""
+    if max_piped_chars is not None and len(text) > max_piped_chars:
+        note = f"\n(input truncated to the first {max_piped_chars} characters)"
+        text = text[:max_piped_chars]
+    text = redact_secrets(text)
+
+    return f"{request}\n\nInput piped from stdin:\n```\n{text.rstrip()}\n```{note}"
 
 
 def build_few_shot_messages() -> list[dict[str, str]]:
@@ -219,3 +376,48 @@ Principles:
 - Document significant changes
 
 Always suggest running tests after refactoring."""
+
+
+EXPLAIN_PROMPT = """Explain the following shell command. Do not run it.
+
+Command: {command}
+
+Reply with only a JSON object of this shape:
+{{"summary": "<one line: what the command does>",
+ "flags": [{{"flag": "<flag or argument>", "meaning": "<what it does here>"}}],
+ "risk": "<one line: what could go wrong, or \\"none\\">"}}"""
+
+
+ALTERNATIVES_PROMPT = """
+## Alternatives
+
+When a request is open-ended and there are clearly different ways to do it
+(for example a quick fix versus a thorough one), you may call
+offer_alternatives with two to four named plans instead of running one.
+The user picks a plan and its commands are run for you. For requests with
+one obvious approach, just run it.
+"""
+
+
+READ_ONLY_PROMPT = """
+## Read-Only Query
+
+This request is a read-only query. Only use commands and tools that inspect
+the system without changing it (listing, reading, searching, status checks).
+Do not create, modify, move or delete anything, install packages, or restart
+services; steps that could change something are refused. If the request
+cannot be answered without changes, say which commands would be needed
+instead of running them.
+"""
+
+
+REPLAN_PROMPT = """A step of your plan failed and the user asked you to change approach.
+
+Failed step: {step}
+Output:
+{output}
+
+Steps not run:
+{remaining}
+
+Propose a revised plan for the original request that takes this failure into account."""
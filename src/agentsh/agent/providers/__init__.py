@@ -3,12 +3,18 @@
 Supported providers:
 - AnthropicClient: Claude models via Anthropic API
 - OpenAIClient: GPT models via OpenAI API
+- AzureOpenAIClient: GPT models via Azure OpenAI deployments
+- GeminiClient: Gemini models via Google generateContent API
 - OllamaClient: Local models via Ollama
 - LiteLLMClient: 100+ models via LiteLLM (unified interface)
 - OpenRouterClient: 200+ models via OpenRouter API
+- MockClient: Canned offline responses for testing and demos
 """
 
 from agentsh.agent.providers.anthropic import AnthropicClient
+from agentsh.agent.providers.azure import AzureOpenAIClient
+from agentsh.agent.providers.gemini import GeminiClient
+from agentsh.agent.providers.mock import MockClient
 from agentsh.agent.providers.ollama import OllamaClient
 from agentsh.agent.providers.openai import OpenAIClient
 from agentsh.agent.providers.openrouter import OpenRouterClient
@@ -25,9 +31,12 @@ except ImportError:
 __all__ = [
     "AnthropicClient",
     "OpenAIClient",
+    "AzureOpenAIClient",
+    "GeminiClient",
     "OllamaClient",
     "OpenRouterClient",
     "LiteLLMClient",
+    "MockClient",
 ]
 
 
@@ -35,7 +44,8 @@ def get_client(provider: str, **kwargs):
     """Factory function to get an LLM client by provider name.
 
     Args:
-        provider: Provider name (anthropic, openai, ollama, openrouter, litellm)
+        provider: Provider name (anthropic, openai, azure, gemini, ollama, openrouter,
+            litellm, mock)
         **kwargs: Provider-specific configuration
 
     Returns:
@@ -49,8 +59,11 @@ def get_client(provider: str, **kwargs):
     providers = {
         "anthropic": AnthropicClient,
         "openai": OpenAIClient,
+        "azure": AzureOpenAIClient,
+        "gemini": GeminiClient,
         "ollama": OllamaClient,
         "openrouter": OpenRouterClient,
+        "mock": MockClient,
     }
 
     if _LITELLM_AVAILABLE:
@@ -5,15 +5,24 @@ import os
 from typing import Any, AsyncIterator, Optional
 
 import anthropic
+import httpx
 
+from agentsh.agent.http_client import (
+    request_id_headers,
+    trace_llm_request,
+    trace_llm_response,
+)
 from agentsh.agent.llm_client import (
     LLMClient,
+    LLMNetworkError,
     LLMResponse,
+    LLMTimeoutError,
     Message,
     MessageRole,
     StopReason,
     ToolCall,
     ToolDefinition,
+    split_system_prompt,
 )
 from agentsh.telemetry.logger import get_logger
 
@@ -40,6 +49,7 @@ class AnthropicClient(LLMClient):
         model: str = "claude-sonnet-4-20250514",
         max_retries: int = 3,
         timeout: float = 60.0,
+        client_options: Optional[dict[str, Any]] = None,
     ) -> None:
         """Initialize the Anthropic client.
 
@@ -48,6 +58,8 @@ class AnthropicClient(LLMClient):
             model: Model to use (default: claude-sonnet-4-20250514)
             max_retries: Number of retries on transient errors
             timeout: Request timeout in seconds
+            client_options: httpx client options such as a proxy or CA
+                bundle (see agentsh.agent.http_client.network_options)
         """
         self._api_key = api_key or os.environ.get("ANTHROPIC_API_KEY", "")
         self._model = model
@@ -61,6 +73,7 @@ class AnthropicClient(LLMClient):
             api_key=self._api_key,
             max_retries=max_retries,
             timeout=timeout,
+            **({"http_client": httpx.AsyncClient(**client_options)} if client_options else {}),
         )
 
         logger.info(
@@ -121,9 +134,22 @@ class AnthropicClient(LLMClient):
             has_tools=bool(tools),
         )
 
+        secrets = [self._api_key]
+        headers = request_id_headers()
+        trace_llm_request(self.provider, kwargs, headers, secrets)
+        if headers:
+            kwargs["extra_headers"] = headers
+
         try:
             response = await self._client.messages.create(**kwargs)
+            trace_llm_response(self.provider, response, secrets)
             return self._parse_response(response)
+        except anthropic.APITimeoutError as e:
+            logger.error("Anthropic API timeout", error=str(e))
+            raise LLMTimeoutError(str(e) or "Request timed out") from e
+        except anthropic.APIConnectionError as e:
+            logger.error("Anthropic API connection error", error=str(e))
+            raise LLMNetworkError(str(e) or "Network error") from e
         except anthropic.APIError as e:
             logger.error("Anthropic API error", error=str(e))
             raise
@@ -176,14 +202,12 @@ class AnthropicClient(LLMClient):
         Returns:
             Tuple of (system_prompt, anthropic_messages)
         """
-        system_prompt = ""
+        # Anthropic takes the system prompt as a separate parameter
+        system_prompt, conversation = split_system_prompt(messages)
         anthropic_messages: list[dict[str, Any]] = []
 
-        for msg in messages:
-            if msg.role == MessageRole.SYSTEM:
-                # Anthropic uses a separate system parameter
-                system_prompt = msg.content
-            elif msg.role == MessageRole.USER:
+        for msg in conversation:
+            if msg.role == MessageRole.USER:
                 anthropic_messages.append({"role": "user", "content": msg.content})
             elif msg.role == MessageRole.ASSISTANT:
                 if msg.tool_calls:
@@ -0,0 +1,150 @@
+"""Azure OpenAI Provider - GPT models hosted on Azure."""
+
+import os
+from typing import Any, Optional
+
+import httpx
+
+from agentsh.agent.providers.openrouter import OpenRouterClient
+from agentsh.telemetry.logger import get_logger
+
+logger = get_logger(__name__)
+
+DEFAULT_AZURE_API_VERSION = "2024-06-01"
+
+
+class AzureOpenAIClient(OpenRouterClient):
+    """Azure OpenAI LLM client.
+
+    Azure exposes the OpenAI chat completions API with a different request
+    convention: the key goes in an ``api-key`` header, the deployment name is
+    part of the URL, and every request carries an ``api-version`` query
+    parameter. The request and response bodies are OpenAI-compatible, so
+    message conversion and response parsing are shared with OpenRouterClient.
+
+    Example:
+        client = AzureOpenAIClient(
+            api_key=os.environ["AZURE_OPENAI_API_KEY"],
+            endpoint="https://my-resource.openai.azure.com",
+            deployment="gpt-4o",
+        )
+        response = await client.invoke([Message.user("Hello!")])
+        print(response.content)
+    """
+
+    def __init__(
+        self,
+        api_key: Optional[str] = None,
+        endpoint: Optional[str] = None,
+        deployment: Optional[str] = None,
+        api_version: str = DEFAULT_AZURE_API_VERSION,
+        model: Optional[str] = None,
+        timeout: float = 60.0,
+        max_retries: int = 3,
+        client_options: Optional[dict[str, Any]] = None,
+    ) -> None:
+        """Initialize the Azure OpenAI client.
+
+        Args:
+            api_key: Azure OpenAI key. Uses AZURE_OPENAI_API_KEY env var if not provided.
+            endpoint: Resource endpoint (e.g., "https://my-resource.openai.azure.com").
+                Uses AZURE_OPENAI_ENDPOINT env var if not provided.
+            deployment: Deployment name. Defaults to the model name.
+            api_version: Value for the api-version query parameter
+            model: Model name reported in responses (default: deployment name)
+            timeout: Request timeout in seconds
+            max_retries: Number of retries on transient errors
+            client_options: httpx client options such as a proxy or CA
+                bundle (see agentsh.agent.http_client.network_options)
+
+        Raises:
+            ValueError: If no endpoint or deployment is configured
+        """
+        self._api_key = api_key or os.environ.get("AZURE_OPENAI_API_KEY", "")
+        self._endpoint = (
+            endpoint or os.environ.get("AZURE_OPENAI_ENDPOINT", "")
+        ).rstrip("/")
+        self._deployment = deployment or model or ""
+        self._api_version = api_version
+        self._model = model or self._deployment
+        self._timeout = timeout
+        self._max_retries = max_retries
+
+        if not self._endpoint:
+            raise ValueError("Azure OpenAI requires an endpoint")
+        if not self._deployment:
+            raise ValueError("Azure OpenAI requires a deployment name")
+
+        if not self._api_key:
+            logger.warning("No Azure OpenAI API key provided")
+
+        self._client = httpx.AsyncClient(
+            base_url=self.deployment_url,
+            params={"api-version": self._api_version},
+            timeout=timeout,
+            headers=self._build_headers(),
+            **(client_options or {}),
+        )
+
+        logger.info(
+            "Azure OpenAI client initialized",
+            endpoint=self._endpoint,
+            deployment=self._deployment,
+            api_version=self._api_version,
+            has_api_key=bool(self._api_key),
+        )
+
+    def _build_headers(self) -> dict[str, str]:
+        """Build request headers."""
+        return {
+            "api-key": self._api_key,
+            "Content-Type": "application/json",
+        }
+
+    @property
+    def provider(self) -> str:
+        """Get the provider name."""
+        return "azure"
+
+    @property
+    def deployment_url(self) -> str:
+        """Get the base URL for the configured deployment."""
+        return f"{self._endpoint}/openai/deployments/{self._deployment}"
+
+    @property
+    def chat_completions_url(self) -> str:
+        """Get the full chat completions URL, including api-version."""
+        return (
+            f"{self.deployment_url}/chat/completions"
+            f"?api-version={self._api_version}"
+        )
+
+    async def list_models(self) -> list[dict[str, Any]]:
+        """List available models.
+
+        Azure deployments are bound to a single model, so this reports
+        only the configured deployment.
+
+        Returns:
+            List with a single model info dictionary
+        """
+        return [{"id": self._deployment}]
+
+    async def get_credits(self) -> Optional[float]:
+        """Get remaining API credits.
+
+        Returns:
+            Always None; Azure does not expose a credits endpoint
+        """
+        return None
+
+    async def get_generation_stats(self, generation_id: str) -> Optional[dict[str, Any]]:
+        """Get stats for a specific generation.
+
+        Args:
+            generation_id: The generation ID from a response
+
+        Returns:
+            Always None; Azure does not expose generation stats
+        """
+        return None
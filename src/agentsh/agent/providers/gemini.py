@@ -0,0 +1,345 @@
+"""Gemini LLM Provider - Google Gemini models via the generateContent API."""
+
+import json
+import os
+from typing import Any, AsyncIterator, Optional
+
+import httpx
+
+from agentsh.agent.cost import parse_usage
+from agentsh.agent.http_client import (
+    classify_http_error,
+    parse_json_response,
+    request_id_headers,
+    trace_llm_request,
+    trace_llm_response,
+)
+from agentsh.agent.llm_client import (
+    LLMAPIError,
+    LLMClient,
+    LLMResponse,
+    Message,
+    MessageRole,
+    StopReason,
+    ToolCall,
+    ToolDefinition,
+    split_system_prompt,
+)
+from agentsh.telemetry.logger import get_logger
+
+logger = get_logger(__name__)
+
+GEMINI_BASE_URL = "https://generativelanguage.googleapis.com/v1beta"
+
+
+class GeminiClient(LLMClient):
+    """Google Gemini client.
+
+    Uses Google's ``generateContent`` wire format: messages are sent as
+    ``contents`` with ``parts``, the API key travels in the
+    ``x-goog-api-key`` header (never in the URL, which proxies and logs
+    record), and text comes back in ``candidates[0].content.parts``.
+
+    Example:
+        client = GeminiClient(
+            api_key=os.environ["GEMINI_API_KEY"],
+            model="gemini-1.5-pro",
+        )
+        response = await client.invoke([Message.user("Hello!")])
+        print(response.content)
+    """
+
+    def __init__(
+        self,
+        api_key: Optional[str] = None,
+        model: str = "gemini-1.5-flash",
+        base_url: str = GEMINI_BASE_URL,
+        timeout: float = 60.0,
+        client_options: Optional[dict[str, Any]] = None,
+    ) -> None:
+        """Initialize the Gemini client.
+
+        Args:
+            api_key: Google AI API key. Uses GEMINI_API_KEY or GOOGLE_API_KEY
+                env var if not provided.
+            model: Model to use (e.g., gemini-1.5-pro, gemini-1.5-flash)
+            base_url: API base URL
+            timeout: Request timeout in seconds
+            client_options: httpx client options such as a proxy or CA
+                bundle (see agentsh.agent.http_client.network_options)
+        """
+        self._api_key = (
+            api_key
+            or os.environ.get("GEMINI_API_KEY")
+            or os.environ.get("GOOGLE_API_KEY", "")
+        )
+        self._model = model
+        self._base_url = base_url.rstrip("/")
+        self._timeout = timeout
+
+        self._client = httpx.AsyncClient(
+            base_url=self._base_url,
+            headers={"Content-Type": "application/json", "x-goog-api-key": self._api_key},
+            timeout=timeout,
+            **(client_options or {}),
+        )
+
+        logger.info(
+            "Gemini client initialized",
+            model=model,
+            base_url=self._base_url,
+        )
+
+    @property
+    def provider(self) -> str:
+        """Get the provider name."""
+        return "gemini"
+
+    @property
+    def model(self) -> str:
+        """Get the model name."""
+        return self._model
+
+    def _build_payload(
+        self,
+        messages: list[Message],
+        tools: Optional[list[ToolDefinition]],
+        temperature: float,
+        max_tokens: int,
+    ) -> dict[str, Any]:
+        """Build a generateContent request body.
+
+        Args:
+            messages: Conversation history
+            tools: Available tools
+            temperature: Sampling temperature
+            max_tokens: Maximum tokens to generate
+
+        Returns:
+            Request payload
+        """
+        system, contents = self._convert_messages(messages)
+
+        payload: dict[str, Any] = {
+            "contents": contents,
+            "generationConfig": {
+                "temperature": temperature,
+                "maxOutputTokens": max_tokens,
+            },
+        }
+
+        if system:
+            payload["systemInstruction"] = {"parts": [{"text": system}]}
+
+        if tools:
+            payload["tools"] = [
+                {"functionDeclarations": [t.to_gemini_format() for t in tools]}
+            ]
+
+        return payload
+
+    async def invoke(
+        self,
+        messages: list[Message],
+        tools: Optional[list[ToolDefinition]] = None,
+        temperature: float = 0.0,
+        max_tokens: int = 4096,
+    ) -> LLMResponse:
+        """Invoke Gemini with messages and optional tools.
+
+        Args:
+            messages: Conversation history
+            tools: Available tools
+            temperature: Sampling temperature
+            max_tokens: Maximum tokens to generate
+
+        Returns:
+            LLMResponse with content and/or tool calls
+
+        Raises:
+            LLMAPIError: If the response contains no candidates (e.g. the
+                prompt was blocked by safety filters)
+        """
+        payload = self._build_payload(messages, tools, temperature, max_tokens)
+
+        logger.debug(
+            "Invoking Gemini API",
+            model=self._model,
+            message_count=len(payload["contents"]),
+            has_tools=bool(tools),
+        )
+
+        secrets = [self._api_key]
+        headers = request_id_headers()
+        trace_llm_request(self.provider, payload, {**self._client.headers, **headers}, secrets)
+
+        try:
+            response = await self._client.post(
+                f"/models/{self._model}:generateContent", json=payload, headers=headers
+            )
+            trace_llm_response(self.provider, response.text, secrets)
+            response.raise_for_status()
+            data = parse_json_response(response, self.provider)
+        except httpx.HTTPError as e:
+            logger.error("Gemini API error", error=str(e))
+            error = classify_http_error(e)
+            if error is e:
+                raise
+            raise error from e
+
+        return self._parse_response(data)
+
+    async def stream(
+        self,
+        messages: list[Message],
+        tools: Optional[list[ToolDefinition]] = None,
+        temperature: float = 0.0,
+        max_tokens: int = 4096,
+    ) -> AsyncIterator[str]:
+        """Stream tokens from Gemini.
+
+        Args:
+            messages: Conversation history
+            tools: Available tools
+            temperature: Sampling temperature
+            max_tokens: Maximum tokens
+
+        Yields:
+            Text chunks as they are generated
+        """
+        payload = self._build_payload(messages, tools, temperature, max_tokens)
+
+        async with self._client.stream(
+            "POST",
+            f"/models/{self._model}:streamGenerateContent",
+            params={"alt": "sse"},
+            json=payload,
+        ) as response:
+            response.raise_for_status()
+            async for line in response.aiter_lines():
+                if not line.startswith("data: "):
+                    continue
+                try:
+                    data = json.loads(line[6:])
+                except json.JSONDecodeError:
+                    continue
+                for candidate in data.get("candidates", [])[:1]:
+                    for part in candidate.get("content", {}).get("parts", []):
+                        text = part.get("text")
+                        if text:
+                            yield text
+
+    def _convert_messages(
+        self, messages: list[Message]
+    ) -> tuple[Optional[str], list[dict[str, Any]]]:
+        """Convert our messages to Gemini contents.
+
+        Gemini takes the system prompt separately and names the assistant
+        role "model". Tool results are sent back as ``functionResponse`` parts.
+
+        Args:
+            messages: List of Message objects
+
+        Returns:
+            Tuple of (system instruction, Gemini contents)
+        """
+        system_prompt, conversation = split_system_prompt(messages)
+        contents: list[dict[str, Any]] = []
+
+        for msg in conversation:
+            if msg.role == MessageRole.USER:
+                contents.append({"role": "user", "parts": [{"text": msg.content}]})
+            elif msg.role == MessageRole.ASSISTANT:
+                parts: list[dict[str, Any]] = []
+                if msg.content:
+                    parts.append({"text": msg.content})
+                for tc in msg.tool_calls:
+                    parts.append(
+                        {"functionCall": {"name": tc.name, "args": tc.arguments}}
+                    )
+                contents.append({"role": "model", "parts": parts})
+            elif msg.role == MessageRole.TOOL:
+                contents.append(
+                    {
+                        "role": "user",
+                        "parts": [
+                            {
+                                "functionResponse": {
+                                    "name": msg.name or "",
+                                    "response": {"content": msg.content},
+                                }
+                            }
+                        ],
+                    }
+                )
+
+        return system_prompt or None, contents
+
+    def _parse_response(self, data: dict[str, Any]) -> LLMResponse:
+        """Parse a generateContent response to our format.
+
+        Args:
+            data: Gemini API response
+
+        Returns:
+            LLMResponse object
+
+        Raises:
+            LLMAPIError: If the response has no candidates
+        """
+        candidates = data.get("candidates") or []
+        if not candidates:
+            reason = data.get("promptFeedback", {}).get("blockReason", "no candidates")
+            logger.warning("Gemini returned no candidates", reason=reason)
+            raise LLMAPIError(f"Gemini returned no response (blocked: {reason}).")
+
+        candidate = candidates[0]
+        content = ""
+        tool_calls: list[ToolCall] = []
+
+        for part in candidate.get("content", {}).get("parts", []):
+            if "text" in part:
+                content += part["text"]
+            elif "functionCall" in part:
+                call = part["functionCall"]
+                tool_calls.append(
+                    ToolCall(
+                        id=f"call_{len(tool_calls)}",
+                        name=call.get("name", ""),
+                        arguments=call.get("args", {}),
+                    )
+                )
+
+        finish_reason = candidate.get("finishReason", "")
+        if tool_calls:
+            stop_reason = StopReason.TOOL_USE
+        elif finish_reason == "MAX_TOKENS":
+            stop_reason = StopReason.MAX_TOKENS
+        else:
+            stop_reason = StopReason.END_TURN
+
+        input_tokens, output_tokens = parse_usage(data.get("usageMetadata"))
+
+        return LLMResponse(
+            content=content,
+            tool_calls=tool_calls,
+            stop_reason=stop_reason,
+            input_tokens=input_tokens,
+            output_tokens=output_tokens,
+            model=data.get("modelVersion", self._model),
+        )
+
+    async def close(self) -> None:
+        """Close the HTTP client."""
+        await self._client.aclose()
+
+    def count_tokens(self, text: str) -> int:
+        """Estimate token count for text.
+
+        Args:
+            text: Text to count
+
+        Returns:
+            Estimated token count (rough approximation)
+        """
+        return len(text) // 4
@@ -5,6 +5,7 @@ import os
 from typing import Any, AsyncIterator, Optional
 
 from agentsh.agent.llm_client import (
+    JSON_RESPONSE_FORMAT,
     LLMClient,
     LLMResponse,
     Message,
@@ -12,6 +13,8 @@ from agentsh.agent.llm_client import (
     StopReason,
     ToolCall,
     ToolDefinition,
+    json_response_requested,
+    split_system_prompt,
 )
 from agentsh.telemetry.logger import get_logger
 
@@ -184,6 +187,8 @@ class LiteLLMClient(LLMClient):
 
         if tools:
             kwargs["tools"] = [t.to_openai_format() for t in tools]
+        if json_response_requested():
+            kwargs["response_format"] = JSON_RESPONSE_FORMAT
 
         # Add any extra provider-specific kwargs
         kwargs.update(self._extra_kwargs)
@@ -257,12 +262,14 @@ class LiteLLMClient(LLMClient):
         Returns:
             List of LiteLLM-formatted messages
         """
+        # The system prompt must come first, whatever its position in messages
+        system_prompt, conversation = split_system_prompt(messages)
         litellm_messages: list[dict[str, Any]] = []
+        if system_prompt:
+            litellm_messages.append({"role": "system", "content": system_prompt})
 
-        for msg in messages:
-            if msg.role == MessageRole.SYSTEM:
-                litellm_messages.append({"role": "system", "content": msg.content})
-            elif msg.role == MessageRole.USER:
+        for msg in conversation:
+            if msg.role == MessageRole.USER:
                 litellm_messages.append({"role": "user", "content": msg.content})
             elif msg.role == MessageRole.ASSISTANT:
                 if msg.tool_calls:
@@ -0,0 +1,222 @@
+"""Mock LLM Provider - Canned responses for offline testing and demos.
+
+Responses come from a JSON rules file instead of an API. Each rule matches
+a case-insensitive substring of the user's request::
+
+    [
+        {
+            "match": "list files",
+            "content": "Listing the current directory.",
+            "tool_calls": [{"name": "shell.run", "arguments": {"command": "ls -la"}}],
+            "answer": "Here are your files."
+        }
+    ]
+
+Rules are tried in order. When a rule has ``tool_calls``, they are returned
+first; once the tool results come back the rule's ``answer`` ends the turn.
+Requests that match no rule get an answer-only echo.
+"""
+
+import json
+from pathlib import Path
+from typing import Any, AsyncIterator, Optional
+
+from agentsh.agent.llm_client import (
+    LLMClient,
+    LLMResponse,
+    Message,
+    MessageRole,
+    StopReason,
+    ToolCall,
+    ToolDefinition,
+)
+from agentsh.telemetry.logger import get_logger
+
+logger = get_logger(__name__)
+
+DEFAULT_RULES_PATH = Path("~/.agentsh/mock_responses.json")
+
+
+def load_mock_rules(path: Path) -> list[dict[str, Any]]:
+    """Load mock response rules from a JSON file.
+
+    Args:
+        path: Rules file path
+
+    Returns:
+        List of rules, empty if the file does not exist
+
+    Raises:
+        ValueError: If the file is not a JSON list of objects with a "match" key
+    """
+    path = path.expanduser()
+    if not path.exists():
+        return []
+
+    try:
+        rules = json.loads(path.read_text())
+    except json.JSONDecodeError as e:
+        raise ValueError(f"Invalid mock responses file {path}: {e}") from e
+
+    if not isinstance(rules, list) or not all(
+        isinstance(rule, dict) and isinstance(rule.get("match"), str) for rule in rules
+    ):
+        raise ValueError(
+            f"Invalid mock responses file {path}: expected a list of objects with a 'match' key"
+        )
+    return rules
+
+
+class MockClient(LLMClient):
+    """Offline LLM client returning canned responses.
+
+    Lets the full request flow (agent loop, security checks, tool execution)
+    run without credentials or network access.
+
+    Example:
+        client = MockClient(rules=[
+            {"match": "list files", "tool_calls": [
+                {"name": "shell.run", "arguments": {"command": "ls"}}
+            ]},
+        ])
+        response = await client.invoke([Message.user("list files")])
+        print(response.tool_calls[0].arguments)
+    """
+
+    def __init__(
+        self,
+        rules: Optional[list[dict[str, Any]]] = None,
+        rules_path: Optional[Path] = None,
+        model: str = "mock",
+    ) -> None:
+        """Initialize the mock client.
+
+        Args:
+            rules: Rules to use directly (takes precedence over rules_path)
+            rules_path: JSON rules file (default: ~/.agentsh/mock_responses.json)
+            model: Model name to report
+        """
+        if rules is None:
+            rules = load_mock_rules(rules_path or DEFAULT_RULES_PATH)
+        self._rules = rules
+        self._model = model
+
+        logger.info("Mock client initialized", rule_count=len(rules))
+
+    @property
+    def provider(self) -> str:
+        """Get the provider name."""
+        return "mock"
+
+    @property
+    def model(self) -> str:
+        """Get the model name."""
+        return self._model
+
+    def _find_rule(self, request: str) -> Optional[dict[str, Any]]:
+        """Find the first rule whose match string occurs in the request.
+
+        Args:
+            request: User request text
+
+        Returns:
+            Matching rule, or None
+        """
+        lowered = request.lower()
+        for rule in self._rules:
+            if rule["match"].lower() in lowered:
+                return rule
+        return None
+
+    async def invoke(
+        self,
+        messages: list[Message],
+        tools: Optional[list[ToolDefinition]] = None,
+        temperature: float = 0.0,
+        max_tokens: int = 4096,
+    ) -> LLMResponse:
+        """Return the canned response for the latest user request.
+
+        Args:
+            messages: Conversation history
+            tools: Available tools (ignored)
+            temperature: Sampling temperature (ignored)
+            max_tokens: Maximum tokens (ignored)
+
+        Returns:
+            LLMResponse with the rule's tool calls, or its final answer once
+            tool results are present
+        """
+        request = ""
+        tool_results: list[str] = []
+        for msg in messages:
+            if msg.role == MessageRole.USER:
+                request = msg.content
+                tool_results = []
+            elif msg.role == MessageRole.TOOL:
+                tool_results.append(msg.content)
+
+        rule = self._find_rule(request)
+
+        if rule is None:
+            return LLMResponse(
+                content=f"(mock) {request}",
+                stop_reason=StopReason.END_TURN,
+                model=self._model,
+            )
+
+        calls = rule.get("tool_calls") or []
+        if calls and not tool_results:
+            return LLMResponse(
+                content=rule.get("content", ""),
+                tool_calls=[
+                    ToolCall(
+                        id=f"mock_{i}",
+                        name=call["name"],
+                        arguments=call.get("arguments", {}),
+                    )
+                    for i, call in enumerate(calls)
+                ],
+                stop_reason=StopReason.TOOL_USE,
+                model=self._model,
+            )
+
+        answer = rule.get("answer") or rule.get("content") or "\n".join(tool_results)
+        return LLMResponse(
+            content=answer,
+            stop_reason=StopReason.END_TURN,
+            model=self._model,
+        )
+
+    async def stream(
+        self,
+        messages: list[Message],
+        tools: Optional[list[ToolDefinition]] = None,
+        temperature: float = 0.0,
+        max_tokens: int = 4096,
+    ) -> AsyncIterator[str]:
+        """Stream the canned response text.
+
+        Args:
+            messages: Conversation history
+            tools: Available tools (ignored)
+            temperature: Sampling temperature (ignored)
+            max_tokens: Maximum tokens (ignored)
+
+        Yields:
+            The response content as a single chunk
+        """
+        response = await self.invoke(messages, tools, temperature, max_tokens)
+        if response.content:
+            yield response.content
+
+    def count_tokens(self, text: str) -> int:
+        """Estimate token count for text.
+
+        Args:
+            text: Text to count
+
+        Returns:
+            Estimated token count (rough approximation)
+        """
+        return len(text) // 4
@@ -6,6 +6,13 @@ from typing import Any, AsyncIterator, Optional
 
 import httpx
 
+from agentsh.agent.http_client import (
+    classify_http_error,
+    parse_json_response,
+    request_id_headers,
+    trace_llm_request,
+    trace_llm_response,
+)
 from agentsh.agent.llm_client import (
     LLMClient,
     LLMResponse,
@@ -14,6 +21,8 @@ from agentsh.agent.llm_client import (
     StopReason,
     ToolCall,
     ToolDefinition,
+    json_response_requested,
+    split_system_prompt,
 )
 from agentsh.telemetry.logger import get_logger
 
@@ -113,6 +122,8 @@ class OllamaClient(LLMClient):
         if tools:
             # Ollama uses OpenAI-compatible tool format
             payload["tools"] = [t.to_openai_format() for t in tools]
+        if json_response_requested():
+            payload["format"] = "json"
 
         logger.debug(
             "Invoking Ollama API",
@@ -121,14 +132,21 @@ class OllamaClient(LLMClient):
             has_tools=bool(tools),
         )
 
+        headers = request_id_headers()
+        trace_llm_request(self.provider, payload, {**self._client.headers, **headers})
+
         try:
-            response = await self._client.post("/api/chat", json=payload)
+            response = await self._client.post("/api/chat", json=payload, headers=headers)
+            trace_llm_response(self.provider, response.text)
             response.raise_for_status()
-            data = response.json()
+            data = parse_json_response(response, self.provider)
             return self._parse_response(data)
         except httpx.HTTPError as e:
             logger.error("Ollama API error", error=str(e))
-            raise
+            error = classify_http_error(e)
+            if error is e:
+                raise
+            raise error from e
 
     async def stream(
         self,
@@ -186,12 +204,14 @@ class OllamaClient(LLMClient):
         Returns:
             List of Ollama-formatted messages
         """
+        # The system prompt must come first, whatever its position in messages
+        system_prompt, conversation = split_system_prompt(messages)
         ollama_messages: list[dict[str, Any]] = []
+        if system_prompt:
+            ollama_messages.append({"role": "system", "content": system_prompt})
 
-        for msg in messages:
-            if msg.role == MessageRole.SYSTEM:
-                ollama_messages.append({"role": "system", "content": msg.content})
-            elif msg.role == MessageRole.USER:
+        for msg in conversation:
+            if msg.role == MessageRole.USER:
                 ollama_messages.append({"role": "user", "content": msg.content})
             elif msg.role == MessageRole.ASSISTANT:
                 if msg.tool_calls:
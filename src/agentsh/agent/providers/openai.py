@@ -4,16 +4,27 @@ import json
 import os
 from typing import Any, AsyncIterator, Optional
 
+import httpx
 import openai
 
+from agentsh.agent.http_client import (
+    request_id_headers,
+    trace_llm_request,
+    trace_llm_response,
+)
 from agentsh.agent.llm_client import (
+    JSON_RESPONSE_FORMAT,
     LLMClient,
+    LLMNetworkError,
     LLMResponse,
+    LLMTimeoutError,
     Message,
     MessageRole,
     StopReason,
     ToolCall,
     ToolDefinition,
+    json_response_requested,
+    split_system_prompt,
 )
 from agentsh.telemetry.logger import get_logger
 
@@ -41,6 +52,7 @@ class OpenAIClient(LLMClient):
         base_url: Optional[str] = None,
         max_retries: int = 3,
         timeout: float = 60.0,
+        client_options: Optional[dict[str, Any]] = None,
     ) -> None:
         """Initialize the OpenAI client.
 
@@ -50,6 +62,8 @@ class OpenAIClient(LLMClient):
             base_url: Optional custom base URL (for Azure, etc.)
             max_retries: Number of retries on transient errors
             timeout: Request timeout in seconds
+            client_options: httpx client options such as a proxy or CA
+                bundle (see agentsh.agent.http_client.network_options)
         """
         self._api_key = api_key or os.environ.get("OPENAI_API_KEY", "")
         self._model = model
@@ -65,6 +79,7 @@ class OpenAIClient(LLMClient):
             base_url=base_url,
             max_retries=max_retries,
             timeout=timeout,
+            **({"http_client": httpx.AsyncClient(**client_options)} if client_options else {}),
         )
 
         logger.info(
@@ -113,6 +128,8 @@ class OpenAIClient(LLMClient):
 
         if tools:
             kwargs["tools"] = [t.to_openai_format() for t in tools]
+        if json_response_requested():
+            kwargs["response_format"] = JSON_RESPONSE_FORMAT
 
         logger.debug(
             "Invoking OpenAI API",
@@ -121,9 +138,22 @@ class OpenAIClient(LLMClient):
             has_tools=bool(tools),
         )
 
+        secrets = [self._api_key]
+        headers = request_id_headers()
+        trace_llm_request(self.provider, kwargs, headers, secrets)
+        if headers:
+            kwargs["extra_headers"] = headers
+
         try:
             response = await self._client.chat.completions.create(**kwargs)
+            trace_llm_response(self.provider, response, secrets)
             return self._parse_response(response)
+        except openai.APITimeoutError as e:
+            logger.error("OpenAI API timeout", error=str(e))
+            raise LLMTimeoutError(str(e) or "Request timed out") from e
+        except openai.APIConnectionError as e:
+            logger.error("OpenAI API connection error", error=str(e))
+            raise LLMNetworkError(str(e) or "Network error") from e
         except openai.APIError as e:
             logger.error("OpenAI API error", error=str(e))
             raise
@@ -164,6 +194,19 @@ class OpenAIClient(LLMClient):
             if chunk.choices and chunk.choices[0].delta.content:
                 yield chunk.choices[0].delta.content
 
+    async def list_models(self) -> list[dict[str, Any]]:
+        """List models from the /v1/models endpoint.
+
+        Returns:
+            Model info dictionaries, or an empty list on error
+        """
+        try:
+            page = await self._client.models.list()
+            return [model.model_dump() for model in page.data]
+        except openai.OpenAIError as e:
+            logger.warning("Failed to list OpenAI models", error=str(e))
+            return []
+
     def _convert_messages(self, messages: list[Message]) -> list[dict[str, Any]]:
         """Convert our messages to OpenAI format.
 
@@ -173,12 +216,14 @@ class OpenAIClient(LLMClient):
         Returns:
             List of OpenAI-formatted messages
         """
+        # The system prompt must come first, whatever its position in messages
+        system_prompt, conversation = split_system_prompt(messages)
         openai_messages: list[dict[str, Any]] = []
+        if system_prompt:
+            openai_messages.append({"role": "system", "content": system_prompt})
 
-        for msg in messages:
-            if msg.role == MessageRole.SYSTEM:
-                openai_messages.append({"role": "system", "content": msg.content})
-            elif msg.role == MessageRole.USER:
+        for msg in conversation:
+            if msg.role == MessageRole.USER:
                 openai_messages.append({"role": "user", "content": msg.content})
             elif msg.role == MessageRole.ASSISTANT:
                 if msg.tool_calls:
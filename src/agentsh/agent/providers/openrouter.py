@@ -6,7 +6,16 @@ from typing import Any, AsyncIterator, Optional
 
 import httpx
 
+from agentsh.agent.cost import parse_usage
+from agentsh.agent.http_client import (
+    classify_http_error,
+    parse_json_response,
+    request_id_headers,
+    trace_llm_request,
+    trace_llm_response,
+)
 from agentsh.agent.llm_client import (
+    JSON_RESPONSE_FORMAT,
     LLMClient,
     LLMResponse,
     Message,
@@ -14,6 +23,8 @@ from agentsh.agent.llm_client import (
     StopReason,
     ToolCall,
     ToolDefinition,
+    json_response_requested,
+    split_system_prompt,
 )
 from agentsh.telemetry.logger import get_logger
 
@@ -168,6 +179,8 @@ class OpenRouterClient(LLMClient):
 
         if tools:
             payload["tools"] = [t.to_openai_format() for t in tools]
+        if json_response_requested():
+            payload["response_format"] = JSON_RESPONSE_FORMAT
 
         logger.debug(
             "Invoking OpenRouter API",
@@ -176,14 +189,22 @@ class OpenRouterClient(LLMClient):
             has_tools=bool(tools),
         )
 
+        secrets = [self._api_key]
+        headers = request_id_headers()
+        trace_llm_request(self.provider, payload, {**self._client.headers, **headers}, secrets)
+
         try:
-            response = await self._client.post("/chat/completions", json=payload)
+            response = await self._client.post("/chat/completions", json=payload, headers=headers)
+            trace_llm_response(self.provider, response.text, secrets)
             response.raise_for_status()
-            data = response.json()
+            data = parse_json_response(response, self.provider)
             return self._parse_response(data)
         except httpx.HTTPError as e:
             logger.error("OpenRouter API error", error=str(e))
-            raise
+            error = classify_http_error(e)
+            if error is e:
+                raise
+            raise error from e
 
     async def stream(
         self,
@@ -244,12 +265,14 @@ class OpenRouterClient(LLMClient):
         Returns:
             List of OpenRouter-formatted messages
         """
+        # The system prompt must come first, whatever its position in messages
+        system_prompt, conversation = split_system_prompt(messages)
         openrouter_messages: list[dict[str, Any]] = []
+        if system_prompt:
+            openrouter_messages.append({"role": "system", "content": system_prompt})
 
-        for msg in messages:
-            if msg.role == MessageRole.SYSTEM:
-                openrouter_messages.append({"role": "system", "content": msg.content})
-            elif msg.role == MessageRole.USER:
+        for msg in conversation:
+            if msg.role == MessageRole.USER:
                 openrouter_messages.append({"role": "user", "content": msg.content})
             elif msg.role == MessageRole.ASSISTANT:
                 if msg.tool_calls:
@@ -332,9 +355,7 @@ class OpenRouterClient(LLMClient):
             stop_reason = StopReason.TOOL_USE
 
         # Get token usage
-        usage = data.get("usage", {})
-        input_tokens = usage.get("prompt_tokens", 0)
-        output_tokens = usage.get("completion_tokens", 0)
+        input_tokens, output_tokens = parse_usage(data.get("usage"))
 
         return LLMResponse(
             content=content,
@@ -0,0 +1,96 @@
+"""Replanning - Let the user stop a failing plan and ask for a new one.
+
+When a step fails during an AI request, the user can continue with the
+remaining steps, stop, or ask the AI to adjust. Adjusting skips the
+remaining steps and sends them, with the failure output, back to the
+model for a revised plan, whose steps go through the usual confirmation
+flow. ``llm.max_replans`` bounds how often one request can be replanned.
+"""
+
+import json
+from enum import Enum
+from typing import Callable
+
+from agentsh.agent.llm_client import ToolCall
+from agentsh.agent.prompts import REPLAN_PROMPT
+from agentsh.telemetry.logger import truncate_preview
+
+# Bytes of failure output sent back with a replan request
+MAX_REPLAN_OUTPUT_BYTES = 2000
+
+
+class FailureAction(str, Enum):
+    """What to do after a step fails."""
+
+    CONTINUE = "continue"
+    ABORT = "abort"
+    ADJUST = "adjust"
+
+
+# Keys accepted at the failure prompt
+FAILURE_KEYS = {
+    "c": FailureAction.CONTINUE,
+    "": FailureAction.CONTINUE,
+    "s": FailureAction.ABORT,
+    "a": FailureAction.ADJUST,
+}
+
+
+def describe_step(tool_call: ToolCall) -> str:
+    """Describe a step for the user and the model.
+
+    Args:
+        tool_call: Step to describe
+
+    Returns:
+        The command for shell steps, else the tool name and arguments
+    """
+    command = tool_call.arguments.get("command")
+    if isinstance(command, str) and command:
+        return command
+    return f"{tool_call.name} {json.dumps(tool_call.arguments, default=str)}"
+
+
+def build_replan_message(failed: ToolCall, output: str, remaining: list[ToolCall]) -> str:
+    """Build the message asking the model for a revised plan.
+
+    Args:
+        failed: Step that failed
+        output: The failed step's output
+        remaining: Steps of the plan that were not run
+
+    Returns:
+        Message text to send as the user's turn
+    """
+    steps = "\n".join(f"- {describe_step(call)}" for call in remaining) or "(none)"
+    return REPLAN_PROMPT.format(
+        step=describe_step(failed),
+        output=truncate_preview(output, MAX_REPLAN_OUTPUT_BYTES),
+        remaining=steps,
+    )
+
+
+def prompt_failure_action(
+    step: str,
+    output: str,
+    input_fn: Callable[[str], str] = input,
+) -> FailureAction:
+    """Ask the user what to do after a step failed.
+
+    Args:
+        step: Description of the failed step
+        output: The step's output
+        input_fn: Function reading a line of input
+
+    Returns:
+        The chosen action; EOF or Ctrl-C stops the plan
+    """
+    print(f"\nStep failed: {step}\n{truncate_preview(output)}")
+    while True:
+        try:
+            choice = input_fn("[c]ontinue / [s]top / [a]djust plan > ").strip().lower()
+        except (EOFError, KeyboardInterrupt):
+            return FailureAction.ABORT
+        if choice[:1] in FAILURE_KEYS:
+            return FAILURE_KEYS[choice[:1]]
+        print("Please answer c, s or a.")
@@ -5,6 +5,7 @@ Provides a wrapper around LLM clients that adds:
 - Cached response fallback
 - Circuit breaker pattern
 - Health monitoring
+- Failover to a secondary provider
 """
 
 import asyncio
@@ -26,6 +27,10 @@ from agentsh.telemetry.logger import get_logger
 
 logger = get_logger(__name__)
 
+# Provider SDK errors (anthropic, openai) for requests that never got an
+# answer; they do not subclass the builtin ConnectionError or TimeoutError
+SDK_AVAILABILITY_ERRORS = frozenset({"APIConnectionError", "APITimeoutError"})
+
 
 class CircuitState(str, Enum):
     """Circuit breaker states."""
@@ -598,6 +603,158 @@ class ResilientLLMClient(LLMClient):
         return self._client.count_tokens(text)
 
 
+def is_availability_error(error: Exception) -> bool:
+    """Check whether an error means the provider is unavailable.
+
+    Timeouts, network failures (including the provider SDKs' connection
+    and timeout errors), rate limits and server errors (HTTP 5xx) count;
+    errors about the request itself, such as a bad request, do not, as
+    another provider would likely reject it too.
+
+    Args:
+        error: Exception raised by an LLM client
+
+    Returns:
+        True if another provider might succeed
+    """
+    if isinstance(error, (ConnectionError, TimeoutError, asyncio.TimeoutError)):
+        return True
+    if any(cls.__name__ in SDK_AVAILABILITY_ERRORS for cls in type(error).__mro__):
+        return True
+
+    status = getattr(error, "status_code", None)
+    if status is None:
+        response = getattr(error, "response", None)
+        status = getattr(response, "status_code", None)
+    if isinstance(status, int):
+        return status in (408, 429) or status >= 500
+    return False
+
+
+class FailoverLLMClient(LLMClient):
+    """LLM client that switches to a fallback provider when the primary fails.
+
+    Each request goes to the primary first. If it fails with an
+    availability error (see :func:`is_availability_error`), the same
+    messages, including the conversation so far, are sent to the fallback.
+
+    Example:
+        client = FailoverLLMClient(OpenAIClient(...), OllamaClient(model="llama3"))
+        response = await client.invoke(messages)
+    """
+
+    def __init__(
+        self,
+        primary: LLMClient,
+        fallback: LLMClient,
+        on_switch: Optional[Callable[[str], None]] = None,
+    ) -> None:
+        """Initialize the failover client.
+
+        Args:
+            primary: Client tried first
+            fallback: Client used when the primary is unavailable
+            on_switch: Callback receiving a note whenever a request is
+                routed to the fallback
+        """
+        self._primary = primary
+        self._fallback = fallback
+        self._on_switch = on_switch
+
+    @property
+    def provider(self) -> str:
+        """Get the primary provider name."""
+        return self._primary.provider
+
+    @property
+    def model(self) -> str:
+        """Get the primary model name."""
+        return self._primary.model
+
+    async def invoke(
+        self,
+        messages: list[Message],
+        tools: Optional[list[ToolDefinition]] = None,
+        temperature: float = 0.0,
+        max_tokens: int = 4096,
+    ) -> LLMResponse:
+        """Invoke the primary, or the fallback if the primary is unavailable.
+
+        Args:
+            messages: Conversation history
+            tools: Available tools
+            temperature: Sampling temperature
+            max_tokens: Maximum tokens
+
+        Returns:
+            LLMResponse from whichever provider answered
+        """
+        try:
+            return await self._primary.invoke(messages, tools, temperature, max_tokens)
+        except Exception as e:
+            if not is_availability_error(e):
+                raise
+            self._note_switch(e)
+        return await self._fallback.invoke(messages, tools, temperature, max_tokens)
+
+    async def stream(
+        self,
+        messages: list[Message],
+        tools: Optional[list[ToolDefinition]] = None,
+        temperature: float = 0.0,
+        max_tokens: int = 4096,
+    ) -> AsyncIterator[str]:
+        """Stream from the primary, or from the fallback if it fails first.
+
+        Once the primary has produced output, errors are raised instead of
+        switching, so a response is never mixed from two providers.
+
+        Args:
+            messages: Conversation history
+            tools: Available tools
+            temperature: Sampling temperature
+            max_tokens: Maximum tokens
+
+        Yields:
+            Text chunks
+        """
+        started = False
+        try:
+            async for chunk in self._primary.stream(messages, tools, temperature, max_tokens):
+                started = True
+                yield chunk
+            return
+        except Exception as e:
+            if started or not is_availability_error(e):
+                raise
+            self._note_switch(e)
+
+        async for chunk in self._fallback.stream(messages, tools, temperature, max_tokens):
+            yield chunk
+
+    def count_tokens(self, text: str) -> int:
+        """Delegate token counting to the primary client."""
+        return self._primary.count_tokens(text)
+
+    def _note_switch(self, error: Exception) -> None:
+        """Log and report that a request goes to the fallback.
+
+        Args:
+            error: Error from the primary provider
+        """
+        logger.warning(
+            "Primary LLM provider unavailable, using fallback",
+            primary=self._primary.provider,
+            fallback=self._fallback.provider,
+            error=str(error),
+        )
+        if self._on_switch is not None:
+            self._on_switch(
+                f"{self._primary.provider} unavailable ({error}); "
+                f"using fallback {self._fallback.provider}/{self._fallback.model}"
+            )
+
+
 def create_resilient_client(
     client: LLMClient,
     max_retries: int = 3,
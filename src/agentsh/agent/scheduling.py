@@ -0,0 +1,91 @@
+"""Step scheduling - Order independent plan steps into parallel batches.
+
+When ``shell.parallel`` is enabled, the AI may mark a step with a
+``step_id`` and list the steps it needs first in ``depends_on``. Steps
+whose dependencies have all finished form a batch that runs concurrently.
+Plans without dependency information run one step at a time, as before.
+"""
+
+from typing import Any
+
+from agentsh.agent.llm_client import ToolCall
+
+# Tool arguments carrying dependency information
+STEP_ID_ARG = "step_id"
+DEPENDS_ON_ARG = "depends_on"
+
+# Schema added to every tool definition when parallel steps are enabled
+DEPENDENCY_PARAMETERS: dict[str, Any] = {
+    STEP_ID_ARG: {
+        "type": "string",
+        "description": "Short id for this step, referenced by depends_on of later steps",
+    },
+    DEPENDS_ON_ARG: {
+        "type": "array",
+        "items": {"type": "string"},
+        "description": (
+            "step_id values that must finish before this step runs. Steps without "
+            "dependencies in the same response may run at the same time."
+        ),
+    },
+}
+
+
+def extract_dependencies(tool_call: ToolCall) -> ToolCall:
+    """Move step_id and depends_on from a tool call's arguments to its fields.
+
+    Args:
+        tool_call: Tool call as returned by the LLM
+
+    Returns:
+        The same tool call, whose arguments no longer contain the
+        dependency keys
+    """
+    step_id = tool_call.arguments.pop(STEP_ID_ARG, None)
+    depends_on = tool_call.arguments.pop(DEPENDS_ON_ARG, None)
+    if step_id:
+        tool_call.step_id = str(step_id)
+    if isinstance(depends_on, str):
+        depends_on = [depends_on]
+    if isinstance(depends_on, list):
+        tool_call.depends_on = [str(dep) for dep in depends_on if dep]
+    return tool_call
+
+
+def schedule_batches(tool_calls: list[ToolCall]) -> list[list[ToolCall]]:
+    """Group tool calls into batches that can run concurrently.
+
+    Each batch holds the steps whose dependencies all ran in earlier
+    batches, in their original order. Dependencies on unknown ids are
+    ignored. If no step declares dependencies, or they form a cycle, the
+    steps run one at a time in their original order.
+
+    Args:
+        tool_calls: Tool calls from one LLM response, in order
+
+    Returns:
+        Batches of tool calls, in execution order
+    """
+    sequential = [[tool_call] for tool_call in tool_calls]
+    if not any(tool_call.depends_on for tool_call in tool_calls):
+        return sequential
+
+    known = {tool_call.key for tool_call in tool_calls}
+    done: set[str] = set()
+    pending = list(tool_calls)
+    batches: list[list[ToolCall]] = []
+
+    while pending:
+        ready = [
+            tool_call
+            for tool_call in pending
+            if all(dep in done or dep not in known for dep in tool_call.depends_on)
+        ]
+        if not ready:
+            return sequential
+        batches.append(ready)
+        done.update(tool_call.key for tool_call in ready)
+        started = {id(tool_call) for tool_call in ready}
+        pending = [tool_call for tool_call in pending if id(tool_call) not in started]
+
+    return batches
@@ -11,6 +11,7 @@ DEFAULT_AUDIT_LOG = DEFAULT_CONFIG_DIR / "audit.log"
 DEFAULT_LOG_FILE = DEFAULT_CONFIG_DIR / "agentsh.log"
 DEFAULT_DEVICES_FILE = DEFAULT_CONFIG_DIR / "devices.yaml"
 DEFAULT_PLUGINS_DIR = DEFAULT_CONFIG_DIR / "plugins"
+DEFAULT_SESSIONS_DIR = DEFAULT_CONFIG_DIR / "sessions"
 
 # Default shell settings
 DEFAULT_SHELL = "zsh"
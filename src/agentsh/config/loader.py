@@ -1,8 +1,9 @@
 """Configuration loading with hierarchy support."""
 
 import os
+import re
 from pathlib import Path
-from typing import Any, Optional
+from typing import Any, Callable, Optional
 
 import yaml
 
@@ -14,6 +15,39 @@ def get_default_config_path() -> Path:
     return Path.home() / ".agentsh" / "config.yaml"
 
 
+def get_xdg_config_path() -> Path:
+    """Get the user configuration path under the XDG config directory.
+
+    Uses ``$XDG_CONFIG_HOME/agentsh/config.yaml``, falling back to
+    ``~/.config`` when ``XDG_CONFIG_HOME`` is unset or empty.
+    """
+    config_home = os.environ.get("XDG_CONFIG_HOME")
+    base = Path(config_home).expanduser() if config_home else Path.home() / ".config"
+    return base / "agentsh" / "config.yaml"
+
+
+def get_config_dir_paths() -> list[Path]:
+    """Get the drop-in config files in ``~/.agentsh/config.d``.
+
+    Files ending in ``.yaml`` or ``.yml`` are returned in lexical order of
+    their names, so ``20-providers.yaml`` is merged after (and overrides)
+    ``10-security.yaml``.
+    """
+    config_dir = get_default_config_path().parent / "config.d"
+    if not config_dir.is_dir():
+        return []
+    return sorted(
+        (path for path in config_dir.iterdir()
+         if path.suffix in (".yaml", ".yml") and path.is_file()),
+        key=lambda path: path.name,
+    )
+
+
+def get_project_config_path() -> Path:
+    """Get the project configuration path, ``.agentsh.yaml`` in the cwd."""
+    return Path.cwd() / ".agentsh.yaml"
+
+
 def get_config_paths() -> list[Path]:
     """Get ordered list of configuration paths to check."""
     paths = []
@@ -23,13 +57,21 @@ def get_config_paths() -> list[Path]:
     if system_config.exists():
         paths.append(system_config)
 
-    # User config
+    # XDG user config
+    xdg_config = get_xdg_config_path()
+    if xdg_config.exists():
+        paths.append(xdg_config)
+
+    # User config (~/.agentsh, kept for back-compat and overrides the XDG one)
     user_config = get_default_config_path()
     if user_config.exists():
         paths.append(user_config)
 
+    # Drop-in user configs, merged after the main user config
+    paths.extend(get_config_dir_paths())
+
     # Project-level config
-    project_config = Path.cwd() / ".agentsh.yaml"
+    project_config = get_project_config_path()
     if project_config.exists():
         paths.append(project_config)
 
@@ -115,53 +157,208 @@ def _parse_env_value(value: str) -> Any:
     return value
 
 
-def load_config(
+# Source reported for fields no file or variable sets
+DEFAULT_ORIGIN = "default"
+
+# Settings naming programs AgentSH runs; the project config comes with
+# whatever repository is checked out, so it may not set them
+PROJECT_FORBIDDEN_KEYS = (
+    "security.pre_exec_hook",
+    "shell.exec_shell",
+    "shell.motd_command",
+    "shell.rc_file",
+)
+
+# Keys whose values are credentials, never shown by ``agentsh config show``
+SECRET_KEY = re.compile(r"(?:^|_)(?:api_key|token|password|secret)$")
+
+
+def _leaf_keys(data: dict[str, Any], prefix: str = "") -> list[str]:
+    """List the dotted keys of the non-mapping values in nested settings."""
+    keys = []
+    for key, value in data.items():
+        dotted = f"{prefix}{key}"
+        if isinstance(value, dict) and value:
+            keys.extend(_leaf_keys(value, f"{dotted}."))
+        else:
+            keys.append(dotted)
+    return keys
+
+
+def _without_keys(data: dict[str, Any], dotted_keys: tuple[str, ...]) -> dict[str, Any]:
+    """Copy nested settings without the given dotted keys."""
+    result = dict(data)
+    for dotted in dotted_keys:
+        section, _, key = dotted.rpartition(".")
+        current = result
+        for part in section.split(".") if section else []:
+            if not isinstance(current.get(part), dict):
+                break
+            current[part] = dict(current[part])
+            current = current[part]
+        else:
+            current.pop(key, None)
+    return result
+
+
+def _env_var_name(dotted_key: str) -> str:
+    """Get the AGENTSH_* variable that sets a dotted key."""
+    return "AGENTSH_" + dotted_key.replace(".", "__").upper()
+
+
+def load_config_with_origins(
     config_path: Optional[Path] = None,
     include_env: bool = True,
-) -> AgentSHConfig:
-    """Load configuration from all sources with proper hierarchy.
+) -> tuple[AgentSHConfig, dict[str, str]]:
+    """Load configuration and record which source set each field.
 
-    Loading order (later overrides earlier):
-    1. Default values (from schema)
-    2. System config (/etc/agentsh/config.yaml)
-    3. User config (~/.agentsh/config.yaml)
-    4. Project config (.agentsh.yaml in cwd)
-    5. Explicit config file (--config argument)
-    6. Environment variables (AGENTSH_*)
+    Sources are merged as described in :func:`load_config`.
 
     Args:
         config_path: Optional explicit configuration file path
         include_env: Whether to include environment variable overrides
 
     Returns:
-        Validated AgentSHConfig instance
+        The validated configuration, and the source of each field set by a
+        file or variable, keyed by dotted name (e.g. "llm.model" ->
+        "env AGENTSH_LLM__MODEL"); fields missing from the map are defaults
     """
-    # Start with empty config (defaults come from schema)
     merged_config: dict[str, Any] = {}
+    origins: dict[str, str] = {}
+
+    def apply(source: dict[str, Any], origin: Callable[[str], str]) -> None:
+        nonlocal merged_config
+        merged_config = deep_merge(merged_config, source)
+        for key in _leaf_keys(source):
+            # A value replacing a whole section also replaces its fields
+            for replaced in [k for k in origins if k.startswith(f"{key}.")]:
+                del origins[replaced]
+            origins[key] = origin(key)
 
     # Load from standard paths
+    project_config = get_project_config_path()
     for path in get_config_paths():
         try:
-            file_config = load_yaml_config(path)
-            merged_config = deep_merge(merged_config, file_config)
+            data = load_yaml_config(path)
         except Exception:
             # Skip files that can't be read
-            pass
+            continue
+        if path == project_config:
+            data = _without_keys(data, PROJECT_FORBIDDEN_KEYS)
+        apply(data, lambda _, path=path: str(path))
 
     # Load explicit config file if provided
     if config_path:
         if not config_path.exists():
             raise FileNotFoundError(f"Configuration file not found: {config_path}")
-        explicit_config = load_yaml_config(config_path)
-        merged_config = deep_merge(merged_config, explicit_config)
+        apply(load_yaml_config(config_path), lambda _: str(config_path))
 
     # Apply environment variable overrides
     if include_env:
-        env_overrides = get_env_overrides()
-        merged_config = deep_merge(merged_config, env_overrides)
+        apply(get_env_overrides(), lambda key: f"env {_env_var_name(key)}")
+        if os.environ.get("NO_COLOR"):
+            apply({"shell": {"color": False}}, lambda _: "env NO_COLOR")
 
     # Create and validate configuration
-    return AgentSHConfig(**merged_config)
+    return AgentSHConfig(**merged_config), origins
+
+
+def load_config(
+    config_path: Optional[Path] = None,
+    include_env: bool = True,
+) -> AgentSHConfig:
+    """Load configuration from all sources with proper hierarchy.
+
+    Loading order (later overrides earlier):
+    1. Default values (from schema)
+    2. System config (/etc/agentsh/config.yaml)
+    3. XDG user config ($XDG_CONFIG_HOME/agentsh/config.yaml, by default
+       ~/.config/agentsh/config.yaml)
+    4. User config (~/.agentsh/config.yaml), then the files in
+       ~/.agentsh/config.d/*.yaml in lexical order of their names
+    5. Project config (.agentsh.yaml in cwd), which cannot set the
+       settings in PROJECT_FORBIDDEN_KEYS
+    6. Explicit config file (--config argument)
+    7. Environment variables (AGENTSH_*; a non-empty NO_COLOR turns off
+       shell.color)
+
+    Args:
+        config_path: Optional explicit configuration file path
+        include_env: Whether to include environment variable overrides
+
+    Returns:
+        Validated AgentSHConfig instance
+    """
+    config, _ = load_config_with_origins(config_path, include_env)
+    return config
+
+
+def _yaml_scalar(value: Any) -> str:
+    """Render a value on one line of YAML."""
+    text = yaml.safe_dump(value, default_flow_style=True, width=float("inf")).strip()
+    return text.removesuffix("\n...").strip()
+
+
+def _mask_secrets(data: dict[str, Any]) -> dict[str, Any]:
+    """Replace the values of credential settings with "<set>"."""
+    masked = {}
+    for key, value in data.items():
+        if isinstance(value, dict):
+            value = _mask_secrets(value)
+        elif SECRET_KEY.search(key) and value:
+            value = "<set>"
+        masked[key] = value
+    return masked
+
+
+def _format_section(
+    data: dict[str, Any],
+    origins: Optional[dict[str, str]],
+    prefix: str = "",
+    indent: int = 0,
+) -> list[str]:
+    """Render nested settings as YAML lines, optionally with their origins."""
+    lines = []
+    pad = "  " * indent
+    for key, value in data.items():
+        dotted = f"{prefix}{key}"
+        if isinstance(value, dict) and value:
+            lines.append(f"{pad}{key}:")
+            lines.extend(_format_section(value, origins, f"{dotted}.", indent + 1))
+            continue
+        line = f"{pad}{key}: {_yaml_scalar(value)}"
+        if origins is not None:
+            line += f"  # {origins.get(dotted, DEFAULT_ORIGIN)}"
+        lines.append(line)
+    return lines
+
+
+def format_config(config: AgentSHConfig, origins: Optional[dict[str, str]] = None) -> str:
+    """Render the effective configuration as YAML.
+
+    Credentials are never shown: the LLM API key is listed as ``<set>``
+    or ``<not set>`` depending on llm.api_key_env, and any other setting
+    named like a key, token, password or secret as ``<set>``.
+
+    Args:
+        config: Loaded configuration
+        origins: Source of each field (see :func:`load_config_with_origins`);
+            when given, every value is annotated with where it came from
+
+    Returns:
+        YAML text
+    """
+    data = _mask_secrets(config.model_dump(mode="json"))
+    llm: dict[str, Any] = {}
+    for key, value in data["llm"].items():
+        llm[key] = value
+        if key == "api_key_env":
+            llm["api_key"] = "<set>" if os.environ.get(value) else "<not set>"
+    data["llm"] = llm
+
+    if origins is not None:
+        origins = {**origins, "llm.api_key": f"env {config.llm.api_key_env}"}
+    return "\n".join(_format_section(data, origins)) + "\n"
 
 
 def create_default_config(path: Path) -> None:
@@ -188,3 +385,33 @@ def create_default_config(path: Path) -> None:
 
     with open(path, "w") as f:
         f.write(yaml_content)
+
+
+def describe_config_changes(old: AgentSHConfig, new: AgentSHConfig) -> list[str]:
+    """Summarize the changes between two configurations that affect the AI.
+
+    Covers the security mode, the LLM provider and model, and the on/off
+    safety settings.
+
+    Args:
+        old: Configuration in use
+        new: Newly loaded configuration
+
+    Returns:
+        One "name: old -> new" line per changed setting
+    """
+    changes = []
+    for name, before, after in (
+        ("security.mode", old.security.mode.value, new.security.mode.value),
+        ("llm.provider", old.llm.provider.value, new.llm.provider.value),
+        ("llm.model", old.llm.model, new.llm.model),
+    ):
+        if before != after:
+            changes.append(f"{name}: {before} -> {after}")
+
+    for field in type(new.security).model_fields:
+        before = getattr(old.security, field)
+        after = getattr(new.security, field)
+        if isinstance(after, bool) and before != after:
+            changes.append(f"security.{field}: {before} -> {after}")
+    return changes
@@ -1,10 +1,13 @@
 """Configuration schemas using Pydantic for validation."""
 
+import re
 from enum import Enum
 from pathlib import Path
 from typing import Any, Optional
 
-from pydantic import BaseModel, Field, field_validator
+from pydantic import BaseModel, Field, field_validator, model_validator
+
+from agentsh.utils.validators import load_ca_bundle
 
 
 class LLMProvider(str, Enum):
@@ -13,6 +16,9 @@ class LLMProvider(str, Enum):
     ANTHROPIC = "anthropic"
     OPENAI = "openai"
     OLLAMA = "ollama"
+    AZURE = "azure"
+    GEMINI = "gemini"
+    MOCK = "mock"
 
 
 class SecurityMode(str, Enum):
@@ -23,6 +29,40 @@ class SecurityMode(str, Enum):
     LENIENT = "lenient"  # Fewer restrictions (dangerous)
 
 
+class SafetyPreset(str, Enum):
+    """Bundles of security settings selected with one switch."""
+
+    DEFAULT = "default"  # Use the individual settings as given
+    PARANOID = "paranoid"  # Confirm every command that is not read-only
+    PERMISSIVE = "permissive"  # Confirm only what rules ask for
+
+
+# Field values applied by each preset unless set explicitly
+SAFETY_PRESETS: dict[SafetyPreset, dict[str, Any]] = {
+    SafetyPreset.DEFAULT: {},
+    SafetyPreset.PARANOID: {
+        "require_confirmation": True,
+        "confirm_all": True,
+        "allow_autonomous": False,
+        "redact_secrets": True,
+    },
+    SafetyPreset.PERMISSIVE: {
+        "mode": SecurityMode.LENIENT,
+        "require_confirmation": False,
+        "confirm_all": False,
+    },
+}
+
+
+class RuleAction(str, Enum):
+    """What to do with a command matching a safety rule."""
+
+    BLOCK = "block"  # Refuse to run
+    CONFIRM = "confirm"  # Always ask first
+    WARN = "warn"  # Run as usual, but show a warning
+    ALLOW = "allow"  # Run without confirmation
+
+
 class MemoryType(str, Enum):
     """Memory storage types."""
 
@@ -38,6 +78,54 @@ class CompletionMode(str, Enum):
     HYBRID = "hybrid"  # Merge AgentSH + shell completions (default)
 
 
+class OutputEncoding(str, Enum):
+    """Handling of command output that is not valid UTF-8."""
+
+    LOSSY = "lossy"  # Replace invalid bytes with U+FFFD
+    STRICT = "strict"  # Show a [binary output: N bytes] placeholder
+
+
+class HistoryWindowMode(str, Enum):
+    """How the conversation history sent with a request is limited."""
+
+    MESSAGES = "messages"  # Keep the last N messages
+    TOKENS = "tokens"  # Keep as many recent messages as fit in N estimated tokens
+
+
+class PlanVerbosity(str, Enum):
+    """Detail shown for each step of a plan before it runs."""
+
+    MINIMAL = "minimal"  # Numbered commands only
+    NORMAL = "normal"  # Plus descriptions and safety flags
+    VERBOSE = "verbose"  # Plus risk warnings and previewed effects
+
+
+class SpinnerStyle(str, Enum):
+    """Progress spinner styles."""
+
+    DOTS = "dots"
+    LINE = "line"
+    ARROWS = "arrows"
+    BOUNCE = "bounce"
+    BRAILLE = "braille"
+    NONE = "none"  # No spinner
+
+
+class ModelPricing(BaseModel):
+    """Price of one model, used for plan cost estimates."""
+
+    input: float = Field(
+        default=0.0,
+        ge=0,
+        description="USD per million prompt tokens",
+    )
+    output: float = Field(
+        default=0.0,
+        ge=0,
+        description="USD per million completion tokens",
+    )
+
+
 class LLMConfig(BaseModel):
     """LLM provider configuration."""
 
@@ -64,13 +152,12 @@ class LLMConfig(BaseModel):
         gt=0,
         description="Maximum tokens in response",
     )
-    fallback_provider: Optional[LLMProvider] = Field(
+    fallback: Optional["LLMConfig"] = Field(
         default=None,
-        description="Fallback provider if primary fails",
-    )
-    fallback_model: Optional[str] = Field(
-        default=None,
-        description="Fallback model to use",
+        description=(
+            "Secondary provider settings (provider, model, api_key_env, ...) used when "
+            "the primary is unreachable, rate limited or returns a server error"
+        ),
     )
     timeout_seconds: int = Field(
         default=60,
@@ -82,6 +169,170 @@ class LLMConfig(BaseModel):
         ge=0,
         description="Maximum retry attempts for failed calls",
     )
+    max_info_rounds: int = Field(
+        default=3,
+        ge=0,
+        description="Maximum rounds of read-only context probes per request (0 disables)",
+    )
+    max_steps: int = Field(
+        default=20,
+        ge=1,
+        description="Maximum tool calls accepted from a single AI response",
+    )
+    reject_excess_steps: bool = Field(
+        default=False,
+        description="Reject responses over max_steps instead of truncating them",
+    )
+    total_timeout_seconds: int = Field(
+        default=0,
+        ge=0,
+        description="Wall-clock limit for running all steps of one AI request (0 disables)",
+    )
+    max_replans: int = Field(
+        default=2,
+        ge=0,
+        description="Times one request's plan can be revised after a failed step (0 disables)",
+    )
+    history_window: int = Field(
+        default=10,
+        ge=0,
+        description=(
+            "Earlier conversation sent with each request: messages, or estimated tokens "
+            "when history_window_mode is tokens (0 disables)"
+        ),
+    )
+    history_window_mode: HistoryWindowMode = Field(
+        default=HistoryWindowMode.MESSAGES,
+        description="Limit the history by message count (messages) or estimated tokens (tokens)",
+    )
+    history_results_chars: int = Field(
+        default=2000,
+        ge=0,
+        description=(
+            "Characters of the redacted step results remembered with each answer, "
+            "so follow-ups can refer to them (0 disables)"
+        ),
+    )
+    context_files: list[str] = Field(
+        default_factory=list,
+        description="Files added to every request's context, relative to the working directory",
+    )
+    context_file_extensions: Optional[list[str]] = Field(
+        default=None,
+        description="Only read context files with these extensions, e.g. .md (all if unset)",
+    )
+    context_file_max_lines: int = Field(
+        default=200,
+        ge=1,
+        description="Maximum lines of each context file included in a request",
+    )
+    json_mode: bool = Field(
+        default=False,
+        description=(
+            "Ask providers with a JSON mode (OpenAI-compatible, Ollama) for a JSON "
+            "object when a structured answer is expected"
+        ),
+    )
+    min_request_interval_ms: int = Field(
+        default=0,
+        ge=0,
+        description="Minimum milliseconds between AI requests (0 disables)",
+    )
+    reject_rapid_requests: bool = Field(
+        default=False,
+        description="Reject requests sent sooner than min_request_interval_ms instead of delaying",
+    )
+    max_context_size: int = Field(
+        default=20000,
+        gt=0,
+        description="Maximum characters of piped stdin sent with a request",
+    )
+    validate_model: bool = Field(
+        default=False,
+        description="Warn at startup if the provider's model list doesn't include the model",
+    )
+    show_plan_cost: bool = Field(
+        default=True,
+        description="Show the tokens used by the request behind a plan before it is confirmed",
+    )
+    pricing: dict[str, ModelPricing] = Field(
+        default_factory=dict,
+        description=(
+            "Prices keyed by model name, adding a dollar estimate to the plan's "
+            "token usage"
+        ),
+    )
+    domain_hint: Optional[str] = Field(
+        default=None,
+        description="Extra context for the AI about your domain (e.g., 'Kubernetes ops')",
+    )
+    azure_endpoint: Optional[str] = Field(
+        default=None,
+        description="Azure OpenAI resource endpoint (e.g., https://name.openai.azure.com)",
+    )
+    azure_deployment: Optional[str] = Field(
+        default=None,
+        description="Azure OpenAI deployment name (defaults to the model name)",
+    )
+    azure_api_version: str = Field(
+        default="2024-06-01",
+        description="Azure OpenAI api-version query parameter",
+    )
+    mock_responses_path: Optional[Path] = Field(
+        default=None,
+        description=(
+            "Canned responses for the mock provider "
+            "(default: ~/.agentsh/mock_responses.json)"
+        ),
+    )
+    socks_proxy: Optional[str] = Field(
+        default=None,
+        description="SOCKS5 proxy for AI provider requests (e.g., socks5://127.0.0.1:1080)",
+    )
+    ca_cert_path: Optional[Path] = Field(
+        default=None,
+        description=(
+            "PEM bundle of root certificates trusted for AI provider connections, "
+            "e.g. a corporate TLS inspection CA"
+        ),
+    )
+
+    @model_validator(mode="before")
+    @classmethod
+    def map_legacy_fallback(cls, data: Any) -> Any:
+        """Turn the old fallback_provider/fallback_model keys into llm.fallback."""
+        if not isinstance(data, dict):
+            return data
+        data = dict(data)
+        provider = data.pop("fallback_provider", None)
+        model = data.pop("fallback_model", None)
+        if (provider or model) and data.get("fallback") is None:
+            fallback: dict[str, Any] = {"provider": provider or data.get("provider")}
+            if model:
+                fallback["model"] = model
+            data["fallback"] = {k: v for k, v in fallback.items() if v is not None}
+        return data
+
+    @field_validator("socks_proxy")
+    @classmethod
+    def validate_socks_proxy(cls, v: Optional[str]) -> Optional[str]:
+        """Validate the proxy is a SOCKS5 URL."""
+        if v is not None and not re.match(r"socks5h?://[^\s/]+", v):
+            raise ValueError(f"llm.socks_proxy must be a socks5:// or socks5h:// URL, got {v!r}")
+        return v
+
+    @field_validator("ca_cert_path")
+    @classmethod
+    def validate_ca_cert_path(cls, v: Optional[Path]) -> Optional[Path]:
+        """Validate the CA bundle can be read and holds PEM certificates."""
+        if v is None:
+            return v
+        path = v.expanduser()
+        try:
+            load_ca_bundle(path)
+        except ValueError as e:
+            raise ValueError(f"llm.ca_cert_path: {e}") from e
+        return path
 
 
 class ShellConfig(BaseModel):
@@ -100,6 +351,18 @@ class ShellConfig(BaseModel):
         gt=0,
         description="Maximum history entries",
     )
+    history_ignore_space: bool = Field(
+        default=True,
+        description="Don't record input that starts with a space",
+    )
+    history_ignore_patterns: list[str] = Field(
+        default_factory=list,
+        description="Regex patterns for commands that are never recorded in history",
+    )
+    history_redact_secrets: bool = Field(
+        default=True,
+        description="Redact API keys, tokens and passwords before writing history",
+    )
     ai_prefix: str = Field(
         default="ai ",
         description="Prefix to force AI routing",
@@ -129,11 +392,127 @@ class ShellConfig(BaseModel):
         default=None,
         description="Custom RC file to source (~/.agentshrc by default)",
     )
+    motd_command: Optional[str] = Field(
+        default=None,
+        description="Command whose output is shown once at interactive startup",
+    )
+    record_session: bool = Field(
+        default=False,
+        description=(
+            "Record terminal output to ~/.agentsh/sessions/<id>.cast "
+            "(raw capture, not redacted; may contain sensitive data)"
+        ),
+    )
+    color: bool = Field(
+        default=True,
+        description="Use ANSI colors in prompts and AI output",
+    )
+    output_encoding: OutputEncoding = Field(
+        default=OutputEncoding.LOSSY,
+        description=(
+            "How command output that is not valid UTF-8 is shown and passed to the AI "
+            "(lossy or strict)"
+        ),
+    )
+    prompt: Optional[str] = Field(
+        default="{user}@{host}:{cwd} [{mode}]$ ",
+        description=(
+            "PS1 template for shells started from AgentSH ({user}, {host}, {cwd}, "
+            "{mode}); unset to keep the shell's own prompt"
+        ),
+    )
+    mode_indicators: dict[str, str] = Field(
+        default_factory=dict,
+        description="Text shown for {mode} per security mode (default: the mode name)",
+    )
+    spinner: SpinnerStyle = Field(
+        default=SpinnerStyle.DOTS,
+        description="Progress spinner style (none disables it; static text when not a TTY)",
+    )
+    render_markdown: bool = Field(
+        default=True,
+        description="Render markdown in AI answers (plain text when off or not a TTY)",
+    )
+    redact_display: bool = Field(
+        default=True,
+        description="Mask secrets in commands shown for approval (the real command still runs)",
+    )
+    plan_verbosity: PlanVerbosity = Field(
+        default=PlanVerbosity.NORMAL,
+        description=(
+            "Detail of the plan shown before its steps run: minimal (commands), "
+            "normal (descriptions and flags) or verbose (warnings and effects)"
+        ),
+    )
+    parallel: bool = Field(
+        default=False,
+        description="Run plan steps whose dependencies have finished at the same time",
+    )
+    max_parallel: int = Field(
+        default=4,
+        ge=1,
+        description="Maximum plan steps running at the same time when parallel is on",
+    )
+    exec_shell: Optional[str] = Field(
+        default=None,
+        description=(
+            "Shell that runs AI steps with -c, e.g. bash (unset uses backend, falling "
+            "back to sh; not read from a project .agentsh.yaml)"
+        ),
+    )
+    default_size: tuple[int, int] = Field(
+        default=(24, 80),
+        description="Terminal rows and columns used when the size cannot be detected",
+    )
+
+    @field_validator("default_size")
+    @classmethod
+    def validate_default_size(cls, v: tuple[int, int]) -> tuple[int, int]:
+        """Validate both dimensions are positive."""
+        if v[0] <= 0 or v[1] <= 0:
+            raise ValueError("shell.default_size needs positive rows and columns")
+        return v
+
+
+class SafetyRule(BaseModel):
+    """A command pattern and the action to take when it matches."""
+
+    pattern: str = Field(description="Regex searched for in the command")
+    action: RuleAction = Field(
+        default=RuleAction.BLOCK,
+        description="Action for matching commands: block, confirm, warn or allow",
+    )
+
+    @field_validator("pattern")
+    @classmethod
+    def validate_pattern(cls, v: str) -> str:
+        """Validate the pattern is a regex."""
+        try:
+            re.compile(v)
+        except re.error as e:
+            raise ValueError(f"Invalid rule pattern {v!r}: {e}") from e
+        return v
+
+
+class ToolPolicy(BaseModel):
+    """Confirmation override for one tool."""
+
+    requires_confirmation: Optional[bool] = Field(
+        default=None,
+        description="Always confirm the tool (true) or never (false); unset keeps its default",
+    )
 
 
 class SecurityConfig(BaseModel):
     """Security and permission configuration."""
 
+    preset: SafetyPreset = Field(
+        default=SafetyPreset.DEFAULT,
+        description=(
+            "Settings bundle (default, paranoid, permissive); fields set explicitly "
+            "override the preset"
+        ),
+    )
     mode: SecurityMode = Field(
         default=SecurityMode.NORMAL,
         description="Security enforcement mode",
@@ -146,10 +525,29 @@ class SecurityConfig(BaseModel):
         default=False,
         description="Allow agent to execute without approval",
     )
+    confirm_all: bool = Field(
+        default=False,
+        description="Confirm every command that is not read-only or matched by an allow rule",
+    )
+    auto_run_allowlist: list[str] = Field(
+        default_factory=list,
+        description=(
+            "With allow_autonomous, regex patterns of commands that may run without "
+            "confirmation; each simple command of a line must match a pattern in "
+            "full. Every other command is confirmed"
+        ),
+    )
     audit_log_path: Optional[Path] = Field(
         default=None,
         description="Path to audit log file",
     )
+    logged_events: Optional[list[str]] = Field(
+        default=None,
+        description=(
+            "Audit actions to persist (e.g., command_blocked, security_violation); "
+            "all actions are logged when unset"
+        ),
+    )
     deny_patterns: list[str] = Field(
         default_factory=lambda: [
             r"^rm\s+-rf\s+/$",
@@ -157,18 +555,130 @@ class SecurityConfig(BaseModel):
             r"^dd\s+if=.*of=/dev",
             r":()\{\s*:\|:&\s*\};:",
         ],
-        description="Regex patterns to always block",
+        description="Regex patterns to always block (shorthand for block rules)",
+    )
+    rules: list[SafetyRule] = Field(
+        default_factory=list,
+        description=(
+            "Ordered command rules; a matching block rule (or deny pattern) always "
+            "wins, otherwise the first matching rule decides the action"
+        ),
     )
     max_command_length: int = Field(
         default=10000,
         gt=0,
-        description="Maximum command length to execute",
+        description=(
+            "Longest command run without confirmation; longer commands are too long "
+            "to review and always require approval"
+        ),
+    )
+    reject_long_commands: bool = Field(
+        default=False,
+        description="Block commands longer than max_command_length instead of confirming them",
+    )
+    block_root_shells: bool = Field(
+        default=False,
+        description=(
+            "Block commands opening an interactive root shell (sudo -i, sudo su) "
+            "instead of confirming them"
+        ),
+    )
+    require_retype_for_critical: bool = Field(
+        default=False,
+        description=(
+            "Confirm commands affecting a critical service or rated CRITICAL by retyping "
+            "them (or a short token) instead of answering y"
+        ),
+    )
+    redact_secrets: bool = Field(
+        default=True,
+        description="Redact API keys, tokens and passwords in diagnostic output (:why)",
+    )
+    redact_network_identifiers: bool = Field(
+        default=False,
+        description="Redact IP addresses and hostnames in the audit log",
+    )
+    redact_private_only: bool = Field(
+        default=False,
+        description=(
+            "With redact_network_identifiers, only redact private addresses and internal "
+            "hostnames (loopback is kept)"
+        ),
     )
     approval_timeout_seconds: int = Field(
         default=60,
         gt=0,
         description="Timeout for approval prompts",
     )
+    rm_to_trash: bool = Field(
+        default=False,
+        description="Move files the agent deletes with rm to ~/.agentsh/trash/<session>/",
+    )
+    critical_services: list[str] = Field(
+        default_factory=list,
+        description=(
+            "Services whose stop/restart/disable always needs approval, in addition "
+            "to ssh and sshd (e.g., postgresql, nginx)"
+        ),
+    )
+    workspace_root: Optional[Path] = Field(
+        default=None,
+        description=(
+            "Directory AI steps should stay in; a step that cd's outside it is "
+            "confirmed with a warning, and fs.apply_patch is confined to it "
+            "(unset disables the warning)"
+        ),
+    )
+    tool_policy: dict[str, ToolPolicy] = Field(
+        default_factory=dict,
+        description=(
+            "Per-tool overrides keyed by tool name (e.g., fs.delete), consulted before "
+            "the tool's own confirmation default"
+        ),
+    )
+    pre_exec_hook: Optional[str] = Field(
+        default=None,
+        description=(
+            "Command run before each AI step with the step as JSON on stdin; it answers "
+            "allow, deny or modify (see agentsh.security.pre_exec)"
+        ),
+    )
+    pre_exec_hook_timeout_seconds: float = Field(
+        default=5.0,
+        gt=0,
+        description="Seconds the pre-exec hook may take before the step is denied",
+    )
+
+    @field_validator("auto_run_allowlist")
+    @classmethod
+    def validate_auto_run_allowlist(cls, v: list[str]) -> list[str]:
+        """Validate the allowlist entries are regexes."""
+        for pattern in v:
+            try:
+                re.compile(pattern)
+            except re.error as e:
+                raise ValueError(f"Invalid auto_run_allowlist pattern {pattern!r}: {e}") from e
+        return v
+
+    @model_validator(mode="before")
+    @classmethod
+    def apply_preset(cls, data: Any) -> Any:
+        """Fill in the preset's values for fields not set explicitly."""
+        if not isinstance(data, dict) or "preset" not in data:
+            return data
+        preset = SafetyPreset(data["preset"])
+        return {**SAFETY_PRESETS[preset], **data}
+
+    def effective_rules(self) -> list[SafetyRule]:
+        """Get all rules in evaluation order.
+
+        Returns:
+            Configured rules followed by deny_patterns as block rules
+        """
+        return self.rules + [
+            SafetyRule(pattern=pattern, action=RuleAction.BLOCK)
+            for pattern in self.deny_patterns
+        ]
 
 
 class MemoryConfig(BaseModel):
@@ -1,6 +1,12 @@
 """Plugin system for AgentSH extensibility."""
 
 from agentsh.plugins.base import Toolset, ToolsetRegistry, get_toolset_registry
+from agentsh.plugins.external import (
+    ExternalToolset,
+    PluginExecutor,
+    PluginManifest,
+    PluginProtocol,
+)
 from agentsh.plugins.lazy import (
     LazyPlugin,
     LazyPluginRegistry,
@@ -20,6 +26,11 @@ __all__ = [
     "Toolset",
     "ToolsetRegistry",
     "get_toolset_registry",
+    # External plugins
+    "ExternalToolset",
+    "PluginExecutor",
+    "PluginManifest",
+    "PluginProtocol",
     # Lazy loading
     "LazyPlugin",
     "LazyPluginRegistry",
@@ -2,12 +2,14 @@
 
 from agentsh.plugins.builtin.code import CodeToolset
 from agentsh.plugins.builtin.filesystem import FilesystemToolset
+from agentsh.plugins.builtin.notes import NotesToolset
 from agentsh.plugins.builtin.process import ProcessToolset
 from agentsh.plugins.builtin.shell import ShellToolset
 
 __all__ = [
     "CodeToolset",
     "FilesystemToolset",
+    "NotesToolset",
     "ProcessToolset",
     "ShellToolset",
 ]
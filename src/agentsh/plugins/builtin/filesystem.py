@@ -1,19 +1,49 @@
 """Filesystem toolset - File operations."""
 
+import codecs
 import fnmatch
 import os
+import re
 import shutil
 import stat
 from datetime import datetime
 from pathlib import Path
-from typing import TYPE_CHECKING, Optional
+from typing import TYPE_CHECKING, Any, Optional
 
 from agentsh.plugins.base import Toolset
 from agentsh.tools.base import RiskLevel, ToolResult
+from agentsh.utils.patch import PatchError, apply_hunks, parse_unified_diff
 
 if TYPE_CHECKING:
     from agentsh.tools.registry import ToolRegistry
 
+# Supported sort modes for fs.list
+LIST_SORT_MODES = ("name", "name_ci", "size", "mtime")
+
+# Bytes inspected by fs.read to tell binary files from text
+BINARY_SNIFF_BYTES = 8192
+
+# Files larger than this are skipped by fs.grep
+GREP_MAX_FILE_SIZE = 1024 * 1024
+
+
+def looks_binary(chunk: bytes, encoding: str = "utf-8") -> bool:
+    """Guess whether the start of a file is binary data.
+
+    Text rarely contains null bytes, except in UTF-16 and UTF-32, which
+    are therefore never reported as binary.
+
+    Args:
+        chunk: First bytes of the file
+        encoding: Encoding the file is expected to use
+
+    Returns:
+        True if the chunk contains a null byte
+    """
+    if codecs.lookup(encoding).name.startswith(("utf-16", "utf-32")):
+        return False
+    return b"\0" in chunk
+
 
 class FilesystemToolset(Toolset):
     """Provides filesystem operation tools.
@@ -21,14 +51,26 @@ class FilesystemToolset(Toolset):
     Tools:
     - fs.read: Read file contents
     - fs.write: Write to a file
+    - fs.apply_patch: Apply a unified diff to one or more files
     - fs.list: List directory contents
     - fs.delete: Delete a file or directory
     - fs.copy: Copy a file or directory
     - fs.move: Move or rename a file
     - fs.search: Search for files by pattern
+    - fs.grep: Search file contents with a regex
     - fs.info: Get file/directory information
     """
 
+    def __init__(self, root: Optional[Path] = None) -> None:
+        """Initialize the filesystem toolset.
+
+        Args:
+            root: Sandbox root fs.apply_patch is confined to; relative
+                paths in a patch are resolved against it (defaults to the
+                working directory)
+        """
+        self.root = (root or Path.cwd()).expanduser().resolve()
+
     @property
     def name(self) -> str:
         return "filesystem"
@@ -37,6 +79,16 @@ class FilesystemToolset(Toolset):
     def description(self) -> str:
         return "Read, write, and manage files and directories"
 
+    def configure(self, config: dict[str, Any]) -> None:
+        """Configure the toolset from its plugin settings.
+
+        Args:
+            config: Plugin configuration; ``root`` sets the sandbox root,
+                which the loader sets to security.workspace_root if that is set
+        """
+        if config.get("root"):
+            self.root = Path(config["root"]).expanduser().resolve()
+
     def register_tools(self, registry: "ToolRegistry") -> None:
         """Register filesystem tools."""
         registry.register_tool(
@@ -96,6 +148,36 @@ class FilesystemToolset(Toolset):
             plugin_name=self.name,
         )
 
+        registry.register_tool(
+            name="fs.apply_patch",
+            handler=self.apply_patch,
+            description=(
+                "Apply a unified diff to one or more files. Context lines must match "
+                "the current file contents exactly; prefer this over fs.write for edits. "
+                "Either every file is changed or none is."
+            ),
+            parameters={
+                "type": "object",
+                "properties": {
+                    "patch": {
+                        "type": "string",
+                        "description": "Unified diff (as produced by diff -u or git diff)",
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": (
+                            "Target file for a single-file patch "
+                            "(default: path from the diff header)"
+                        ),
+                    },
+                },
+                "required": ["patch"],
+            },
+            risk_level=RiskLevel.MEDIUM,
+            requires_confirmation=True,
+            plugin_name=self.name,
+        )
+
         registry.register_tool(
             name="fs.list",
             handler=self.list_directory,
@@ -119,6 +201,19 @@ class FilesystemToolset(Toolset):
                         "type": "boolean",
                         "description": "Include hidden files (default: false)",
                     },
+                    "sort": {
+                        "type": "string",
+                        "enum": list(LIST_SORT_MODES),
+                        "description": (
+                            "Sort order: name, name_ci (case-insensitive), "
+                            "size (largest first), mtime (newest first). "
+                            "Default: name_ci"
+                        ),
+                    },
+                    "dirs_first": {
+                        "type": "boolean",
+                        "description": "List directories before files (default: true)",
+                    },
                 },
                 "required": ["path"],
             },
@@ -219,6 +314,50 @@ class FilesystemToolset(Toolset):
             plugin_name=self.name,
         )
 
+        registry.register_tool(
+            name="fs.grep",
+            handler=self.grep_files,
+            description=(
+                "Search file contents for a regular expression. "
+                "Returns matching lines as 'file:line: text'."
+            ),
+            parameters={
+                "type": "object",
+                "properties": {
+                    "pattern": {
+                        "type": "string",
+                        "description": "Regular expression to search for",
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": "File or directory to search (default: current dir)",
+                    },
+                    "ignore_case": {
+                        "type": "boolean",
+                        "description": "Match case-insensitively (default: false)",
+                    },
+                    "max_matches": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "Maximum matching lines to return (default: 100)",
+                    },
+                    "include": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "Only search files matching these globs (e.g., '*.py')",
+                    },
+                    "exclude": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "Skip files and directories matching these globs",
+                    },
+                },
+                "required": ["pattern"],
+            },
+            risk_level=RiskLevel.SAFE,
+            plugin_name=self.name,
+        )
+
         registry.register_tool(
             name="fs.info",
             handler=self.get_info,
@@ -268,20 +407,42 @@ class FilesystemToolset(Toolset):
                     error=f"Not a file: {path}",
                 )
 
-            # Check file size
             size = file_path.stat().st_size
+            with open(file_path, "rb") as f:
+                head = f.read(BINARY_SNIFF_BYTES)
+            if looks_binary(head, encoding):
+                return ToolResult(
+                    success=False,
+                    error=(
+                        f"Binary file: {path} ({size} bytes). Inspect it with a hex dump "
+                        "instead, e.g. 'xxd FILE | head' or 'head -c 256 FILE' via shell.run."
+                    ),
+                    metadata={"size": size, "binary": True},
+                )
+
+            # Check file size
             if size > max_bytes:
-                with open(file_path, "r", encoding=encoding) as f:
-                    content = f.read(max_bytes)
+                with open(file_path, "rb") as f:
+                    raw = f.read(max_bytes)
+                # A non-final decode holds back a character split by the cut
+                content = codecs.getincrementaldecoder(encoding)().decode(raw, final=False)
                 return ToolResult(
                     success=True,
-                    output=f"{content}\n\n... (truncated, file is {size} bytes)",
+                    output=(
+                        f"{content}\n\n... (truncated: read {len(raw)} bytes, "
+                        f"file is {size} bytes)"
+                    ),
+                    metadata={"size": size, "bytes_read": len(raw), "truncated": True},
                 )
 
             with open(file_path, "r", encoding=encoding) as f:
                 content = f.read()
 
-            return ToolResult(success=True, output=content)
+            return ToolResult(
+                success=True,
+                output=content,
+                metadata={"size": size, "bytes_read": size, "truncated": False},
+            )
 
         except UnicodeDecodeError:
             return ToolResult(
@@ -345,12 +506,133 @@ class FilesystemToolset(Toolset):
                 error=f"Failed to write file: {str(e)}",
             )
 
+    def apply_patch(self, patch: str, path: Optional[str] = None) -> ToolResult:
+        """Apply a unified diff.
+
+        Every file in the patch is checked before anything is written, so a
+        patch that does not match one file leaves all files untouched. If
+        writing fails part-way, the files already written are restored.
+        Patches touching files outside the sandbox root are rejected.
+
+        Args:
+            patch: Unified diff text
+            path: Target file, overriding the diff header (single-file patches only)
+
+        Returns:
+            ToolResult with a summary of changed files
+        """
+        try:
+            file_patches = parse_unified_diff(patch)
+        except PatchError as e:
+            return ToolResult(success=False, error=f"Invalid patch: {e}")
+
+        if path is not None and len(file_patches) != 1:
+            return ToolResult(
+                success=False,
+                error="The path argument can only be used with a single-file patch",
+            )
+
+        # Compute all new contents first so a mismatch aborts before any write
+        planned: list[tuple[Path, Optional[str]]] = []
+        originals: dict[Path, Optional[str]] = {}
+        try:
+            for file_patch in file_patches:
+                target = Path(self.root, path or file_patch.path).expanduser().resolve()
+                if not target.is_relative_to(self.root):
+                    return ToolResult(
+                        success=False,
+                        error=f"Patch touches {target}, outside the sandbox root {self.root}",
+                    )
+
+                if file_patch.is_new_file:
+                    if target.exists():
+                        return ToolResult(
+                            success=False,
+                            error=f"Patch creates {target} but it already exists",
+                        )
+                    original = ""
+                    originals[target] = None
+                else:
+                    if not target.is_file():
+                        return ToolResult(success=False, error=f"File not found: {target}")
+                    original = target.read_text(encoding="utf-8")
+                    originals[target] = original
+
+                patched = apply_hunks(original, file_patch.hunks, path=str(target))
+                planned.append((target, None if file_patch.is_deletion else patched))
+
+            written: list[Path] = []
+            created_dirs: list[Path] = []
+            try:
+                for target, content in planned:
+                    written.append(target)
+                    if content is None:
+                        target.unlink()
+                    else:
+                        missing = [d for d in target.parents if not d.exists()]
+                        target.parent.mkdir(parents=True, exist_ok=True)
+                        created_dirs.extend(reversed(missing))
+                        target.write_text(content, encoding="utf-8")
+            except OSError:
+                self._restore_files(written, originals, created_dirs)
+                raise
+
+        except PatchError as e:
+            return ToolResult(success=False, error=f"Patch rejected: {e}")
+        except UnicodeDecodeError:
+            return ToolResult(success=False, error="Cannot patch a non UTF-8 file")
+        except PermissionError as e:
+            return ToolResult(success=False, error=f"Permission denied: {e.filename}")
+        except Exception as e:
+            return ToolResult(success=False, error=f"Failed to apply patch: {str(e)}")
+
+        hunk_count = sum(len(fp.hunks) for fp in file_patches)
+        summary = "\n".join(
+            f"{'deleted' if content is None else 'patched'} {target}"
+            for target, content in planned
+        )
+        return ToolResult(
+            success=True,
+            output=f"Applied {hunk_count} hunk(s) to {len(planned)} file(s)\n{summary}",
+        )
+
+    @staticmethod
+    def _restore_files(
+        paths: list[Path],
+        originals: dict[Path, Optional[str]],
+        created_dirs: list[Path],
+    ) -> None:
+        """Put files back as they were before a patch was applied.
+
+        Args:
+            paths: Files the patch wrote or deleted, in order
+            originals: Previous contents, None for files the patch created
+            created_dirs: Directories created for new files, in the order
+                they were created
+        """
+        for target in reversed(paths):
+            try:
+                original = originals[target]
+                if original is None:
+                    target.unlink(missing_ok=True)
+                else:
+                    target.write_text(original, encoding="utf-8")
+            except OSError:
+                continue
+        for directory in reversed(created_dirs):
+            try:
+                directory.rmdir()
+            except OSError:
+                continue
+
     def list_directory(
         self,
         path: str,
         recursive: bool = False,
         pattern: Optional[str] = None,
         include_hidden: bool = False,
+        sort: str = "name_ci",
+        dirs_first: bool = True,
     ) -> ToolResult:
         """List directory contents.
 
@@ -359,10 +641,18 @@ class FilesystemToolset(Toolset):
             recursive: List recursively
             pattern: Glob pattern filter
             include_hidden: Include hidden files
+            sort: Sort mode (name, name_ci, size, mtime)
+            dirs_first: Group directories before files
 
         Returns:
             ToolResult with file listing
         """
+        if sort not in LIST_SORT_MODES:
+            return ToolResult(
+                success=False,
+                error=f"Invalid sort mode: {sort} (expected one of: {', '.join(LIST_SORT_MODES)})",
+            )
+
         try:
             dir_path = Path(path).expanduser().resolve()
 
@@ -378,7 +668,7 @@ class FilesystemToolset(Toolset):
                     error=f"Not a directory: {path}",
                 )
 
-            entries = []
+            items: list[tuple[str, Path]] = []
 
             if recursive:
                 for item in dir_path.rglob("*"):
@@ -395,8 +685,7 @@ class FilesystemToolset(Toolset):
                     if pattern and not fnmatch.fnmatch(name, pattern):
                         continue
 
-                    entry_type = "d" if item.is_dir() else "f"
-                    entries.append(f"[{entry_type}] {name}")
+                    items.append((name, item))
             else:
                 for item in dir_path.iterdir():
                     name = item.name
@@ -409,10 +698,13 @@ class FilesystemToolset(Toolset):
                     if pattern and not fnmatch.fnmatch(name, pattern):
                         continue
 
-                    entry_type = "d" if item.is_dir() else "f"
-                    entries.append(f"[{entry_type}] {name}")
+                    items.append((name, item))
 
-            entries.sort()
+            items.sort(key=lambda entry: self._list_sort_key(entry, sort, dirs_first))
+
+            entries = [
+                f"[{'d' if item.is_dir() else 'f'}] {name}" for name, item in items
+            ]
 
             if not entries:
                 return ToolResult(
@@ -436,6 +728,38 @@ class FilesystemToolset(Toolset):
                 error=f"Failed to list directory: {str(e)}",
             )
 
+    @staticmethod
+    def _list_sort_key(
+        entry: tuple[str, Path], sort: str, dirs_first: bool
+    ) -> tuple:
+        """Build a sort key for a directory entry.
+
+        Ties are always broken by case-insensitive name and then exact
+        name, so the order is deterministic across platforms.
+
+        Args:
+            entry: (display name, path) pair
+            sort: Sort mode
+            dirs_first: Group directories before files
+
+        Returns:
+            Tuple usable as a sort key
+        """
+        name, item = entry
+        group = 0 if dirs_first and item.is_dir() else 1
+        tie_break = (name.casefold(), name)
+
+        if sort == "name":
+            return (group, name)
+        if sort in ("size", "mtime"):
+            try:
+                st = item.stat()
+                value = st.st_size if sort == "size" else st.st_mtime
+            except OSError:
+                value = 0
+            return (group, -value, tie_break)
+        return (group, tie_break)
+
     def delete_file(
         self,
         path: str,
@@ -637,6 +961,110 @@ class FilesystemToolset(Toolset):
                 error=f"Search failed: {str(e)}",
             )
 
+    def grep_files(
+        self,
+        pattern: str,
+        path: Optional[str] = None,
+        ignore_case: bool = False,
+        max_matches: int = 100,
+        include: Optional[list[str]] = None,
+        exclude: Optional[list[str]] = None,
+    ) -> ToolResult:
+        """Search file contents for a regular expression.
+
+        Binary files and files over GREP_MAX_FILE_SIZE are skipped. Globs
+        are matched against file and directory names. Paths outside the
+        sandbox root are rejected, and files linking outside it are skipped.
+
+        Args:
+            pattern: Regular expression
+            path: File or directory to search
+            ignore_case: Match case-insensitively
+            max_matches: Maximum matching lines to return, at least 1
+            include: Only search files matching one of these globs
+            exclude: Skip files and directories matching one of these globs
+
+        Returns:
+            ToolResult with "file:line: text" lines, and the matches as
+            (file, line, text) in metadata
+        """
+        if max_matches < 1:
+            return ToolResult(success=False, error="max_matches must be at least 1")
+        try:
+            regex = re.compile(pattern, re.IGNORECASE if ignore_case else 0)
+        except re.error as e:
+            return ToolResult(success=False, error=f"Invalid pattern: {e}")
+
+        root = Path(self.root, Path(path or ".").expanduser()).resolve()
+        if not root.is_relative_to(self.root):
+            return ToolResult(
+                success=False,
+                error=f"Cannot search {root}, outside the sandbox root {self.root}",
+            )
+        if not root.exists():
+            return ToolResult(success=False, error=f"Path not found: {path}")
+
+        def excluded(name: str) -> bool:
+            return any(fnmatch.fnmatch(name, glob) for glob in exclude or ())
+
+        def included(name: str) -> bool:
+            return not include or any(fnmatch.fnmatch(name, glob) for glob in include)
+
+        if root.is_file():
+            files = [root]
+        else:
+            files = []
+            for dirpath, dirnames, filenames in os.walk(root):
+                dirnames[:] = sorted(d for d in dirnames if not excluded(d))
+                files.extend(
+                    Path(dirpath) / name
+                    for name in sorted(filenames)
+                    if included(name) and not excluded(name)
+                )
+
+        matches: list[tuple[str, int, str]] = []
+        skipped = 0
+        limited = False
+        for file_path in files:
+            if not file_path.resolve().is_relative_to(self.root):
+                skipped += 1
+                continue
+            try:
+                if file_path.stat().st_size > GREP_MAX_FILE_SIZE:
+                    skipped += 1
+                    continue
+                with open(file_path, "rb") as f:
+                    raw = f.read()
+            except OSError:
+                skipped += 1
+                continue
+            if looks_binary(raw[:BINARY_SNIFF_BYTES]):
+                continue
+
+            name = file_path.name if root.is_file() else str(file_path.relative_to(root))
+            text = raw.decode("utf-8", errors="replace")
+            for number, line in enumerate(text.splitlines(), 1):
+                if regex.search(line):
+                    matches.append((name, number, line))
+                    if len(matches) >= max_matches:
+                        limited = True
+                        break
+            if limited:
+                break
+
+        metadata = {"matches": matches, "limited": limited, "skipped": skipped}
+        if not matches:
+            return ToolResult(
+                success=True,
+                output=f"No matches for '{pattern}'",
+                metadata=metadata,
+            )
+
+        output = "\n".join(f"{name}:{number}: {line}" for name, number, line in matches)
+        if limited:
+            output += f"\n\n... (limited to {max_matches} matches)"
+        return ToolResult(success=True, output=output, metadata=metadata)
+
     def get_info(self, path: str) -> ToolResult:
         """Get file/directory information.
 
@@ -0,0 +1,73 @@
+"""Notes toolset - Let the AI record project facts."""
+
+import os
+from typing import TYPE_CHECKING, Optional
+
+from agentsh.agent.project_notes import append_note
+from agentsh.plugins.base import Toolset
+from agentsh.tools.base import RiskLevel, ToolResult
+
+if TYPE_CHECKING:
+    from agentsh.tools.registry import ToolRegistry
+
+
+class NotesToolset(Toolset):
+    """Provides project notes tools.
+
+    Tools:
+    - notes.append: Record a fact in the project's .agentsh/notes.md
+    """
+
+    @property
+    def name(self) -> str:
+        return "notes"
+
+    @property
+    def description(self) -> str:
+        return "Remember facts about the current project across sessions"
+
+    def register_tools(self, registry: "ToolRegistry") -> None:
+        """Register notes tools."""
+        registry.register_tool(
+            name="notes.append",
+            handler=self.append,
+            description=(
+                "Record a lasting, project-specific fact (e.g. how to deploy, which port "
+                "the database uses) in the project notes shown in future sessions."
+            ),
+            parameters={
+                "type": "object",
+                "properties": {
+                    "note": {
+                        "type": "string",
+                        "description": "The fact to remember, as one short line",
+                    },
+                    "cwd": {
+                        "type": "string",
+                        "description": "Directory inside the project (default: current)",
+                    },
+                },
+                "required": ["note"],
+            },
+            risk_level=RiskLevel.LOW,
+            plugin_name=self.name,
+            examples=["notes.append(note='Deploys go through make ship')"],
+        )
+
+    def append(self, note: str, cwd: Optional[str] = None) -> ToolResult:
+        """Append a note to the project notes.
+
+        Args:
+            note: Fact to remember
+            cwd: Directory inside the project
+
+        Returns:
+            ToolResult naming the notes file
+        """
+        try:
+            path = append_note(cwd or os.getcwd(), note)
+        except ValueError as e:
+            return ToolResult(success=False, error=str(e))
+        except OSError as e:
+            return ToolResult(success=False, error=f"Failed to write notes: {e}")
+        return ToolResult(success=True, output=f"Noted in {path}")
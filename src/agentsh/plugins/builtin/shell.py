@@ -3,15 +3,59 @@
 import asyncio
 import os
 import shlex
+import shutil
 import subprocess
-from typing import TYPE_CHECKING, Any, Optional
+from pathlib import Path
+from typing import TYPE_CHECKING, Any, Callable, Optional
 
 from agentsh.plugins.base import Toolset
-from agentsh.tools.base import RiskLevel, ToolResult
+from agentsh.security.trash import rewrite_rm_to_trash
+from agentsh.telemetry.logger import get_logger
+from agentsh.tools.base import RiskLevel, ToolResult, decode_output
+from agentsh.utils.validators import PathValidator
 
 if TYPE_CHECKING:
     from agentsh.tools.registry import ToolRegistry
 
+logger = get_logger(__name__)
+
+# Shell used when neither shell.exec_shell nor shell.backend names a usable one
+DEFAULT_EXEC_SHELL = "/bin/sh"
+
+# Shells that run POSIX ``-c`` scripts; others (fish, csh, tcsh, agentsh
+# itself) would misread the AI's commands
+POSIX_SHELLS = ("sh", "bash", "zsh", "dash", "ksh", "mksh", "ash")
+
+
+def resolve_exec_shell(
+    configured: Optional[str] = None,
+    backend: Optional[str] = None,
+) -> str:
+    """Pick the shell that runs commands, so their syntax is the user's.
+
+    Tries shell.exec_shell, then shell.backend, then falls back to
+    /bin/sh. A name without a slash is looked up in PATH; shells that are
+    not POSIX shells are skipped.
+
+    Args:
+        configured: The shell.exec_shell setting
+        backend: The shell.backend setting
+
+    Returns:
+        Path of an executable shell accepting ``-c``
+    """
+    for candidate in (configured, backend):
+        if not candidate:
+            continue
+        path = shutil.which(os.path.expanduser(candidate))
+        if not path:
+            logger.warning("Shell for commands not found, trying the next one", shell=candidate)
+        elif os.path.basename(os.path.realpath(path)) not in POSIX_SHELLS:
+            logger.warning("Shell for commands is not a POSIX shell, skipped", shell=candidate)
+        else:
+            return path
+    return DEFAULT_EXEC_SHELL
+
 
 class ShellToolset(Toolset):
     """Provides shell command execution tools.
@@ -23,6 +67,87 @@ class ShellToolset(Toolset):
     - shell.env: Get environment variable value
     """
 
+    def __init__(
+        self,
+        confirm_cwd: Optional[Callable[[str], bool]] = None,
+        trash_dir: Optional[Path] = None,
+        strict_output: bool = False,
+        exec_shell: Optional[str] = None,
+        backend: Optional[str] = None,
+    ) -> None:
+        """Initialize the shell toolset.
+
+        Args:
+            confirm_cwd: Asked before running in a protected working
+                directory (e.g. /etc); without it such directories are refused
+            trash_dir: Move rm targets here instead of deleting them
+                (security.rm_to_trash); None deletes as usual
+            strict_output: Show output that is not valid UTF-8 as a
+                placeholder instead of lossy text (shell.output_encoding)
+            exec_shell: Shell that runs commands with ``-c``
+                (shell.exec_shell); None uses backend, falling back to sh
+            backend: The user's shell (shell.backend)
+        """
+        self._confirm_cwd = confirm_cwd
+        self.trash_dir = trash_dir
+        self.strict_output = strict_output
+        self.exec_shell = exec_shell
+        self.backend = backend
+
+    def configure(self, config: dict[str, Any]) -> None:
+        """Configure the toolset from its plugin settings.
+
+        Args:
+            config: Plugin configuration; ``exec_shell`` and ``backend``
+                choose the shell running commands, and ``confirm_cwd``,
+                ``trash_dir`` and ``strict_output`` are as in the constructor
+        """
+        if "exec_shell" in config:
+            self.exec_shell = config["exec_shell"]
+        if "backend" in config:
+            self.backend = config["backend"]
+        if "confirm_cwd" in config:
+            self._confirm_cwd = config["confirm_cwd"]
+        if "trash_dir" in config:
+            self.trash_dir = config["trash_dir"]
+        if "strict_output" in config:
+            self.strict_output = bool(config["strict_output"])
+
+    def _check_working_dir(self, working_dir: str) -> Optional[ToolResult]:
+        """Validate a command's working directory before running it.
+
+        Args:
+            working_dir: Requested working directory
+
+        Returns:
+            A failed ToolResult if the command must not run there, else None
+        """
+        if not os.path.exists(working_dir):
+            return ToolResult(
+                success=False,
+                error=f"Working directory not found (does not exist): {working_dir}",
+                exit_code=1,
+            )
+        if not os.path.isdir(working_dir):
+            return ToolResult(
+                success=False,
+                error=f"Working directory is not a directory: {working_dir}",
+                exit_code=1,
+            )
+
+        resolved = os.path.realpath(working_dir)
+        for protected in PathValidator.PROTECTED_DIRS - {"/"}:
+            if resolved == protected or resolved.startswith(f"{protected}/"):
+                if self._confirm_cwd is not None and self._confirm_cwd(resolved):
+                    return None
+                return ToolResult(
+                    success=False,
+                    error=f"Working directory is inside protected path {protected}: {resolved}",
+                    exit_code=1,
+                )
+
+        return None
+
     @property
     def name(self) -> str:
         return "shell"
@@ -53,6 +178,26 @@ class ShellToolset(Toolset):
                         "description": "Timeout in seconds",
                         "default": 30,
                     },
+                    "description": {
+                        "type": "string",
+                        "description": "One-line summary of what this step does",
+                    },
+                    "is_destructive": {
+                        "type": "boolean",
+                        "description": (
+                            "True if this step deletes or overwrites data, needs sudo, "
+                            "or stops services"
+                        ),
+                    },
+                    "expected_exit_codes": {
+                        "type": "array",
+                        "items": {"type": "integer"},
+                        "description": (
+                            "Exit codes that mean the step worked, e.g. [0, 1] for grep "
+                            "where 1 means no match (default [0]); any other code, "
+                            "0 included, fails the step"
+                        ),
+                    },
                 },
                 "required": ["command"],
             },
@@ -125,6 +270,9 @@ class ShellToolset(Toolset):
         command: str,
         cwd: Optional[str] = None,
         timeout: int = 30,
+        description: Optional[str] = None,
+        is_destructive: bool = False,
+        expected_exit_codes: Optional[list[int]] = None,
     ) -> ToolResult:
         """Execute a shell command.
 
@@ -132,6 +280,12 @@ class ShellToolset(Toolset):
             command: Shell command to execute
             cwd: Working directory (optional)
             timeout: Timeout in seconds
+            description: Summary of the step, used for plan export (unused here)
+            is_destructive: Risk flag set by the AI, used for confirmation
+                and plan export (unused here)
+            expected_exit_codes: Exit codes treated as success, replacing
+                the default [0], so [1] makes exit code 0 a failure; the
+                actual code is still reported in the result
 
         Returns:
             ToolResult with command output
@@ -139,17 +293,27 @@ class ShellToolset(Toolset):
         if not command.strip():
             return ToolResult(success=False, error="Empty command")
 
-        # Resolve working directory
+        # Resolve and validate working directory
         working_dir = cwd or os.getcwd()
-        if not os.path.isdir(working_dir):
-            return ToolResult(
-                success=False,
-                error=f"Working directory does not exist: {working_dir}",
-            )
+        if cwd:
+            rejected = self._check_working_dir(working_dir)
+            if rejected is not None:
+                return rejected
+
+        notes: list[str] = []
+        if self.trash_dir is not None:
+            rewrite = rewrite_rm_to_trash(command, self.trash_dir, working_dir)
+            command = rewrite.command
+            if rewrite.trash_dir is not None:
+                notes.append(f"Moved to trash: {rewrite.trash_dir}")
+            if rewrite.warning:
+                notes.append(f"Warning: {rewrite.warning}")
 
         try:
-            # Run command with subprocess
-            proc = await asyncio.create_subprocess_shell(
+            # Run command in the user's shell, so bash/zsh syntax works
+            proc = await asyncio.create_subprocess_exec(
+                resolve_exec_shell(self.exec_shell, self.backend),
+                "-c",
                 command,
                 stdout=asyncio.subprocess.PIPE,
                 stderr=asyncio.subprocess.PIPE,
@@ -170,8 +334,8 @@ class ShellToolset(Toolset):
                 )
 
             # Decode output
-            stdout_str = stdout.decode("utf-8", errors="replace").strip()
-            stderr_str = stderr.decode("utf-8", errors="replace").strip()
+            stdout_str = decode_output(stdout, self.strict_output).strip()
+            stderr_str = decode_output(stderr, self.strict_output).strip()
 
             # Combine output
             output = stdout_str
@@ -180,19 +344,32 @@ class ShellToolset(Toolset):
                     output += f"\n\nSTDERR:\n{stderr_str}"
                 else:
                     output = f"STDERR:\n{stderr_str}"
+            if notes:
+                output = "\n".join([*notes, output]) if output else "\n".join(notes)
 
             # Check exit code
-            if proc.returncode == 0:
+            expected = expected_exit_codes or [0]
+            if proc.returncode not in expected:
+                error = f"Command exited with code {proc.returncode}"
+                if expected != [0]:
+                    error += f", expected {', '.join(str(code) for code in expected)}"
+                return ToolResult(
+                    success=False,
+                    output=output,
+                    error=error,
+                    exit_code=proc.returncode,
+                )
+            elif proc.returncode == 0:
                 return ToolResult(
                     success=True,
                     output=output or "(no output)",
                     exit_code=0,
                 )
             else:
+                note = f"(exit code {proc.returncode}, expected)"
                 return ToolResult(
-                    success=False,
-                    output=output,
-                    error=f"Command exited with code {proc.returncode}",
+                    success=True,
+                    output=f"{output}\n{note}" if output else note,
                     exit_code=proc.returncode,
                 )
 
@@ -0,0 +1,352 @@
+"""External plugins - Tools implemented by a program in any language.
+
+A directory in the plugins directory containing a ``plugin.json`` manifest
+describes a plugin whose tools are run by an external command. For each
+tool call the command is started, sent one JSON request on stdin, and
+must print one JSON response on stdout.
+
+Two framings are supported, chosen by the manifest's ``protocol`` key:
+
+- ``agentsh`` (default): ``{"id", "tool", "params"}`` in, and
+  ``{"id", "output"}`` or ``{"id", "error"}`` out
+- ``jsonrpc``: JSON-RPC 2.0, with the tool name as ``method`` and a
+  ``result`` or ``error`` object in the response, so existing JSON-RPC
+  libraries can implement plugins
+
+Example manifest:
+    {
+      "name": "weather",
+      "description": "Weather lookups",
+      "command": ["python3", "weather.py"],
+      "protocol": "jsonrpc",
+      "tools": [{"name": "forecast", "description": "...", "parameters": {...}}]
+    }
+"""
+
+import json
+import shlex
+import subprocess
+import uuid
+from dataclasses import dataclass, field
+from enum import Enum
+from pathlib import Path
+from typing import TYPE_CHECKING, Any, Callable, Optional
+
+from agentsh.plugins.base import Toolset
+from agentsh.telemetry.logger import get_logger
+from agentsh.tools.base import RiskLevel, ToolResult
+
+if TYPE_CHECKING:
+    from agentsh.tools.registry import ToolRegistry
+
+logger = get_logger(__name__)
+
+MANIFEST_FILE = "plugin.json"
+
+JSONRPC_VERSION = "2.0"
+
+
+class PluginProtocol(str, Enum):
+    """Message framing spoken by an external plugin."""
+
+    AGENTSH = "agentsh"
+    JSONRPC = "jsonrpc"
+
+
+@dataclass
+class PluginToolSpec:
+    """A tool declared in a plugin manifest.
+
+    Attributes:
+        name: Tool name, registered as ``<plugin>.<name>``
+        description: What the tool does
+        parameters: JSON schema for the tool's parameters
+        risk_level: Risk classification (defaults to medium)
+    """
+
+    name: str
+    description: str = ""
+    parameters: dict[str, Any] = field(default_factory=dict)
+    risk_level: RiskLevel = RiskLevel.MEDIUM
+
+
+@dataclass
+class PluginManifest:
+    """Contents of a plugin's ``plugin.json``.
+
+    Attributes:
+        name: Plugin name
+        command: Program and arguments run for each tool call
+        description: What the plugin provides
+        protocol: Message framing the program speaks
+        tools: Tools the plugin provides
+        directory: Directory the command runs in
+    """
+
+    name: str
+    command: list[str]
+    description: str = ""
+    protocol: PluginProtocol = PluginProtocol.AGENTSH
+    tools: list[PluginToolSpec] = field(default_factory=list)
+    directory: Optional[Path] = None
+
+    @classmethod
+    def load(cls, path: Path) -> "PluginManifest":
+        """Read a plugin manifest.
+
+        Args:
+            path: Path to plugin.json
+
+        Returns:
+            The parsed manifest
+
+        Raises:
+            ValueError: If the file is not a valid manifest
+        """
+        try:
+            data = json.loads(path.read_text(encoding="utf-8"))
+        except (OSError, json.JSONDecodeError) as e:
+            raise ValueError(f"Cannot read plugin manifest {path}: {e}") from e
+        if not isinstance(data, dict) or not data.get("name") or not data.get("command"):
+            raise ValueError(f"Plugin manifest {path} needs a name and a command")
+
+        command = data["command"]
+        if isinstance(command, str):
+            command = shlex.split(command)
+        try:
+            protocol = PluginProtocol(data.get("protocol", PluginProtocol.AGENTSH.value))
+            tools = [
+                PluginToolSpec(
+                    name=tool["name"],
+                    description=tool.get("description", ""),
+                    parameters=tool.get("parameters") or {"type": "object", "properties": {}},
+                    risk_level=RiskLevel(tool.get("risk_level", RiskLevel.MEDIUM.value)),
+                )
+                for tool in data.get("tools", [])
+            ]
+        except (KeyError, TypeError, ValueError) as e:
+            raise ValueError(f"Invalid plugin manifest {path}: {e}") from e
+
+        return cls(
+            name=str(data["name"]),
+            command=[str(part) for part in command],
+            description=str(data.get("description", "")),
+            protocol=protocol,
+            tools=tools,
+            directory=path.parent,
+        )
+
+
+def encode_request(
+    protocol: PluginProtocol,
+    request_id: str,
+    tool: str,
+    params: dict[str, Any],
+) -> dict[str, Any]:
+    """Build the request sent to a plugin for a tool call.
+
+    Args:
+        protocol: Framing the plugin speaks
+        request_id: Identifier echoed back in the response
+        tool: Tool name, without the plugin prefix
+        params: Tool arguments
+
+    Returns:
+        The request message
+    """
+    if protocol == PluginProtocol.JSONRPC:
+        return {"jsonrpc": JSONRPC_VERSION, "id": request_id, "method": tool, "params": params}
+    return {"id": request_id, "tool": tool, "params": params}
+
+
+def _output_text(value: Any) -> str:
+    """Turn a plugin's result into tool output."""
+    if value is None:
+        return ""
+    if isinstance(value, str):
+        return value
+    if isinstance(value, dict) and isinstance(value.get("output"), str):
+        return value["output"]
+    return json.dumps(value)
+
+
+def decode_response(
+    protocol: PluginProtocol,
+    request_id: str,
+    response: Any,
+) -> ToolResult:
+    """Turn a plugin's response into a tool result.
+
+    Args:
+        protocol: Framing the plugin speaks
+        request_id: Identifier of the request being answered
+        response: The decoded JSON response
+
+    Returns:
+        ToolResult with the output, or the plugin's error
+    """
+    if not isinstance(response, dict):
+        return ToolResult(success=False, error="Plugin response is not a JSON object")
+    if response.get("id") != request_id:
+        return ToolResult(success=False, error="Plugin response does not match the request id")
+
+    if protocol == PluginProtocol.JSONRPC:
+        if response.get("jsonrpc") != JSONRPC_VERSION:
+            return ToolResult(success=False, error="Plugin response is not JSON-RPC 2.0")
+        error = response.get("error")
+        if error is not None:
+            if not isinstance(error, dict):
+                return ToolResult(success=False, error=f"Plugin error: {error}")
+            return ToolResult(
+                success=False,
+                error=f"Plugin error {error.get('code')}: {error.get('message', '')}",
+                metadata={"data": error["data"]} if "data" in error else {},
+            )
+        if "result" not in response:
+            return ToolResult(success=False, error="Plugin response has no result or error")
+        return ToolResult(success=True, output=_output_text(response["result"]))
+
+    if response.get("error"):
+        return ToolResult(success=False, error=f"Plugin error: {response['error']}")
+    return ToolResult(success=True, output=_output_text(response.get("output")))
+
+
+class PluginExecutor:
+    """Runs an external plugin's command for each tool call.
+
+    Example:
+        executor = PluginExecutor(["./weather"], PluginProtocol.JSONRPC)
+        result = executor.call("forecast", {"city": "Oslo"})
+    """
+
+    def __init__(
+        self,
+        command: list[str],
+        protocol: PluginProtocol = PluginProtocol.AGENTSH,
+        cwd: Optional[Path] = None,
+        timeout: float = 30.0,
+    ) -> None:
+        """Initialize the executor.
+
+        Args:
+            command: Program and arguments to run
+            protocol: Framing the program speaks
+            cwd: Directory to run the command in
+            timeout: Seconds a call may take
+        """
+        self.command = command
+        self.protocol = protocol
+        self.cwd = cwd
+        self.timeout = timeout
+
+    def call(self, tool: str, params: dict[str, Any]) -> ToolResult:
+        """Run one tool call through the plugin.
+
+        Args:
+            tool: Tool name, without the plugin prefix
+            params: Tool arguments
+
+        Returns:
+            ToolResult from the plugin's response
+        """
+        request_id = uuid.uuid4().hex
+        request = encode_request(self.protocol, request_id, tool, params)
+        try:
+            completed = subprocess.run(
+                self.command,
+                input=json.dumps(request) + "\n",
+                capture_output=True,
+                text=True,
+                cwd=self.cwd,
+                timeout=self.timeout,
+            )
+        except subprocess.TimeoutExpired:
+            return ToolResult(success=False, error=f"Plugin timed out after {self.timeout:g}s")
+        except OSError as e:
+            return ToolResult(success=False, error=f"Cannot run plugin: {e}")
+        logger.debug("Plugin call finished", tool=tool, exit_code=completed.returncode)
+
+        try:
+            response = json.loads(completed.stdout)
+        except json.JSONDecodeError:
+            stderr = completed.stderr.strip()
+            return ToolResult(
+                success=False,
+                error=f"Plugin did not reply with JSON (exit code {completed.returncode})"
+                + (f": {stderr[:200]}" if stderr else ""),
+                exit_code=completed.returncode,
+            )
+        return decode_response(self.protocol, request_id, response)
+
+
+class ExternalToolset(Toolset):
+    """Toolset whose tools are run by an external plugin program."""
+
+    def __init__(self, manifest: PluginManifest) -> None:
+        """Initialize from a manifest.
+
+        Args:
+            manifest: The plugin's manifest
+        """
+        self.manifest = manifest
+        self.executor = PluginExecutor(
+            manifest.command,
+            protocol=manifest.protocol,
+            cwd=manifest.directory,
+        )
+
+    @property
+    def name(self) -> str:
+        return self.manifest.name
+
+    @property
+    def description(self) -> str:
+        return self.manifest.description
+
+    def register_tools(self, registry: "ToolRegistry") -> None:
+        """Register the tools declared in the manifest."""
+        for spec in self.manifest.tools:
+            registry.register_tool(
+                name=f"{self.name}.{spec.name}",
+                handler=self._handler(spec.name),
+                description=spec.description,
+                parameters=spec.parameters,
+                risk_level=spec.risk_level,
+                plugin_name=self.name,
+            )
+
+    def _handler(self, tool: str) -> Callable[..., ToolResult]:
+        """Make the handler that runs one of the plugin's tools.
+
+        The tool name is bound in a closure, not a keyword default, so no
+        argument of a call can replace it.
+
+        Args:
+            tool: Tool name as declared in the manifest
+
+        Returns:
+            Handler passing its keyword arguments to the plugin
+        """
+
+        def handler(**params: Any) -> ToolResult:
+            return self.executor.call(tool, params)
+
+        return handler
+
+
+def external_toolset_class(manifest: PluginManifest) -> type[Toolset]:
+    """Make a Toolset class bound to a manifest, for plugin discovery.
+
+    Args:
+        manifest: The plugin's manifest
+
+    Returns:
+        A Toolset subclass constructible without arguments
+    """
+
+    class _ManifestToolset(ExternalToolset):
+        def __init__(self) -> None:
+            super().__init__(manifest)
+
+    _ManifestToolset.__name__ = f"ExternalToolset[{manifest.name}]"
+    return _ManifestToolset
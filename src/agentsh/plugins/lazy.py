@@ -287,6 +287,7 @@ BUILTIN_PLUGINS: list[tuple[str, str, str]] = [
     ("filesystem", "agentsh.plugins.builtin.filesystem", "FilesystemToolset"),
     ("process", "agentsh.plugins.builtin.process", "ProcessToolset"),
     ("code", "agentsh.plugins.builtin.code", "CodeToolset"),
+    ("notes", "agentsh.plugins.builtin.notes", "NotesToolset"),
     ("remote", "agentsh.plugins.builtin.remote", "RemoteToolset"),
 ]
 
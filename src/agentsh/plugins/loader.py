@@ -4,9 +4,12 @@ import importlib
 import importlib.metadata
 import sys
 from pathlib import Path
-from typing import TYPE_CHECKING, Optional
+from typing import TYPE_CHECKING, Any, Callable, Optional
 
+from agentsh.config.schemas import OutputEncoding
 from agentsh.plugins.base import Toolset, ToolsetRegistry, get_toolset_registry
+from agentsh.plugins.external import MANIFEST_FILE, PluginManifest, external_toolset_class
+from agentsh.security.trash import session_trash_dir
 from agentsh.telemetry.logger import get_logger
 
 if TYPE_CHECKING:
@@ -56,6 +59,13 @@ def discover_builtin_plugins() -> list[type[Toolset]]:
     except ImportError:
         pass
 
+    try:
+        from agentsh.plugins.builtin.notes import NotesToolset
+
+        plugins.append(NotesToolset)
+    except ImportError:
+        pass
+
     return plugins
 
 
@@ -95,7 +105,8 @@ def discover_directory_plugins(plugins_dir: Path) -> list[type[Toolset]]:
     """Discover plugins from a directory.
 
     Each subdirectory or .py file in the plugins directory is checked
-    for a Toolset subclass.
+    for a Toolset subclass. A subdirectory with a plugin.json manifest is
+    an external plugin whose tools are run by the manifest's command.
 
     Args:
         plugins_dir: Directory to scan for plugins
@@ -123,6 +134,12 @@ def discover_directory_plugins(plugins_dir: Path) -> list[type[Toolset]]:
                     plugins.append(plugin_class)
                     logger.info("Discovered plugin from file", file=str(item))
 
+            elif item.is_dir() and (item / MANIFEST_FILE).exists():
+                # External plugin
+                manifest = PluginManifest.load(item / MANIFEST_FILE)
+                plugins.append(external_toolset_class(manifest))
+                logger.info("Discovered external plugin", path=str(item))
+
             elif item.is_dir() and (item / "__init__.py").exists():
                 # Package plugin
                 module_name = item.name
@@ -162,10 +179,40 @@ def _find_toolset_class(module: object) -> Optional[type[Toolset]]:
     return None
 
 
+def _core_settings(
+    config: "AgentSHConfig",
+    name: str,
+    confirm_cwd: Optional[Callable[[str], bool]] = None,
+) -> dict[str, Any]:
+    """Get the settings a builtin toolset takes from outside its plugin section.
+
+    Args:
+        config: AgentSH configuration
+        name: Toolset name
+        confirm_cwd: Asked before a shell step runs in a protected directory
+
+    Returns:
+        Settings passed to the toolset's configure()
+    """
+    if name == "shell":
+        return {
+            "exec_shell": config.shell.exec_shell,
+            "backend": config.shell.backend,
+            "trash_dir": session_trash_dir() if config.security.rm_to_trash else None,
+            "strict_output": config.shell.output_encoding == OutputEncoding.STRICT,
+            "confirm_cwd": confirm_cwd,
+        }
+    if name == "filesystem" and config.security.workspace_root is not None:
+        return {"root": str(config.security.workspace_root)}
+    return {}
+
+
 def load_plugins(
     config: "AgentSHConfig",
     tool_registry: "ToolRegistry",
     plugins_dir: Optional[Path] = None,
+    registry: Optional[ToolsetRegistry] = None,
+    confirm_cwd: Optional[Callable[[str], bool]] = None,
 ) -> ToolsetRegistry:
     """Load all plugins based on configuration.
 
@@ -173,11 +220,16 @@ def load_plugins(
         config: AgentSH configuration
         tool_registry: Tool registry to register tools with
         plugins_dir: Optional custom plugins directory
+        registry: Toolset registry to load into (default: the global one,
+            which registers each toolset's tools only once per process)
+        confirm_cwd: Asked before a shell step runs in a protected working
+            directory; without it such directories are refused
 
     Returns:
         ToolsetRegistry with loaded plugins
     """
-    registry = get_toolset_registry()
+    if registry is None:
+        registry = get_toolset_registry()
 
     # Discover all available plugins
     all_plugins: list[type[Toolset]] = []
@@ -213,7 +265,12 @@ def load_plugins(
         success = registry.load_toolset(
             name=plugin_config.name,
             tool_registry=tool_registry,
-            config=plugin_config.config,
+            # Core settings win over the plugin section, which a project
+            # config may set
+            config={
+                **plugin_config.config,
+                **_core_settings(config, plugin_config.name, confirm_cwd),
+            },
         )
 
         if success:
@@ -221,4 +278,7 @@ def load_plugins(
         else:
             logger.warning("Plugin not found or failed to load", name=plugin_config.name)
 
+    for tool_name, policy in config.security.tool_policy.items():
+        tool_registry.set_confirmation_override(tool_name, policy.requires_confirmation)
+
     return registry
@@ -2,6 +2,7 @@
 
 from agentsh.security.approval import (
     ApprovalFlow,
+    ApprovalKind,
     ApprovalRequest,
     ApprovalResponse,
     ApprovalResult,
@@ -20,6 +21,7 @@ from agentsh.security.controller import (
     SecurityDecision,
     ValidationResult,
 )
+from agentsh.security.effects import preview_effects
 from agentsh.security.policies import (
     DevicePolicy,
     PolicyManager,
@@ -45,11 +47,14 @@ __all__ = [
     "User",
     "RBAC",
     # Approval
+    "ApprovalKind",
     "ApprovalResult",
     "ApprovalRequest",
     "ApprovalResponse",
     "ApprovalFlow",
     "AutoApprover",
+    # Effects
+    "preview_effects",
     # Audit
     "AuditAction",
     "AuditEvent",
@@ -1,27 +1,117 @@
 """Human-in-the-Loop Approval - Interactive command approval."""
 
+import os
+import secrets
+import shlex
+import subprocess
 import sys
-from dataclasses import dataclass
+import tempfile
+from dataclasses import dataclass, field
 from datetime import datetime
 from enum import Enum
 from typing import Callable, Optional
 
 from agentsh.security.classifier import RiskLevel
 from agentsh.telemetry.logger import get_logger
+from agentsh.utils.validators import redact_secrets
 
 logger = get_logger(__name__)
 
 
+PLAN_HEADER = """\
+# Edit the commands below, one per line. Lines starting with '#' are ignored.
+# End a line with '\\' to continue a command on the next line.
+# Save and quit to continue; delete every command to cancel.
+"""
+
+
+# Commands longer than this are confirmed with a random token instead of
+# being retyped
+RETYPE_MAX_CHARS = 80
+
+# Length of the token typed to confirm, in hex characters
+RETYPE_TOKEN_CHARS = 6
+
+# Commands at this risk level or above are confirmed every time; they are
+# never trusted for the rest of the session
+SESSION_APPROVAL_LIMIT = RiskLevel.HIGH
+
+
+class PlanParseError(ValueError):
+    """An edited plan buffer could not be parsed back into commands."""
+
+
+def serialize_plan(commands: list[str]) -> str:
+    """Render commands as an editable text buffer.
+
+    Multi-line commands are written with trailing backslashes so that
+    parse_plan() restores them exactly.
+
+    Args:
+        commands: Commands in execution order
+
+    Returns:
+        Buffer text with an explanatory comment header
+    """
+    body = "\n".join(command.replace("\n", "\\\n") for command in commands)
+    return f"{PLAN_HEADER}{body}\n"
+
+
+def parse_plan(buffer: str) -> list[str]:
+    """Parse an edited plan buffer back into commands.
+
+    Args:
+        buffer: Text produced by serialize_plan() and edited by the user
+
+    Returns:
+        Commands in order. Empty if the user deleted every command.
+
+    Raises:
+        PlanParseError: If a continuation line is left unterminated
+    """
+    commands: list[str] = []
+    pending: Optional[list[str]] = None
+
+    for line in buffer.splitlines():
+        if pending is None and (not line.strip() or line.lstrip().startswith("#")):
+            continue
+
+        parts = pending if pending is not None else []
+        if line.endswith("\\"):
+            parts.append(line[:-1])
+            pending = parts
+            continue
+
+        parts.append(line)
+        commands.append("\n".join(parts).strip())
+        pending = None
+
+    if pending is not None:
+        raise PlanParseError("Last command ends with '\\' but has no continuation line")
+
+    return commands
+
+
 class ApprovalResult(Enum):
     """Result of an approval request."""
 
     APPROVED = "approved"  # User approved the command
+    APPROVED_SESSION = "approved_session"  # Approved, and trusted for the rest of the session
     DENIED = "denied"  # User denied the command
     EDITED = "edited"  # User edited the command
     TIMEOUT = "timeout"  # Request timed out
     SKIPPED = "skipped"  # Approval was skipped (permissive mode)
 
 
+class ApprovalKind(str, Enum):
+    """How a command that ran was authorized, as recorded in the audit log."""
+
+    CONFIRMED = "confirmed"  # User approved it, now or for the session
+    AUTO_APPROVED = "auto_approved"  # Policy let it run without asking
+    EDITED = "edited"  # User edited it, then approved the edited command
+    RETYPED = "retyped"  # User typed it (or a token) again to approve it
+
+
 @dataclass
 class ApprovalRequest:
     """Request for human approval.
@@ -32,6 +122,11 @@ class ApprovalRequest:
         reasons: Reasons for requiring approval
         context: Additional context (cwd, device, etc.)
         timeout: Approval timeout in seconds
+        effects: Plain-English description of the command's side effects
+        require_retype: Approve only when the user types the command (or a
+            confirmation token) again, instead of answering "y"
+        plan: Commands of the plan from this one on, edited together in
+            $EDITOR (empty for a command on its own)
     """
 
     command: str
@@ -39,6 +134,9 @@ class ApprovalRequest:
     reasons: list[str]
     context: dict[str, str]
     timeout: float = 30.0
+    effects: list[str] = field(default_factory=list)
+    require_retype: bool = False
+    plan: list[str] = field(default_factory=list)
 
 
 @dataclass
@@ -51,6 +149,8 @@ class ApprovalResponse:
         approver: Who approved/denied
         timestamp: When the decision was made
         reason: Reason for the decision
+        plan: The edited plan, one command per step from this one on, when
+            it was edited in $EDITOR; ``command`` is its first command
     """
 
     result: ApprovalResult
@@ -58,6 +158,7 @@ class ApprovalResponse:
     approver: str
     timestamp: datetime
     reason: Optional[str] = None
+    plan: list[str] = field(default_factory=list)
 
 
 class ApprovalFlow:
@@ -95,6 +196,8 @@ class ApprovalFlow:
         use_color: bool = True,
         input_func: Optional[Callable[[], str]] = None,
         output_func: Optional[Callable[[str], None]] = None,
+        editor_func: Optional[Callable[[str], Optional[str]]] = None,
+        redact_display: bool = True,
     ) -> None:
         """Initialize the approval flow.
 
@@ -102,22 +205,70 @@ class ApprovalFlow:
             use_color: Whether to use ANSI colors
             input_func: Custom input function (for testing)
             output_func: Custom output function (for testing)
+            editor_func: Custom editor function taking and returning the
+                buffer text, or None if editing failed (for testing)
+            redact_display: Mask secrets in commands shown on screen. The
+                command that runs is never changed.
         """
         self.use_color = use_color
+        self.redact_display = redact_display
         self._input = input_func or self._default_input
+        self._text_input = input_func or self._default_text_input
         self._output = output_func or self._default_output
+        self._editor = editor_func or self._default_editor
 
     def _default_input(self) -> str:
-        """Default input function."""
+        """Default input function for answers, which are case-insensitive."""
+        return self._default_text_input().lower()
+
+    def _default_text_input(self) -> str:
+        """Default input function for text kept as typed.
+
+        Used for edited and retyped commands, and for the approval answer
+        itself, where ``E`` and ``e`` are different options.
+        """
         try:
-            return input().strip().lower()
+            return input().strip()
         except EOFError:
             return "n"
 
+    def _default_editor(self, text: str) -> Optional[str]:
+        """Open text in $VISUAL or $EDITOR and return the saved result."""
+        editor = os.environ.get("VISUAL") or os.environ.get("EDITOR") or "vi"
+
+        fd, path = tempfile.mkstemp(prefix="agentsh-plan-", suffix=".sh")
+        try:
+            with os.fdopen(fd, "w") as f:
+                f.write(text)
+
+            result = subprocess.run([*shlex.split(editor), path])
+            if result.returncode != 0:
+                logger.warning("Editor exited with error", editor=editor, code=result.returncode)
+                return None
+
+            with open(path) as f:
+                return f.read()
+        except OSError as e:
+            logger.warning("Failed to run editor", editor=editor, error=str(e))
+            return None
+        finally:
+            os.unlink(path)
+
     def _default_output(self, text: str) -> None:
         """Default output function."""
         print(text, file=sys.stderr)
 
+    def _shown(self, command: str) -> str:
+        """Get a command as it should be displayed.
+
+        Args:
+            command: Command to display
+
+        Returns:
+            The command with secrets masked when redact_display is set
+        """
+        return redact_secrets(command) if self.redact_display else command
+
     def _colorize(self, text: str, color: str) -> str:
         """Apply color to text if colors enabled."""
         if self.use_color:
@@ -136,18 +287,22 @@ class ApprovalFlow:
         Returns:
             ApprovalResponse with the user's decision
         """
-        import os
-
         approver = os.environ.get("USER", os.environ.get("USERNAME", "unknown"))
 
         # Display approval request
         self._display_request(request)
 
+        if request.require_retype:
+            return self._handle_retype(request, approver)
+
         # Get user response
-        self._output("\n[y]es / [n]o / [e]dit / [s]kip > ")
+        session_allowed = request.risk_level < SESSION_APPROVAL_LIMIT
+        always = "[a]lways this session / " if session_allowed else ""
+        self._output(f"\n[y]es / {always}[n]o / [e]dit / [E]dit in $EDITOR / [s]kip > ")
 
         try:
-            response = self._input()
+            raw = self._text_input().strip()
+            response = raw.lower()
         except KeyboardInterrupt:
             self._output("\nApproval cancelled.\n")
             return ApprovalResponse(
@@ -168,6 +323,16 @@ class ApprovalFlow:
                 timestamp=datetime.now(),
             )
 
+        elif response in ("a", "always") and session_allowed:
+            logger.info("Command approved for this session", command=request.command[:50])
+            return ApprovalResponse(
+                result=ApprovalResult.APPROVED_SESSION,
+                command=request.command,
+                approver=approver,
+                timestamp=datetime.now(),
+                reason="Approved for this session",
+            )
+
         elif response in ("n", "no", ""):
             logger.info("Command denied", command=request.command[:50])
             return ApprovalResponse(
@@ -177,6 +342,9 @@ class ApprovalFlow:
                 timestamp=datetime.now(),
             )
 
+        elif raw == "E":
+            return self._handle_editor(request, approver)
+
         elif response in ("e", "edit"):
             return self._handle_edit(request, approver)
 
@@ -201,6 +369,59 @@ class ApprovalFlow:
                 reason=f"Invalid response: {response}",
             )
 
+    def _handle_retype(self, request: ApprovalRequest, approver: str) -> ApprovalResponse:
+        """Approve a dangerous command only if the user types it again.
+
+        Commands that are long, span several lines or are shown with
+        secrets masked are confirmed with a short random token instead.
+
+        Args:
+            request: The approval request
+            approver: User being asked
+
+        Returns:
+            APPROVED if the typed text matches exactly, DENIED otherwise
+        """
+        command = request.command.strip()
+        shown = self._shown(command)
+        if len(command) > RETYPE_MAX_CHARS or "\n" in command or shown != command:
+            expected = secrets.token_hex(RETYPE_TOKEN_CHARS // 2)
+            self._output(
+                f"\nThis operation is dangerous. Type {self._colorize(expected, self.BOLD)} "
+                "to run it (anything else cancels) > "
+            )
+        else:
+            expected = command
+            self._output(
+                "\nThis operation is dangerous. Type the command exactly to run it "
+                "(anything else cancels) > "
+            )
+
+        try:
+            typed = self._text_input().strip()
+        except KeyboardInterrupt:
+            typed = None
+
+        if typed == expected:
+            logger.info("Dangerous command approved by retyping", command=command[:50])
+            return ApprovalResponse(
+                result=ApprovalResult.APPROVED,
+                command=request.command,
+                approver=approver,
+                timestamp=datetime.now(),
+                reason="Confirmed by retyping",
+            )
+
+        self._output("Text did not match. Command denied.\n")
+        logger.info("Dangerous command denied: retype mismatch", command=command[:50])
+        return ApprovalResponse(
+            result=ApprovalResult.DENIED,
+            command=request.command,
+            approver=approver,
+            timestamp=datetime.now(),
+            reason="Retyped text did not match",
+        )
+
     def _display_request(self, request: ApprovalRequest) -> None:
         """Display the approval request to the user.
 
@@ -219,13 +440,18 @@ class ApprovalFlow:
 
         self._output(f"\n  Risk Level: {self._colorize(request.risk_level.name, color)}")
         self._output(f"\n  Command:")
-        self._output(f"    {self._colorize(request.command, self.BOLD)}")
+        self._output(f"    {self._colorize(self._shown(request.command), self.BOLD)}")
 
         if request.reasons:
             self._output(f"\n  Reasons:")
             for reason in request.reasons:
                 self._output(f"    - {reason}")
 
+        if request.effects:
+            self._output(f"\n  Effects:")
+            for effect in request.effects:
+                self._output(f"    - {effect}")
+
         if request.context:
             self._output(f"\n  Context:")
             for key, value in request.context.items():
@@ -246,19 +472,19 @@ class ApprovalFlow:
             ApprovalResponse with edited command
         """
         self._output("\nEdit command (press Enter to confirm):")
-        self._output(f"Original: {request.command}")
+        self._output(f"Original: {self._shown(request.command)}")
         self._output("New command: ")
 
         try:
-            edited = self._input()
+            edited = self._text_input()
             if not edited:
                 edited = request.command
 
             # Re-display for confirmation
-            self._output(f"\nEdited command: {edited}")
+            self._output(f"\nEdited command: {self._shown(edited)}")
             self._output("Approve edited command? [y/n] > ")
 
-            confirm = self._input()
+            confirm = self._input().strip().lower()
             if confirm in ("y", "yes"):
                 logger.info(
                     "Command edited and approved",
@@ -285,6 +511,88 @@ class ApprovalFlow:
         )
 
 
+    def _handle_editor(
+        self, request: ApprovalRequest, approver: str
+    ) -> ApprovalResponse:
+        """Handle editing the plan in an external editor.
+
+        The whole plan from this command on is edited as one buffer, one
+        command per step. Steps can be changed or removed but not added;
+        the buffer is re-opened if it cannot be parsed, until the user
+        aborts.
+
+        Args:
+            request: Original approval request
+            approver: User doing the editing
+
+        Returns:
+            ApprovalResponse with the edited command and plan
+        """
+        plan = request.plan or [request.command]
+        buffer = serialize_plan(plan)
+
+        try:
+            while True:
+                edited_buffer = self._editor(buffer)
+                if edited_buffer is None:
+                    self._output("\nEditor failed. Command denied.\n")
+                    break
+
+                try:
+                    commands = parse_plan(edited_buffer)
+                    if len(commands) > len(plan):
+                        raise PlanParseError(
+                            f"The plan has {len(plan)} step(s) but {len(commands)} commands; "
+                            "put commands that belong to one step on the same line"
+                        )
+                except PlanParseError as e:
+                    self._output(f"\nCould not parse edited plan: {e}")
+                    self._output("[r]eopen editor / [a]bort > ")
+                    if self._input().strip().lower() in ("r", "reopen"):
+                        buffer = edited_buffer
+                        continue
+                    break
+
+                if not commands:
+                    self._output("\nAll commands removed. Command denied.\n")
+                    break
+
+                self._output("\nEdited plan:")
+                for number, command in enumerate(commands, start=1):
+                    self._output(f"  {number}. {self._shown(command)}")
+                if len(commands) < len(plan):
+                    self._output(f"  ({len(plan) - len(commands)} step(s) removed)")
+                self._output("Approve edited plan? [y/n] > ")
+
+                if self._input().strip().lower() in ("y", "yes"):
+                    logger.info(
+                        "Plan edited in editor and approved",
+                        original=request.command[:50],
+                        edited=commands[0][:50],
+                        steps=len(commands),
+                    )
+                    return ApprovalResponse(
+                        result=ApprovalResult.EDITED,
+                        command=commands[0],
+                        approver=approver,
+                        timestamp=datetime.now(),
+                        reason=f"Edited from: {request.command}",
+                        plan=commands,
+                    )
+                break
+
+        except KeyboardInterrupt:
+            self._output("\nEdit cancelled.\n")
+
+        return ApprovalResponse(
+            result=ApprovalResult.DENIED,
+            command=request.command,
+            approver=approver,
+            timestamp=datetime.now(),
+            reason="Edit cancelled",
+        )
+
+
 class AutoApprover:
     """Automatic approver for non-interactive contexts.
 
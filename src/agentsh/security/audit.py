@@ -6,10 +6,12 @@ from dataclasses import asdict, dataclass
 from datetime import datetime
 from enum import Enum
 from pathlib import Path
-from typing import Any, Optional
+from typing import Any, Iterable, Optional
 
+from agentsh.security.approval import ApprovalKind
 from agentsh.security.classifier import RiskLevel
-from agentsh.telemetry.logger import get_logger
+from agentsh.telemetry.logger import current_request_id, get_logger
+from agentsh.utils.validators import redact_network_identifiers
 
 logger = get_logger(__name__)
 
@@ -29,6 +31,7 @@ class AuditAction(Enum):
     TOOL_FAILED = "tool_failed"
     CONFIG_CHANGED = "config_changed"
     SECURITY_VIOLATION = "security_violation"
+    PLAN_INCOMPLETE = "plan_incomplete"
 
 
 @dataclass
@@ -46,6 +49,10 @@ class AuditEvent:
         device_id: Target device (if applicable)
         session_id: Session identifier
         metadata: Additional context
+        model: AI model that produced the action (if applicable)
+        provider: AI provider that served the model (if applicable)
+        request_id: AI request the action belongs to, matching the
+            X-Request-Id header and the request_id in debug logs
     """
 
     timestamp: datetime
@@ -58,6 +65,9 @@ class AuditEvent:
     device_id: Optional[str] = None
     session_id: Optional[str] = None
     metadata: Optional[dict[str, Any]] = None
+    model: Optional[str] = None
+    provider: Optional[str] = None
+    request_id: Optional[str] = None
 
     def to_dict(self) -> dict[str, Any]:
         """Convert to dictionary for serialization."""
@@ -86,6 +96,15 @@ class AuditEvent:
         if self.metadata:
             d["metadata"] = self.metadata
 
+        if self.model is not None:
+            d["model"] = self.model
+
+        if self.provider is not None:
+            d["provider"] = self.provider
+
+        if self.request_id is not None:
+            d["request_id"] = self.request_id
+
         return d
 
     def to_json(self) -> str:
@@ -106,6 +125,9 @@ class AuditEvent:
             device_id=data.get("device_id"),
             session_id=data.get("session_id"),
             metadata=data.get("metadata"),
+            model=data.get("model"),
+            provider=data.get("provider"),
+            request_id=data.get("request_id"),
         )
 
 
@@ -126,6 +148,12 @@ class AuditLogger:
 
         # Query recent events
         events = audit.get_recent(n=10)
+
+    Noisy sessions can restrict which actions are persisted:
+        audit = AuditLogger(logged_events=["command_blocked", "security_violation"])
+
+    Regulated environments can keep addresses and hostnames out of the log:
+        audit = AuditLogger(redact_network=True, redact_private_only=True)
     """
 
     def __init__(
@@ -133,6 +161,12 @@ class AuditLogger:
         log_path: Optional[Path] = None,
         session_id: Optional[str] = None,
         max_file_size: int = 10 * 1024 * 1024,  # 10 MB
+        logged_events: Optional[Iterable[str]] = None,
+        model: Optional[str] = None,
+        provider: Optional[str] = None,
+        max_write_failures: int = 3,
+        redact_network: bool = False,
+        redact_private_only: bool = False,
     ) -> None:
         """Initialize the audit logger.
 
@@ -140,10 +174,26 @@ class AuditLogger:
             log_path: Path to audit log file
             session_id: Current session identifier
             max_file_size: Maximum log file size before rotation
+            logged_events: AuditAction values to persist. None logs everything.
+            model: AI model recorded on events that don't set one
+            provider: AI provider recorded on events that don't set one
+            max_write_failures: Consecutive write failures before logging is
+                disabled for the rest of the session
+            redact_network: Redact IP addresses and hostnames in logged events
+            redact_private_only: With redact_network, only redact private
+                addresses and internal hostnames, keeping loopback
         """
         self.log_path = log_path or self._default_path()
         self.session_id = session_id or self._generate_session_id()
         self.max_file_size = max_file_size
+        self.logged_events = self._parse_logged_events(logged_events)
+        self.model = model
+        self.provider = provider
+        self.max_write_failures = max_write_failures
+        self.redact_network = redact_network
+        self.redact_private_only = redact_private_only
+        self._write_failures = 0
+        self._disabled = False
 
         # Ensure log directory exists
         self.log_path.parent.mkdir(parents=True, exist_ok=True)
@@ -154,6 +204,66 @@ class AuditLogger:
             session_id=self.session_id,
         )
 
+    @staticmethod
+    def _parse_logged_events(
+        logged_events: Optional[Iterable[str]],
+    ) -> Optional[set[AuditAction]]:
+        """Resolve configured event names to audit actions.
+
+        Args:
+            logged_events: Action names from configuration
+
+        Returns:
+            Set of actions to persist, or None to persist everything
+        """
+        if logged_events is None:
+            return None
+
+        actions: set[AuditAction] = set()
+        for name in logged_events:
+            try:
+                actions.add(AuditAction(name.strip().lower()))
+            except ValueError:
+                logger.warning("Unknown audit event in logged_events", event=name)
+        return actions
+
+    @property
+    def disabled(self) -> bool:
+        """Check if logging was disabled after repeated write failures."""
+        return self._disabled
+
+    def should_log(self, action: AuditAction) -> bool:
+        """Check whether an action is persisted by this logger.
+
+        Args:
+            action: Action type to check
+
+        Returns:
+            True if events of this type are written
+        """
+        return self.logged_events is None or action in self.logged_events
+
+    def redact_if_needed(self, text: str) -> str:
+        """Apply the configured redaction to text written to the log.
+
+        Args:
+            text: Command, result or metadata value
+
+        Returns:
+            Text with network identifiers redacted when enabled
+        """
+        if not self.redact_network:
+            return text
+        return redact_network_identifiers(text, private_only=self.redact_private_only)
+
+    def _redact_value(self, value: Any) -> Any:
+        """Redact a metadata value, including the strings of a list."""
+        if isinstance(value, str):
+            return self.redact_if_needed(value)
+        if isinstance(value, list):
+            return [self._redact_value(item) for item in value]
+        return value
+
     def _default_path(self) -> Path:
         """Get default audit log path."""
         return Path.home() / ".agentsh" / "audit.log"
@@ -174,10 +284,33 @@ class AuditLogger:
         Args:
             event: Event to log
         """
+        if self._disabled:
+            return
+
+        if not self.should_log(event.action):
+            logger.debug("Audit event filtered", action=event.action.value)
+            return
+
         # Set session ID if not set
         if event.session_id is None:
             event.session_id = self.session_id
 
+        # Attribute the event to the active model
+        if event.model is None:
+            event.model = self.model
+        if event.provider is None:
+            event.provider = self.provider
+        if event.request_id is None:
+            event.request_id = current_request_id()
+
+        event.command = self.redact_if_needed(event.command)
+        if event.result is not None:
+            event.result = self.redact_if_needed(event.result)
+        if event.metadata:
+            event.metadata = {
+                key: self._redact_value(value) for key, value in event.metadata.items()
+            }
+
         # Check file size and rotate if needed
         self._check_rotation()
 
@@ -186,13 +319,36 @@ class AuditLogger:
             with open(self.log_path, "a") as f:
                 f.write(event.to_json() + "\n")
 
+            self._write_failures = 0
             logger.debug(
                 "Audit event logged",
                 action=event.action.value,
                 user=event.user,
             )
         except Exception as e:
-            logger.error("Failed to write audit log", error=str(e))
+            self._record_write_failure(e)
+
+    def _record_write_failure(self, error: Exception) -> None:
+        """Count a failed write and disable logging if failures persist.
+
+        Only the first failure and the final disablement are reported, so a
+        full disk does not produce one error per event.
+
+        Args:
+            error: Exception raised by the write
+        """
+        self._write_failures += 1
+
+        if self._write_failures >= self.max_write_failures:
+            self._disabled = True
+            logger.warning(
+                "Audit logging disabled after repeated write failures",
+                log_path=str(self.log_path),
+                failures=self._write_failures,
+                error=str(error),
+            )
+        elif self._write_failures == 1:
+            logger.error("Failed to write audit log", error=str(error))
 
     def _check_rotation(self) -> None:
         """Check if log file needs rotation."""
@@ -220,6 +376,12 @@ class AuditLogger:
         risk_level: RiskLevel = RiskLevel.SAFE,
         device_id: Optional[str] = None,
         metadata: Optional[dict] = None,
+        exit_code: Optional[int] = None,
+        started_at: Optional[datetime] = None,
+        duration_ms: Optional[int] = None,
+        safety_flags: Optional[list[str]] = None,
+        warnings: Optional[list[str]] = None,
+        approval: Optional[ApprovalKind] = None,
     ) -> None:
         """Log a command execution.
 
@@ -229,7 +391,29 @@ class AuditLogger:
             risk_level: Risk level of command
             device_id: Target device
             metadata: Additional context
+            exit_code: Exit code of the command; non-zero is logged as failed
+            started_at: When the command started
+            duration_ms: How long the command ran in milliseconds
+            safety_flags: Why the command was flagged, such as DESTRUCTIVE
+                (see CommandRiskAssessment.safety_flags)
+            warnings: Risk reasons found when the command was checked
+            approval: How the command was authorized (confirmed by the
+                user, auto-approved by policy, edited or retyped)
         """
+        metadata = dict(metadata or {})
+        if exit_code is not None:
+            metadata["exit_code"] = exit_code
+        if started_at is not None:
+            metadata["started_at"] = started_at.isoformat()
+        if duration_ms is not None:
+            metadata["duration_ms"] = duration_ms
+        if safety_flags:
+            metadata["safety_flags"] = safety_flags
+        if warnings:
+            metadata["warnings"] = warnings
+        if approval is not None:
+            metadata["approval"] = approval.value
+
         self.log(
             AuditEvent(
                 timestamp=datetime.now(),
@@ -237,9 +421,9 @@ class AuditLogger:
                 user=user or self._get_user(),
                 command=command,
                 risk_level=risk_level,
-                result="success",
+                result="success" if not exit_code else "failed",
                 device_id=device_id,
-                metadata=metadata,
+                metadata=metadata or None,
             )
         )
 
@@ -372,6 +556,39 @@ class AuditLogger:
             )
         )
 
+    def log_plan_incomplete(
+        self,
+        request: str,
+        reason: str,
+        steps: list[dict[str, Any]],
+        user: Optional[str] = None,
+    ) -> None:
+        """Log a plan that stopped before all its steps ran.
+
+        Args:
+            request: The request the plan was for
+            reason: Why the plan stopped (e.g. "Plan stopped", "Plan timeout")
+            steps: The steps that ran before it stopped, in order
+            user: User who made the request
+        """
+        steps = [
+            {
+                key: self.redact_if_needed(value) if isinstance(value, str) else value
+                for key, value in step.items()
+            }
+            for step in steps
+        ]
+        self.log(
+            AuditEvent(
+                timestamp=datetime.now(),
+                action=AuditAction.PLAN_INCOMPLETE,
+                user=user or self._get_user(),
+                command=request,
+                result="incomplete",
+                metadata={"reason": reason, "steps": steps},
+            )
+        )
+
     def get_recent(self, n: int = 100) -> list[AuditEvent]:
         """Get recent audit events.
 
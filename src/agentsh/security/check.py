@@ -0,0 +1,198 @@
+"""Safety check - Show how a command would be classified without running it.
+
+Backs ``ai check '<command>'``, which lets users tuning ``security.rules``,
+``deny_patterns`` or the security mode see the full risk assessment of a
+command and what AgentSH would do with it. No AI call is made and nothing
+is executed or written to the audit log.
+
+A matching block rule always wins; otherwise rules are evaluated in order
+and the first match decides the action:
+
+- ``block``: refuse the command
+- ``confirm``: always ask, even when ``require_confirmation`` is off
+- ``warn``: decide as usual, adding a warning
+- ``allow``: run without confirmation. CRITICAL and over-long commands
+  and those the security mode blocks stay blocked, and possible
+  exfiltration is still confirmed.
+
+The security controller applies the same rules when commands run.
+
+With ``allow_autonomous`` and an ``auto_run_allowlist``, a command that
+would run freely is confirmed unless each of its simple commands matches
+an allowlist pattern in full.
+"""
+
+from dataclasses import dataclass, field
+from enum import Enum
+from typing import Optional
+
+from agentsh.config.schemas import RuleAction, SafetyRule, SecurityConfig
+from agentsh.security.classifier import CommandRiskAssessment, RiskClassifier
+from agentsh.security.controller import auto_runnable, match_rule
+from agentsh.security.policies import SecurityPolicy
+
+
+class CheckOutcome(Enum):
+    """What AgentSH would do with a checked command."""
+
+    BLOCKED = "blocked"
+    CONFIRM = "needs confirmation"
+    RUN = "runs freely"
+
+
+@dataclass
+class SafetyCheck:
+    """Result of a dry safety check.
+
+    Attributes:
+        assessment: Full risk assessment of the command
+        outcome: What would happen if the command were run
+        reason: Why that outcome was chosen
+        rule: First safety rule matching the command, if any
+        warnings: Warnings to show before running the command
+    """
+
+    assessment: CommandRiskAssessment
+    outcome: CheckOutcome
+    reason: str
+    rule: Optional[SafetyRule] = None
+    warnings: list[str] = field(default_factory=list)
+
+    @property
+    def action(self) -> RuleAction:
+        """The resolved action for the command."""
+        if self.outcome == CheckOutcome.BLOCKED:
+            return RuleAction.BLOCK
+        if self.outcome == CheckOutcome.CONFIRM:
+            return RuleAction.CONFIRM
+        if self.warnings:
+            return RuleAction.WARN
+        return RuleAction.ALLOW
+
+
+def policy_for_config(config: SecurityConfig) -> SecurityPolicy:
+    """Map the configured security mode to a policy.
+
+    Args:
+        config: Security configuration
+
+    Returns:
+        Policy matching ``security.mode``
+    """
+    mode = config.mode.value
+    if mode == "strict":
+        return SecurityPolicy.strict()
+    if mode == "lenient":
+        return SecurityPolicy.permissive()
+    return SecurityPolicy.standard()
+
+
+def check_command(
+    command: str,
+    config: SecurityConfig,
+    classifier: Optional[RiskClassifier] = None,
+) -> SafetyCheck:
+    """Classify a command and decide what would happen to it.
+
+    Args:
+        command: Command to check
+        config: Security configuration (rules, mode, confirmation)
+        classifier: Risk classifier to use
+
+    Returns:
+        SafetyCheck with the assessment, matched rule and outcome
+    """
+    classifier = classifier or RiskClassifier(critical_services=config.critical_services)
+    too_long = len(command) > config.max_command_length
+    assessment = classifier.classify(command)
+    policy = policy_for_config(config)
+    level = assessment.risk_level
+    rule = match_rule(command, config.effective_rules())
+    action = rule.action if rule else None
+    warnings: list[str] = []
+
+    if action == RuleAction.WARN:
+        warnings.append(f"Matches warn rule {rule.pattern}")
+
+    if assessment.is_blocked:
+        outcome, reason = CheckOutcome.BLOCKED, f"risk level {level.name} is always blocked"
+    elif too_long and config.reject_long_commands:
+        outcome, reason = CheckOutcome.BLOCKED, "longer than security.max_command_length"
+    elif assessment.opens_root_shell and config.block_root_shells:
+        outcome, reason = CheckOutcome.BLOCKED, "security.block_root_shells blocks root shells"
+    elif action == RuleAction.BLOCK:
+        outcome, reason = CheckOutcome.BLOCKED, f"matches block rule {rule.pattern}"
+    elif action == RuleAction.CONFIRM:
+        outcome, reason = CheckOutcome.CONFIRM, f"matches confirm rule {rule.pattern}"
+    elif assessment.possible_exfiltration:
+        outcome, reason = CheckOutcome.CONFIRM, "possible exfiltration is always confirmed"
+    elif assessment.opens_root_shell:
+        outcome, reason = CheckOutcome.CONFIRM, "an unmonitored root shell is always confirmed"
+    elif too_long:
+        outcome, reason = CheckOutcome.CONFIRM, "longer than security.max_command_length"
+    elif policy.is_blocked_by_mode(level):
+        outcome, reason = CheckOutcome.BLOCKED, f"{config.mode.value} mode blocks {level.name}"
+    elif action == RuleAction.ALLOW:
+        outcome, reason = CheckOutcome.RUN, f"matches allow rule {rule.pattern}"
+    elif config.require_confirmation and (
+        assessment.requires_approval or policy.requires_approval(level)
+    ):
+        outcome, reason = CheckOutcome.CONFIRM, f"{config.mode.value} mode confirms {level.name}"
+    elif config.confirm_all and not classifier.is_read_only(command):
+        outcome, reason = CheckOutcome.CONFIRM, "security.confirm_all: not a read-only command"
+    else:
+        outcome, reason = CheckOutcome.RUN, f"{level.name} needs no confirmation"
+
+    if (
+        outcome == CheckOutcome.RUN
+        and config.allow_autonomous
+        and config.auto_run_allowlist
+        and not auto_runnable(command, assessment, config.auto_run_allowlist)
+    ):
+        outcome, reason = CheckOutcome.CONFIRM, "not in security.auto_run_allowlist"
+
+    return SafetyCheck(
+        assessment=assessment,
+        outcome=outcome,
+        reason=reason,
+        rule=rule,
+        warnings=warnings,
+    )
+
+
+def format_safety_check(check: SafetyCheck) -> str:
+    """Render a safety check for display.
+
+    Args:
+        check: Result of :func:`check_command`
+
+    Returns:
+        Multi-line report listing every flag and the outcome
+    """
+    assessment = check.assessment
+
+    def yes_no(value: bool) -> str:
+        return "yes" if value else "no"
+
+    lines = [
+        f"Command:              {assessment.command}",
+        f"Risk level:           {assessment.risk_level.name}",
+        f"Safe:                 {yes_no(assessment.is_safe)}",
+        f"Blocked:              {yes_no(assessment.is_blocked)}",
+        f"Requires approval:    {yes_no(assessment.requires_approval)}",
+        f"Command substitution: {yes_no(assessment.uses_command_substitution)}",
+        f"Exfiltration:         {yes_no(assessment.possible_exfiltration)}",
+        f"Root shell:           {yes_no(assessment.opens_root_shell)}",
+        f"System permissions:   {yes_no(assessment.changes_system_permissions)}",
+        f"Overwrites files:     {yes_no(assessment.overwrites_files)}",
+        "Reasons:",
+    ]
+    lines.extend(f"  - {reason}" for reason in assessment.reasons)
+    if assessment.matched_patterns:
+        lines.append("Matched patterns:")
+        lines.extend(f"  - {pattern}" for pattern in assessment.matched_patterns)
+    if check.rule:
+        lines.append(f"Rule:                 {check.rule.pattern} ({check.rule.action.value})")
+    lines.extend(f"Warning:              {warning}" for warning in check.warnings)
+    lines.append(f"Outcome:              {check.outcome.value} ({check.reason})")
+    return "\n".join(lines)
@@ -1,15 +1,572 @@
 """Risk Classifier - Analyzes commands for security risks."""
 
+import os
 import re
 import shlex
 from dataclasses import dataclass, field
 from enum import Enum, IntEnum
+from pathlib import Path
 from typing import Optional
 
 from agentsh.telemetry.logger import get_logger
 
 logger = get_logger(__name__)
 
+# Shell constructs that can introduce side effects into an otherwise read-only command
+READ_ONLY_DISALLOWED = re.compile(r"[;&|<>`\n\r]|\$\(")
+
+# Commands that run the command given in their arguments; a read-only check
+# judges the wrapped command instead
+COMMAND_WRAPPERS = ("env", "nice", "timeout", "xargs")
+
+# Wrapper options that take a separate value
+WRAPPER_VALUE_OPTIONS = {
+    "env": {"-u", "--unset", "-C", "--chdir"},
+    "nice": {"-n", "--adjustment"},
+    "timeout": {"-s", "--signal", "-k", "--kill-after"},
+    "xargs": {
+        "-a", "--arg-file", "-d", "--delimiter", "-E", "-e", "-I", "-i", "-L", "-l",
+        "-n", "--max-args", "-P", "--max-procs", "-s", "--max-chars",
+    },
+}
+
+# find actions that change files or run other commands
+FIND_WRITING_ACTIONS = ("-delete", "-exec", "-ok", "-fprint", "-fls")
+
+# hostname options that only print; anything else may set the hostname
+HOSTNAME_READ_OPTIONS = {
+    "-a", "-A", "-d", "-f", "-i", "-I", "-s", "--alias", "--all-fqdns",
+    "--all-ip-addresses", "--domain", "--fqdn", "--ip-address", "--long", "--short",
+}
+
+# date options that only print; anything else may set the clock
+DATE_READ_OPTIONS = {"-u", "--utc", "--universal", "-R", "--rfc-email"}
+
+# Commands whose targets must be known before running them; a substitution in
+# their arguments means the targets cannot be determined statically
+DESTRUCTIVE_COMMAND = re.compile(
+    r"(?:^|[;&|(]\s*)(?:sudo\s+)?"
+    r"(rm|rmdir|kill|pkill|killall|chmod|chown|chgrp|mv|dd|shred|truncate)\b"
+)
+
+# Commands run with elevated privileges
+PRIVILEGED_COMMAND = re.compile(r"(?:^|[;&|(]\s*)(?:sudo|doas)\b")
+
+# Services always treated as critical; stopping them can lock out remote users
+BUILTIN_CRITICAL_SERVICES = ("ssh", "sshd")
+
+# systemctl verbs that take a service down, followed by the unit list
+SYSTEMCTL_STOP = re.compile(
+    r"(?:^|[;&|(]\s*)(?:sudo\s+)?systemctl\s+(?:-\S+\s+)*"
+    r"(?:stop|restart|try-restart|reload-or-restart|disable|mask|kill)\s+([^;&|)]+)"
+)
+
+# service <name> stop/restart
+SERVICE_STOP = re.compile(
+    r"(?:^|[;&|(]\s*)(?:sudo\s+)?service\s+(\S+)\s+(?:stop|restart|force-reload)\b"
+)
+
+# Commands that dump the environment, which usually holds credentials
+ENV_DUMP = re.compile(r"^(?:sudo\s+)?(?:env|printenv|set|export\s+-p)(?:\s|$)")
+
+# Files and directories that typically hold secrets
+SENSITIVE_PATH = re.compile(
+    r"\.(?:ssh|aws|gnupg|kube|docker)/|\.(?:netrc|pgpass|git-credentials)\b"
+    r"|(?:^|[\s/'\"=])\.env(?:\.\w+)?(?=$|[\s'\"])|/etc/shadow\b|\bid_(?:rsa|ecdsa|ed25519)\b"
+)
+
+# Environment variables that look like credentials
+SECRET_VARIABLE = re.compile(r"\$\{?\w*(?:SECRET|TOKEN|PASSWORD|PASSWD|API_KEY|ACCESS_KEY)\w*")
+
+# Commands that send data over the network
+NETWORK_COMMAND = re.compile(
+    r"^(?:sudo\s+)?(?:curl|wget|nc|ncat|netcat|socat|ssh|scp|sftp|ftp|telnet|rsync)\b"
+)
+
+# Shells that, run through sudo without a command, give an interactive root shell
+ROOT_SHELLS = frozenset({"sh", "bash", "zsh", "fish", "dash", "ksh", "tcsh", "csh", "su"})
+
+# sudo short options that take an argument (the rest of the cluster or the next word)
+SUDO_ARG_OPTIONS = frozenset("CDghprtTuUR")
+
+# sudo long options that take an argument
+SUDO_ARG_LONG_OPTIONS = frozenset(
+    {
+        "--chdir",
+        "--chroot",
+        "--close-from",
+        "--command-timeout",
+        "--group",
+        "--host",
+        "--other-user",
+        "--prompt",
+        "--role",
+        "--type",
+        "--user",
+    }
+)
+
+# Commands that change permissions or ownership, with the options they accept
+PERMISSION_COMMANDS = {"chmod": "cfvRhHLP", "chown": "cfvRhHLP", "chgrp": "cfvRhHLP"}
+
+# Directories whose recursive permission change breaks the system or the login
+SYSTEM_DIRECTORIES = frozenset(
+    {
+        "/",
+        "/bin",
+        "/boot",
+        "/dev",
+        "/etc",
+        "/home",
+        "/lib",
+        "/lib64",
+        "/opt",
+        "/proc",
+        "/root",
+        "/sbin",
+        "/sys",
+        "/usr",
+        "/usr/bin",
+        "/usr/lib",
+        "/usr/local",
+        "/usr/sbin",
+        "/var",
+        "/Users",
+        "/System",
+        "/Library",
+    }
+)
+
+# Ways of naming the home directory
+HOME_DIRECTORIES = frozenset({"~", "$HOME", "${HOME}"})
+
+# Redirection operators that truncate their target (``>>`` appends instead)
+CLOBBER_OPERATORS = frozenset({">", ">|", "&>"})
+
+# Directories where overwriting any file through a redirection is dangerous
+PROTECTED_REDIRECT_DIRECTORIES = (
+    "/etc",
+    "/boot",
+    "/usr",
+    "/bin",
+    "/sbin",
+    "/lib",
+    "/lib64",
+    "/root",
+    "/System",
+    "/Library",
+)
+
+# Placeholder for substituted output when analyzing the outer command
+SUBSTITUTION_PLACEHOLDER = "__SUBST__"
+
+# Nesting depth beyond which substitutions are not analyzed
+MAX_SUBSTITUTION_DEPTH = 5
+
+
+def extract_substitutions(command: str) -> tuple[str, list[str], bool]:
+    """Find ``$(...)`` and backtick command substitutions.
+
+    Substitutions inside single quotes are literal and ignored. Arithmetic
+    expansion (``$((...))``) is not a command substitution.
+
+    Args:
+        command: Shell command line
+
+    Returns:
+        Tuple of (outer command with each substitution replaced by a
+        placeholder, inner commands, whether parsing succeeded). Parsing
+        fails on unbalanced parentheses or backticks.
+    """
+    outer: list[str] = []
+    inner: list[str] = []
+    i = 0
+    in_single = False
+    in_double = False
+
+    while i < len(command):
+        char = command[i]
+
+        if char == "\\" and not in_single and i + 1 < len(command):
+            outer.append(command[i:i + 2])
+            i += 2
+            continue
+
+        if char == "'" and not in_double:
+            in_single = not in_single
+        elif char == '"' and not in_single:
+            in_double = not in_double
+        elif not in_single and command.startswith("$(", i) and not command.startswith("$((", i):
+            depth = 1
+            j = i + 2
+            while j < len(command) and depth:
+                if command[j] == "(":
+                    depth += 1
+                elif command[j] == ")":
+                    depth -= 1
+                j += 1
+            if depth:
+                return command, inner, False
+            inner.append(command[i + 2:j - 1])
+            outer.append(SUBSTITUTION_PLACEHOLDER)
+            i = j
+            continue
+        elif not in_single and char == "`":
+            end = command.find("`", i + 1)
+            if end == -1:
+                return command, inner, False
+            inner.append(command[i + 1:end])
+            outer.append(SUBSTITUTION_PLACEHOLDER)
+            i = end + 1
+            continue
+
+        outer.append(char)
+        i += 1
+
+    return "".join(outer), inner, True
+
+
+def _is_sensitive_source(stage: str) -> bool:
+    """Check whether a pipeline stage outputs environment or secret files."""
+    return bool(ENV_DUMP.match(stage) or SENSITIVE_PATH.search(stage))
+
+
+def _outputs_secrets(command: str) -> bool:
+    """Check whether any part of a command, substitutions included, outputs secrets."""
+    outer, inner, _ = extract_substitutions(command)
+    stages = re.split(r"\s*(?:;|&&|\|\||\n|\|&?)\s*", outer)
+    return any(_is_sensitive_source(stage.strip()) for stage in stages) or any(
+        _outputs_secrets(body) for body in inner
+    )
+
+
+def _sends_secrets(stage: str) -> bool:
+    """Check whether a network command uploads a secret file or variable."""
+    if SECRET_VARIABLE.search(stage):
+        return True
+    # curl -d @file, --upload-file, and input redirection
+    for match in re.finditer(r"(?:@|<\s*|--upload-file\s+|-T\s+)([^\s'\"]+)", stage):
+        if SENSITIVE_PATH.search(match.group(1)):
+            return True
+    return False
+
+
+def detect_exfiltration(command: str) -> bool:
+    """Detect secrets or the environment being sent over the network.
+
+    Flags a sensitive source (``env``, ``printenv``, reads of ``~/.ssh``,
+    ``~/.aws`` or ``.env`` files) piped into a network command such as
+    ``curl`` or ``nc``, written to ``/dev/tcp``, or uploaded directly by
+    one (``curl -d @~/.aws/credentials``, ``curl -d "$(cat ~/.ssh/id_rsa)"``).
+    The bodies of command substitutions are checked the same way.
+
+    Args:
+        command: Shell command line
+
+    Returns:
+        True if the command may leak secrets
+    """
+    outer, bodies, _ = extract_substitutions(command)
+    if any(detect_exfiltration(body) for body in bodies):
+        return True
+
+    # Substitutions are numbered in order of their placeholders
+    substituted = 0
+    for pipeline in re.split(r"\s*(?:;|&&|\|\||\n)\s*", outer):
+        stages = [stage.strip() for stage in re.split(r"\|&?", pipeline)]
+        source_seen = False
+        for stage in stages:
+            count = stage.count(SUBSTITUTION_PLACEHOLDER)
+            stage_bodies = bodies[substituted : substituted + count]
+            substituted += count
+            reads_secrets = any(_outputs_secrets(body) for body in stage_bodies)
+            if NETWORK_COMMAND.match(stage):
+                if source_seen or reads_secrets or _sends_secrets(stage):
+                    return True
+            elif reads_secrets or _is_sensitive_source(stage):
+                source_seen = True
+                if re.search(r">\s*/dev/(?:tcp|udp)/", stage):
+                    return True
+    return False
+
+
+def _sudo_opens_shell(words: list[str]) -> bool:
+    """Check whether a ``sudo`` invocation (without the word sudo) opens a shell."""
+    index = 0
+    while index < len(words) and words[index].startswith("-"):
+        option = words[index]
+        index += 1
+        if option == "--":
+            break
+        if option.startswith("--"):
+            if option in ("--shell", "--login"):
+                return True
+            if option in SUDO_ARG_LONG_OPTIONS:
+                index += 1
+            continue
+        for position, flag in enumerate(option[1:], start=2):
+            if flag in "si":
+                return True
+            if flag in SUDO_ARG_OPTIONS:
+                # The argument is the rest of the cluster, or the next word
+                if position == len(option):
+                    index += 1
+                break
+
+    if index >= len(words):
+        return False
+    program = words[index].rsplit("/", 1)[-1]
+    args = words[index + 1 :]
+    if program not in ROOT_SHELLS:
+        return False
+    if program == "su":
+        return not any(arg in ("-c", "--command") or arg.startswith("--command=") for arg in args)
+    # sudo bash -l opens a shell; sudo bash script.sh or bash -c '...' does not
+    return not any(arg == "-c" or not arg.startswith("-") for arg in args)
+
+
+def opens_root_shell(command: str) -> bool:
+    """Detect ``sudo`` invocations that leave the user in a root shell.
+
+    Flags ``sudo -s``, ``sudo -i``, ``sudo su [-]`` and ``sudo bash``
+    (or another shell) without a command. Commands typed into such a shell
+    are never seen by AgentSH, so they escape its analysis entirely.
+
+    Args:
+        command: Shell command line
+
+    Returns:
+        True if the command opens an interactive root shell
+    """
+    for stage in re.split(r"\s*(?:;|&&|\|\||\|&?|&|\n)\s*", command):
+        try:
+            words = shlex.split(stage)
+        except ValueError:
+            words = stage.split()
+        if words and words[0] == "sudo" and _sudo_opens_shell(words[1:]):
+            return True
+    return False
+
+
+def _system_directory(path: str) -> Optional[str]:
+    """Get the system or home directory a path names, if it names one."""
+    path = path.strip("'\"")
+    if path.endswith("/*"):
+        path = path[:-2] or "/"
+    if path in HOME_DIRECTORIES or path.rstrip("/") in HOME_DIRECTORIES:
+        return "~"
+    normalized = "/" + "/".join(part for part in path.split("/") if part not in ("", "."))
+    if path.startswith("/") and normalized in SYSTEM_DIRECTORIES:
+        return normalized
+    return None
+
+
+def recursive_permission_targets(command: str) -> list[str]:
+    """Find system directories changed by a recursive chmod, chown or chgrp.
+
+    Recursively changing the mode or owner of ``/``, ``/usr``, ``/etc`` or
+    the home directory cannot be undone easily and often leaves the system
+    unbootable or the user unable to log in, whatever mode is set.
+
+    Args:
+        command: Shell command line
+
+    Returns:
+        System directories targeted (``~`` for the home directory), in
+        command order
+    """
+    targets: list[str] = []
+    for stage in re.split(r"\s*(?:;|&&|\|\||\|&?|&|\n)\s*", command):
+        try:
+            words = shlex.split(stage)
+        except ValueError:
+            words = stage.split()
+        while words and words[0] in ("sudo", "doas"):
+            words = words[1:]
+            while words and words[0].startswith("-"):
+                option = words.pop(0)
+                if option == "--":
+                    break
+                if len(option) == 2 and option[1] in SUDO_ARG_OPTIONS and words:
+                    words.pop(0)
+        if not words or words[0].rsplit("/", 1)[-1] not in PERMISSION_COMMANDS:
+            continue
+
+        options = PERMISSION_COMMANDS[words[0].rsplit("/", 1)[-1]]
+        recursive = False
+        operands = []
+        for word in words[1:]:
+            if word == "--recursive":
+                recursive = True
+            elif word.startswith("--"):
+                # --reference=FILE replaces the mode or owner operand
+                if word.startswith("--reference"):
+                    operands.append("")
+            elif len(word) > 1 and word[0] == "-" and all(c in options for c in word[1:]):
+                recursive = recursive or "R" in word
+            else:
+                operands.append(word)
+        if not recursive:
+            continue
+
+        # The first operand is the mode or owner, the rest are paths
+        for path in operands[1:]:
+            target = _system_directory(path)
+            if target and target not in targets:
+                targets.append(target)
+    return targets
+
+
+def _in_protected_directory(path: str) -> bool:
+    """Check whether an absolute path lies under a protected directory."""
+    return any(
+        path == directory or path.startswith(f"{directory}/")
+        for directory in PROTECTED_REDIRECT_DIRECTORIES
+    )
+
+
+def clobbered_files(command: str, cwd: Optional[Path] = None) -> list[str]:
+    """Find files a ``>`` redirection would overwrite.
+
+    A ``>`` (or ``>|``, ``&>``) redirection silently truncates its target, so
+    ``cat config.yaml > config.yaml.bak`` destroys the backup it was meant to
+    restore from. A target counts when it is an existing, non-empty file or
+    lies under a protected system directory. Files are only inspected with
+    ``stat``; nothing is opened. Appending (``>>``) and ``/dev`` targets are
+    ignored.
+
+    Args:
+        command: Shell command line
+        cwd: Directory relative targets are resolved against (defaults to
+            the current directory)
+
+    Returns:
+        Redirection targets as written in the command, in command order
+    """
+    lexer = shlex.shlex(command, posix=True, punctuation_chars=True)
+    lexer.whitespace_split = True
+    try:
+        tokens = list(lexer)
+    except ValueError:
+        return []
+
+    base = cwd or Path.cwd()
+    targets: list[str] = []
+    for operator, target in zip(tokens, tokens[1:]):
+        # Operators run together with a preceding separator, e.g. "a;>b"
+        if operator.lstrip(";|(") not in CLOBBER_OPERATORS or target in targets:
+            continue
+        if not target or target[0] in "();<>|&":
+            continue
+        path = Path(target).expanduser()
+        if not path.is_absolute():
+            path = base / path
+        absolute = os.path.normpath(path)
+        if absolute == "/dev" or absolute.startswith("/dev/"):
+            continue
+        try:
+            existing = path.is_file() and path.stat().st_size > 0
+        except OSError:
+            existing = False
+        if existing or _in_protected_directory(absolute):
+            targets.append(target)
+    return targets
+
+
+def outside_workspace(path: str, workspace_root: str) -> bool:
+    """Check whether a directory lies outside the workspace.
+
+    Args:
+        path: Directory; relative paths are taken from the workspace root
+        workspace_root: Directory commands should stay within
+
+    Returns:
+        True if the directory is neither the root nor below it
+    """
+    root = os.path.normpath(os.path.expanduser(workspace_root))
+    resolved = os.path.normpath(os.path.join(root, os.path.expanduser(path)))
+    return resolved != root and not resolved.startswith(root.rstrip("/") + "/")
+
+
+def cd_outside_workspace(command: str, cwd: str, workspace_root: str) -> list[str]:
+    """Find ``cd`` targets of a command that lie outside the workspace.
+
+    After ``cd /etc``, relative paths in the rest of the command (``rm -rf
+    *``) act on files the user never meant to touch. The directory is
+    followed through the command, so ``cd build && cd ../..`` is resolved
+    from ``build``. ``pushd`` and ``builtin cd`` count as ``cd``, also
+    inside ``( ... )`` and ``{ ...; }`` groups. Targets containing
+    variables or substitutions, ``cd -`` and stack rotations like
+    ``pushd +1`` cannot be resolved and are ignored.
+
+    Args:
+        command: Shell command line
+        cwd: Directory the command starts in
+        workspace_root: Directory the command should stay within
+
+    Returns:
+        Targets as written in the command, in command order
+    """
+    root = os.path.normpath(os.path.expanduser(workspace_root))
+    current = os.path.normpath(os.path.join(root, os.path.expanduser(cwd)))
+    outside: list[str] = []
+
+    for stage in re.split(r"\s*(?:;|&&|\|\||\|&?|&|\n)\s*", command):
+        stage = stage.lstrip("({ \t")
+        try:
+            words = shlex.split(stage)
+        except ValueError:
+            words = stage.split()
+        if words[:1] in (["builtin"], ["command"]):
+            words = words[1:]
+        if not words or words[0] not in ("cd", "pushd"):
+            continue
+        operands = [word for word in words[1:] if not word.startswith("-") or word == "-"]
+        if operands:
+            target = operands[0].rstrip(")}")
+        elif words[0] == "cd":
+            target = "~"
+        else:
+            continue
+        if target in ("-", "") or target.startswith("+") or "$" in target or "`" in target:
+            continue
+        current = os.path.normpath(os.path.join(current, os.path.expanduser(target)))
+        if outside_workspace(current, root) and target not in outside:
+            outside.append(target)
+    return outside
+
+
+def _service_name(unit: str) -> str:
+    """Strip the ``.service`` suffix and any template instance from a unit."""
+    name = unit.removesuffix(".service")
+    return name.split("@", 1)[0] if "@" in name else name
+
+
+def affected_critical_services(command: str, services: frozenset[str]) -> list[str]:
+    """Find critical services a command stops, restarts or disables.
+
+    Covers ``systemctl stop|restart|disable|mask|kill <unit>...`` and
+    ``service <name> stop|restart``.
+
+    Args:
+        command: Shell command line
+        services: Names of critical services (without ``.service``)
+
+    Returns:
+        Critical services affected, in command order
+    """
+    names = []
+    for match in SYSTEMCTL_STOP.finditer(command):
+        names.extend(unit for unit in match.group(1).split() if not unit.startswith("-"))
+    names.extend(match.group(1) for match in SERVICE_STOP.finditer(command))
+
+    affected = []
+    for name in names:
+        name = _service_name(name.strip("'\""))
+        if name in services and name not in affected:
+            affected.append(name)
+    return affected
+
 
 class RiskLevel(IntEnum):
     """Risk level classification for commands.
@@ -51,6 +608,20 @@ class CommandRiskAssessment:
         matched_patterns: Patterns that matched
         is_blocked: Whether command should be blocked
         requires_approval: Whether command needs human approval
+        uses_command_substitution: Whether the command contains ``$(...)``
+            or backtick substitution
+        affects_critical_service: Whether the command stops, restarts or
+            disables a critical service
+        possible_exfiltration: Whether the command may send secrets or the
+            environment over the network
+        too_long: Whether the command exceeds the configured maximum
+            length, making it impractical to review
+        opens_root_shell: Whether the command drops the user into an
+            interactive root shell (``sudo -i``, ``sudo su``)
+        changes_system_permissions: Whether the command recursively
+            changes the mode or owner of a system or home directory
+        overwrites_files: Whether a ``>`` redirection truncates an existing
+            file or a file in a protected directory
     """
 
     command: str
@@ -59,12 +630,43 @@ class CommandRiskAssessment:
     matched_patterns: list[str] = field(default_factory=list)
     is_blocked: bool = False
     requires_approval: bool = False
+    uses_command_substitution: bool = False
+    affects_critical_service: bool = False
+    possible_exfiltration: bool = False
+    too_long: bool = False
+    opens_root_shell: bool = False
+    changes_system_permissions: bool = False
+    overwrites_files: bool = False
 
     @property
     def is_safe(self) -> bool:
         """Check if command is safe to execute."""
         return self.risk_level <= RiskLevel.LOW and not self.is_blocked
 
+    def safety_flags(self) -> list[str]:
+        """Summarize why the command was flagged, as short tags.
+
+        Returns:
+            Tags such as DESTRUCTIVE or PRIVILEGED, in a fixed order (empty
+            for an unremarkable command)
+        """
+        checks = [
+            ("BLOCKED", self.is_blocked),
+            ("NEEDS_APPROVAL", self.requires_approval),
+            (
+                "DESTRUCTIVE",
+                self.overwrites_files or bool(DESTRUCTIVE_COMMAND.search(self.command)),
+            ),
+            ("PRIVILEGED", bool(PRIVILEGED_COMMAND.search(self.command))),
+            ("ROOT_SHELL", self.opens_root_shell),
+            ("CRITICAL_SERVICE", self.affects_critical_service),
+            ("SYSTEM_PERMISSIONS", self.changes_system_permissions),
+            ("EXFILTRATION", self.possible_exfiltration),
+            ("COMMAND_SUBSTITUTION", self.uses_command_substitution),
+            ("TOO_LONG", self.too_long),
+        ]
+        return [flag for flag, applies in checks if applies]
+
 
 class RiskClassifier:
     """Classifies command risk levels based on patterns.
@@ -373,15 +975,33 @@ class RiskClassifier:
         self,
         additional_patterns: Optional[list[RiskPattern]] = None,
         blocked_commands: Optional[list[str]] = None,
+        critical_services: Optional[list[str]] = None,
+        max_command_length: Optional[int] = None,
+        reject_long_commands: bool = False,
+        block_root_shells: bool = False,
     ) -> None:
         """Initialize the risk classifier.
 
         Args:
             additional_patterns: Extra patterns to include
             blocked_commands: Specific commands to always block
+            critical_services: Services to protect in addition to
+                :data:`BUILTIN_CRITICAL_SERVICES`
+            max_command_length: Commands longer than this are flagged as
+                too long to review (None disables the check)
+            reject_long_commands: Block commands over max_command_length
+                instead of requiring approval
+            block_root_shells: Block commands opening an interactive root
+                shell instead of requiring approval
         """
+        self.max_command_length = max_command_length
+        self.reject_long_commands = reject_long_commands
+        self.block_root_shells = block_root_shells
         self._patterns: list[RiskPattern] = []
         self._blocked_commands: set[str] = set(blocked_commands or [])
+        self.critical_services = frozenset(
+            [*BUILTIN_CRITICAL_SERVICES, *(_service_name(s) for s in critical_services or [])]
+        )
 
         # Add patterns in order of severity (check critical first)
         self._patterns.extend(self.CRITICAL_PATTERNS)
@@ -402,9 +1022,44 @@ class RiskClassifier:
     def classify(self, command: str) -> CommandRiskAssessment:
         """Classify the risk level of a command.
 
+        Command substitutions are classified recursively, and a destructive
+        command whose arguments come from a substitution always requires
+        approval, since its targets cannot be known in advance. A command
+        longer than max_command_length always requires approval, or is
+        blocked with reject_long_commands. So does a command opening an
+        interactive root shell, which is blocked with block_root_shells.
+
         Args:
             command: Shell command to analyze
 
+        Returns:
+            CommandRiskAssessment with risk details
+        """
+        result = self._classify(command, depth=0)
+
+        if self.max_command_length is not None and len(command) > self.max_command_length:
+            result.too_long = True
+            result.requires_approval = True
+            result.reasons.append(
+                f"Command is {len(command)} characters, over the {self.max_command_length} "
+                "allowed (security.max_command_length); too long to review"
+            )
+            if self.reject_long_commands:
+                result.is_blocked = True
+        if result.opens_root_shell:
+            result.requires_approval = True
+            if self.block_root_shells:
+                result.is_blocked = True
+                result.reasons.append("Root shells are blocked (security.block_root_shells)")
+        return result
+
+    def _classify(self, command: str, depth: int) -> CommandRiskAssessment:
+        """Classify a command, recursing into command substitutions.
+
+        Args:
+            command: Shell command to analyze
+            depth: Current substitution nesting depth
+
         Returns:
             CommandRiskAssessment with risk details
         """
@@ -443,6 +1098,70 @@ class RiskClassifier:
                 if pattern.risk_level == RiskLevel.CRITICAL:
                     break
 
+        critical = affected_critical_services(command, self.critical_services)
+        if critical:
+            matched_reasons.append(f"Affects critical service: {', '.join(critical)}")
+            max_risk = max(max_risk, RiskLevel.HIGH)
+
+        exfiltration = detect_exfiltration(command)
+        if exfiltration:
+            matched_reasons.append(
+                "WARNING: possible exfiltration - sensitive data is sent to a network command"
+            )
+            max_risk = max(max_risk, RiskLevel.HIGH)
+
+        system_targets = recursive_permission_targets(command)
+        if system_targets:
+            matched_reasons.append(
+                "Recursive permission change on system directory: "
+                + ", ".join(system_targets)
+            )
+            max_risk = RiskLevel.CRITICAL
+
+        overwritten = clobbered_files(command)
+        if overwritten:
+            matched_reasons.append(
+                "WARNING: will overwrite existing file: " + ", ".join(overwritten)
+            )
+            protected = any(
+                _in_protected_directory(os.path.normpath(Path(target).expanduser()))
+                for target in overwritten
+            )
+            max_risk = max(max_risk, RiskLevel.HIGH if protected else RiskLevel.MEDIUM)
+
+        root_shell = opens_root_shell(command)
+        if root_shell:
+            matched_reasons.append(
+                "WARNING: this opens an unmonitored root shell - commands typed in it "
+                "are not checked by AgentSH"
+            )
+            max_risk = max(max_risk, RiskLevel.HIGH)
+
+        # Analyze command substitutions
+        outer, inner_commands, parsed = extract_substitutions(command)
+        uses_substitution = bool(inner_commands) or not parsed
+
+        if uses_substitution:
+            matched_reasons.append("Uses command substitution")
+
+            if not parsed or depth >= MAX_SUBSTITUTION_DEPTH:
+                matched_reasons.append("Command substitution could not be analyzed")
+            else:
+                for inner in inner_commands:
+                    inner_result = self._classify(inner, depth + 1)
+                    for reason in inner_result.reasons:
+                        if reason not in ("No known risk patterns", "Uses command substitution"):
+                            matched_reasons.append(f"In substitution: {reason}")
+                    matched_pattern_names.extend(inner_result.matched_patterns)
+                    max_risk = max(max_risk, inner_result.risk_level)
+
+            # Substituted output decides what a destructive command acts on
+            if DESTRUCTIVE_COMMAND.search(outer) and max_risk < RiskLevel.HIGH:
+                matched_reasons.append(
+                    "Destructive command with targets from command substitution"
+                )
+                max_risk = RiskLevel.HIGH
+
         # Determine blocking and approval requirements
         is_blocked = max_risk >= RiskLevel.CRITICAL
         requires_approval = max_risk >= RiskLevel.HIGH
@@ -454,6 +1173,12 @@ class RiskClassifier:
             matched_patterns=matched_pattern_names,
             is_blocked=is_blocked,
             requires_approval=requires_approval,
+            uses_command_substitution=uses_substitution,
+            affects_critical_service=bool(critical),
+            possible_exfiltration=exfiltration,
+            opens_root_shell=root_shell,
+            changes_system_permissions=bool(system_targets),
+            overwrites_files=bool(overwritten),
         )
 
         logger.debug(
@@ -492,3 +1217,169 @@ class RiskClassifier:
             True if command is safe to execute
         """
         return self.classify(command).is_safe
+
+    def is_read_only(self, command: str) -> bool:
+        """Check if a command is a known read-only operation.
+
+        Stricter than is_safe: the command must match at least one SAFE
+        pattern, match nothing riskier, and must not chain, pipe, redirect,
+        or substitute other commands.
+
+        Args:
+            command: Command to check
+
+        Returns:
+            True if the command can run without side effects
+        """
+        command = command.strip()
+        if not command or READ_ONLY_DISALLOWED.search(command):
+            return False
+
+        try:
+            words = shlex.split(command)
+        except ValueError:
+            return False
+        if not words:
+            return False
+
+        wrapped = _unwrap_command(words)
+        if wrapped is None:
+            return False
+        if wrapped:
+            return self.is_read_only(shlex.join(wrapped))
+        if _has_writing_arguments(words):
+            return False
+
+        assessment = self.classify(command)
+        return (
+            assessment.risk_level == RiskLevel.SAFE
+            and not assessment.is_blocked
+            and bool(assessment.matched_patterns)
+        )
+
+    def is_read_only_pipeline(self, command: str) -> bool:
+        """Check if every command of a simple pipeline is read-only.
+
+        Like is_read_only, but commands may be joined with ``|`` (such as
+        ``ps aux | grep nginx``), as long as each one is read-only.
+
+        Args:
+            command: Command to check
+
+        Returns:
+            True if the pipeline can run without side effects
+        """
+        if "||" in command:
+            return False
+        parts = command.split("|")
+        return all(self.is_read_only(part) for part in parts)
+
+
+def _unwrap_command(words: list[str]) -> Optional[list[str]]:
+    """Strip a wrapper such as ``env`` or ``timeout`` from a command.
+
+    Args:
+        words: Command split into words
+
+    Returns:
+        The wrapped command's words, an empty list if the command is not
+        wrapped or the wrapper runs nothing, or None if the wrapper's
+        arguments cannot be parsed
+    """
+    name = os.path.basename(words[0])
+    if name not in COMMAND_WRAPPERS:
+        return []
+
+    value_options = WRAPPER_VALUE_OPTIONS[name]
+    rest = words[1:]
+    index = 0
+    while index < len(rest):
+        word = rest[index]
+        if word == "--":
+            index += 1
+            break
+        if name == "env" and _is_split_string(word):
+            # env -S splits its value into more arguments, options included
+            if word in ("-S", "--split-string"):
+                if index + 1 >= len(rest):
+                    return None
+                value, after = rest[index + 1], index + 2
+            else:
+                value, after = _split_string_value(word), index + 1
+            try:
+                rest = shlex.split(value) + rest[after:]
+            except ValueError:
+                return None
+            index = 0
+            continue
+        if word in value_options:
+            index += 2
+        elif word.startswith("-") and word != "-":
+            index += 1
+        elif name == "env" and "=" in word:
+            index += 1
+        else:
+            break
+    if index > len(rest):
+        return None
+
+    rest = rest[index:]
+    if name == "timeout":
+        # The duration comes before the command
+        if not rest:
+            return None
+        rest = rest[1:]
+    if not rest and name == "timeout":
+        return None
+    if not rest and name == "xargs":
+        # xargs runs echo when no command is given
+        return ["echo"]
+    return rest
+
+
+def _is_split_string(word: str) -> bool:
+    """Check whether an env argument is -S/--split-string, in any spelling."""
+    if word == "--split-string" or word.startswith("--split-string="):
+        return True
+    return not word.startswith("--") and word.startswith("-") and "S" in word[1:]
+
+
+def _split_string_value(word: str) -> str:
+    """Get the value attached to -S (e.g. ``-Sls``) or ``--split-string=``."""
+    if word.startswith("--"):
+        return word.partition("=")[2]
+    return word[word.index("S") + 1:]
+
+
+def _has_writing_arguments(words: list[str]) -> bool:
+    """Check for arguments that make a listing command change the system.
+
+    Args:
+        words: Command split into words
+
+    Returns:
+        True if the arguments write files, run commands or change settings
+    """
+    name = os.path.basename(words[0])
+    args = words[1:]
+
+    if name == "find":
+        return any(arg.startswith(FIND_WRITING_ACTIONS) for arg in args)
+    if name == "sort":
+        return any(
+            arg.startswith("--output")
+            or (arg.startswith("-") and not arg.startswith("--") and "o" in arg[1:])
+            for arg in args
+        )
+    if name == "hostname":
+        return any(arg not in HOSTNAME_READ_OPTIONS for arg in args)
+    if name == "date":
+        return any(
+            not (
+                arg.startswith("+")
+                or arg in DATE_READ_OPTIONS
+                or arg.startswith(("-I", "--iso-8601", "--rfc-3339"))
+            )
+            for arg in args
+        )
+    return False
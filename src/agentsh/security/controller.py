@@ -1,24 +1,33 @@
 """Security Controller - Central security enforcement."""
 
-from dataclasses import dataclass
+import hashlib
+import re
+from dataclasses import dataclass, field
 from enum import Enum
 from typing import Optional, Union
 
+from agentsh.config.schemas import RuleAction, SafetyRule
 from agentsh.security.approval import (
     ApprovalFlow,
+    ApprovalKind,
     ApprovalRequest,
     ApprovalResponse,
     ApprovalResult,
     AutoApprover,
+    SESSION_APPROVAL_LIMIT,
 )
 from agentsh.security.audit import AuditLogger
 from agentsh.security.classifier import CommandRiskAssessment, RiskClassifier, RiskLevel
+from agentsh.security.effects import preview_effects
 from agentsh.security.policies import PolicyManager, SecurityPolicy
 from agentsh.security.rbac import RBAC, Role, User
 from agentsh.telemetry.logger import get_logger, LoggerMixin
 
 logger = get_logger(__name__)
 
+# Separators between the simple commands of a command line
+SIMPLE_COMMAND_SEPARATOR = re.compile(r"\s*(?:;|&&|\|\||\|&?|&|\n)\s*")
+
 
 class ValidationResult(Enum):
     """Result of security validation."""
@@ -28,6 +37,55 @@ class ValidationResult(Enum):
     BLOCKED = "blocked"  # Command is blocked
 
 
+def auto_runnable(command: str, assessment: CommandRiskAssessment, allowlist: list[str]) -> bool:
+    """Check whether a command may run unasked in autonomous mode.
+
+    Every simple command of the line (split at ``;``, ``&&``, ``||``,
+    ``|``, ``&`` and newlines) must match a pattern in full, so ``ls``
+    allows neither ``cols`` nor ``ls; rm x``.
+
+    Args:
+        command: Command to check
+        assessment: The command's risk assessment
+        allowlist: Regex patterns from security.auto_run_allowlist
+
+    Returns:
+        True if the command matches the allowlist and raises no concern
+        (LOW risk at most, no approval flag, no command substitution)
+    """
+    if (
+        assessment.risk_level > RiskLevel.LOW
+        or assessment.requires_approval
+        or assessment.uses_command_substitution
+    ):
+        return False
+    commands = [part for part in SIMPLE_COMMAND_SEPARATOR.split(command.strip()) if part]
+    return bool(commands) and all(
+        any(re.fullmatch(pattern, part) for pattern in allowlist) for part in commands
+    )
+
+
+def match_rule(command: str, rules: list[SafetyRule]) -> Optional[SafetyRule]:
+    """Find the rule deciding what happens to a command.
+
+    A matching block rule always wins, so an earlier allow rule cannot
+    let a blocked command through; otherwise the first matching rule
+    decides.
+
+    Args:
+        command: Command to match
+        rules: Rules in evaluation order
+
+    Returns:
+        The deciding rule, or None if no rule matches
+    """
+    matching = [rule for rule in rules if re.search(rule.pattern, command)]
+    for rule in matching:
+        if rule.action == RuleAction.BLOCK:
+            return rule
+    return matching[0] if matching else None
+
+
 @dataclass
 class SecurityContext:
     """Context for security decisions.
@@ -57,6 +115,9 @@ class SecurityDecision:
         risk_assessment: Risk analysis of the command
         reason: Explanation of the decision
         approved_by: Who approved (if applicable)
+        approval: How an allowed command was authorized
+        plan: The rest of the plan as the user edited it, starting with
+            ``command`` (empty unless the plan was edited)
     """
 
     result: ValidationResult
@@ -64,6 +125,8 @@ class SecurityDecision:
     risk_assessment: CommandRiskAssessment
     reason: str
     approved_by: Optional[str] = None
+    approval: Optional[ApprovalKind] = None
+    plan: list[str] = field(default_factory=list)
 
 
 class SecurityController(LoggerMixin):
@@ -93,6 +156,10 @@ class SecurityController(LoggerMixin):
         rbac: Optional[RBAC] = None,
         approval_flow: Optional[Union[ApprovalFlow, AutoApprover]] = None,
         audit_logger: Optional[AuditLogger] = None,
+        auto_run_allowlist: Optional[list[str]] = None,
+        require_retype_for_critical: bool = False,
+        confirm_all: bool = False,
+        rules: Optional[list[SafetyRule]] = None,
     ) -> None:
         """Initialize the security controller.
 
@@ -102,15 +169,90 @@ class SecurityController(LoggerMixin):
             rbac: RBAC manager
             approval_flow: Approval flow handler
             audit_logger: Audit logger
+            auto_run_allowlist: Regex patterns of commands allowed to run
+                without confirmation in autonomous mode. When given, every
+                command that does not match one, or raises any concern, needs
+                approval (None keeps the policy's decision)
+            require_retype_for_critical: Approve commands affecting a
+                critical service or rated CRITICAL only when the user
+                retypes them
+            confirm_all: Confirm every command that is not read-only
+                (security.confirm_all, set by the paranoid preset)
+            rules: Command rules in evaluation order (security.rules then
+                deny_patterns); block rules and the security mode win over
+                allow rules
         """
         self.classifier = classifier or RiskClassifier()
         self.policy_manager = policy_manager or PolicyManager()
         self.rbac = rbac or RBAC()
         self.approval_flow = approval_flow or ApprovalFlow()
         self.audit = audit_logger or AuditLogger()
+        # Hashes of exact commands the user trusted for this session
+        # ("always this session"); kept in memory only
+        self._session_allowed: set[str] = set()
+        self.auto_run_allowlist = auto_run_allowlist
+        self.require_retype_for_critical = require_retype_for_critical
+        self.confirm_all = confirm_all
+        self.rules = rules or []
 
         self.logger.info("SecurityController initialized")
 
+    @staticmethod
+    def _command_key(command: str) -> str:
+        """Hash a command for the session allowance."""
+        return hashlib.sha256(command.encode("utf-8")).hexdigest()
+
+    def allow_for_session(self, command: str) -> None:
+        """Let an exact command run without confirmation for the rest of the session.
+
+        Args:
+            command: Command (or tool call description) to trust
+        """
+        self._session_allowed.add(self._command_key(command))
+        self.logger.info("Command allowed for this session", command=command[:50])
+
+    def is_allowed_for_session(self, command: str) -> bool:
+        """Check whether the user trusted this exact command for the session.
+
+        Args:
+            command: Command (or tool call description) to check
+
+        Returns:
+            True if it may run without confirmation
+        """
+        return self._command_key(command) in self._session_allowed
+
+    def _session_allowed_decision(
+        self, command: str, assessment: CommandRiskAssessment
+    ) -> SecurityDecision:
+        """Record and return the decision for a command trusted for the session."""
+        self.audit.log_command_approved(
+            command=command,
+            approver="session",
+            risk_level=assessment.risk_level,
+        )
+        return SecurityDecision(
+            result=ValidationResult.ALLOW,
+            command=command,
+            risk_assessment=assessment,
+            reason="Allowed for this session",
+            approved_by="session",
+            approval=ApprovalKind.CONFIRMED,
+        )
+
+    @staticmethod
+    def _approval_kind(response: ApprovalResponse, retyped: bool) -> ApprovalKind:
+        """Tell how an approved request was authorized."""
+        if response.approver.startswith("auto:"):
+            return ApprovalKind.AUTO_APPROVED
+        return ApprovalKind.RETYPED if retyped else ApprovalKind.CONFIRMED
+
+    def _requires_retype(self, assessment: CommandRiskAssessment) -> bool:
+        """Check whether approving a command takes retyping it."""
+        return self.require_retype_for_critical and (
+            assessment.affects_critical_service or assessment.risk_level >= RiskLevel.CRITICAL
+        )
+
     def check(
         self,
         command: str,
@@ -145,6 +287,25 @@ class SecurityController(LoggerMixin):
                 reason=f"Command blocked: {', '.join(risk_assessment.reasons)}",
             )
 
+        # Step 2b: Apply the configured command rules; block rules win
+        rule = match_rule(command, self.rules)
+        action = rule.action if rule else None
+        if action == RuleAction.BLOCK:
+            reason = f"Blocked by rule {rule.pattern}"
+            self.audit.log_command_blocked(
+                command=command,
+                reason=reason,
+                risk_level=risk_assessment.risk_level,
+            )
+            return SecurityDecision(
+                result=ValidationResult.BLOCKED,
+                command=command,
+                risk_assessment=risk_assessment,
+                reason=reason,
+            )
+        if action == RuleAction.WARN:
+            risk_assessment.reasons.append(f"Matches warn rule {rule.pattern}")
+
         # Step 3: Get policy for device
         policy = self.policy_manager.get_policy(context.device_id)
 
@@ -180,9 +341,38 @@ class SecurityController(LoggerMixin):
                 reason=rbac_reason,
             )
 
-        # Step 6: Check if policy requires approval
-        if policy.requires_approval(risk_assessment.risk_level):
+        # Step 6: Check if policy requires approval, unless an allow rule
+        # matched; possible exfiltration, root shells, commands too long to
+        # review and those matching a confirm rule are always confirmed
+        allowed_by_rule = action == RuleAction.ALLOW
+        if allowed_by_rule:
+            needs_approval = False
+        elif policy.requires_approval(risk_assessment.risk_level) or (
+            self.confirm_all and not self.classifier.is_read_only(command)
+        ):
             needs_approval = True
+        if (
+            action == RuleAction.CONFIRM
+            or risk_assessment.possible_exfiltration
+            or risk_assessment.opens_root_shell
+            or risk_assessment.too_long
+        ):
+            needs_approval = True
+
+        # In autonomous mode with an allowlist, only allowlisted commands
+        # without any concern run unasked
+        if (
+            not needs_approval
+            and not allowed_by_rule
+            and self.auto_run_allowlist is not None
+            and not auto_runnable(command, risk_assessment, self.auto_run_allowlist)
+        ):
+            return SecurityDecision(
+                result=ValidationResult.NEED_APPROVAL,
+                command=command,
+                risk_assessment=risk_assessment,
+                reason="Requires approval: not in security.auto_run_allowlist",
+            )
 
         # Step 7: Return decision
         if needs_approval:
@@ -198,12 +388,14 @@ class SecurityController(LoggerMixin):
             command=command,
             risk_assessment=risk_assessment,
             reason="Allowed by policy",
+            approval=ApprovalKind.AUTO_APPROVED,
         )
 
     def validate_and_approve(
         self,
         command: str,
         context: SecurityContext,
+        plan: Optional[list[str]] = None,
     ) -> SecurityDecision:
         """Validate a command and handle approval if needed.
 
@@ -212,6 +404,8 @@ class SecurityController(LoggerMixin):
         Args:
             command: Command to validate
             context: Security context
+            plan: Commands of the plan from this one on, offered for
+                editing together when approval is asked
 
         Returns:
             Final SecurityDecision
@@ -229,6 +423,12 @@ class SecurityController(LoggerMixin):
 
         # If needs approval, run approval flow
         if decision.result == ValidationResult.NEED_APPROVAL:
+            if (
+                decision.risk_assessment.risk_level < SESSION_APPROVAL_LIMIT
+                and self.is_allowed_for_session(command)
+            ):
+                return self._session_allowed_decision(command, decision.risk_assessment)
+
             if not context.interactive:
                 # Non-interactive mode - deny by default
                 self.audit.log_command_denied(
@@ -253,12 +453,15 @@ class SecurityController(LoggerMixin):
                     "cwd": context.cwd or "unknown",
                     "device": context.device_id or "local",
                 },
+                effects=preview_effects(command, cwd=context.cwd),
+                require_retype=self._requires_retype(decision.risk_assessment),
+                plan=plan or [],
             )
 
             approval_response = self.approval_flow.request_approval(approval_request)
 
             return self._handle_approval_response(
-                approval_response, decision, context
+                approval_response, decision, context, retyped=approval_request.require_retype
             )
 
         return decision
@@ -268,6 +471,7 @@ class SecurityController(LoggerMixin):
         response: ApprovalResponse,
         original_decision: SecurityDecision,
         context: SecurityContext,
+        retyped: bool = False,
     ) -> SecurityDecision:
         """Handle the approval response.
 
@@ -275,11 +479,17 @@ class SecurityController(LoggerMixin):
             response: Approval response
             original_decision: Original security decision
             context: Security context
+            retyped: Whether approving took retyping the command
 
         Returns:
             Updated SecurityDecision
         """
-        if response.result == ApprovalResult.APPROVED:
+        if response.result in (ApprovalResult.APPROVED, ApprovalResult.APPROVED_SESSION):
+            if (
+                response.result == ApprovalResult.APPROVED_SESSION
+                and original_decision.risk_assessment.risk_level < SESSION_APPROVAL_LIMIT
+            ):
+                self.allow_for_session(response.command)
             self.audit.log_command_approved(
                 command=response.command,
                 approver=response.approver,
@@ -291,6 +501,7 @@ class SecurityController(LoggerMixin):
                 risk_assessment=original_decision.risk_assessment,
                 reason="Approved by user",
                 approved_by=response.approver,
+                approval=self._approval_kind(response, retyped),
             )
 
         elif response.result == ApprovalResult.EDITED:
@@ -316,6 +527,8 @@ class SecurityController(LoggerMixin):
                 risk_assessment=new_decision.risk_assessment,
                 reason=f"Edited and approved by {response.approver}",
                 approved_by=response.approver,
+                approval=ApprovalKind.EDITED,
+                plan=response.plan,
             )
 
         elif response.result == ApprovalResult.SKIPPED:
@@ -344,6 +557,91 @@ class SecurityController(LoggerMixin):
                 reason=response.reason or "Denied by user",
             )
 
+    def confirm_tool(
+        self,
+        description: str,
+        risk_level: RiskLevel,
+        context: SecurityContext,
+        reasons: Optional[list[str]] = None,
+    ) -> SecurityDecision:
+        """Ask the user to confirm a tool that requires confirmation.
+
+        Used for tools whose policy demands confirmation regardless of
+        what they do. Edits are not applied, so only an explicit approval
+        lets the tool run.
+
+        Args:
+            description: The tool call, as shown to the user
+            risk_level: The tool's declared risk level
+            context: Security context
+            reasons: Why confirmation is needed, shown to the user
+                (default: the tool requires it)
+
+        Returns:
+            ALLOW if approved, else BLOCKED
+        """
+        assessment = CommandRiskAssessment(
+            command=description,
+            risk_level=risk_level,
+            reasons=reasons or ["Tool requires confirmation"],
+            requires_approval=True,
+        )
+
+        if risk_level < SESSION_APPROVAL_LIMIT and self.is_allowed_for_session(description):
+            return self._session_allowed_decision(description, assessment)
+
+        if not context.interactive:
+            reason = "Confirmation required but running non-interactively"
+            self.audit.log_command_denied(command=description, reason=reason, risk_level=risk_level)
+            return SecurityDecision(
+                result=ValidationResult.BLOCKED,
+                command=description,
+                risk_assessment=assessment,
+                reason=reason,
+            )
+
+        request = ApprovalRequest(
+            command=description,
+            risk_level=risk_level,
+            reasons=assessment.reasons,
+            context={
+                "user": context.user.name,
+                "cwd": context.cwd or "unknown",
+                "device": context.device_id or "local",
+            },
+            require_retype=self.require_retype_for_critical and risk_level >= RiskLevel.CRITICAL,
+        )
+        response = self.approval_flow.request_approval(request)
+
+        if response.result in (ApprovalResult.APPROVED, ApprovalResult.APPROVED_SESSION):
+            if (
+                response.result == ApprovalResult.APPROVED_SESSION
+                and risk_level < SESSION_APPROVAL_LIMIT
+            ):
+                self.allow_for_session(description)
+            self.audit.log_command_approved(
+                command=description,
+                approver=response.approver,
+                risk_level=risk_level,
+            )
+            return SecurityDecision(
+                result=ValidationResult.ALLOW,
+                command=description,
+                risk_assessment=assessment,
+                reason="Approved by user",
+                approved_by=response.approver,
+                approval=self._approval_kind(response, request.require_retype),
+            )
+
+        reason = response.reason or "Denied by user"
+        self.audit.log_command_denied(command=description, reason=reason, risk_level=risk_level)
+        return SecurityDecision(
+            result=ValidationResult.BLOCKED,
+            command=description,
+            risk_assessment=assessment,
+            reason=reason,
+        )
+
     def is_safe(self, command: str) -> bool:
         """Quick check if a command is safe.
 
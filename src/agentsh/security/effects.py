@@ -0,0 +1,174 @@
+"""Side-effect preview - Plain-English descriptions of what a command will do.
+
+Only simple invocations of a few well-known commands are described. The
+filesystem is consulted read-only (``stat`` and glob expansion); anything
+involving pipes, redirection, variables or command substitution is left
+undescribed rather than guessed at.
+"""
+
+import glob
+import os
+import re
+import shlex
+from typing import Optional
+
+# Shell constructs whose effect cannot be determined statically
+UNPREDICTABLE = re.compile(r"[|;&<>`(){}]|\$")
+
+GLOB_CHARS = re.compile(r"[*?\[]")
+
+# Leading wrappers that do not change what the wrapped command touches
+TRANSPARENT_PREFIXES = {"sudo", "command", "nice"}
+
+
+def _plural(count: int, noun: str, plural: Optional[str] = None) -> str:
+    """Format a count with a singular or plural noun."""
+    return f"{count} {noun}" if count == 1 else f"{count} {plural or noun + 's'}"
+
+
+def _split_args(args: list[str]) -> tuple[set[str], list[str]]:
+    """Split arguments into flag characters/names and operands.
+
+    Args:
+        args: Arguments after the command name
+
+    Returns:
+        Tuple of (flags, operands). Short flags are split into characters,
+        long flags keep their ``--`` prefix.
+    """
+    flags: set[str] = set()
+    operands: list[str] = []
+    end_of_options = False
+
+    for arg in args:
+        if end_of_options or not arg.startswith("-") or arg == "-":
+            operands.append(arg)
+        elif arg == "--":
+            end_of_options = True
+        elif arg.startswith("--"):
+            flags.add(arg.split("=", 1)[0])
+        else:
+            flags.update(arg[1:])
+
+    return flags, operands
+
+
+def _expand(operands: list[str], cwd: str) -> Optional[list[str]]:
+    """Expand operands the way the shell would, without touching anything.
+
+    Args:
+        operands: Path operands as typed
+        cwd: Directory relative paths are resolved against
+
+    Returns:
+        Absolute paths that exist, or None if a glob matched nothing
+    """
+    paths: list[str] = []
+
+    for operand in operands:
+        pattern = os.path.join(cwd, os.path.expanduser(operand))
+        if GLOB_CHARS.search(operand):
+            matches = sorted(glob.glob(pattern))
+            if not matches:
+                return None
+            paths.extend(matches)
+        elif os.path.lexists(pattern):
+            paths.append(pattern)
+
+    return paths
+
+
+def _describe_rm(args: list[str], cwd: str) -> list[str]:
+    """Describe an ``rm`` invocation."""
+    flags, operands = _split_args(args)
+    paths = _expand(operands, cwd)
+    if not paths:
+        return []
+
+    recursive = bool(flags & {"r", "R", "--recursive"})
+    dirs = [p for p in paths if os.path.isdir(p) and not os.path.islink(p)]
+
+    effects = [f"will delete {_plural(len(paths), 'path')}"]
+    if dirs and recursive:
+        directories = _plural(len(dirs), "directory", "directories")
+        effects.append(f"will recursively delete the contents of {directories}")
+    return effects
+
+
+def _describe_transfer(verb: str, args: list[str], cwd: str) -> list[str]:
+    """Describe a ``cp`` or ``mv`` invocation."""
+    flags, operands = _split_args(args)
+    if len(operands) < 2 or "t" in flags or "--target-directory" in flags:
+        return []
+
+    sources = _expand(operands[:-1], cwd)
+    if not sources:
+        return []
+
+    dest = os.path.join(cwd, os.path.expanduser(operands[-1]))
+    effects = [f"will {verb} {_plural(len(sources), 'path')} to {operands[-1]}"]
+
+    if len(sources) == 1 and os.path.isfile(dest):
+        effects.append(f"will overwrite {operands[-1]}")
+    return effects
+
+
+def _describe_chmod(args: list[str], cwd: str) -> list[str]:
+    """Describe a ``chmod`` invocation."""
+    flags, operands = _split_args(args)
+    if len(operands) < 2:
+        return []
+
+    mode, targets = operands[0], operands[1:]
+    paths = _expand(targets, cwd)
+    if not paths:
+        return []
+
+    effect = f"will change permissions of {_plural(len(paths), 'path')} to {mode}"
+    if flags & {"R", "--recursive"}:
+        effect += " recursively"
+    return [effect]
+
+
+def preview_effects(command: str, cwd: Optional[str] = None) -> list[str]:
+    """Describe the likely side effects of a command.
+
+    Supports simple ``rm``, ``mv``, ``cp`` and ``chmod`` invocations. Globs
+    are expanded against the filesystem to count targets; nothing is
+    modified.
+
+    Args:
+        command: Shell command line
+        cwd: Working directory the command will run in (default: current)
+
+    Returns:
+        Effect descriptions, e.g. ["will delete 3 paths"]. Empty if the
+        effect cannot be determined safely.
+    """
+    if UNPREDICTABLE.search(command):
+        return []
+
+    try:
+        tokens = shlex.split(command)
+    except ValueError:
+        return []
+
+    # Quoted globs are literal; shlex drops the quotes, so give up
+    if ("'" in command or '"' in command) and any(GLOB_CHARS.search(t) for t in tokens):
+        return []
+
+    while tokens and tokens[0] in TRANSPARENT_PREFIXES:
+        tokens = tokens[1:]
+    if not tokens:
+        return []
+
+    name, args = os.path.basename(tokens[0]), tokens[1:]
+    base = cwd or os.getcwd()
+
+    if name == "rm":
+        return _describe_rm(args, base)
+    if name in ("cp", "mv"):
+        return _describe_transfer("copy" if name == "cp" else "move", args, base)
+    if name == "chmod":
+        return _describe_chmod(args, base)
+    return []
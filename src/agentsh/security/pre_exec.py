@@ -0,0 +1,132 @@
+"""Pre-execution hook - Let an external policy engine vet each AI step.
+
+When ``security.pre_exec_hook`` is set, the command it names is run before
+every step the AI proposes. It receives the step as JSON on stdin::
+
+    {"tool": "shell.run", "arguments": {"command": "rm -rf build"},
+     "command": "rm -rf build", "cwd": "/home/me/project"}
+
+and answers with a JSON verdict on stdout::
+
+    {"action": "allow"}
+    {"action": "deny", "reason": "rm is not allowed in CI"}
+    {"action": "modify", "command": "rm -ri build", "reason": "made interactive"}
+
+Exiting 0 without output allows the step. The hook fails closed: a
+non-zero exit, a timeout or an unreadable answer denies the step.
+"""
+
+import json
+import os
+import shlex
+import subprocess
+from dataclasses import dataclass
+from enum import Enum
+from typing import Any, Optional
+
+from agentsh.telemetry.logger import get_logger
+
+logger = get_logger(__name__)
+
+# Characters of the hook's stderr quoted when it fails
+HOOK_ERROR_CHARS = 200
+
+
+class HookAction(str, Enum):
+    """What the pre-execution hook decided for a step."""
+
+    ALLOW = "allow"
+    DENY = "deny"
+    MODIFY = "modify"
+
+
+@dataclass
+class HookVerdict:
+    """Answer of the pre-execution hook.
+
+    Attributes:
+        action: Whether the step runs, is refused, or runs changed
+        command: Replacement command (modify only)
+        reason: Explanation given by the hook
+    """
+
+    action: HookAction
+    command: Optional[str] = None
+    reason: str = ""
+
+
+def parse_verdict(output: str) -> HookVerdict:
+    """Read the hook's verdict from its output.
+
+    Args:
+        output: What the hook printed on stdout
+
+    Returns:
+        The verdict; DENY if the output cannot be understood
+    """
+    if not output.strip():
+        return HookVerdict(HookAction.ALLOW)
+    try:
+        data = json.loads(output)
+        action = HookAction(str(data.get("action", "")).lower())
+    except (json.JSONDecodeError, AttributeError, ValueError):
+        return HookVerdict(HookAction.DENY, reason="pre-exec hook gave an unreadable answer")
+
+    reason = str(data.get("reason") or "")
+    if action == HookAction.MODIFY:
+        command = data.get("command")
+        if not isinstance(command, str) or not command.strip():
+            return HookVerdict(HookAction.DENY, reason="pre-exec hook modified without a command")
+        return HookVerdict(action, command=command, reason=reason)
+    return HookVerdict(action, reason=reason)
+
+
+def run_pre_exec_hook(
+    hook: str,
+    step: dict[str, Any],
+    timeout: float,
+    cwd: Optional[str] = None,
+) -> HookVerdict:
+    """Ask the pre-execution hook about a step.
+
+    Args:
+        hook: Hook command line (program path and arguments; ~ is expanded)
+        step: The step, sent as JSON on stdin
+        timeout: Seconds the hook may take
+        cwd: Directory to run the hook in
+
+    Returns:
+        The hook's verdict; DENY if it fails, times out or cannot be run
+    """
+    argv = shlex.split(hook)
+    if not argv:
+        return HookVerdict(HookAction.DENY, reason="security.pre_exec_hook is empty")
+    argv[0] = os.path.expanduser(argv[0])
+
+    try:
+        completed = subprocess.run(
+            argv,
+            input=json.dumps(step, default=str),
+            capture_output=True,
+            text=True,
+            timeout=timeout,
+            cwd=cwd or None,
+        )
+    except subprocess.TimeoutExpired:
+        logger.warning("Pre-exec hook timed out", hook=hook, timeout=timeout)
+        return HookVerdict(HookAction.DENY, reason=f"pre-exec hook timed out after {timeout:g}s")
+    except OSError as e:
+        logger.warning("Pre-exec hook could not run", hook=hook, error=str(e))
+        return HookVerdict(HookAction.DENY, reason=f"pre-exec hook could not run: {e}")
+
+    if completed.returncode != 0:
+        stderr = completed.stderr.strip()[:HOOK_ERROR_CHARS]
+        return HookVerdict(
+            HookAction.DENY,
+            reason=f"pre-exec hook exited with {completed.returncode}"
+            + (f": {stderr}" if stderr else ""),
+        )
+
+    verdict = parse_verdict(completed.stdout)
+    logger.debug("Pre-exec hook verdict", action=verdict.action.value, reason=verdict.reason)
+    return verdict
@@ -0,0 +1,298 @@
+"""Safe delete - Move rm targets to a trash directory instead of deleting.
+
+With ``security.rm_to_trash`` enabled, a plain ``rm`` run by the agent is
+rewritten into a move to ``~/.agentsh/trash/<session>/<n>/`` so mistakes
+can be undone by moving the files back. Each rm call gets its own
+numbered directory, so repeated deletions of the same name never clash.
+
+Only simple commands are rewritten. Targets under the temp directory are
+still removed for real, and rm falls back to a real delete with a warning
+when the rewrite is not feasible:
+
+- flags with no move equivalent (e.g. ``--one-file-system``)
+- unquoted globs or variables, which would need the shell to expand them
+- several targets sharing a file name
+- ``-i``/``-I``, where rm asks the user before removing anything
+
+A directory named without ``-r`` (or an empty one without ``-d``) is not
+moved: the rewritten command fails for it with rm's error, as rm would.
+"""
+
+import os
+import shlex
+import tempfile
+import time
+from dataclasses import dataclass, field
+from pathlib import Path
+from typing import Optional
+
+# Root of all trash directories
+TRASH_ROOT = Path.home() / ".agentsh" / "trash"
+
+# Short rm flags that keep their meaning when moving instead
+SHORT_FLAGS = frozenset("rRfiIvd")
+
+# --interactive values under which rm does not ask
+NEVER_ASK = frozenset({"never", "no", "none"})
+
+# Long rm flags that keep their meaning when moving instead
+LONG_FLAGS = frozenset({"--recursive", "--force", "--verbose", "--dir", "--interactive"})
+
+# Characters that make a command more than a single rm call
+SHELL_OPERATORS = (";", "&", "|", "<", ">", "`", "$(", "\n")
+
+# Characters the shell would expand in an unquoted word
+EXPANSION_CHARS = ("*", "?", "[", "$", "{")
+
+
+@dataclass
+class RmInvocation:
+    """A parsed rm command.
+
+    Attributes:
+        flags: Options in the order given
+        targets: Paths to remove
+    """
+
+    flags: list[str] = field(default_factory=list)
+    targets: list[str] = field(default_factory=list)
+
+    @property
+    def force(self) -> bool:
+        """Whether missing targets are ignored (-f)."""
+        return "--force" in self.flags or any(
+            not flag.startswith("--") and "f" in flag for flag in self.flags
+        )
+
+    @property
+    def recursive(self) -> bool:
+        """Whether directories are removed with their contents (-r, -R)."""
+        return "--recursive" in self.flags or any(
+            not flag.startswith("--") and ("r" in flag or "R" in flag) for flag in self.flags
+        )
+
+    @property
+    def empty_dirs(self) -> bool:
+        """Whether empty directories are removed (-d)."""
+        return "--dir" in self.flags or any(
+            not flag.startswith("--") and "d" in flag for flag in self.flags
+        )
+
+    @property
+    def interactive(self) -> bool:
+        """Whether rm asks before removing; the last of -f and -i/-I wins."""
+        asks = False
+        for flag in self.flags:
+            if flag.startswith("--"):
+                name, _, when = flag.partition("=")
+                if name == "--force":
+                    asks = False
+                elif name == "--interactive":
+                    asks = when not in NEVER_ASK
+                continue
+            for char in flag[1:]:
+                if char in "iI":
+                    asks = True
+                elif char == "f":
+                    asks = False
+        return asks
+
+    def unsupported_flags(self) -> list[str]:
+        """Get the flags a move cannot reproduce."""
+        unsupported = []
+        for flag in self.flags:
+            if flag.startswith("--"):
+                if flag.split("=", 1)[0] not in LONG_FLAGS:
+                    unsupported.append(flag)
+            elif not set(flag[1:]) <= SHORT_FLAGS:
+                unsupported.append(flag)
+        return unsupported
+
+
+@dataclass
+class TrashRewrite:
+    """Result of rewriting a command for safe delete.
+
+    Attributes:
+        command: Command to run instead
+        trash_dir: Directory the targets are moved to, if any
+        trashed: Targets moved to the trash
+        warning: Why the command still deletes for real, if it does
+    """
+
+    command: str
+    trash_dir: Optional[Path] = None
+    trashed: list[str] = field(default_factory=list)
+    warning: Optional[str] = None
+
+
+def session_trash_dir(session_id: Optional[str] = None) -> Path:
+    """Get the trash directory for a session.
+
+    Args:
+        session_id: Session identifier (defaults to the start time and PID)
+
+    Returns:
+        Directory under :data:`TRASH_ROOT`
+    """
+    name = session_id or f"{time.strftime('%Y%m%d-%H%M%S')}-{os.getpid()}"
+    return TRASH_ROOT / name
+
+
+def parse_rm(command: str) -> Optional[RmInvocation]:
+    """Parse a command that is a single rm call.
+
+    Args:
+        command: Shell command
+
+    Returns:
+        The parsed call, or None if the command is not a single rm call
+    """
+    if any(op in command for op in SHELL_OPERATORS):
+        return None
+    try:
+        parts = shlex.split(command)
+    except ValueError:
+        return None
+    if not parts or parts[0] not in ("rm", "/bin/rm", "/usr/bin/rm"):
+        return None
+
+    invocation = RmInvocation()
+    options_done = False
+    for part in parts[1:]:
+        if not options_done and part == "--":
+            options_done = True
+        elif not options_done and part.startswith("-") and part != "-":
+            invocation.flags.append(part)
+        else:
+            invocation.targets.append(part)
+    return invocation
+
+
+def _has_expansion(command: str) -> bool:
+    """Check for glob or variable characters outside single quotes."""
+    lexer = shlex.shlex(command, posix=False)
+    lexer.whitespace_split = True
+    for token in lexer:
+        if token[:1] == "'":
+            continue
+        if any(char in token for char in EXPANSION_CHARS):
+            return True
+    return False
+
+
+def temp_roots() -> set[str]:
+    """Get the resolved temp directories whose contents are deleted for real."""
+    return {os.path.realpath(tempfile.gettempdir()), os.path.realpath("/tmp")}
+
+
+def is_temp_path(path: str, cwd: str) -> bool:
+    """Check whether a path lies under the temp directory.
+
+    Args:
+        path: Target as given to rm
+        cwd: Directory the command runs in
+
+    Returns:
+        True for paths under the system temp directory or /tmp
+    """
+    resolved = os.path.realpath(os.path.join(cwd, os.path.expanduser(path)))
+    return any(resolved.startswith(f"{root}/") for root in temp_roots())
+
+
+def _next_trash_dir(trash_dir: Path) -> Path:
+    """Pick an unused numbered directory for one rm call."""
+    index = 1
+    while (trash_dir / str(index)).exists():
+        index += 1
+    return trash_dir / str(index)
+
+
+def rewrite_rm_to_trash(command: str, trash_dir: Path, cwd: str) -> TrashRewrite:
+    """Rewrite an rm command into a move to the trash.
+
+    Args:
+        command: Command about to run
+        trash_dir: Session trash directory
+        cwd: Directory the command runs in
+
+    Returns:
+        TrashRewrite with the command to run; unchanged for anything that
+        is not an rm call
+    """
+    invocation = parse_rm(command)
+    if invocation is None or not invocation.targets:
+        return TrashRewrite(command=command)
+
+    unsupported = invocation.unsupported_flags()
+    if unsupported:
+        return TrashRewrite(
+            command=command,
+            warning=f"rm flags {' '.join(unsupported)} cannot be moved to trash; deleting",
+        )
+    if _has_expansion(command):
+        return TrashRewrite(
+            command=command,
+            warning="rm with globs or variables cannot be moved to trash; deleting",
+        )
+    if invocation.interactive:
+        return TrashRewrite(
+            command=command,
+            warning="rm -i asks before each removal and cannot be moved to trash; deleting",
+        )
+
+    targets = [os.path.expanduser(t) for t in invocation.targets]
+    temp_targets = [t for t in targets if is_temp_path(t, cwd)]
+    to_trash = [t for t in targets if t not in temp_targets]
+    if invocation.force:
+        # rm -f ignores missing targets; mv would fail on them
+        to_trash = [t for t in to_trash if os.path.lexists(os.path.join(cwd, t))]
+    # rm refuses directories without -r (or -d for empty ones); mv would not
+    refused = [t for t in to_trash if not _removable(os.path.join(cwd, t), invocation)]
+    to_trash = [t for t in to_trash if t not in refused]
+    if not to_trash:
+        return TrashRewrite(command=command)
+
+    names = [os.path.basename(os.path.normpath(t)) for t in to_trash]
+    if len(set(names)) != len(names):
+        return TrashRewrite(
+            command=command,
+            warning="rm targets share a file name and cannot be moved to trash; deleting",
+        )
+
+    dest = _next_trash_dir(trash_dir)
+    quoted_dest = shlex.quote(str(dest))
+    moved = " ".join(shlex.quote(t) for t in to_trash)
+    parts = [f"mkdir -p {quoted_dest}", f"mv -- {moved} {quoted_dest}/"]
+    if temp_targets:
+        flags = " ".join(shlex.quote(f) for f in invocation.flags)
+        temp = " ".join(shlex.quote(t) for t in temp_targets)
+        parts.append(f"rm {flags} -- {temp}" if flags else f"rm -- {temp}")
+    if refused:
+        parts.extend(
+            f"echo {shlex.quote(f'rm: cannot remove {t!r}: Is a directory')} >&2"
+            for t in refused
+        )
+        parts.append("false")
+
+    return TrashRewrite(command=" && ".join(parts), trash_dir=dest, trashed=to_trash)
+
+
+def _removable(path: str, invocation: RmInvocation) -> bool:
+    """Check whether rm with these flags would remove a path rather than refuse.
+
+    Args:
+        path: Target, resolved against the command's directory
+        invocation: The rm call
+
+    Returns:
+        False for a directory rm would refuse to remove
+    """
+    if os.path.islink(path) or not os.path.isdir(path) or invocation.recursive:
+        return True
+    if invocation.empty_dirs:
+        try:
+            return not os.listdir(path)
+        except OSError:
+            return False
+    return False
@@ -40,6 +40,7 @@ from agentsh.shell.prompt import (
     strip_ansi,
 )
 from agentsh.shell.pty_manager import PTYManager
+from agentsh.shell.recorder import SessionRecorder
 from agentsh.shell.wrapper import ShellWrapper
 
 __all__ = [
@@ -47,6 +48,8 @@ __all__ = [
     "ShellWrapper",
     # PTY Manager
     "PTYManager",
+    # Session Recorder
+    "SessionRecorder",
     # Completer
     "CompletionResult",
     "CompletionType",
@@ -0,0 +1,154 @@
+"""Crash cleanup - Reap child shells and restore the terminal on a crash.
+
+An unhandled exception in the main loop can leave the shell spawned in a
+PTY running as an orphan, and a terminal in raw mode behind. ChildGuard
+ties a child process to an object that kills and reaps it when released
+or garbage collected, and :func:`install_crash_hook` installs an
+exception hook that restores the terminal settings and reaps every
+guarded child before the traceback is printed. Callers that catch the
+exception themselves use :func:`save_terminal` and
+:func:`restore_terminal` directly.
+"""
+
+import signal
+import sys
+import termios
+import weakref
+from types import TracebackType
+from typing import Any, Optional
+
+from agentsh.telemetry.logger import get_logger
+
+logger = get_logger(__name__)
+
+# Children that have not been reaped yet
+_live_guards: "weakref.WeakSet[ChildGuard]" = weakref.WeakSet()
+
+
+class ChildGuard:
+    """Kill and reap a child process when the guard is dropped.
+
+    The child only needs ``isalive()``, ``kill(sig)`` and ``wait()``, as
+    offered by ``ptyprocess.PtyProcess``.
+
+    Example:
+        guard = ChildGuard(process)
+        try:
+            run(process)
+        finally:
+            guard.reap()
+    """
+
+    def __init__(self, child: Any) -> None:
+        """Start guarding a child process.
+
+        Args:
+            child: Process to reap when the guard is dropped
+        """
+        self._child: Optional[Any] = child
+        _live_guards.add(self)
+
+    @property
+    def active(self) -> bool:
+        """Whether the child has not been reaped yet."""
+        return self._child is not None
+
+    def reap(self) -> None:
+        """Kill the child if it is still running and wait for it to exit.
+
+        Safe to call more than once; errors are logged, not raised, as
+        this runs during crash handling and garbage collection.
+        """
+        child, self._child = self._child, None
+        if child is None:
+            return
+        _live_guards.discard(self)
+        try:
+            if child.isalive():
+                child.kill(signal.SIGKILL)
+            child.wait()
+        except Exception as e:
+            logger.debug("Failed to reap child process", error=str(e))
+
+    def __enter__(self) -> "ChildGuard":
+        """Context manager entry."""
+        return self
+
+    def __exit__(self, exc_type: object, exc_val: object, exc_tb: object) -> None:
+        """Context manager exit."""
+        self.reap()
+
+    def __del__(self) -> None:
+        """Reap the child when the guard is garbage collected."""
+        self.reap()
+
+
+def reap_children() -> int:
+    """Kill and reap every guarded child process.
+
+    Returns:
+        Number of children reaped
+    """
+    guards = list(_live_guards)
+    for guard in guards:
+        guard.reap()
+    return len(guards)
+
+
+def save_terminal(stream: Any = None) -> Optional[tuple[int, list[Any]]]:
+    """Save the terminal settings of a stream.
+
+    Args:
+        stream: Terminal to save (defaults to stdin)
+
+    Returns:
+        Saved settings for :func:`restore_terminal`, or None if the
+        stream is not a terminal
+    """
+    stream = sys.stdin if stream is None else stream
+    try:
+        if stream is not None and stream.isatty():
+            fd = stream.fileno()
+            return (fd, termios.tcgetattr(fd))
+    except (AttributeError, OSError, ValueError, termios.error):
+        pass
+    return None
+
+
+def restore_terminal(saved: Optional[tuple[int, list[Any]]]) -> None:
+    """Put back terminal settings saved by :func:`save_terminal`.
+
+    Args:
+        saved: Saved settings; None does nothing
+    """
+    if saved is None:
+        return
+    try:
+        termios.tcsetattr(saved[0], termios.TCSADRAIN, saved[1])
+    except (OSError, termios.error):
+        pass
+
+
+def install_crash_hook(stream: Any = None) -> None:
+    """Restore the terminal and reap children on an unhandled exception.
+
+    The terminal settings of ``stream`` are saved now and put back by the
+    hook, so a crash while the terminal is in raw mode does not leave it
+    garbled. The previous ``sys.excepthook`` still runs afterwards.
+
+    Args:
+        stream: Terminal to restore (defaults to stdin)
+    """
+    saved = save_terminal(stream)
+    previous = sys.excepthook
+
+    def hook(
+        exc_type: type[BaseException],
+        exc: BaseException,
+        tb: Optional[TracebackType],
+    ) -> None:
+        restore_terminal(saved)
+        reap_children()
+        previous(exc_type, exc, tb)
+
+    sys.excepthook = hook
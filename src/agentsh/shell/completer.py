@@ -25,6 +25,7 @@ class CompletionType(str, Enum):
     """Types of completions."""
 
     SPECIAL_COMMAND = "special_command"
+    AI_SUBCOMMAND = "ai_subcommand"
     TOOL = "tool"
     FILE_PATH = "file_path"
     SHELL_COMMAND = "shell_command"
@@ -49,6 +50,7 @@ class ShellCompleter:
     - Current input prefix
     - Available tools
     - Special commands
+    - Subcommands after the AI prefix (e.g. ``ai check``)
     - File system paths
     - Underlying shell completions (in hybrid mode)
 
@@ -67,17 +69,21 @@ class ShellCompleter:
         self,
         mode: CompletionMode = CompletionMode.HYBRID,
         config: Optional[CompletionConfig] = None,
+        ai_prefix: str = "ai ",
     ) -> None:
         """Initialize the completer.
 
         Args:
             mode: Completion mode (NATIVE, PASSTHROUGH, HYBRID)
             config: Completion configuration
+            ai_prefix: Prefix that forces AI routing and starts AI subcommands
         """
         self._mode = mode
         self._config = config or CompletionConfig(mode=mode)
+        self._ai_prefix = ai_prefix
         self._tools: dict[str, str] = {}  # name -> description
         self._special_commands: dict[str, str] = {}  # command -> description
+        self._ai_subcommands: dict[str, list[str]] = {}  # subcommand -> argument choices
         self._history: list[str] = []
         self._matches: list[str] = []
         self._installed = False
@@ -145,6 +151,51 @@ class ShellCompleter:
         """
         self._special_commands.update(commands)
 
+    def register_ai_subcommand(
+        self, subcommand: str, arguments: Optional[list[str]] = None
+    ) -> None:
+        """Register a subcommand of the AI prefix for completion.
+
+        Args:
+            subcommand: Subcommand name (e.g., 'check')
+            arguments: Fixed choices for its first argument, if any
+        """
+        self._ai_subcommands[subcommand] = list(arguments or [])
+
+    def register_ai_subcommands(self, subcommands: dict[str, list[str]]) -> None:
+        """Register multiple AI subcommands.
+
+        Args:
+            subcommands: Dict of subcommand -> argument choices
+        """
+        for subcommand, arguments in subcommands.items():
+            self.register_ai_subcommand(subcommand, arguments)
+
+    def complete_ai(self, line: str) -> list[str]:
+        """Complete the word being typed after the AI prefix.
+
+        Args:
+            line: Input line up to the cursor
+
+        Returns:
+            Matching subcommands, or argument choices once a subcommand
+            has been typed. Empty when the line is not an AI line.
+        """
+        stripped = line.lstrip()
+        if not stripped.startswith(self._ai_prefix):
+            return []
+
+        words = stripped[len(self._ai_prefix) :].split(" ")
+        current = words[-1]
+        if len(words) == 1:
+            candidates = list(self._ai_subcommands)
+        elif len(words) == 2 and words[0] in self._ai_subcommands:
+            candidates = self._ai_subcommands[words[0]]
+        else:
+            return []
+
+        return sorted(c for c in candidates if c.startswith(current))
+
     def add_to_history(self, entry: str) -> None:
         """Add an entry to completion history.
 
@@ -192,6 +243,11 @@ class ShellCompleter:
         if self._mode == CompletionMode.PASSTHROUGH:
             return self._get_shell_completions(text, line)
 
+        # AI subcommands are AgentSH's own; the shell knows nothing about them
+        ai_completions = self.complete_ai(line)
+        if ai_completions:
+            return ai_completions
+
         # Get AgentSH completions
         agentsh_completions = self._get_agentsh_completions(text, stripped_line)
 
@@ -399,6 +455,8 @@ def setup_completion(
     special_commands: Optional[dict[str, str]] = None,
     mode: CompletionMode = CompletionMode.HYBRID,
     config: Optional[CompletionConfig] = None,
+    ai_subcommands: Optional[dict[str, list[str]]] = None,
+    ai_prefix: str = "ai ",
 ) -> ShellCompleter:
     """Set up tab completion with given tools and commands.
 
@@ -407,12 +465,14 @@ def setup_completion(
         special_commands: Dict of command name -> description
         mode: Completion mode (NATIVE, PASSTHROUGH, HYBRID)
         config: Completion configuration
+        ai_subcommands: Dict of AI subcommand -> argument choices
+        ai_prefix: Prefix that starts AI subcommands
 
     Returns:
         Configured ShellCompleter
     """
     global _completer
-    _completer = ShellCompleter(mode=mode, config=config)
+    _completer = ShellCompleter(mode=mode, config=config, ai_prefix=ai_prefix)
 
     if tools:
         _completer.register_tools(tools)
@@ -420,5 +480,8 @@ def setup_completion(
     if special_commands:
         _completer.register_special_commands(special_commands)
 
+    if ai_subcommands:
+        _completer.register_ai_subcommands(ai_subcommands)
+
     _completer.install()
     return _completer
@@ -25,11 +25,11 @@ _agentsh_completions() {{
     local cur prev words cword
     _init_completion || return
 
-    local commands="config status completions devices help"
+    local commands="config status doctor completions devices help"
     local config_commands="init show edit reset"
     local devices_commands="list add remove status"
     local completions_shells="bash zsh fish"
-    local global_opts="--help --version --config --log-level --login --norc --noprofile --rcfile --mcp-server --profile-startup"
+    local global_opts="--help --version --config --log-level --debug --login --norc --noprofile --rcfile --init-shell --mcp-server --profile-startup"
 
     case "${{prev}}" in
         agentsh)
@@ -56,6 +56,10 @@ _agentsh_completions() {{
             COMPREPLY=($(compgen -W "DEBUG INFO WARNING ERROR" -- "${{cur}}"))
             return
             ;;
+        --init-shell)
+            COMPREPLY=($(compgen -W "${{completions_shells}}" -- "${{cur}}"))
+            return
+            ;;
         bash|zsh|fish)
             COMPREPLY=($(compgen -W "--install" -- "${{cur}}"))
             return
@@ -97,6 +101,7 @@ _agentsh() {{
     commands=(
         'config:Manage configuration'
         'status:Show status'
+        'doctor:Show an environment report'
         'completions:Generate shell completions'
         'devices:Device management'
         'help:Show help'
@@ -124,10 +129,12 @@ _agentsh() {{
         '--version[Show version]'
         '--config[Configuration file]:file:_files -g "*.yaml"'
         '--log-level[Log level]:level:(DEBUG INFO WARNING ERROR)'
+        '--debug[Debug logging with LLM request bodies]'
         '(-l --login)'{{'{{-l,--login}}'}}'[Run as login shell]'
         '--norc[Skip rc files]'
         '--noprofile[Skip profile files]'
         '--rcfile[Custom RC file]:file:_files'
+        '--init-shell[Print shell integration snippet]:shell:(bash zsh fish)'
         '--mcp-server[Run as MCP server]'
         '--profile-startup[Profile startup time]'
     )
@@ -188,16 +195,19 @@ complete -c agentsh -s h -l help -d 'Show help message'
 complete -c agentsh -l version -d 'Show version'
 complete -c agentsh -l config -d 'Configuration file' -r -F
 complete -c agentsh -l log-level -d 'Log level' -xa 'DEBUG INFO WARNING ERROR'
+complete -c agentsh -l debug -d 'Debug logging with LLM request bodies'
 complete -c agentsh -s l -l login -d 'Run as login shell'
 complete -c agentsh -l norc -d 'Skip rc files'
 complete -c agentsh -l noprofile -d 'Skip profile files'
 complete -c agentsh -l rcfile -d 'Custom RC file' -r -F
+complete -c agentsh -l init-shell -d 'Print shell integration snippet' -xa 'bash zsh fish'
 complete -c agentsh -l mcp-server -d 'Run as MCP server'
 complete -c agentsh -l profile-startup -d 'Profile startup time'
 
 # Commands
 complete -c agentsh -n __fish_use_subcommand -a config -d 'Manage configuration'
 complete -c agentsh -n __fish_use_subcommand -a status -d 'Show status'
+complete -c agentsh -n __fish_use_subcommand -a doctor -d 'Show an environment report'
 complete -c agentsh -n __fish_use_subcommand -a completions -d 'Generate shell completions'
 complete -c agentsh -n __fish_use_subcommand -a devices -d 'Device management'
 complete -c agentsh -n __fish_use_subcommand -a help -d 'Show help'
@@ -369,6 +369,7 @@ Session Management:
   :history [n]     Show command history (last n entries)
   :reset           Reset AI conversation context
   :config          Show current configuration
+  :why             Show the prompt sent for the last AI request and its reply
 
 Exit:
   :quit            Exit AgentSH
@@ -378,6 +379,14 @@ Exit:
 Input Prefixes:
   !<command>       Force shell execution
   ai <request>     Force AI processing
+  ai check '<cmd>' Show how a command would be classified (no AI call)
+  ai explain '<cmd>'
+                   Explain a command: what it does, each flag, and its risk
+  ai redo          Run the last executed plan again, confirming each step anew
+  ai reload        Re-read the configuration files, keeping the conversation
+  ai ask <request> Answer using read-only commands only; anything else is refused
+  ai export-chat <file>
+                   Save the AI session as Markdown, with secrets redacted
 """,
                 see_also=["history", "config", "status"],
             )
@@ -2,12 +2,14 @@
 
 import json
 import os
+import re
 from dataclasses import asdict, dataclass
 from datetime import datetime
 from pathlib import Path
 from typing import Iterator, Optional
 
 from agentsh.telemetry.logger import get_logger
+from agentsh.utils.validators import redact_secrets
 
 logger = get_logger(__name__)
 
@@ -50,6 +52,7 @@ class HistoryManager:
     - Deduplication of consecutive identical commands
     - Search functionality
     - Configurable maximum size
+    - Filtering of space-prefixed and ignored commands, and secret redaction
 
     Example:
         history = HistoryManager(path=Path("~/.agentsh/history.json"))
@@ -69,6 +72,9 @@ class HistoryManager:
         path: Optional[Path] = None,
         max_entries: int = 10000,
         deduplicate: bool = True,
+        ignore_space: bool = False,
+        ignore_patterns: Optional[list[str]] = None,
+        redact: bool = False,
     ) -> None:
         """Initialize history manager.
 
@@ -76,10 +82,22 @@ class HistoryManager:
             path: Path to history file. Uses default if None.
             max_entries: Maximum number of entries to keep
             deduplicate: Remove consecutive duplicate commands
+            ignore_space: Skip input that starts with a space
+            ignore_patterns: Regex patterns; matching commands are not recorded
+            redact: Redact secrets (API keys, tokens, passwords) before storing
         """
         self.path = path or self._default_path()
         self.max_entries = max_entries
         self.deduplicate = deduplicate
+        self.ignore_space = ignore_space
+        self.redact = redact
+
+        self._ignore_patterns: list[re.Pattern[str]] = []
+        for pattern in ignore_patterns or []:
+            try:
+                self._ignore_patterns.append(re.compile(pattern))
+            except re.error as e:
+                logger.warning("Invalid history ignore pattern", pattern=pattern, error=str(e))
 
         self._entries: list[HistoryEntry] = []
         self._cursor: int = 0  # For up/down navigation
@@ -146,6 +164,7 @@ class HistoryManager:
         command: str,
         is_ai_request: bool = False,
         exit_code: Optional[int] = None,
+        original: Optional[str] = None,
     ) -> None:
         """Add a command to history.
 
@@ -153,10 +172,13 @@ class HistoryManager:
             command: Command string
             is_ai_request: Whether this was an AI request
             exit_code: Exit code of the command
+            original: Raw input line, checked for a leading space when
+                ignore_space is set (default: command)
         """
-        command = command.strip()
-        if not command:
+        filtered = self.filter(command, original)
+        if filtered is None:
             return
+        command = filtered
 
         # Deduplicate consecutive identical commands
         if self.deduplicate and self._entries:
@@ -187,6 +209,31 @@ class HistoryManager:
 
         self._cursor = len(self._entries)
 
+    def filter(self, command: str, original: Optional[str] = None) -> Optional[str]:
+        """Apply ignore_space, ignore_patterns and redaction to a command.
+
+        Args:
+            command: Command string
+            original: Raw input line, checked for a leading space when
+                ignore_space is set (default: command)
+
+        Returns:
+            The command as it may be stored, or None if it must not be
+        """
+        if self.ignore_space and (original if original is not None else command)[:1] == " ":
+            return None
+
+        command = command.strip()
+        if not command:
+            return None
+
+        if any(p.search(command) for p in self._ignore_patterns):
+            return None
+
+        if self.redact:
+            command = redact_secrets(command)
+        return command
+
     def get_recent(
         self,
         n: int = 20,
@@ -343,12 +390,26 @@ class ReadlineHistory:
     def save(self) -> bool:
         """Save readline history.
 
+        readline keeps every line typed, so the lines the history manager
+        would not record (space-prefixed or ignored) are dropped, and
+        secrets redacted, before the file is written.
+
         Returns:
             True if saved successfully
         """
         try:
             import readline
 
+            lines = [
+                readline.get_history_item(index)
+                for index in range(1, readline.get_current_history_length() + 1)
+            ]
+            kept = [self.manager.filter(line) for line in lines if line is not None]
+            readline.clear_history()
+            for line in kept:
+                if line is not None:
+                    readline.add_history(line)
+
             readline_path = self.manager.path.with_suffix(".readline")
             readline_path.parent.mkdir(parents=True, exist_ok=True)
             readline.write_history_file(str(readline_path))
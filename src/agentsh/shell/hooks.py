@@ -0,0 +1,167 @@
+"""Shell status hooks - Track the real shell's last exit status and directory.
+
+Commands typed directly into the shell never pass through AgentSH, so
+their exit status and any ``cd`` are only known to the shell itself. To
+surface them, the shell reports each status with the OSC 133 ``D`` marker
+and its working directory with OSC 7, both used by terminal shell
+integration::
+
+    ESC ] 133 ; D ; <status> BEL
+    ESC ] 7 ; file://<host><path> BEL
+
+Three sources emit the markers:
+
+- The rc snippet from ``agentsh --init-shell <shell>`` (bash, zsh, fish)
+- ``PROMPT_COMMAND``, injected by :func:`hook_environment` into the
+  environment of bash shells AgentSH spawns in a PTY. An rc file that
+  replaces ``PROMPT_COMMAND`` outright disables this; appending to it
+  (``PROMPT_COMMAND+=...``) keeps it working.
+- The report :func:`report_command` appends to shell commands the wrapper
+  runs with ``-c``, which never show a prompt
+
+:class:`ShellStatusTracker` scans PTY output for the markers, handling
+sequences split across reads.
+"""
+
+import re
+from pathlib import Path
+from typing import Optional
+from urllib.parse import unquote
+
+# OSC 133 "command finished" marker, terminated by BEL or ST (ESC \)
+STATUS_MARKER = re.compile(rb"\x1b\]133;D;(-?\d+)(?:\x07|\x1b\\)")
+
+# OSC 7 working directory report (file://host/path), terminated by BEL or ST
+CWD_MARKER = re.compile(rb"\x1b\]7;file://[^/\x07\x1b]*(/[^\x07\x1b]*)(?:\x07|\x1b\\)")
+
+# Incomplete OSC sequence at the end of a read, carried over to the next one
+PARTIAL_MARKER = re.compile(rb"\x1b(?:\][^\x07\x1b]*\x1b?)?\Z")
+
+# Longest partial sequence kept between reads (a long path in OSC 7)
+MAX_PARTIAL_BYTES = 4096
+
+# PROMPT_COMMAND entry; restores $? afterwards for any entries that follow
+BASH_PROMPT_COMMAND = (
+    "__agentsh_status=$?; "
+    "printf '\\033]133;D;%s\\007' \"$__agentsh_status\"; "
+    "printf '\\033]7;file://%s%s\\007' \"${HOSTNAME:-localhost}\" \"$PWD\"; "
+    "(exit $__agentsh_status)"
+)
+
+# Shells whose syntax the report appended by report_command() is written in
+REPORTING_SHELLS = ("sh", "bash", "zsh", "dash", "ksh", "mksh", "ash")
+
+
+def parse_exit_status(data: bytes) -> Optional[int]:
+    """Extract the last reported exit status from shell output.
+
+    Args:
+        data: Raw PTY output
+
+    Returns:
+        Status from the last complete OSC 133 D marker, or None
+    """
+    matches = STATUS_MARKER.findall(data)
+    if not matches:
+        return None
+    return int(matches[-1])
+
+
+def parse_cwd(data: bytes) -> Optional[str]:
+    """Extract the last reported working directory from shell output.
+
+    Args:
+        data: Raw PTY output
+
+    Returns:
+        Path from the last complete OSC 7 marker (percent-decoded), or None
+    """
+    matches = CWD_MARKER.findall(data)
+    if not matches:
+        return None
+    return unquote(matches[-1].decode("utf-8", errors="replace"))
+
+
+def hook_environment(shell_path: str, env: dict[str, str]) -> dict[str, str]:
+    """Add the status hook to a shell's environment.
+
+    Only bash reads its prompt hook from the environment; zsh and fish
+    need the ``--init-shell`` snippet instead.
+
+    Args:
+        shell_path: Shell executable
+        env: Environment to extend (not modified)
+
+    Returns:
+        New environment with the hook installed when supported
+    """
+    if Path(shell_path).name != "bash":
+        return dict(env)
+
+    hooked = dict(env)
+    existing = env.get("PROMPT_COMMAND", "")
+    if BASH_PROMPT_COMMAND not in existing:
+        # Run first so $? is still the status of the user's command
+        hooked["PROMPT_COMMAND"] = (
+            f"{BASH_PROMPT_COMMAND};{existing}" if existing else BASH_PROMPT_COMMAND
+        )
+    return hooked
+
+
+def report_command(shell_path: str, command: str) -> str:
+    """Make a command report its exit status and final directory.
+
+    The report runs after the command on its own line and keeps the
+    command's exit status. Commands for shells outside REPORTING_SHELLS
+    are returned unchanged; an ``exit`` in the command skips the report.
+
+    Args:
+        shell_path: Shell that will run the command with ``-c``
+        command: Command line as typed
+
+    Returns:
+        Command line to pass to the shell
+    """
+    if Path(shell_path).name not in REPORTING_SHELLS:
+        return command
+    return f"{command}\n{BASH_PROMPT_COMMAND}"
+
+
+class ShellStatusTracker:
+    """Follow exit statuses and directories reported in a stream of PTY output.
+
+    Example:
+        tracker = ShellStatusTracker()
+        tracker.feed(pty.read())
+        if tracker.last_exit_code:
+            print(f"last command failed in {tracker.cwd}")
+    """
+
+    def __init__(self) -> None:
+        """Initialize with no status or directory seen."""
+        self.last_exit_code: Optional[int] = None
+        self.cwd: Optional[str] = None
+        self._pending = b""
+
+    def feed(self, data: bytes) -> Optional[int]:
+        """Scan a chunk of output for status and directory markers.
+
+        Args:
+            data: Bytes read from the PTY
+
+        Returns:
+            The newest status found in this chunk, or None
+        """
+        buffer = self._pending + data
+        status = parse_exit_status(buffer)
+        if status is not None:
+            self.last_exit_code = status
+        cwd = parse_cwd(buffer)
+        if cwd is not None:
+            self.cwd = cwd
+
+        # Keep a trailing partial marker for the next chunk
+        partial = PARTIAL_MARKER.search(buffer, max(0, len(buffer) - MAX_PARTIAL_BYTES))
+        self._pending = partial.group(0) if partial else b""
+
+        return status
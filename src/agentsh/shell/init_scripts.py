@@ -0,0 +1,113 @@
+"""Shell integration snippets for AgentSH.
+
+Generates the snippet users add to their shell rc file so the shell reports
+its state to terminals and to AgentSH. After every command the hook emits:
+
+- OSC 7 with the current directory (``file://host/path``), for cd tracking
+- OSC 133 prompt markers (``D;<status>`` then ``A``), for prompt detection
+
+Usage mirrors ``starship init``: ``eval "$(agentsh --init-shell bash)"``.
+"""
+
+from agentsh import __version__
+
+# Hook function defined by every snippet
+HOOK_NAME = "__agentsh_precmd"
+
+SUPPORTED_SHELLS = ("bash", "zsh", "fish")
+
+
+def get_bash_init() -> str:
+    """Generate bash integration snippet.
+
+    Returns:
+        Bash snippet content
+    """
+    return f'''# AgentSH shell integration v{__version__}
+# Install: echo 'eval "$(agentsh --init-shell bash)"' >> ~/.bashrc
+
+if [[ -z "${{__agentsh_init:-}}" ]]; then
+    __agentsh_init=1
+
+    {HOOK_NAME}() {{
+        local last_status=$?
+        printf '\\033]133;D;%s\\007' "$last_status"
+        printf '\\033]7;file://%s%s\\007' "${{HOSTNAME:-localhost}}" "$PWD"
+        printf '\\033]133;A\\007'
+        return $last_status
+    }}
+
+    if [[ ";${{PROMPT_COMMAND:-}};" != *";{HOOK_NAME};"* ]]; then
+        PROMPT_COMMAND="{HOOK_NAME}${{PROMPT_COMMAND:+;$PROMPT_COMMAND}}"
+    fi
+fi
+'''
+
+
+def get_zsh_init() -> str:
+    """Generate zsh integration snippet.
+
+    Returns:
+        Zsh snippet content
+    """
+    return f'''# AgentSH shell integration v{__version__}
+# Install: echo 'eval "$(agentsh --init-shell zsh)"' >> ~/.zshrc
+
+if [[ -z "${{__agentsh_init:-}}" ]]; then
+    __agentsh_init=1
+
+    {HOOK_NAME}() {{
+        local last_status=$?
+        printf '\\033]133;D;%s\\007' "$last_status"
+        printf '\\033]7;file://%s%s\\007' "${{HOST:-localhost}}" "$PWD"
+        printf '\\033]133;A\\007'
+    }}
+
+    autoload -Uz add-zsh-hook
+    add-zsh-hook precmd {HOOK_NAME}
+fi
+'''
+
+
+def get_fish_init() -> str:
+    """Generate fish integration snippet.
+
+    Returns:
+        Fish snippet content
+    """
+    return f'''# AgentSH shell integration v{__version__}
+# Install: echo 'agentsh --init-shell fish | source' >> ~/.config/fish/config.fish
+
+if not set -q __agentsh_init
+    set -g __agentsh_init 1
+
+    function {HOOK_NAME} --on-event fish_prompt
+        set -l last_status $status
+        printf '\\033]133;D;%s\\007' $last_status
+        printf '\\033]7;file://%s%s\\007' (hostname) $PWD
+        printf '\\033]133;A\\007'
+    end
+end
+'''
+
+
+def get_init_script(shell: str) -> str:
+    """Get integration snippet for specified shell.
+
+    Args:
+        shell: Shell type (bash, zsh, fish)
+
+    Returns:
+        Snippet content
+
+    Raises:
+        ValueError: If shell is not supported
+    """
+    if shell == "bash":
+        return get_bash_init()
+    elif shell == "zsh":
+        return get_zsh_init()
+    elif shell == "fish":
+        return get_fish_init()
+    else:
+        raise ValueError(f"Unsupported shell: {shell}")
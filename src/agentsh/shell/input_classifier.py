@@ -17,6 +17,12 @@ class InputType(Enum):
     SHELL_COMMAND = auto()  # Execute directly in shell
     AI_REQUEST = auto()  # Send to AI agent
     SPECIAL_COMMAND = auto()  # Internal AgentSH command
+    SAFETY_CHECK = auto()  # Show how a command would be classified (ai check '...')
+    EXPLAIN = auto()  # Ask the AI to explain a command (ai explain '...')
+    REDO = auto()  # Run the last executed plan again (ai redo)
+    RELOAD = auto()  # Re-read the configuration files (ai reload)
+    EXPORT_CHAT = auto()  # Save the AI session as Markdown (ai export-chat <file>)
+    READ_ONLY_QUERY = auto()  # AI request limited to read-only commands (ai ask <request>)
     EMPTY = auto()  # Empty input
 
 
@@ -37,6 +43,8 @@ class InputClassifier:
     Routing rules:
     1. Force shell: Input starts with shell_prefix (default: "!")
     2. Force AI: Input starts with ai_prefix (default: "ai ")
+       - ai check '<command>' shows the command's safety classification
+       - ai explain '<command>' asks the AI for a structured explanation
     3. Special commands: Input starts with ":" (e.g., :help, :config)
     4. Heuristic classification based on input characteristics
 
@@ -49,6 +57,24 @@ class InputClassifier:
         # Force AI
         classifier.classify("ai list all python files")  # -> AI_REQUEST
 
+        # Safety check (no AI call)
+        classifier.classify("ai check 'rm -rf build'")  # -> SAFETY_CHECK
+
+        # Structured explanation
+        classifier.classify("ai explain 'tar -xzf a.tgz'")  # -> EXPLAIN
+
+        # Run the last plan again (no AI call)
+        classifier.classify("ai redo")  # -> REDO
+
+        # Re-read the configuration files
+        classifier.classify("ai reload")  # -> RELOAD
+
+        # Save the AI session as a Markdown transcript
+        classifier.classify("ai export-chat chat.md")  # -> EXPORT_CHAT
+
+        # Question answered with read-only commands only
+        classifier.classify("ai ask why is the disk full")  # -> READ_ONLY_QUERY
+
         # Special command
         classifier.classify(":help")  # -> SPECIAL_COMMAND
 
@@ -86,6 +112,24 @@ class InputClassifier:
     # Special command prefix
     SPECIAL_PREFIX = ":"
 
+    # Subcommand after the AI prefix that checks a quoted command's safety
+    CHECK_SUBCOMMAND = "check"
+
+    # Subcommand after the AI prefix that explains a quoted command
+    EXPLAIN_SUBCOMMAND = "explain"
+
+    # Subcommand after the AI prefix that re-runs the last executed plan
+    REDO_SUBCOMMAND = "redo"
+
+    # Subcommand after the AI prefix that reloads the configuration
+    RELOAD_SUBCOMMAND = "reload"
+
+    # Subcommand after the AI prefix that saves the session to a file
+    EXPORT_CHAT_SUBCOMMAND = "export-chat"
+
+    # Subcommand after the AI prefix for a request that may not change anything
+    ASK_SUBCOMMAND = "ask"
+
     def __init__(
         self,
         ai_prefix: str = "ai ",
@@ -144,6 +188,44 @@ class InputClassifier:
 
         if stripped.startswith(self.ai_prefix):
             content = stripped[len(self.ai_prefix) :].strip()
+            for subcommand, input_type in (
+                (self.REDO_SUBCOMMAND, InputType.REDO),
+                (self.RELOAD_SUBCOMMAND, InputType.RELOAD),
+            ):
+                if content == subcommand:
+                    return ClassifiedInput(
+                        input_type=input_type,
+                        content="",
+                        original=original,
+                        reason=f"'{subcommand}' subcommand",
+                    )
+            name, _, argument = content.partition(" ")
+            if name == self.EXPORT_CHAT_SUBCOMMAND:
+                return ClassifiedInput(
+                    input_type=InputType.EXPORT_CHAT,
+                    content=argument.strip(),
+                    original=original,
+                    reason=f"'{name}' subcommand",
+                )
+            if name == self.ASK_SUBCOMMAND and argument.strip():
+                return ClassifiedInput(
+                    input_type=InputType.READ_ONLY_QUERY,
+                    content=argument.strip(),
+                    original=original,
+                    reason=f"'{name}' subcommand",
+                )
+            for subcommand, input_type in (
+                (self.CHECK_SUBCOMMAND, InputType.SAFETY_CHECK),
+                (self.EXPLAIN_SUBCOMMAND, InputType.EXPLAIN),
+            ):
+                quoted = self._parse_quoted_subcommand(content, subcommand)
+                if quoted is not None:
+                    return ClassifiedInput(
+                        input_type=input_type,
+                        content=quoted,
+                        original=original,
+                        reason=f"'{subcommand}' subcommand",
+                    )
             return ClassifiedInput(
                 input_type=InputType.AI_REQUEST,
                 content=content,
@@ -164,6 +246,31 @@ class InputClassifier:
         # Heuristic classification
         return self._heuristic_classify(stripped, original)
 
+    def _parse_quoted_subcommand(self, content: str, subcommand: str) -> Optional[str]:
+        """Extract the command from ``<subcommand> '<command>'``.
+
+        The command must be a single quoted word, so requests such as
+        "check disk usage" or "explain this code" still go to the AI.
+
+        Args:
+            content: Input with the AI prefix removed
+            subcommand: Subcommand name (e.g. "check")
+
+        Returns:
+            The unquoted command, or None if this is not the subcommand
+        """
+        name, _, rest = content.partition(" ")
+        rest = rest.strip()
+        if name != subcommand or rest[:1] not in ("'", '"'):
+            return None
+        try:
+            words = shlex.split(rest)
+        except ValueError:
+            return None
+        if len(words) != 1 or not words[0].strip():
+            return None
+        return words[0].strip()
+
     def _heuristic_classify(self, text: str, original: str) -> ClassifiedInput:
         """Use heuristics to classify ambiguous input.
 
@@ -324,6 +431,7 @@ SPECIAL_COMMANDS = {
     "clear": "Clear the screen",
     "reset": "Reset AI conversation context",
     "status": "Show system status",
+    "why": "Show the prompt and reply for the last AI request",
     "remember": "Store a note in memory (usage: :remember <note>)",
     "recall": "Search memory (usage: :recall <query>)",
     "forget": "Delete from memory (usage: :forget <id>)",
@@ -333,6 +441,17 @@ SPECIAL_COMMANDS = {
 }
 
 
+# Subcommands after the AI prefix, with their argument choices for completion
+AI_SUBCOMMANDS: dict[str, list[str]] = {
+    InputClassifier.CHECK_SUBCOMMAND: [],
+    InputClassifier.EXPLAIN_SUBCOMMAND: [],
+    InputClassifier.REDO_SUBCOMMAND: [],
+    InputClassifier.RELOAD_SUBCOMMAND: [],
+    InputClassifier.EXPORT_CHAT_SUBCOMMAND: [],
+    InputClassifier.ASK_SUBCOMMAND: [],
+}
+
+
 def parse_special_command(content: str) -> tuple[str, list[str]]:
     """Parse a special command into command name and arguments.
 
@@ -157,8 +157,12 @@ class LoginShellManager:
         """
         return sys.stdin.isatty()
 
-    def setup_environment(self) -> None:
-        """Set up environment variables for the session."""
+    def setup_environment(self, ps1: Optional[str] = None) -> None:
+        """Set up environment variables for the session.
+
+        Args:
+            ps1: Prompt exported to shells started from the session
+        """
         session = self.session
 
         # Set session ID
@@ -171,6 +175,9 @@ class LoginShellManager:
         # Set shell name
         os.environ["AGENTSH_SHELL"] = "agentsh"
 
+        if ps1 is not None:
+            os.environ["PS1"] = ps1
+
         # Ensure basic environment
         if "HOME" not in os.environ:
             os.environ["HOME"] = str(session.home)
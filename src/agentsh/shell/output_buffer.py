@@ -0,0 +1,82 @@
+"""Output buffer - Keep the tail of the shell's terminal output.
+
+Failures usually happen in commands typed directly into the shell, whose
+output never passes through AgentSH's own steps. :class:`OutputRingBuffer`
+holds the last few kilobytes of PTY output so ``ai fix`` can show the AI
+what actually went wrong on screen. Only a bounded amount is ever kept in
+memory, and escape sequences are stripped when the tail is read.
+"""
+
+import re
+
+# Bytes of terminal output kept by default
+DEFAULT_OUTPUT_BUFFER_BYTES = 64 * 1024
+
+# CSI and OSC escape sequences, and other two-byte escapes
+ESCAPE_SEQUENCE = re.compile(
+    r"\x1b\[[0-?]*[ -/]*[@-~]|\x1b\][^\x07\x1b]*(?:\x07|\x1b\\)|\x1b[@-Z\\-_]"
+)
+
+
+class OutputRingBuffer:
+    """Bounded buffer of the most recent terminal output.
+
+    Example:
+        buffer = OutputRingBuffer(max_bytes=4096)
+        buffer.write(pty.read())
+        print(buffer.tail(max_chars=500))
+    """
+
+    def __init__(self, max_bytes: int = DEFAULT_OUTPUT_BUFFER_BYTES) -> None:
+        """Initialize an empty buffer.
+
+        Args:
+            max_bytes: Most bytes kept; older output is dropped
+        """
+        if max_bytes <= 0:
+            raise ValueError("max_bytes must be positive")
+        self.max_bytes = max_bytes
+        self._data = bytearray()
+
+    def __len__(self) -> int:
+        """Get the number of bytes held."""
+        return len(self._data)
+
+    def write(self, data: bytes) -> None:
+        """Append output, dropping the oldest bytes beyond max_bytes.
+
+        Args:
+            data: Bytes read from the PTY
+        """
+        self._data.extend(data[-self.max_bytes :])
+        overflow = len(self._data) - self.max_bytes
+        if overflow > 0:
+            del self._data[:overflow]
+
+    def clear(self) -> None:
+        """Drop all buffered output."""
+        self._data.clear()
+
+    def tail(self, max_chars: int = 4000) -> str:
+        """Get the end of the buffered output as plain text.
+
+        Escape sequences (colors, cursor movement, shell integration
+        markers) are removed, carriage returns normalized, and a line cut
+        by the character limit is dropped.
+
+        Args:
+            max_chars: Most characters returned
+
+        Returns:
+            The most recent output, or "" if none was seen
+        """
+        text = self._data.decode("utf-8", errors="replace")
+        text = ESCAPE_SEQUENCE.sub("", text).replace("\r\n", "\n").replace("\r", "\n")
+        if len(text) <= max_chars:
+            return text.strip("\n")
+        text = text[-max_chars:]
+        # Start at a line boundary rather than mid-line
+        newline = text.find("\n")
+        if 0 <= newline < len(text) - 1:
+            text = text[newline + 1 :]
+        return text.strip("\n")
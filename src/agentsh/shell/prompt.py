@@ -1,6 +1,7 @@
 """Prompt Renderer - Custom shell prompt with status indicators."""
 
 import os
+import re
 import subprocess
 from dataclasses import dataclass
 from enum import Enum
@@ -124,19 +125,23 @@ class PromptRenderer:
         self.use_emoji = use_emoji
         self.indicator = indicator
 
-    def get_context(self) -> PromptContext:
+    def get_context(self, cwd: Optional[Path] = None) -> PromptContext:
         """Gather context information for prompt rendering.
 
+        Args:
+            cwd: The shell's working directory, when it is tracked apart
+                from AgentSH's own (defaults to the process directory)
+
         Returns:
             PromptContext with current environment info
         """
-        cwd = Path.cwd()
+        cwd = cwd or Path.cwd()
         user = os.environ.get("USER", os.environ.get("USERNAME", "user"))
         hostname = os.uname().nodename
 
         # Get git info
-        git_branch = self._get_git_branch()
-        git_dirty = self._is_git_dirty() if git_branch else False
+        git_branch = self._get_git_branch(cwd)
+        git_dirty = self._is_git_dirty(cwd) if git_branch else False
 
         # Get virtual environment
         virtual_env = self._get_virtual_env()
@@ -322,11 +327,12 @@ class PromptRenderer:
             return f"{color}{text}{Colors.RESET}"
         return text
 
-    def _get_git_branch(self) -> Optional[str]:
+    def _get_git_branch(self, cwd: Optional[Path] = None) -> Optional[str]:
         """Get current git branch name."""
         try:
             result = subprocess.run(
                 ["git", "rev-parse", "--abbrev-ref", "HEAD"],
+                cwd=cwd,
                 capture_output=True,
                 text=True,
                 timeout=1,
@@ -337,11 +343,12 @@ class PromptRenderer:
             pass
         return None
 
-    def _is_git_dirty(self) -> bool:
+    def _is_git_dirty(self, cwd: Optional[Path] = None) -> bool:
         """Check if git working directory has changes."""
         try:
             result = subprocess.run(
                 ["git", "status", "--porcelain"],
+                cwd=cwd,
                 capture_output=True,
                 text=True,
                 timeout=1,
@@ -375,7 +382,53 @@ def strip_ansi(text: str) -> str:
     Returns:
         Text with ANSI codes removed
     """
-    import re
-
     ansi_pattern = re.compile(r"\x1b\[[0-9;]*m")
     return ansi_pattern.sub("", text)
+
+
+# Prompt escapes for each placeholder of a PS1 template, per shell
+PS1_ESCAPES = {
+    "bash": {"user": "\\u", "host": "\\h", "cwd": "\\w"},
+    "zsh": {"user": "%n", "host": "%m", "cwd": "%~"},
+}
+
+PS1_PLACEHOLDER = re.compile(r"\{(user|host|cwd|mode)\}")
+
+
+def _escape_ps1_text(text: str, shell: str) -> str:
+    """Escape literal text so the shell shows it as-is in PS1."""
+    if shell == "zsh":
+        return text.replace("%", "%%")
+    # bash: "\$" shows "$", or "#" for root
+    return re.sub(r"([\\$`])", r"\\\1", text)
+
+
+def template_to_ps1(template: str, shell: str, mode: str = "") -> Optional[str]:
+    """Translate a prompt template into a PS1 value for a shell.
+
+    ``{user}``, ``{host}`` and ``{cwd}`` become the shell's own prompt
+    escapes, so they stay current as the user moves around; ``{mode}`` is
+    replaced with the given text. Other text is escaped to show literally.
+
+    Args:
+        template: Template such as "{user}@{host}:{cwd} [{mode}]$ "
+        shell: Shell name or path (bash, zsh)
+        mode: Text for ``{mode}``
+
+    Returns:
+        PS1 value, or None for shells that do not read PS1 (e.g. fish)
+    """
+    shell = Path(shell).name
+    escapes = PS1_ESCAPES.get(shell)
+    if escapes is None:
+        return None
+
+    parts = []
+    position = 0
+    for match in PS1_PLACEHOLDER.finditer(template):
+        parts.append(_escape_ps1_text(template[position : match.start()], shell))
+        name = match.group(1)
+        parts.append(_escape_ps1_text(mode, shell) if name == "mode" else escapes[name])
+        position = match.end()
+    parts.append(_escape_ps1_text(template[position:], shell))
+    return "".join(parts)
@@ -1,17 +1,117 @@
 """PTY Manager - Manages pseudo-terminal for shell subprocess."""
 
+import fcntl
 import os
+import select
 import signal
 import shutil
+import struct
+import termios
+import tty
 from pathlib import Path
-from typing import Optional
+from typing import TYPE_CHECKING, Optional
 
 import ptyprocess
 
+from agentsh.shell.cleanup import ChildGuard
+from agentsh.shell.hooks import ShellStatusTracker, hook_environment, report_command
+from agentsh.shell.output_buffer import DEFAULT_OUTPUT_BUFFER_BYTES, OutputRingBuffer
 from agentsh.telemetry.logger import get_logger, LoggerMixin
 
+if TYPE_CHECKING:
+    from agentsh.shell.recorder import SessionRecorder
+
 logger = get_logger(__name__)
 
+# Terminal size (rows, cols) used when it cannot be detected
+DEFAULT_TERMINAL_SIZE = (24, 80)
+
+
+def size_from_env(env: dict[str, str]) -> Optional[tuple[int, int]]:
+    """Read the terminal size from the ``LINES`` and ``COLUMNS`` variables.
+
+    Args:
+        env: Environment variables
+
+    Returns:
+        (rows, cols), or None unless both are set to positive integers
+    """
+    try:
+        rows = int(env.get("LINES", "").strip())
+        cols = int(env.get("COLUMNS", "").strip())
+    except ValueError:
+        return None
+    if rows <= 0 or cols <= 0:
+        return None
+    return (rows, cols)
+
+
+def _size_from_stdout() -> Optional[tuple[int, int]]:
+    """Get the size of the terminal on stdout."""
+    try:
+        size = os.get_terminal_size()
+    except OSError:
+        return None
+    return (size.lines, size.columns) if size.lines and size.columns else None
+
+
+def _size_from_ioctl(fd: int) -> Optional[tuple[int, int]]:
+    """Ask the terminal behind a file descriptor for its size."""
+    try:
+        packed = fcntl.ioctl(fd, termios.TIOCGWINSZ, struct.pack("HHHH", 0, 0, 0, 0))
+    except OSError:
+        return None
+    rows, cols = struct.unpack("HHHH", packed)[:2]
+    return (rows, cols) if rows and cols else None
+
+
+def _size_from_controlling_tty() -> Optional[tuple[int, int]]:
+    """Ask the controlling terminal for its size, even with stdio redirected."""
+    for fd in (0, 1, 2):
+        size = _size_from_ioctl(fd)
+        if size:
+            return size
+    try:
+        fd = os.open("/dev/tty", os.O_RDONLY | os.O_NOCTTY)
+    except OSError:
+        return None
+    try:
+        return _size_from_ioctl(fd)
+    finally:
+        os.close(fd)
+
+
+def detect_terminal_size(
+    default: tuple[int, int] = DEFAULT_TERMINAL_SIZE,
+    env: Optional[dict[str, str]] = None,
+) -> tuple[int, int]:
+    """Detect the terminal size, trying several sources.
+
+    Inside tmux or screen, or with stdout redirected,
+    ``os.get_terminal_size`` can fail or report 0x0. The size is then
+    asked from the controlling terminal directly, read from ``LINES`` and
+    ``COLUMNS``, and only then taken from ``default``.
+
+    Args:
+        default: (rows, cols) used when no source reports a size
+        env: Environment variables (defaults to os.environ)
+
+    Returns:
+        (rows, cols)
+    """
+    sources = (
+        ("stdout", _size_from_stdout),
+        ("ioctl", _size_from_controlling_tty),
+        ("environment", lambda: size_from_env(os.environ if env is None else env)),
+    )
+    for source, detect in sources:
+        size = detect()
+        if size:
+            logger.debug("Terminal size detected", source=source, rows=size[0], cols=size[1])
+            return size
+    logger.debug("Terminal size unknown, using default", rows=default[0], cols=default[1])
+    return default
+
 
 class PTYManager(LoggerMixin):
     """Manages a pseudo-terminal with a shell subprocess.
@@ -36,6 +136,13 @@ class PTYManager(LoggerMixin):
         env: Optional[dict[str, str]] = None,
         cwd: Optional[Path] = None,
         dimensions: Optional[tuple[int, int]] = None,
+        recorder: Optional["SessionRecorder"] = None,
+        status_hook: bool = True,
+        output_buffer_bytes: int = DEFAULT_OUTPUT_BUFFER_BYTES,
+        default_size: tuple[int, int] = DEFAULT_TERMINAL_SIZE,
+        command: Optional[str] = None,
+        status_tracker: Optional[ShellStatusTracker] = None,
+        output_buffer: Optional[OutputRingBuffer] = None,
     ) -> None:
         """Initialize PTY manager.
 
@@ -44,13 +151,39 @@ class PTYManager(LoggerMixin):
             env: Environment variables for the shell. Uses current env if None.
             cwd: Working directory for the shell. Uses current dir if None.
             dimensions: Terminal dimensions (rows, cols). Auto-detected if None.
+            recorder: Optional session recorder that receives all PTY output
+            status_hook: Inject a prompt hook so the shell reports each
+                command's exit status and working directory (bash only; see
+                agentsh.shell.hooks)
+            output_buffer_bytes: Bytes of recent output kept for ``ai fix``
+            default_size: Terminal size (rows, cols) used when it cannot
+                be detected
+            command: Run this command with ``-c`` instead of an interactive
+                shell; with status_hook, it reports its status and final
+                directory when done
+            status_tracker: Tracker fed with the output, to follow one shell
+                session across several PTYs (a new one if None)
+            output_buffer: Buffer the output is kept in, shared the same way
+                (a new one of output_buffer_bytes if None)
         """
         self.shell_path = shell_path or self._detect_shell()
         self.env = env or dict(os.environ)
+        self.command = command
+        if command is not None and status_hook:
+            self.command = report_command(self.shell_path, command)
+        elif status_hook:
+            self.env = hook_environment(self.shell_path, self.env)
+        self.status_tracker = status_tracker or ShellStatusTracker()
+        if output_buffer is None:
+            output_buffer = OutputRingBuffer(output_buffer_bytes)
+        self.output_buffer = output_buffer
         self.cwd = str(cwd) if cwd else os.getcwd()
+        self.default_size = default_size
         self.dimensions = dimensions or self._get_terminal_size()
+        self.recorder = recorder
 
         self._process: Optional[ptyprocess.PtyProcess] = None
+        self._guard: Optional[ChildGuard] = None
         self._original_sigwinch: Optional[signal.Handlers] = None
 
     def _detect_shell(self) -> str:
@@ -70,12 +203,7 @@ class PTYManager(LoggerMixin):
 
     def _get_terminal_size(self) -> tuple[int, int]:
         """Get current terminal size."""
-        try:
-            size = os.get_terminal_size()
-            return (size.lines, size.columns)
-        except OSError:
-            # Default size if not in a terminal
-            return (24, 80)
+        return detect_terminal_size(self.default_size)
 
     def spawn(self) -> None:
         """Spawn the shell process in a PTY.
@@ -93,13 +221,20 @@ class PTYManager(LoggerMixin):
             dimensions=self.dimensions,
         )
 
+        if self.command is not None:
+            argv = [self.shell_path, "-c", self.command]
+        else:
+            argv = [self.shell_path, "-i"]  # -i for interactive
+
         try:
             self._process = ptyprocess.PtyProcess.spawn(
-                [self.shell_path, "-i"],  # -i for interactive
+                argv,
                 cwd=self.cwd,
                 env=self.env,
                 dimensions=self.dimensions,
             )
+            # Reap the shell even if we crash before close() runs
+            self._guard = ChildGuard(self._process)
 
             # Set up SIGWINCH handler for terminal resize
             self._setup_sigwinch_handler()
@@ -161,13 +296,13 @@ class PTYManager(LoggerMixin):
         try:
             if timeout is not None:
                 # Use read with timeout
-                import select
-
                 ready, _, _ = select.select([self._process.fd], [], [], timeout)
                 if not ready:
                     return b""
 
-            return self._process.read(size)
+            data = self._process.read(size)
+            self._consume(data)
+            return data
 
         except EOFError:
             raise
@@ -175,6 +310,13 @@ class PTYManager(LoggerMixin):
             self.logger.error("Read error", error=str(e))
             raise
 
+    def _consume(self, data: bytes) -> None:
+        """Pass output to the status tracker, output buffer and recorder."""
+        self.status_tracker.feed(data)
+        self.output_buffer.write(data)
+        if self.recorder is not None:
+            self.recorder.write(data)
+
     def read_nonblocking(self, size: int = 1024) -> bytes:
         """Read data from PTY without blocking.
 
@@ -211,6 +353,62 @@ class PTYManager(LoggerMixin):
             self.logger.error("Write error", error=str(e))
             raise
 
+    def interact(self, input_fd: Optional[int] = None, output_fd: int = 1) -> int:
+        """Connect the shell to the terminal until it exits.
+
+        Output is copied to ``output_fd`` and, as with read(), seen by the
+        status tracker, output buffer and recorder. Input from ``input_fd``
+        is forwarded with the terminal in raw mode, so keys like Ctrl+C
+        reach the shell; without it, the shell reads EOF.
+
+        Args:
+            input_fd: Terminal to forward input from, or None
+            output_fd: Where the shell's output is written
+
+        Returns:
+            The shell's exit status (128 + signal number if it was killed)
+
+        Raises:
+            RuntimeError: If PTY not spawned
+        """
+        if self._process is None:
+            raise RuntimeError("PTY not spawned")
+
+        saved_mode = None
+        if input_fd is None:
+            self._process.sendeof()
+        elif os.isatty(input_fd):
+            saved_mode = termios.tcgetattr(input_fd)
+            tty.setraw(input_fd)
+
+        forwarding = input_fd is not None
+        try:
+            while True:
+                sources = [self._process.fd, input_fd] if forwarding else [self._process.fd]
+                ready, _, _ = select.select(sources, [], [])
+                if forwarding and input_fd in ready:
+                    data = os.read(input_fd, 1024)
+                    if data:
+                        self._process.write(data)
+                    else:
+                        forwarding = False
+                        self._process.sendeof()
+                if self._process.fd in ready:
+                    try:
+                        data = self._process.read(1024)
+                    except EOFError:
+                        break
+                    self._consume(data)
+                    os.write(output_fd, data)
+        finally:
+            if saved_mode is not None:
+                termios.tcsetattr(input_fd, termios.TCSADRAIN, saved_mode)
+
+        self._process.wait()
+        if self._process.exitstatus is not None:
+            return self._process.exitstatus
+        return 128 + (self._process.signalstatus or 0)
+
     def write_line(self, line: str) -> int:
         """Write a line to the PTY (adds newline).
 
@@ -259,6 +457,34 @@ class PTYManager(LoggerMixin):
             return None
         return self._process.exitstatus
 
+    @property
+    def last_command_status(self) -> Optional[int]:
+        """Get the exit status of the last command run in the shell.
+
+        Reported by the shell's prompt hook; None until a status is seen.
+        """
+        return self.status_tracker.last_exit_code
+
+    def recent_output(self, max_chars: int = 4000) -> str:
+        """Get the tail of the shell's recent output as plain text.
+
+        Args:
+            max_chars: Most characters returned
+
+        Returns:
+            The most recent output, with escape sequences removed
+        """
+        return self.output_buffer.tail(max_chars)
+
+    @property
+    def current_directory(self) -> str:
+        """Get the shell's working directory.
+
+        Follows ``cd`` in the shell as reported by its prompt hook (OSC 7);
+        the directory the shell started in until a report is seen.
+        """
+        return self.status_tracker.cwd or self.cwd
+
     def close(self, force: bool = False) -> None:
         """Close the PTY and terminate the shell.
 
@@ -291,6 +517,9 @@ class PTYManager(LoggerMixin):
                     # Force kill if graceful shutdown fails
                     self._process.terminate(force=True)
 
+        if self._guard is not None:
+            self._guard.reap()
+            self._guard = None
         self._process = None
         self.logger.info("PTY closed")
 
@@ -0,0 +1,143 @@
+"""Session Recorder - Asciinema-style capture of terminal output.
+
+Recordings are raw terminal captures. Nothing is redacted, so cast files
+may contain secrets, tokens, or any other sensitive output shown on screen.
+"""
+
+import codecs
+import json
+import time
+import uuid
+from pathlib import Path
+from typing import Callable, Optional, TextIO, Union
+
+from agentsh.config.defaults import DEFAULT_SESSIONS_DIR
+from agentsh.telemetry.logger import get_logger
+
+logger = get_logger(__name__)
+
+CAST_VERSION = 2
+
+
+class SessionRecorder:
+    """Writes terminal output to an asciicast v2 file.
+
+    The file starts with a JSON header line, followed by one JSON array per
+    frame: ``[elapsed_seconds, "o", data]``. Elapsed times are measured with
+    a monotonic clock, so frames are always non-decreasing.
+
+    Example:
+        recorder = SessionRecorder(dimensions=(24, 80))
+        recorder.start()
+        recorder.write(b"$ ls\\r\\n")
+        recorder.close()
+    """
+
+    def __init__(
+        self,
+        session_id: Optional[str] = None,
+        sessions_dir: Optional[Path] = None,
+        dimensions: tuple[int, int] = (24, 80),
+        clock: Callable[[], float] = time.monotonic,
+    ) -> None:
+        """Initialize the recorder.
+
+        Args:
+            session_id: Session identifier used for the file name; the
+                shell passes the audit log's so entries can be matched up
+            sessions_dir: Directory for cast files (default: ~/.agentsh/sessions)
+            dimensions: Terminal dimensions (rows, cols)
+            clock: Monotonic clock used for frame timestamps
+        """
+        self.session_id = session_id or str(uuid.uuid4())[:8]
+        self.sessions_dir = sessions_dir or DEFAULT_SESSIONS_DIR
+        self.dimensions = dimensions
+        self._clock = clock
+        self._start: Optional[float] = None
+        self._last_elapsed = 0.0
+        self._file: Optional[TextIO] = None
+        # PTY reads can split a multibyte character; keep the partial bytes
+        self._decoder = codecs.getincrementaldecoder("utf-8")(errors="replace")
+
+    @property
+    def path(self) -> Path:
+        """Get the cast file path."""
+        return self.sessions_dir / f"{self.session_id}.cast"
+
+    @property
+    def is_recording(self) -> bool:
+        """Check if the recorder is open."""
+        return self._file is not None
+
+    def start(self) -> None:
+        """Open the cast file and write the header."""
+        if self._file is not None:
+            return
+
+        self.sessions_dir.mkdir(parents=True, exist_ok=True)
+        self._file = open(self.path, "w", encoding="utf-8")
+        self._start = self._clock()
+        self._last_elapsed = 0.0
+        self._decoder.reset()
+
+        rows, cols = self.dimensions
+        header = {
+            "version": CAST_VERSION,
+            "width": cols,
+            "height": rows,
+            "timestamp": int(time.time()),
+        }
+        self._file.write(json.dumps(header) + "\n")
+        self._file.flush()
+
+        logger.info("Session recording started", path=str(self.path))
+
+    def write(self, data: Union[bytes, str]) -> None:
+        """Record an output frame.
+
+        Args:
+            data: Output bytes or text as shown on the terminal
+        """
+        if self._file is None or self._start is None or not data:
+            return
+
+        if isinstance(data, bytes):
+            data = self._decoder.decode(data)
+            if not data:
+                return
+        self._write_frame(data)
+
+    def _write_frame(self, data: str) -> None:
+        """Write one output frame, timestamped now."""
+        if self._file is None or self._start is None:
+            return
+
+        # Guard against clocks that step backwards
+        elapsed = max(self._clock() - self._start, self._last_elapsed)
+        self._last_elapsed = elapsed
+
+        frame = [round(elapsed, 6), "o", data]
+        self._file.write(json.dumps(frame) + "\n")
+        self._file.flush()
+
+    def close(self) -> None:
+        """Close the cast file."""
+        if self._file is None:
+            return
+
+        # Bytes of a character cut off at the end
+        rest = self._decoder.decode(b"", final=True)
+        if rest:
+            self._write_frame(rest)
+        self._file.close()
+        self._file = None
+        logger.info("Session recording saved", path=str(self.path))
+
+    def __enter__(self) -> "SessionRecorder":
+        """Context manager entry."""
+        self.start()
+        return self
+
+    def __exit__(self, exc_type: object, exc_val: object, exc_tb: object) -> None:
+        """Context manager exit."""
+        self.close()
@@ -2,33 +2,51 @@
 
 import os
 import select
+import shutil
 import sys
 import termios
 import tty
-from typing import TYPE_CHECKING, Callable, Optional
-
+from pathlib import Path
+from typing import TYPE_CHECKING, Callable, Optional, TextIO
+
+from agentsh.agent.explain import format_explanation, parse_explanation
+from agentsh.agent.llm_client import json_response
+from agentsh.agent.prompts import is_fix_request
+from agentsh.security.check import check_command, format_safety_check
+from agentsh.shell.completer import ShellCompleter, setup_completion
+from agentsh.shell.completion_modes import CompletionConfig, CompletionMode
 from agentsh.shell.help import show_help
 from agentsh.shell.history import HistoryManager, ReadlineHistory
+from agentsh.shell.hooks import ShellStatusTracker
 from agentsh.shell.memory import (
     format_memory_list,
     get_memory_store,
 )
 from agentsh.shell.input_classifier import (
+    AI_SUBCOMMANDS,
     ClassifiedInput,
     InputClassifier,
     InputType,
     SPECIAL_COMMANDS,
     parse_special_command,
 )
+from agentsh.shell.output_buffer import OutputRingBuffer
 from agentsh.shell.prompt import AgentStatus, PromptRenderer, PromptStyle
 from agentsh.shell.pty_manager import PTYManager
+from agentsh.shell.recorder import SessionRecorder
 from agentsh.telemetry.logger import get_logger, LoggerMixin
+from agentsh.utils.markdown import render_markdown, strip_markdown
+from agentsh.utils.sysinfo import get_command_output
+from agentsh.utils.validators import redact_secrets
 
 if TYPE_CHECKING:
     from agentsh.config.schemas import AgentSHConfig
 
 logger = get_logger(__name__)
 
+# Maximum seconds to wait for the MOTD command at startup
+MOTD_TIMEOUT = 2.0
+
 
 class ShellWrapper(LoggerMixin):
     """Wraps the user's shell with AI capabilities.
@@ -38,6 +56,7 @@ class ShellWrapper(LoggerMixin):
     - Input classification to route between shell and AI
     - Custom prompt rendering with status indicators
     - Command history management
+    - Tracking the shell's working directory across commands
     - Integration with AI agent (Phase 2)
 
     The shell operates in two modes:
@@ -50,16 +69,21 @@ class ShellWrapper(LoggerMixin):
         shell.run()
     """
 
-    def __init__(self, config: "AgentSHConfig") -> None:
+    def __init__(self, config: "AgentSHConfig", config_path: Optional[Path] = None) -> None:
         """Initialize the shell wrapper.
 
         Args:
             config: AgentSH configuration
+            config_path: Explicit config file the configuration was loaded
+                from, read again by ``ai reload``
         """
         self.config = config
+        self._config_path = config_path
         self._running = False
         self._agent_status = AgentStatus.IDLE
         self._last_exit_code = 0
+        # Status of the last shell command, told to the AI
+        self._last_command_status: Optional[int] = None
 
         # Initialize components
         self._pty: Optional[PTYManager] = None
@@ -70,15 +94,28 @@ class ShellWrapper(LoggerMixin):
         )
         self._prompt = PromptRenderer(
             style=PromptStyle.STANDARD,
-            use_color=True,
+            use_color=config.shell.color,
             use_emoji=False,
         )
         self._history = HistoryManager(
             max_entries=config.shell.history_size,
+            ignore_space=config.shell.history_ignore_space,
+            ignore_patterns=config.shell.history_ignore_patterns,
+            redact=config.shell.history_redact_secrets,
         )
+        # Status and directory reported by each shell command
+        self._shell_status = ShellStatusTracker()
+        # Recent shell command output, shown to the AI for ai fix
+        self._output = OutputRingBuffer()
 
         # AI handler callback (set in Phase 2)
         self._ai_handler: Optional[Callable[[str], str]] = None
+        self._ai_handler_factory: Optional[Callable[["AgentSHConfig"], Callable]] = None
+
+        # Optional raw terminal capture
+        self._recorder: Optional[SessionRecorder] = None
+        if config.shell.record_session:
+            self._recorder = SessionRecorder()
 
         self.logger.info(
             "ShellWrapper initialized",
@@ -86,36 +123,100 @@ class ShellWrapper(LoggerMixin):
             ai_prefix=config.shell.ai_prefix,
         )
 
-    def set_ai_handler(self, handler: Callable[[str], str]) -> None:
+    def set_ai_handler(
+        self,
+        handler: Callable[[str], str],
+        factory: Optional[Callable[["AgentSHConfig"], Callable]] = None,
+    ) -> None:
         """Set the AI request handler.
 
         Args:
             handler: Function that takes AI request and returns response
+            factory: Builds a handler from a configuration; used to rebuild
+                the handler on ``ai reload``
         """
         self._ai_handler = handler
+        self._ai_handler_factory = factory
+
+        # Name the recording after the audit session so the two match up
+        session_id = getattr(handler, "session_id", None)
+        if (
+            self._recorder is not None
+            and not self._recorder.is_recording
+            and isinstance(session_id, str)
+        ):
+            self._recorder.session_id = session_id
+
+    @property
+    def cwd(self) -> str:
+        """The shell's working directory, following ``cd`` in shell commands."""
+        cwd = self._shell_status.cwd
+        return cwd if cwd and os.path.isdir(cwd) else os.getcwd()
+
+    @property
+    def interactive(self) -> bool:
+        """Whether stdin is a terminal, enabling the prompt and line editing."""
+        return sys.stdin is not None and sys.stdin.isatty()
 
     def run(self) -> None:
         """Run the interactive shell REPL.
 
-        This is the main entry point for interactive mode.
+        This is the main entry point for interactive mode. When stdin is
+        not a terminal (a pipe, script or CI job), input is read line by
+        line instead, without the banner, prompt, readline or completion.
         """
         self._running = True
-        self.logger.info("Starting AgentSH shell")
+        interactive = self.interactive
+        self.logger.info("Starting AgentSH shell", interactive=interactive)
 
         # Load history
         self._history.load()
-        readline_history = ReadlineHistory(self._history)
-        readline_history.setup()
+        readline_history: Optional[ReadlineHistory] = None
+        completer: Optional[ShellCompleter] = None
+        if interactive:
+            readline_history = ReadlineHistory(self._history)
+            readline_history.setup()
+            completer = self._setup_completion()
+
+        if self._recorder is not None:
+            self._recorder.start()
 
         try:
-            self._run_repl()
+            if interactive:
+                self._run_repl()
+            else:
+                self._run_lines(sys.stdin)
         finally:
             # Save history
             self._history.save()
-            readline_history.save()
+            if readline_history is not None:
+                readline_history.save()
+            if completer is not None:
+                completer.uninstall()
+            if self._recorder is not None:
+                self._recorder.close()
             self._running = False
             self.logger.info("Shell session ended")
 
+    def _setup_completion(self) -> ShellCompleter:
+        """Install tab completion for special commands and AI subcommands.
+
+        Returns:
+            The installed completer
+        """
+        mode = CompletionMode(self.config.shell.completion_mode.value)
+        return setup_completion(
+            special_commands=SPECIAL_COMMANDS,
+            mode=mode,
+            config=CompletionConfig(
+                mode=mode,
+                shell=self.config.shell.backend,
+                timeout=self.config.shell.completion_timeout,
+            ),
+            ai_subcommands=AI_SUBCOMMANDS,
+            ai_prefix=self.config.shell.ai_prefix,
+        )
+
     def _run_repl(self) -> None:
         """Main REPL loop."""
         self._print_welcome()
@@ -124,6 +225,7 @@ class ShellWrapper(LoggerMixin):
             try:
                 # Render prompt
                 prompt = self._prompt.render_ps1(
+                    context=self._prompt.get_context(Path(self.cwd)),
                     agent_status=self._agent_status,
                     last_exit_code=self._last_exit_code,
                 )
@@ -136,6 +238,8 @@ class ShellWrapper(LoggerMixin):
                     print()
                     break
 
+                self._record(f"{prompt}{user_input}\r\n")
+
                 # Process input
                 self._process_input(user_input)
 
@@ -145,6 +249,17 @@ class ShellWrapper(LoggerMixin):
                 self._last_exit_code = 130
                 continue
 
+    def _run_lines(self, stream: TextIO) -> None:
+        """Process input line by line, without a prompt.
+
+        Args:
+            stream: Input to read until EOF or :quit
+        """
+        for line in stream:
+            if not self._running:
+                break
+            self._process_input(line.rstrip("\n"))
+
     def _process_input(self, user_input: str) -> None:
         """Process user input and route appropriately.
 
@@ -167,9 +282,27 @@ class ShellWrapper(LoggerMixin):
         elif classified.input_type == InputType.SPECIAL_COMMAND:
             self._handle_special_command(classified)
 
+        elif classified.input_type == InputType.SAFETY_CHECK:
+            print(self._format_safety_check(classified.content))
+
+        elif classified.input_type == InputType.EXPLAIN:
+            self._handle_explain(classified.content)
+
+        elif classified.input_type == InputType.REDO:
+            self._handle_redo()
+
+        elif classified.input_type == InputType.RELOAD:
+            self._handle_reload()
+
+        elif classified.input_type == InputType.EXPORT_CHAT:
+            self._handle_export_chat(classified.content)
+
         elif classified.input_type == InputType.AI_REQUEST:
             self._handle_ai_request(classified)
 
+        elif classified.input_type == InputType.READ_ONLY_QUERY:
+            self._handle_ai_request(classified, read_only=True)
+
         elif classified.input_type == InputType.SHELL_COMMAND:
             self._handle_shell_command(classified)
 
@@ -195,6 +328,8 @@ class ShellWrapper(LoggerMixin):
             self._reset_context()
         elif command == "status":
             self._show_status()
+        elif command == "why":
+            print(self._format_why())
         elif command == "remember":
             self._handle_remember(args)
         elif command == "recall":
@@ -208,6 +343,45 @@ class ShellWrapper(LoggerMixin):
             print(f"Unknown command: {command}")
             print("Type :help for available commands")
 
+    def _format_why(self) -> str:
+        """Describe what was sent to the AI for the last request.
+
+        Shows the assembled system prompt, the user message and each raw
+        AI reply. Secrets are redacted when security.redact_secrets is set.
+
+        Returns:
+            Text to display
+        """
+        exchange = getattr(self._ai_handler, "last_exchange", None)
+        if exchange is None:
+            return "No AI request has been made yet."
+
+        sections = [
+            "=== System prompt ===",
+            exchange.system_prompt.strip(),
+            "",
+            "=== User message ===",
+            exchange.request,
+        ]
+        for i, reply in enumerate(exchange.responses, start=1):
+            sections.extend(["", f"=== AI response (step {i}) ===", reply])
+
+        text = "\n".join(sections)
+        if self.config.security.redact_secrets:
+            text = redact_secrets(text)
+        return text
+
+    def _format_safety_check(self, command: str) -> str:
+        """Describe how the security settings would treat a command.
+
+        Args:
+            command: Command from ``ai check '<command>'``
+
+        Returns:
+            Classification report
+        """
+        return format_safety_check(check_command(command, self.config.security))
+
     def _handle_remember(self, args: list[str]) -> None:
         """Handle :remember command.
 
@@ -258,32 +432,197 @@ class ShellWrapper(LoggerMixin):
         else:
             print(f"Memory {entry_id} not found")
 
-    def _handle_ai_request(self, classified: ClassifiedInput) -> None:
+    def _handle_ai_request(self, classified: ClassifiedInput, read_only: bool = False) -> None:
         """Handle AI request.
 
         Args:
             classified: Classified input
+            read_only: Refuse every step that could change the system (ai ask)
         """
         request = classified.content
 
         # Add to history
-        self._history.add(request, is_ai_request=True)
+        self._history.add(request, is_ai_request=True, original=classified.original)
 
         if self._ai_handler:
             self._agent_status = AgentStatus.THINKING
+            # Handlers default to AgentSH's own directory until a cd is seen
+            options = {}
+            if read_only:
+                options["read_only"] = True
+            if self._shell_status.cwd:
+                options["cwd"] = self.cwd
+            if self._last_command_status is not None:
+                options["last_exit_code"] = self._last_command_status
+            if is_fix_request(request) and len(self._output):
+                options["recent_output"] = self._output.tail()
             try:
-                response = self._ai_handler(request)
+                reply = self._ai_handler(request, **options)
+                response = self._format_response(reply)
                 print(response)
+                self._record(f"{response}\r\n")
                 self._agent_status = AgentStatus.IDLE
-                self._last_exit_code = 0
+                # Handlers may report the aggregate status of executed steps
+                status = getattr(self._ai_handler, "last_status", 0)
+                self._set_ai_status(status if isinstance(status, int) else 0)
             except Exception as e:
                 self._agent_status = AgentStatus.ERROR
                 print(f"AI Error: {e}")
-                self._last_exit_code = 1
+                self._set_ai_status(1)
         else:
             # AI not yet implemented
             self._show_ai_placeholder(request)
 
+    def _handle_explain(self, command: str) -> None:
+        """Handle ``ai explain '<command>'``.
+
+        Args:
+            command: Command to explain
+        """
+        if not self._ai_handler:
+            self._show_ai_placeholder(f"explain {command}")
+            return
+        # Explaining must not run anything, so it never goes through the
+        # tool-enabled request handler
+        explain = getattr(self._ai_handler, "explain", None)
+        if explain is None:
+            print("Explain is not available: the AI handler cannot answer without tools.")
+            return
+
+        self._agent_status = AgentStatus.THINKING
+        try:
+            # JSON mode only constrains the reply; parsing stays lenient for
+            # providers without it
+            with json_response(self.config.llm.json_mode):
+                response = explain(command)
+            explanation = parse_explanation(response)
+        except Exception as e:
+            self._agent_status = AgentStatus.ERROR
+            print(f"AI Error: {e}")
+            return
+        self._agent_status = AgentStatus.IDLE
+
+        if explanation.structured:
+            text = format_explanation(explanation, use_color=self.config.shell.color)
+        else:
+            text = self._format_response(explanation.summary)
+        print(text)
+        self._record(f"{text}\r\n")
+
+    def _handle_redo(self) -> None:
+        """Handle ``ai redo``: run the last executed plan again.
+
+        Unlike replaying a request from history, the AI is not asked again;
+        the stored steps are re-run with fresh confirmation.
+        """
+        redo = getattr(self._ai_handler, "redo", None)
+        if redo is None:
+            print("Redo is not available: AI is not configured.")
+            return
+
+        self._agent_status = AgentStatus.THINKING
+        try:
+            response = self._format_response(redo())
+        except Exception as e:
+            self._agent_status = AgentStatus.ERROR
+            print(f"AI Error: {e}")
+            self._set_ai_status(1)
+            return
+        self._agent_status = AgentStatus.IDLE
+        print(response)
+        self._record(f"{response}\r\n")
+        status = getattr(self._ai_handler, "last_status", 0)
+        self._set_ai_status(status if isinstance(status, int) else 0)
+
+    def _handle_reload(self) -> None:
+        """Handle ``ai reload``: re-read the configuration files.
+
+        The AI handler is rebuilt with the new configuration and takes over
+        the conversation so far. If loading fails, the current configuration
+        stays in use.
+        """
+        from agentsh.config.loader import describe_config_changes, load_config
+
+        try:
+            config = load_config(self._config_path)
+            handler = self._ai_handler
+            if self._ai_handler_factory is not None:
+                handler = self._ai_handler_factory(config)
+        except Exception as e:
+            print(f"Reload failed, keeping the current configuration: {e}")
+            return
+
+        # Carry the conversation and its exportable turns over to the new agent
+        for attribute in ("conversation", "turns"):
+            previous = getattr(self._ai_handler, attribute, None)
+            current = getattr(handler, attribute, None)
+            if previous and current is not None and current is not previous:
+                current.extend(previous)
+
+        changes = describe_config_changes(self.config, config)
+        self.config = config
+        self._ai_handler = handler
+        self._classifier = InputClassifier(
+            ai_prefix=config.shell.ai_prefix,
+            shell_prefix=config.shell.shell_prefix,
+            default_to_ai=config.shell.default_to_ai,
+        )
+        self._prompt.use_color = config.shell.color
+        self.logger.info("Configuration reloaded", changes=changes)
+
+        print("Configuration reloaded.")
+        for change in changes:
+            print(f"  {change}")
+
+    def _handle_export_chat(self, path: str) -> None:
+        """Handle ``ai export-chat <file>``: save the session as Markdown.
+
+        Args:
+            path: File to write the transcript to
+        """
+        export = getattr(self._ai_handler, "export_chat", None)
+        if export is None:
+            print("Export is not available: AI is not configured.")
+            return
+        if not path:
+            print(f"Usage: {self.config.shell.ai_prefix}export-chat <file>")
+            return
+
+        try:
+            written = export(Path(path))
+        except OSError as e:
+            print(f"Export failed: {e}")
+            return
+        print(f"Conversation exported to {written}")
+
+    def _format_response(self, response: str) -> str:
+        """Format an AI answer for the terminal.
+
+        Markdown is rendered when enabled and stdout is a TTY; otherwise it
+        is stripped so pipes and logs get plain text.
+
+        Args:
+            response: Raw AI response text
+
+        Returns:
+            Text ready to print
+        """
+        if self.config.shell.render_markdown and sys.stdout.isatty():
+            return render_markdown(response, use_color=self.config.shell.color)
+        return strip_markdown(response)
+
+    def _set_ai_status(self, status: int) -> None:
+        """Record the exit status of the last AI request.
+
+        The status becomes the prompt's last exit code and is exported as
+        AGENTSH_LAST_STATUS so subsequent shell commands can inspect it.
+
+        Args:
+            status: Exit status (0 success, 1 failed, 130 cancelled)
+        """
+        self._last_exit_code = status
+        os.environ["AGENTSH_LAST_STATUS"] = str(status)
+
     def _handle_shell_command(self, classified: ClassifiedInput) -> None:
         """Handle shell command execution.
 
@@ -293,24 +632,61 @@ class ShellWrapper(LoggerMixin):
         command = classified.content
 
         # Add to history
-        self._history.add(command, is_ai_request=False)
-
-        # Execute command using subprocess for simplicity
-        # Full PTY integration comes in a later phase
-        import subprocess
+        self._history.add(command, is_ai_request=False, original=classified.original)
 
         try:
-            result = subprocess.run(
+            exit_code = self._run_shell_command(command)
+            self._last_exit_code = exit_code
+            self._last_command_status = exit_code
+            self._history.add(
                 command,
-                shell=True,
-                executable=self.config.shell.backend,
+                is_ai_request=False,
+                exit_code=exit_code,
+                original=classified.original,
             )
-            self._last_exit_code = result.returncode
-            self._history.add(command, is_ai_request=False, exit_code=result.returncode)
         except Exception as e:
             print(f"Error: {e}")
             self._last_exit_code = 1
 
+    def _run_shell_command(self, command: str) -> int:
+        """Run a shell command on the terminal through a PTY.
+
+        The command runs in the directory the previous one ended in and
+        reports its own final directory (see agentsh.shell.hooks), so a
+        ``cd`` carries over to later commands, the prompt and the AI.
+
+        Args:
+            command: Command line as typed
+
+        Returns:
+            The command's exit status
+        """
+        backend = self.config.shell.backend
+        pty = PTYManager(
+            shell_path=shutil.which(backend) or backend,
+            cwd=Path(self.cwd),
+            command=command,
+            status_tracker=self._shell_status,
+            output_buffer=self._output,
+            recorder=self._recorder,
+        )
+        # Anything printed so far must reach the terminal before the output
+        sys.stdout.flush()
+        pty.spawn()
+        try:
+            return pty.interact(input_fd=sys.stdin.fileno() if self.interactive else None)
+        finally:
+            pty.close()
+
+    def _record(self, text: str) -> None:
+        """Write text to the session recording, if enabled.
+
+        Args:
+            text: Text as shown on the terminal
+        """
+        if self._recorder is not None:
+            self._recorder.write(text)
+
     def _print_welcome(self) -> None:
         """Print welcome message."""
         print("=" * 60)
@@ -324,6 +700,22 @@ class ShellWrapper(LoggerMixin):
         print("    :quit               Exit AgentSH")
         print()
 
+        motd = self._get_motd()
+        if motd:
+            print(motd)
+            print()
+
+    def _get_motd(self) -> Optional[str]:
+        """Run the configured MOTD command and capture its output.
+
+        Returns:
+            The command's stdout, or None if unset, failed, or timed out
+        """
+        command = self.config.shell.motd_command
+        if not command:
+            return None
+
+        return get_command_output(["/bin/sh", "-c", command], timeout=MOTD_TIMEOUT)
 
     def _show_config(self) -> None:
         """Show current configuration."""
@@ -45,8 +45,12 @@ from agentsh.telemetry.health import (
 from agentsh.telemetry.logger import (
     LoggerMixin,
     bind_context,
+    current_request_id,
     get_logger,
+    new_request_id,
+    request_context,
     setup_logging,
+    truncate_preview,
 )
 from agentsh.telemetry.metrics import (
     AgentSHMetrics,
@@ -61,8 +65,12 @@ from agentsh.telemetry.metrics import (
 __all__ = [
     # Logger
     "setup_logging",
+    "truncate_preview",
     "get_logger",
     "bind_context",
+    "current_request_id",
+    "new_request_id",
+    "request_context",
     "LoggerMixin",
     # Events
     "EventType",
@@ -0,0 +1,199 @@
+"""Doctor - One-shot environment report for troubleshooting.
+
+Backs ``agentsh doctor``, which prints what AgentSH resolved at startup:
+version, config files loaded, the effective provider, model and endpoint,
+whether the API key is set (never the key itself), plugin directory
+contents, the detected shell and TTY status. The command exits nonzero
+when the AI would be unavailable, so it can be used in scripts.
+"""
+
+import os
+import sys
+from dataclasses import dataclass, field
+from pathlib import Path
+from typing import Mapping, Optional
+
+from agentsh import __version__
+from agentsh.agent.providers.gemini import GEMINI_BASE_URL
+from agentsh.config.defaults import DEFAULT_PLUGINS_DIR
+from agentsh.config.schemas import AgentSHConfig, LLMProvider
+
+# Providers that work without an API key
+KEYLESS_PROVIDERS = frozenset({LLMProvider.OLLAMA, LLMProvider.MOCK})
+
+# Endpoints used when the configuration does not name one
+DEFAULT_ENDPOINTS = {
+    LLMProvider.ANTHROPIC: "https://api.anthropic.com",
+    LLMProvider.OPENAI: "https://api.openai.com/v1",
+    LLMProvider.GEMINI: GEMINI_BASE_URL,
+    LLMProvider.OLLAMA: "http://localhost:11434",
+}
+
+
+@dataclass
+class DoctorReport:
+    """Environment facts gathered for ``agentsh doctor``.
+
+    Attributes:
+        version: AgentSH version
+        config_paths: Config files loaded, in load order
+        provider: Effective LLM provider
+        model: Effective model
+        endpoint: URL requests are sent to, if known
+        api_key_env: Environment variable the API key is read from
+        api_key_set: Whether that variable is set (None if not needed)
+        plugins_dir: Directory scanned for plugins
+        plugins: Entries in the plugin directory
+        shell: Configured shell backend
+        login_shell: The user's $SHELL
+        stdin_tty: Whether stdin is a terminal
+        stdout_tty: Whether stdout is a terminal
+        problems: Reasons the AI would be unavailable
+    """
+
+    version: str
+    config_paths: list[Path]
+    provider: str
+    model: str
+    endpoint: Optional[str]
+    api_key_env: str
+    api_key_set: Optional[bool]
+    plugins_dir: Path
+    plugins: list[str]
+    shell: str
+    login_shell: Optional[str]
+    stdin_tty: bool
+    stdout_tty: bool
+    problems: list[str] = field(default_factory=list)
+
+    @property
+    def ai_available(self) -> bool:
+        """Whether AI requests can be made with this setup."""
+        return not self.problems
+
+
+def resolve_endpoint(config: AgentSHConfig, env: Mapping[str, str]) -> Optional[str]:
+    """Get the URL the configured provider sends requests to.
+
+    Args:
+        config: AgentSH configuration
+        env: Environment variables
+
+    Returns:
+        Endpoint URL, or None if the provider has none
+    """
+    provider = config.llm.provider
+    if provider == LLMProvider.AZURE:
+        return config.llm.azure_endpoint
+    if provider == LLMProvider.OLLAMA and env.get("OLLAMA_HOST"):
+        return env["OLLAMA_HOST"]
+    return DEFAULT_ENDPOINTS.get(provider)
+
+
+def _is_tty(stream: object) -> bool:
+    """Check whether a stream is a terminal."""
+    try:
+        return bool(stream.isatty())  # type: ignore[attr-defined]
+    except (AttributeError, ValueError):
+        return False
+
+
+def build_report(
+    config: AgentSHConfig,
+    config_paths: list[Path],
+    env: Optional[Mapping[str, str]] = None,
+    plugins_dir: Path = DEFAULT_PLUGINS_DIR,
+) -> DoctorReport:
+    """Gather the environment report.
+
+    Args:
+        config: Loaded configuration
+        config_paths: Config files that were loaded
+        env: Environment variables (defaults to os.environ)
+        plugins_dir: Directory scanned for plugins
+
+    Returns:
+        DoctorReport describing the setup
+    """
+    env = os.environ if env is None else env
+    llm = config.llm
+    problems = []
+
+    api_key_set: Optional[bool] = None
+    if llm.provider not in KEYLESS_PROVIDERS:
+        api_key_set = bool(env.get(llm.api_key_env))
+        if not api_key_set:
+            problems.append(f"API key not set (export {llm.api_key_env})")
+
+    endpoint = resolve_endpoint(config, env)
+    if llm.provider == LLMProvider.AZURE and not endpoint:
+        problems.append("llm.azure_endpoint is not set")
+
+    plugins = sorted(p.name for p in plugins_dir.iterdir()) if plugins_dir.is_dir() else []
+
+    return DoctorReport(
+        version=__version__,
+        config_paths=config_paths,
+        provider=llm.provider.value,
+        model=llm.model,
+        endpoint=endpoint,
+        api_key_env=llm.api_key_env,
+        api_key_set=api_key_set,
+        plugins_dir=plugins_dir,
+        plugins=plugins,
+        shell=config.shell.backend,
+        login_shell=env.get("SHELL"),
+        stdin_tty=_is_tty(sys.stdin),
+        stdout_tty=_is_tty(sys.stdout),
+        problems=problems,
+    )
+
+
+def format_report(report: DoctorReport) -> str:
+    """Render a doctor report for display.
+
+    Args:
+        report: Result of :func:`build_report`
+
+    Returns:
+        Multi-line report
+    """
+
+    def yes_no(value: bool) -> str:
+        return "yes" if value else "no"
+
+    if report.api_key_set is None:
+        api_key = "not needed"
+    else:
+        api_key = f"{'set' if report.api_key_set else 'missing'} (${report.api_key_env})"
+
+    lines = [
+        f"Version:      {report.version}",
+        "Config files:",
+    ]
+    lines.extend(f"  - {path}" for path in report.config_paths)
+    if not report.config_paths:
+        lines.append("  (none, using defaults)")
+    lines.extend(
+        [
+            f"Provider:     {report.provider}",
+            f"Model:        {report.model}",
+            f"Endpoint:     {report.endpoint or 'unknown'}",
+            f"API key:      {api_key}",
+            f"Plugins dir:  {report.plugins_dir}"
+            + ("" if report.plugins_dir.is_dir() else " (missing)"),
+        ]
+    )
+    lines.extend(f"  - {name}" for name in report.plugins)
+    lines.extend(
+        [
+            f"Shell:        {report.shell} ($SHELL={report.login_shell or 'unset'})",
+            f"TTY:          stdin {yes_no(report.stdin_tty)}, stdout {yes_no(report.stdout_tty)}",
+        ]
+    )
+    if report.ai_available:
+        lines.append("AI:           available")
+    else:
+        lines.append("AI:           unavailable")
+        lines.extend(f"  - {problem}" for problem in report.problems)
+    return "\n".join(lines)
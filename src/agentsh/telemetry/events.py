@@ -8,7 +8,7 @@ from datetime import datetime
 from enum import Enum
 from typing import Any, Callable, Optional
 
-from agentsh.telemetry.logger import get_logger
+from agentsh.telemetry.logger import PREVIEW_MAX_BYTES, get_logger, truncate_preview
 
 logger = get_logger(__name__)
 
@@ -415,8 +415,13 @@ def tool_completed_event(
     result_preview: Optional[str] = None,
     session_id: Optional[str] = None,
     user_id: Optional[str] = None,
+    preview_bytes: int = PREVIEW_MAX_BYTES,
 ) -> TelemetryEvent:
-    """Create a tool completed event."""
+    """Create a tool completed event.
+
+    Long result previews keep their head and tail, see
+    :func:`~agentsh.telemetry.logger.truncate_preview`.
+    """
     return TelemetryEvent(
         event_type=EventType.TOOL_COMPLETED if success else EventType.TOOL_FAILED,
         session_id=session_id,
@@ -425,7 +430,9 @@ def tool_completed_event(
             "tool_name": tool_name,
             "success": success,
             "duration_ms": duration_ms,
-            "result_preview": result_preview[:200] if result_preview else None,
+            "result_preview": (
+                truncate_preview(result_preview, preview_bytes) if result_preview else None
+            ),
         },
     )
 
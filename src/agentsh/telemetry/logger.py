@@ -2,12 +2,21 @@
 
 import logging
 import sys
+import uuid
+from collections.abc import Iterator
+from contextlib import contextmanager
 from pathlib import Path
 from typing import Any, Optional
 
 import structlog
 from structlog.typing import Processor
 
+# Default byte budget for previews of command output and tool results
+PREVIEW_MAX_BYTES = 600
+
+# Logging context key holding the id of the AI request being handled
+REQUEST_ID_KEY = "request_id"
+
 
 def setup_logging(
     level: str = "INFO",
@@ -87,6 +96,34 @@ def get_logger(name: str) -> structlog.stdlib.BoundLogger:
     return structlog.get_logger(name)
 
 
+def truncate_preview(text: str, max_bytes: int = PREVIEW_MAX_BYTES) -> str:
+    """Shorten text for logs, keeping both its start and its end.
+
+    The end of command output is often the most useful part (the final
+    error), so the budget is split between head and tail around a marker.
+    Cuts fall on UTF-8 character boundaries.
+
+    Args:
+        text: Text to shorten
+        max_bytes: Total UTF-8 bytes of head and tail to keep (0 or less
+            keeps everything)
+
+    Returns:
+        The text itself if it fits, else head, an elision marker and tail
+    """
+    encoded = text.encode("utf-8")
+    if max_bytes <= 0 or len(encoded) <= max_bytes:
+        return text
+
+    head_bytes = max_bytes // 2
+    tail_bytes = max_bytes - head_bytes
+    # Dropping undecodable bytes trims characters split by the cut
+    head = encoded[:head_bytes].decode("utf-8", errors="ignore")
+    tail = encoded[len(encoded) - tail_bytes :].decode("utf-8", errors="ignore")
+    elided = len(encoded) - len(head.encode("utf-8")) - len(tail.encode("utf-8"))
+    return f"{head}\n[... {elided} bytes elided ...]\n{tail}"
+
+
 def bind_context(**kwargs: Any) -> None:
     """Bind context variables to all future log messages.
 
@@ -110,6 +147,35 @@ def clear_context() -> None:
     structlog.contextvars.clear_contextvars()
 
 
+def new_request_id() -> str:
+    """Generate an id for one AI request."""
+    return str(uuid.uuid4())
+
+
+def current_request_id() -> Optional[str]:
+    """Get the id of the AI request being handled, if any.
+
+    The id is bound with ``bind_context(request_id=...)``, so it also
+    appears on every log line written while handling the request.
+    """
+    return structlog.contextvars.get_contextvars().get(REQUEST_ID_KEY)
+
+
+@contextmanager
+def request_context(request_id: Optional[str] = None) -> Iterator[str]:
+    """Bind a request id for the duration of one AI request.
+
+    Args:
+        request_id: Id to bind (a new one is generated by default)
+
+    Yields:
+        The bound request id
+    """
+    request_id = request_id or new_request_id()
+    with structlog.contextvars.bound_contextvars(**{REQUEST_ID_KEY: request_id}):
+        yield request_id
+
+
 class LoggerMixin:
     """Mixin class to provide logging capabilities to classes."""
 
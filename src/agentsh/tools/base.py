@@ -1,10 +1,31 @@
 """Base classes for the tool system."""
 
 from dataclasses import dataclass, field
+from datetime import datetime
 from enum import Enum
 from typing import Any, Callable, Optional
 
 
+def decode_output(data: bytes, strict: bool = False) -> str:
+    """Decode command output as UTF-8.
+
+    Args:
+        data: Raw output bytes
+        strict: Replace output that is not valid UTF-8 with a
+            ``[binary output: N bytes]`` placeholder instead of decoding it
+            with U+FFFD substitutions
+
+    Returns:
+        Decoded text, or the placeholder
+    """
+    if not strict:
+        return data.decode("utf-8", errors="replace")
+    try:
+        return data.decode("utf-8")
+    except UnicodeDecodeError:
+        return f"[binary output: {len(data)} bytes]"
+
+
 class RiskLevel(str, Enum):
     """Risk classification for tools and commands."""
 
@@ -71,6 +92,7 @@ class ToolResult:
         output: Output from the tool (stdout, return value, etc.)
         error: Error message if failed
         duration_ms: Execution time in milliseconds
+        started_at: When execution started (UTC)
         exit_code: Exit code for shell commands
         metadata: Additional result metadata
     """
@@ -79,6 +101,7 @@ class ToolResult:
     output: str = ""
     error: Optional[str] = None
     duration_ms: int = 0
+    started_at: Optional[datetime] = None
     exit_code: Optional[int] = None
     metadata: dict[str, Any] = field(default_factory=dict)
 
@@ -20,6 +20,7 @@ class ToolRegistry:
 
     def __init__(self) -> None:
         self._tools: dict[str, Tool] = {}
+        self._confirmation_overrides: dict[str, bool] = {}
 
     def register_tool(
         self,
@@ -101,6 +102,35 @@ class ToolRegistry:
         """
         return list(self._tools.keys())
 
+    def set_confirmation_override(self, name: str, requires_confirmation: Optional[bool]) -> None:
+        """Override whether a tool must be confirmed before it runs.
+
+        Overrides may name tools that are registered later.
+
+        Args:
+            name: Tool name
+            requires_confirmation: New requirement, or None to restore the
+                tool's own default
+        """
+        if requires_confirmation is None:
+            self._confirmation_overrides.pop(name, None)
+        else:
+            self._confirmation_overrides[name] = requires_confirmation
+
+    def requires_confirmation(self, name: str) -> bool:
+        """Check whether a tool must be confirmed before it runs.
+
+        Args:
+            name: Tool name
+
+        Returns:
+            The configured override if any, else the tool's declared default
+        """
+        if name in self._confirmation_overrides:
+            return self._confirmation_overrides[name]
+        tool = self._tools.get(name)
+        return tool.requires_confirmation if tool else False
+
     def get_tools_by_risk_level(self, risk_level: RiskLevel) -> list[Tool]:
         """Get tools filtered by risk level.
 
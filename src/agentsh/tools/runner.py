@@ -1,8 +1,10 @@
 """Tool Runner - Executes tools with security, timeout, and retry handling."""
 
 import asyncio
+import json
 import time
 from dataclasses import dataclass
+from datetime import datetime, timezone
 from typing import Any, Optional
 
 from agentsh.security.classifier import RiskLevel as SecurityRiskLevel
@@ -98,6 +100,7 @@ class ToolRunner(LoggerMixin):
         """
         context = context or ExecutionContext()
         start_time = time.time()
+        started_at = datetime.now(timezone.utc)
 
         # Get the tool
         tool = self.registry.get_tool(tool_name)
@@ -131,6 +134,20 @@ class ToolRunner(LoggerMixin):
                 if not security_result.success:
                     return security_result
 
+        # Confirm tools whose policy demands it
+        if self.security_controller and self.registry.requires_confirmation(tool_name):
+            decision = self.security_controller.confirm_tool(
+                f"{tool_name} {json.dumps(arguments, default=str)}",
+                SecurityRiskLevel[tool.risk_level.name],
+                self._security_context(context),
+            )
+            if decision.result != ValidationResult.ALLOW:
+                return ToolResult(
+                    success=False,
+                    error=f"Security: {decision.reason}",
+                    duration_ms=int((time.time() - start_time) * 1000),
+                )
+
         # Execute with retries
         timeout = tool.timeout_seconds or self.default_timeout
         last_error = None
@@ -141,8 +158,9 @@ class ToolRunner(LoggerMixin):
                     tool, arguments, timeout
                 )
 
-                # Calculate duration
+                # Record timing
                 result.duration_ms = int((time.time() - start_time) * 1000)
+                result.started_at = started_at
 
                 if result.success:
                     self.logger.debug(
@@ -189,6 +207,7 @@ class ToolRunner(LoggerMixin):
             success=False,
             error=last_error or "Unknown error",
             duration_ms=int((time.time() - start_time) * 1000),
+            started_at=started_at,
         )
 
     def _validate_arguments(
@@ -267,31 +286,22 @@ class ToolRunner(LoggerMixin):
         }
         return tool_name in command_tools
 
-    def _check_security(
-        self,
-        command: str,
-        context: ExecutionContext,
-    ) -> ToolResult:
-        """Check if command is allowed by security policy.
+    def _security_context(self, context: ExecutionContext) -> SecurityContext:
+        """Build a SecurityContext from an ExecutionContext.
 
         Args:
-            command: Command to check
             context: Execution context
 
         Returns:
-            ToolResult with success=True if allowed, error otherwise
+            SecurityContext for security checks
         """
-        if not self.security_controller:
-            return ToolResult(success=True)
-
-        # Build security context
         user = User(
             id=context.user_id or "agent",
             name=context.user_id or "agent",
             role=Role.OPERATOR,
         )
 
-        security_context = SecurityContext(
+        return SecurityContext(
             user=user,
             device_id=context.device_id,
             cwd=context.cwd,
@@ -299,9 +309,26 @@ class ToolRunner(LoggerMixin):
             interactive=context.interactive,
         )
 
+    def _check_security(
+        self,
+        command: str,
+        context: ExecutionContext,
+    ) -> ToolResult:
+        """Check if command is allowed by security policy.
+
+        Args:
+            command: Command to check
+            context: Execution context
+
+        Returns:
+            ToolResult with success=True if allowed, error otherwise
+        """
+        if not self.security_controller:
+            return ToolResult(success=True)
+
         # Check with security controller
         decision = self.security_controller.validate_and_approve(
-            command, security_context
+            command, self._security_context(context)
         )
 
         if decision.result == ValidationResult.ALLOW:
@@ -19,6 +19,13 @@ from agentsh.utils.async_utils import (
     with_retry,
 )
 from agentsh.utils.env import get_env, get_env_bool, get_env_int, get_env_or_fail
+from agentsh.utils.sysinfo import (
+    SystemContext,
+    collect_system_context,
+    get_command_output,
+    get_hostname,
+    get_os_version,
+)
 from agentsh.utils.ux import (
     Color,
     ErrorContext,
@@ -66,6 +73,7 @@ from agentsh.utils.validators import (
     PathValidator,
     ValidationError,
     ValidationResult,
+    redact_network_identifiers,
     redact_secrets,
     validate_and_sanitize,
 )
@@ -121,6 +129,12 @@ __all__ = [
     "get_env_bool",
     "get_env_int",
     "get_env_or_fail",
+    # System info
+    "SystemContext",
+    "collect_system_context",
+    "get_command_output",
+    "get_hostname",
+    "get_os_version",
     # UX
     "Color",
     "ErrorContext",
@@ -165,6 +179,7 @@ __all__ = [
     "PathValidator",
     "ValidationError",
     "ValidationResult",
+    "redact_network_identifiers",
     "redact_secrets",
     "validate_and_sanitize",
     # Resource Management
@@ -67,13 +67,15 @@ class MarkdownRenderer:
         print(output)
     """
 
-    # Regex patterns for markdown elements
+    # Regex patterns for markdown elements. Emphasis markers must hug their
+    # text and underscores must not sit inside a word, so globs such as
+    # `rm *.log *.tmp` and names such as my_var_name are left alone.
     PATTERNS = {
         "header": re.compile(r"^(#{1,6})\s+(.+)$", re.MULTILINE),
-        "bold": re.compile(r"\*\*(.+?)\*\*"),
-        "italic": re.compile(r"\*(.+?)\*"),
-        "bold_alt": re.compile(r"__(.+?)__"),
-        "italic_alt": re.compile(r"_(.+?)_"),
+        "bold": re.compile(r"(?<!\*)\*\*(?=\S)(.+?)(?<=\S)\*\*(?!\*)"),
+        "italic": re.compile(r"(?<![\w*])\*(?=[^\s*])(.+?)(?<=[^\s*])\*(?![\w*])"),
+        "bold_alt": re.compile(r"(?<!\w)__(?=\S)(.+?)(?<=\S)__(?!\w)"),
+        "italic_alt": re.compile(r"(?<!\w)_(?=\S)(.+?)(?<=\S)_(?!\w)"),
         "inline_code": re.compile(r"`([^`]+)`"),
         "code_block": re.compile(r"```(\w*)\n(.*?)```", re.DOTALL),
         "link": re.compile(r"\[([^\]]+)\]\(([^)]+)\)"),
@@ -108,8 +110,9 @@ class MarkdownRenderer:
         # Process in order to handle nesting
         output = markdown
 
-        # Process code blocks first (to avoid processing markdown inside them)
+        # Process code first (to avoid processing markdown inside it)
         output = self._render_code_blocks(output)
+        output = self._render_inline_code(output)
 
         # Process block elements
         output = self._render_headers(output)
@@ -118,7 +121,6 @@ class MarkdownRenderer:
         output = self._render_lists(output)
 
         # Process inline elements
-        output = self._render_inline_code(output)
         output = self._render_bold(output)
         output = self._render_italic(output)
         output = self._render_strikethrough(output)
@@ -144,15 +146,21 @@ class MarkdownRenderer:
         """
         output = markdown
 
-        # Remove code blocks, keep content
-        output = self.PATTERNS["code_block"].sub(r"\2", output)
+        # Remove code markers, keeping the content away from other processing
+        code: list[str] = []
+
+        def protect(content: str) -> str:
+            code.append(content)
+            return f"\x00CODEBLOCK{len(code) - 1}\x00"
+
+        output = self.PATTERNS["code_block"].sub(lambda m: protect(m.group(2)), output)
+        output = self.PATTERNS["inline_code"].sub(lambda m: protect(m.group(1)), output)
 
         # Remove inline formatting
         output = self.PATTERNS["bold"].sub(r"\1", output)
         output = self.PATTERNS["italic"].sub(r"\1", output)
         output = self.PATTERNS["bold_alt"].sub(r"\1", output)
         output = self.PATTERNS["italic_alt"].sub(r"\1", output)
-        output = self.PATTERNS["inline_code"].sub(r"\1", output)
         output = self.PATTERNS["strikethrough"].sub(r"\1", output)
 
         # Remove headers markers
@@ -168,6 +176,9 @@ class MarkdownRenderer:
         # Remove horizontal rules
         output = self.PATTERNS["hr"].sub("", output)
 
+        for i, content in enumerate(code):
+            output = output.replace(f"\x00CODEBLOCK{i}\x00", content)
+
         return output.strip()
 
     def _render_headers(self, text: str) -> str:
@@ -217,7 +228,8 @@ class MarkdownRenderer:
         Returns:
             Text with rendered code blocks
         """
-        # Store code blocks to protect them from other processing
+        # Store code blocks to protect them from other processing. The
+        # placeholder avoids underscores so italic rendering can't touch it.
         self._code_blocks: list[str] = []
 
         def replace_code_block(match: re.Match) -> str:
@@ -227,7 +239,7 @@ class MarkdownRenderer:
             if self.config.code_block_style == "plain":
                 result = f"\n{code}\n"
                 self._code_blocks.append(result)
-                return f"\x00CODE_BLOCK_{len(self._code_blocks) - 1}\x00"
+                return f"\x00CODEBLOCK{len(self._code_blocks) - 1}\x00"
 
             lines = code.split("\n")
 
@@ -238,7 +250,7 @@ class MarkdownRenderer:
                 else:
                     result = f"\n{indented}\n"
                 self._code_blocks.append(result)
-                return f"\x00CODE_BLOCK_{len(self._code_blocks) - 1}\x00"
+                return f"\x00CODEBLOCK{len(self._code_blocks) - 1}\x00"
 
             # Box style - preserve content exactly
             max_len = max(len(line) for line in lines) if lines else 0
@@ -263,7 +275,7 @@ class MarkdownRenderer:
 
             result = "\n" + "\n".join(box_lines) + "\n"
             self._code_blocks.append(result)
-            return f"\x00CODE_BLOCK_{len(self._code_blocks) - 1}\x00"
+            return f"\x00CODEBLOCK{len(self._code_blocks) - 1}\x00"
 
         return self.PATTERNS["code_block"].sub(replace_code_block, text)
 
@@ -280,25 +292,29 @@ class MarkdownRenderer:
             return text
 
         for i, block in enumerate(self._code_blocks):
-            text = text.replace(f"\x00CODE_BLOCK_{i}\x00", block)
+            text = text.replace(f"\x00CODEBLOCK{i}\x00", block)
 
         return text
 
     def _render_inline_code(self, text: str) -> str:
         """Render inline code.
 
+        Like code blocks, the rendered code is kept behind a placeholder
+        until the inline formatting is done.
+
         Args:
             text: Input text
 
         Returns:
-            Text with rendered inline code
+            Text with inline code replaced by placeholders
         """
-        if not self.config.use_color:
-            return self.PATTERNS["inline_code"].sub(r"`\1`", text)
 
         def replace_code(match: re.Match) -> str:
-            code = match.group(1)
-            return colorize(f"`{code}`", Color.YELLOW)
+            code = f"`{match.group(1)}`"
+            if self.config.use_color:
+                code = colorize(code, Color.YELLOW)
+            self._code_blocks.append(code)
+            return f"\x00CODEBLOCK{len(self._code_blocks) - 1}\x00"
 
         return self.PATTERNS["inline_code"].sub(replace_code, text)
 
@@ -0,0 +1,238 @@
+"""Unified diff parsing and application.
+
+Only the subset of the unified format produced by ``diff -u`` and
+``git diff`` is supported: file headers, ``@@`` hunk headers, context,
+removal and addition lines, and ``\\ No newline at end of file`` markers.
+Hunks are applied strictly - every context and removal line must match the
+target file exactly, otherwise the whole patch is rejected.
+"""
+
+import re
+from dataclasses import dataclass, field
+from typing import Optional
+
+DEV_NULL = "/dev/null"
+
+HUNK_HEADER = re.compile(r"^@@ -(\d+)(?:,(\d+))? \+(\d+)(?:,(\d+))? @@")
+
+
+class PatchError(Exception):
+    """A patch could not be parsed or does not match its target."""
+
+
+@dataclass
+class Hunk:
+    """A single ``@@`` section of a unified diff.
+
+    Attributes:
+        old_start: 1-based start line in the original file
+        old_count: Number of original lines covered
+        new_start: 1-based start line in the patched file
+        new_count: Number of patched lines covered
+        lines: Body lines, each prefixed with ' ', '-' or '+'
+        old_eof_newline: False if the original's last line lacks a newline
+        new_eof_newline: False if the patched last line lacks a newline
+    """
+
+    old_start: int
+    old_count: int
+    new_start: int
+    new_count: int
+    lines: list[str] = field(default_factory=list)
+    old_eof_newline: bool = True
+    new_eof_newline: bool = True
+
+    @property
+    def old_lines(self) -> list[str]:
+        """Lines the hunk expects to find in the original file."""
+        return [line[1:] for line in self.lines if line[0] in " -"]
+
+    @property
+    def new_lines(self) -> list[str]:
+        """Lines the hunk puts in their place."""
+        return [line[1:] for line in self.lines if line[0] in " +"]
+
+
+@dataclass
+class FilePatch:
+    """All hunks for one file.
+
+    Attributes:
+        old_path: Path from the ``---`` header (None for new files)
+        new_path: Path from the ``+++`` header (None for deleted files)
+        hunks: Hunks in file order
+    """
+
+    old_path: Optional[str]
+    new_path: Optional[str]
+    hunks: list[Hunk] = field(default_factory=list)
+
+    @property
+    def path(self) -> str:
+        """Get the path the patch applies to."""
+        return self.new_path or self.old_path or ""
+
+    @property
+    def is_new_file(self) -> bool:
+        """Check if the patch creates a file."""
+        return self.old_path is None
+
+    @property
+    def is_deletion(self) -> bool:
+        """Check if the patch deletes a file."""
+        return self.new_path is None
+
+
+def _parse_header_path(line: str) -> Optional[str]:
+    """Extract the path from a ``---``/``+++`` header line.
+
+    Strips timestamps and git's ``a/``/``b/`` prefixes.
+    """
+    path = line[4:].split("\t", 1)[0].strip()
+    if path == DEV_NULL:
+        return None
+    if path.startswith(("a/", "b/")):
+        path = path[2:]
+    return path
+
+
+def parse_unified_diff(diff: str) -> list[FilePatch]:
+    """Parse a unified diff into per-file patches.
+
+    Args:
+        diff: Unified diff text
+
+    Returns:
+        List of FilePatch objects in diff order
+
+    Raises:
+        PatchError: If the diff is malformed or contains no hunks
+    """
+    patches: list[FilePatch] = []
+    current: Optional[FilePatch] = None
+    hunk: Optional[Hunk] = None
+    lines = diff.splitlines()
+    i = 0
+
+    while i < len(lines):
+        line = lines[i]
+
+        if hunk is not None and (line == "" or line[0] in " -+"):
+            if len(hunk.old_lines) < hunk.old_count or len(hunk.new_lines) < hunk.new_count:
+                # Some tools strip the trailing space from empty context lines
+                hunk.lines.append(line if line else " ")
+                i += 1
+                continue
+
+        if line.startswith("--- ") and i + 1 < len(lines) and lines[i + 1].startswith("+++ "):
+            current = FilePatch(
+                old_path=_parse_header_path(line),
+                new_path=_parse_header_path(lines[i + 1]),
+            )
+            patches.append(current)
+            hunk = None
+            i += 2
+            continue
+
+        match = HUNK_HEADER.match(line)
+        if match:
+            if current is None:
+                raise PatchError(f"Hunk without file header at line {i + 1}")
+            old_count = match.group(2)
+            new_count = match.group(4)
+            hunk = Hunk(
+                old_start=int(match.group(1)),
+                old_count=1 if old_count is None else int(old_count),
+                new_start=int(match.group(3)),
+                new_count=1 if new_count is None else int(new_count),
+            )
+            current.hunks.append(hunk)
+            i += 1
+            continue
+
+        if hunk is not None and line.startswith("\\"):
+            # "\ No newline at end of file" applies to the preceding line
+            if hunk.lines:
+                prefix = hunk.lines[-1][0]
+                if prefix in " -":
+                    hunk.old_eof_newline = False
+                if prefix in " +":
+                    hunk.new_eof_newline = False
+            i += 1
+            continue
+
+        # Anything else (diff --git, index, mode lines) is ignored
+        hunk = None
+        i += 1
+
+    if not any(p.hunks for p in patches):
+        raise PatchError("No hunks found in patch")
+
+    for file_patch in patches:
+        for h in file_patch.hunks:
+            if len(h.old_lines) != h.old_count or len(h.new_lines) != h.new_count:
+                raise PatchError(
+                    f"Hunk @@ -{h.old_start},{h.old_count} +{h.new_start},{h.new_count} @@ "
+                    f"in {file_patch.path} has the wrong number of lines"
+                )
+
+    return patches
+
+
+def apply_hunks(original: str, hunks: list[Hunk], path: str = "file") -> str:
+    """Apply hunks to file content.
+
+    Each hunk must match the original exactly at its stated position.
+
+    Args:
+        original: Current file content
+        hunks: Hunks to apply, in file order
+        path: Path used in error messages
+
+    Returns:
+        Patched content
+
+    Raises:
+        PatchError: If any context or removal line does not match
+    """
+    lines = original.split("\n")
+    eof_newline = original.endswith("\n") or original == ""
+    if original.endswith("\n"):
+        lines.pop()
+    if original == "":
+        lines = []
+
+    result: list[str] = []
+    cursor = 0
+
+    for hunk in hunks:
+        # A zero-length old range names the line *before* the insertion point
+        start = hunk.old_start if hunk.old_count == 0 else hunk.old_start - 1
+        if start < cursor:
+            raise PatchError(f"{path}: overlapping or out-of-order hunk at line {hunk.old_start}")
+
+        expected = hunk.old_lines
+        actual = lines[start:start + len(expected)]
+        if actual != expected:
+            for offset, (want, got) in enumerate(zip(expected, actual + [None] * len(expected))):
+                if want != got:
+                    raise PatchError(
+                        f"{path}: patch does not match at line {start + offset + 1} "
+                        f"(expected {want!r}, found {got!r}); the file may have changed"
+                    )
+
+        result.extend(lines[cursor:start])
+        result.extend(hunk.new_lines)
+        cursor = start + len(expected)
+
+        if cursor == len(lines):
+            if not hunk.new_eof_newline:
+                eof_newline = False
+            elif not hunk.old_eof_newline:
+                eof_newline = True
+
+    result.extend(lines[cursor:])
+
+    if not result:
+        return ""
+    return "\n".join(result) + ("\n" if eof_newline else "")
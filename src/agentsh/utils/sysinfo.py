@@ -0,0 +1,247 @@
+"""System information helpers for building agent context."""
+
+import asyncio
+import os
+import platform
+import subprocess
+from dataclasses import dataclass, field
+from typing import Optional
+
+from agentsh.telemetry.logger import get_logger
+
+logger = get_logger(__name__)
+
+# Default timeout for context probes, in seconds
+DEFAULT_COMMAND_TIMEOUT = 2.0
+
+# Overall time allowed for collecting the system context, in seconds
+DEFAULT_COLLECT_TIMEOUT = 1.0
+
+# Placeholder for facts whose probe failed or timed out
+UNKNOWN = "unknown"
+
+
+def get_command_output(
+    args: list[str],
+    timeout: float = DEFAULT_COMMAND_TIMEOUT,
+) -> Optional[str]:
+    """Run a command and return its stripped stdout.
+
+    The process is killed if it does not finish within the timeout, so a
+    hung probe (e.g. ``systemctl`` waiting on D-Bus) cannot stall startup.
+
+    Args:
+        args: Command and arguments
+        timeout: Maximum seconds to wait for the command
+
+    Returns:
+        Stripped stdout, or None if the command failed, was not found,
+        or timed out
+    """
+    try:
+        proc = subprocess.Popen(
+            args,
+            stdout=subprocess.PIPE,
+            stderr=subprocess.DEVNULL,
+            stdin=subprocess.DEVNULL,
+            text=True,
+        )
+    except OSError:
+        return None
+
+    try:
+        stdout, _ = proc.communicate(timeout=timeout)
+    except subprocess.TimeoutExpired:
+        proc.kill()
+        proc.communicate()
+        logger.debug("Command timed out", command=args[0], timeout=timeout)
+        return None
+
+    if proc.returncode != 0:
+        return None
+
+    return stdout.strip()
+
+
+def get_hostname(timeout: float = DEFAULT_COMMAND_TIMEOUT) -> str:
+    """Get the machine's hostname.
+
+    Args:
+        timeout: Timeout for the ``hostname`` fallback command
+
+    Returns:
+        Hostname, or "localhost" if it cannot be determined
+    """
+    try:
+        name = os.uname().nodename
+        if name:
+            return name
+    except AttributeError:
+        pass
+
+    return get_command_output(["hostname"], timeout=timeout) or "localhost"
+
+
+def get_os_version(timeout: float = DEFAULT_COMMAND_TIMEOUT) -> str:
+    """Get a human-readable OS name and version.
+
+    Args:
+        timeout: Timeout for the ``sw_vers`` probe on macOS
+
+    Returns:
+        OS description, e.g. "Ubuntu 22.04.3 LTS" or "macOS 14.2"
+    """
+    system = platform.system()
+
+    if system == "Linux":
+        try:
+            release = platform.freedesktop_os_release()
+            pretty = release.get("PRETTY_NAME")
+            if pretty:
+                return pretty
+        except (OSError, AttributeError):
+            pass
+    elif system == "Darwin":
+        version = get_command_output(["sw_vers", "-productVersion"], timeout=timeout)
+        if version:
+            return f"macOS {version}"
+
+    return f"{system} {platform.release()}"
+
+
+async def get_command_output_async(
+    args: list[str],
+    timeout: float = DEFAULT_COMMAND_TIMEOUT,
+) -> Optional[str]:
+    """Run a command without blocking the event loop.
+
+    The process is killed if it times out or the caller is cancelled.
+
+    Args:
+        args: Command and arguments
+        timeout: Maximum seconds to wait for the command
+
+    Returns:
+        Stripped stdout, or None if the command failed, was not found,
+        or timed out
+    """
+    try:
+        proc = await asyncio.create_subprocess_exec(
+            *args,
+            stdout=asyncio.subprocess.PIPE,
+            stderr=asyncio.subprocess.DEVNULL,
+            stdin=asyncio.subprocess.DEVNULL,
+        )
+    except OSError:
+        return None
+
+    try:
+        stdout, _ = await asyncio.wait_for(proc.communicate(), timeout=timeout)
+    except asyncio.TimeoutError:
+        logger.debug("Command timed out", command=args[0], timeout=timeout)
+        return None
+    finally:
+        if proc.returncode is None:
+            proc.kill()
+            await proc.wait()
+
+    if proc.returncode != 0:
+        return None
+
+    return stdout.decode("utf-8", errors="replace").strip()
+
+
+@dataclass
+class SystemContext:
+    """Facts about the machine included with AI requests.
+
+    Attributes:
+        hostname: Machine name
+        kernel: Kernel name and release (``uname -sr``)
+        os_version: OS name and version, e.g. "Ubuntu 22.04.3 LTS"
+        timed_out: Probes that did not finish within the collector timeout
+    """
+
+    hostname: str = UNKNOWN
+    kernel: str = UNKNOWN
+    os_version: str = UNKNOWN
+    timed_out: list[str] = field(default_factory=list)
+
+    def known_hostname(self) -> Optional[str]:
+        """Get the hostname, or None if it is unknown."""
+        return None if self.hostname == UNKNOWN else self.hostname
+
+    def os_info(self) -> Optional[str]:
+        """Describe the OS for the system prompt.
+
+        Returns:
+            OS version with the kernel in parentheses, leaving out unknown
+            parts, or None if neither is known
+        """
+        parts = [part for part in (self.os_version, self.kernel) if part != UNKNOWN]
+        if not parts:
+            return None
+        if len(parts) == 2 and parts[0] != parts[1]:
+            return f"{parts[0]} ({parts[1]})"
+        return parts[0]
+
+
+def system_probes() -> dict[str, list[str]]:
+    """Get the commands that fill in SystemContext fields on this platform.
+
+    Returns:
+        Commands keyed by the SystemContext field they provide. On Linux
+        the OS version is read from os-release instead.
+    """
+    probes = {"hostname": ["hostname"], "kernel": ["uname", "-sr"]}
+    if platform.system() == "Darwin":
+        probes["os_version"] = ["sw_vers", "-productVersion"]
+    return probes
+
+
+async def collect_system_context(
+    timeout: float = DEFAULT_COLLECT_TIMEOUT,
+    probes: Optional[dict[str, list[str]]] = None,
+) -> SystemContext:
+    """Collect system facts, running all probes at once.
+
+    Probes still running when the timeout expires are cancelled (their
+    processes killed) and their fields left as "unknown", so one hung
+    command cannot stall an AI request.
+
+    Args:
+        timeout: Overall seconds allowed for all probes together
+        probes: Commands keyed by SystemContext field (defaults to
+            :func:`system_probes`)
+
+    Returns:
+        SystemContext, partial if some probes failed or timed out
+    """
+    probes = system_probes() if probes is None else probes
+    tasks = {
+        name: asyncio.ensure_future(get_command_output_async(args, timeout=timeout))
+        for name, args in probes.items()
+    }
+
+    pending: set[asyncio.Future] = set()
+    if tasks:
+        _, pending = await asyncio.wait(tasks.values(), timeout=timeout)
+        for task in pending:
+            task.cancel()
+        await asyncio.gather(*pending, return_exceptions=True)
+
+    context = SystemContext()
+    for name, task in tasks.items():
+        if task in pending:
+            context.timed_out.append(name)
+            continue
+        output = task.result()
+        if output:
+            setattr(context, name, output.splitlines()[0])
+
+    if "os_version" not in probes:
+        context.os_version = get_os_version()
+
+    if context.timed_out:
+        logger.debug("System context probes timed out", probes=context.timed_out)
+    return context
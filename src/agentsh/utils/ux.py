@@ -8,13 +8,15 @@ Provides user experience enhancements including:
 """
 
 import asyncio
+import os
+import re
 import sys
 import threading
 import time
 from contextlib import contextmanager
 from dataclasses import dataclass, field
 from enum import Enum
-from typing import Any, Callable, Generator, Iterator, Optional, TextIO
+from typing import Any, Callable, Generator, Iterator, Optional, TextIO, Union
 
 
 class Color(str, Enum):
@@ -74,6 +76,91 @@ def colorize(text: str, *colors: Color, use_color: bool = True) -> str:
     return f"{color_codes}{text}{Color.RESET.value}"
 
 
+# Color syntaxes accepted by parse_color besides Color names
+HEX_COLOR = re.compile(r"#([0-9a-fA-F]{2})([0-9a-fA-F]{2})([0-9a-fA-F]{2})")
+PALETTE_COLOR = re.compile(r"ansi\((\d{1,3})\)")
+
+
+def parse_color(name: str) -> Optional[str]:
+    """Parse a color from the configuration into an ANSI escape sequence.
+
+    Accepted forms are Color names such as "green", "bright-cyan" or
+    "bold" (case-insensitive), "#rrggbb" for a 24-bit color and "ansi(N)"
+    for entry N (0-255) of the 256-color palette.
+
+    Args:
+        name: Color as written in the configuration
+
+    Returns:
+        The escape sequence, or None if the color is not recognized
+    """
+    value = name.strip()
+    match = HEX_COLOR.fullmatch(value)
+    if match:
+        red, green, blue = (int(part, 16) for part in match.groups())
+        return f"\033[38;2;{red};{green};{blue}m"
+    match = PALETTE_COLOR.fullmatch(value.lower())
+    if match:
+        index = int(match.group(1))
+        return f"\033[38;5;{index}m" if index <= 255 else None
+    member = Color.__members__.get(value.upper().replace("-", "_").replace(" ", "_"))
+    return member.value if member is not None else None
+
+
+def color_enabled(use_color: bool = True) -> bool:
+    """Decide whether output should be colored.
+
+    Coloring is off when the configuration turns it off (shell.color) or
+    the NO_COLOR environment variable is set to a non-empty value.
+
+    Args:
+        use_color: The configured color setting
+
+    Returns:
+        True if ANSI colors should be used
+    """
+    return use_color and not os.environ.get("NO_COLOR")
+
+
+class Painter:
+    """Applies colors and styles only when coloring is enabled.
+
+    Example:
+        painter = Painter(config.shell.color)
+        print(painter.paint("done", "green", "bold"))
+        print(painter.paint("note", "#ff8800"))
+    """
+
+    def __init__(self, use_color: bool = True) -> None:
+        """Initialize the painter.
+
+        Args:
+            use_color: The configured color setting; NO_COLOR is also honored
+        """
+        self.enabled = color_enabled(use_color)
+
+    def paint(self, text: str, *styles: Union[Color, str]) -> str:
+        """Apply styles to text.
+
+        Args:
+            text: Text to style
+            *styles: Color members or color names accepted by parse_color;
+                unrecognized names are ignored
+
+        Returns:
+            The styled text, or the text unchanged if coloring is disabled
+        """
+        if not self.enabled:
+            return text
+        codes = [
+            style.value if isinstance(style, Color) else parse_color(style) for style in styles
+        ]
+        prefix = "".join(code for code in codes if code)
+        if not prefix:
+            return text
+        return f"{prefix}{text}{Color.RESET.value}"
+
+
 def strip_ansi(text: str) -> str:
     """Remove ANSI escape codes from text.
 
@@ -97,6 +184,7 @@ class SpinnerStyle(str, Enum):
     ARROWS = "arrows"
     BOUNCE = "bounce"
     BRAILLE = "braille"
+    NONE = "none"  # Disabled: no output at all
 
 
 SPINNER_FRAMES = {
@@ -105,12 +193,17 @@ SPINNER_FRAMES = {
     SpinnerStyle.ARROWS: ["←", "↖", "↑", "↗", "→", "↘", "↓", "↙"],
     SpinnerStyle.BOUNCE: ["⠁", "⠂", "⠄", "⠂"],
     SpinnerStyle.BRAILLE: ["⣾", "⣽", "⣻", "⢿", "⡿", "⣟", "⣯", "⣷"],
+    SpinnerStyle.NONE: [],
 }
 
 
 class Spinner:
     """Animated spinner for indicating progress.
 
+    The animation only runs when the stream is a terminal. Otherwise the
+    message is written once as a plain line, so logs and pipes do not fill
+    with escape sequences. SpinnerStyle.NONE disables all output.
+
     Example:
         with Spinner("Loading..."):
             time.sleep(2)
@@ -145,15 +238,41 @@ class Spinner:
         self._frames = SPINNER_FRAMES[style]
         self._frame_idx = 0
         self._running = False
+        self._animated = False
         self._thread: Optional[threading.Thread] = None
         self._interval = 0.1  # 100ms per frame
 
+    @property
+    def enabled(self) -> bool:
+        """Whether the spinner writes anything."""
+        return self.style != SpinnerStyle.NONE
+
+    def _is_tty(self) -> bool:
+        """Check whether the output stream is a terminal."""
+        isatty = getattr(self.stream, "isatty", None)
+        try:
+            return bool(isatty and isatty())
+        except ValueError:
+            # Closed stream
+            return False
+
     def start(self) -> "Spinner":
         """Start the spinner animation."""
         if self._running:
             return self
 
         self._running = True
+        if not self.enabled:
+            return self
+
+        if not self._is_tty():
+            # Static line instead of an animation
+            if self.message:
+                self.stream.write(f"{self.message}\n")
+                self.stream.flush()
+            return self
+
+        self._animated = True
         self._thread = threading.Thread(target=self._animate, daemon=True)
         self._thread.start()
         return self
@@ -169,8 +288,13 @@ class Spinner:
             self._thread.join(timeout=1.0)
             self._thread = None
 
+        if not self.enabled:
+            return
+
         # Clear the spinner line
-        self._clear_line()
+        if self._animated:
+            self._clear_line()
+            self._animated = False
 
         if final_message:
             self.stream.write(f"{final_message}\n")
@@ -4,8 +4,10 @@ Provides security-focused validation for user input, paths,
 commands, and other data entering the system.
 """
 
+import ipaddress
 import os
 import re
+import ssl
 import unicodedata
 from dataclasses import dataclass
 from enum import Enum
@@ -599,3 +601,102 @@ def redact_secrets(text: str, replacement: str = "***REDACTED***") -> str:
         result = re.sub(pattern, repl, result)
 
     return result
+
+
+# Address and hostname candidates for redact_network_identifiers
+IPV4_PATTERN = re.compile(r"(?<![\w.])(?:\d{1,3}\.){3}\d{1,3}(?![\w.])")
+IPV6_PATTERN = re.compile(
+    r"(?<![\w:.])(?:[0-9A-Fa-f]{0,4}:){2,7}[0-9A-Fa-f]{0,4}(?:%\w+)?(?![\w:])"
+)
+HOSTNAME_PATTERN = re.compile(
+    r"(?<![\w.@-])(?:[A-Za-z0-9](?:[A-Za-z0-9-]{0,61}[A-Za-z0-9])?\.)+[A-Za-z]{2,63}(?![\w.-])"
+)
+
+# Domain suffixes used for internal names
+INTERNAL_DOMAIN_SUFFIXES = (
+    ".internal",
+    ".local",
+    ".lan",
+    ".corp",
+    ".intranet",
+    ".home.arpa",
+    ".localdomain",
+)
+
+# Final labels that make a dotted name a file rather than a host
+FILE_EXTENSIONS = frozenset(
+    "bz2 cfg conf csv gz html ini js json lock log md py sh so sql tar tgz toml txt "
+    "xml xz yaml yml zip".split()
+)
+
+
+def _is_internal_hostname(name: str) -> bool:
+    """Check whether a hostname uses an internal domain suffix."""
+    return name.lower().endswith(INTERNAL_DOMAIN_SUFFIXES)
+
+
+def redact_network_identifiers(
+    text: str,
+    private_only: bool = False,
+    replacement: str = "***REDACTED***",
+) -> str:
+    """Redact IP addresses and fully qualified hostnames from text.
+
+    Hostnames need at least three labels (``db1.prod.example.com``) or an
+    internal suffix such as ``.internal`` or ``.local``; dotted names
+    ending in a common file extension are left alone.
+
+    Args:
+        text: Text to redact
+        private_only: Only redact private addresses and internal
+            hostnames, keeping public ones and loopback (127.0.0.1, ::1)
+        replacement: Replacement string for redacted identifiers
+
+    Returns:
+        Text with network identifiers redacted
+    """
+
+    def address(match: re.Match[str]) -> str:
+        try:
+            ip = ipaddress.ip_address(match.group(0).split("%")[0])
+        except ValueError:
+            return match.group(0)
+        if private_only and (ip.is_loopback or not ip.is_private):
+            return match.group(0)
+        return replacement
+
+    def hostname(match: re.Match[str]) -> str:
+        name = match.group(0)
+        internal = _is_internal_hostname(name)
+        if private_only and (not internal or name.lower().startswith("localhost.")):
+            return name
+        if name.rsplit(".", 1)[1].lower() in FILE_EXTENSIONS:
+            return name
+        if not internal and name.count(".") < 2:
+            return name
+        return replacement
+
+    result = IPV6_PATTERN.sub(address, text)
+    result = IPV4_PATTERN.sub(address, result)
+    return HOSTNAME_PATTERN.sub(hostname, result)
+
+
+def load_ca_bundle(path: Path) -> ssl.SSLContext:
+    """Build a TLS context trusting the certificates in a PEM bundle.
+
+    Args:
+        path: PEM file with one or more root certificates
+
+    Returns:
+        SSL context verifying servers against the bundle
+
+    Raises:
+        ValueError: If the file cannot be read or holds no valid certificate
+    """
+    path = path.expanduser()
+    try:
+        return ssl.create_default_context(cafile=str(path))
+    except ssl.SSLError as e:
+        raise ValueError(f"CA bundle {path} is not a PEM certificate bundle") from e
+    except OSError as e:
+        raise ValueError(f"CA bundle {path} cannot be read: {e.strerror or e}") from e
@@ -439,11 +439,11 @@ class ApprovalNode:
 
         response = self.approval_flow.request_approval(request)
 
-        if response.result == ApprovalResult.APPROVED:
+        if response.result in (ApprovalResult.APPROVED, ApprovalResult.APPROVED_SESSION):
             return True, "Approved by user"
         elif response.result == ApprovalResult.EDITED:
             # User edited the command - update the tool call
-            tool_call.arguments["command"] = response.edited_command
+            tool_call.arguments["command"] = response.command
             return True, "Approved with edits"
         else:
             return False, f"Denied: {response.result.value}"
@@ -38,3 +38,9 @@ shell:
 log_level: DEBUG
 """)
     return config_file
+
+
+@pytest.fixture
+def ca_bundle() -> Path:
+    """Path of a PEM bundle holding a self-signed test CA."""
+    return Path(__file__).parent / "fixtures" / "test_ca.pem"
@@ -1,7 +1,12 @@
 """Tests for agent loop module."""
 
+import asyncio
+import time
+from datetime import datetime
+
 import pytest
-from typing import AsyncIterator, Optional
+from pathlib import Path
+from typing import Any, AsyncIterator, Optional
 from unittest.mock import AsyncMock, MagicMock, patch
 
 from agentsh.agent.agent_loop import (
@@ -9,17 +14,25 @@ from agentsh.agent.agent_loop import (
     AgentContext,
     AgentLoop,
     AgentResult,
+    NEED_INFO_TOOL,
+    StepOutcome,
     StreamingAgentLoop,
+    aggregate_exit_status,
 )
 from agentsh.agent.llm_client import (
     LLMClient,
+    LLMError,
     LLMResponse,
+    LLMTruncatedError,
     Message,
+    MessageRole,
     StopReason,
     ToolCall,
     ToolDefinition,
 )
-from agentsh.tools.base import ToolResult
+from agentsh.agent.replan import FailureAction
+from agentsh.security.approval import ApprovalKind
+from agentsh.tools.base import RiskLevel as ToolRiskLevel, ToolResult
 from agentsh.tools.registry import ToolRegistry
 
 
@@ -157,6 +170,39 @@ class TestAgentResult:
         assert len(result.tool_calls_made) == 3
         assert result.total_steps == 3
 
+    def test_exit_status_success(self) -> None:
+        """Should report 0 when all steps succeed."""
+        result = AgentResult(
+            response="Done!",
+            step_outcomes=[StepOutcome.SUCCESS, StepOutcome.SUCCESS],
+        )
+
+        assert result.exit_status == 0
+
+    def test_exit_status_failure_without_steps(self) -> None:
+        """Should report 1 when the request failed before any step."""
+        result = AgentResult(response="Error", success=False, error="API Error")
+
+        assert result.exit_status == 1
+
+
+class TestAggregateExitStatus:
+    """Tests for aggregate_exit_status."""
+
+    @pytest.mark.parametrize(
+        "outcomes,expected",
+        [
+            ([], 0),
+            ([StepOutcome.SUCCESS], 0),
+            ([StepOutcome.SUCCESS, StepOutcome.FAILED, StepOutcome.SUCCESS], 1),
+            ([StepOutcome.SUCCESS, StepOutcome.CANCELLED], 130),
+            ([StepOutcome.FAILED, StepOutcome.CANCELLED, StepOutcome.SUCCESS], 130),
+        ],
+    )
+    def test_mixed_outcomes(self, outcomes: list[StepOutcome], expected: int) -> None:
+        """Should prefer cancelled over failed over success."""
+        assert aggregate_exit_status(outcomes) == expected
+
 
 class TestAgentLoop:
     """Tests for AgentLoop class."""
@@ -234,6 +280,8 @@ class TestAgentLoop:
 
         assert result.success is True
         assert "list_files" in result.tool_calls_made
+        assert result.step_outcomes == [StepOutcome.SUCCESS]
+        assert result.exit_status == 0
 
     @pytest.mark.asyncio
     async def test_invoke_unknown_tool(self, tool_registry: ToolRegistry) -> None:
@@ -258,6 +306,8 @@ class TestAgentLoop:
 
         # Should complete without crashing
         assert result is not None
+        assert result.step_outcomes == [StepOutcome.FAILED]
+        assert result.exit_status == 1
 
     @pytest.mark.asyncio
     async def test_max_steps_limit(self, tool_registry: ToolRegistry) -> None:
@@ -316,7 +366,7 @@ class TestAgentLoopWithSecurity:
             return f"Executed: {command}"
 
         registry.register_tool(
-            name="shell",
+            name="shell.run",
             handler=run_command,
             description="Run a shell command",
             parameters={
@@ -342,7 +392,7 @@ class TestAgentLoopWithSecurity:
                 tool_calls=[
                     ToolCall(
                         id="call_1",
-                        name="shell",
+                        name="shell.run",
                         arguments={"command": "rm -rf /"},
                     ),
                 ],
@@ -361,40 +411,1956 @@ class TestAgentLoopWithSecurity:
 
         # The tool should be blocked by security
         assert result is not None
+        assert result.step_outcomes == [StepOutcome.CANCELLED]
+        assert result.exit_status == 130
+
+    def test_audit_records_model_and_provider(
+        self, tool_registry: ToolRegistry, tmp_path: Path
+    ) -> None:
+        """Should attribute audited actions to the loop's model."""
+        from agentsh.security.audit import AuditLogger
+        from agentsh.security.controller import SecurityController
 
+        audit = AuditLogger(log_path=tmp_path / "audit.log")
+        security = SecurityController(audit_logger=audit)
+        AgentLoop(MockLLMClient(), tool_registry, security_controller=security)
 
-class TestStreamingAgentLoop:
-    """Tests for StreamingAgentLoop class."""
+        assert audit.model == "mock-model"
+        assert audit.provider == "mock"
+
+    @pytest.mark.asyncio
+    async def test_request_id_in_header_and_audit(
+        self, tool_registry: ToolRegistry, tmp_path: Path
+    ) -> None:
+        """Should tag the provider request and audit entry with one request id."""
+        from agentsh.agent.http_client import REQUEST_ID_HEADER, request_id_headers
+        from agentsh.security.audit import AuditLogger
+        from agentsh.security.controller import SecurityController
+        from agentsh.telemetry.logger import current_request_id
+
+        sent_headers: list[dict[str, str]] = []
+
+        class HeaderRecordingClient(MockLLMClient):
+            async def invoke(self, *args: Any, **kwargs: Any) -> LLMResponse:
+                sent_headers.append(request_id_headers())
+                return await super().invoke(*args, **kwargs)
+
+        responses = [
+            LLMResponse(
+                content="Running command.",
+                tool_calls=[
+                    ToolCall(id="call_1", name="shell.run", arguments={"command": "rm -rf /"})
+                ],
+                stop_reason=StopReason.TOOL_USE,
+            ),
+            LLMResponse(content="Blocked.", stop_reason=StopReason.END_TURN),
+        ]
+        audit = AuditLogger(log_path=tmp_path / "audit.log")
+        security = SecurityController(audit_logger=audit)
+        agent = AgentLoop(
+            HeaderRecordingClient(responses), tool_registry, security_controller=security
+        )
+
+        await agent.invoke("Delete everything")
+
+        request_id = sent_headers[0][REQUEST_ID_HEADER]
+        assert all(headers[REQUEST_ID_HEADER] == request_id for headers in sent_headers)
+        assert [event.request_id for event in audit.get_recent()] == [request_id]
+        assert current_request_id() is None
+
+
+class TestRedo:
+    """Tests for re-running the last executed plan."""
 
     @pytest.fixture
-    def streaming_agent(self) -> StreamingAgentLoop:
-        """Create streaming agent."""
-        mock_llm = MockLLMClient([
-            LLMResponse(content="This is a streaming response.")
+    def tool_registry(self) -> ToolRegistry:
+        """Create tool registry with a counting tool."""
+        registry = ToolRegistry()
+        registry.runs = []  # type: ignore[attr-defined]
+        registry.register_tool(
+            name="deploy",
+            handler=lambda target: registry.runs.append(target) or f"deployed {target}",
+            description="Deploy",
+            parameters={"properties": {"target": {"type": "string"}}, "required": ["target"]},
+        )
+        return registry
+
+    @pytest.mark.asyncio
+    async def test_redo_reuses_plan_without_llm(self, tool_registry: ToolRegistry) -> None:
+        """Should run the stored steps again without calling the LLM."""
+        responses = [
+            LLMResponse(
+                content="Deploying.",
+                tool_calls=[ToolCall(id="c1", name="deploy", arguments={"target": "web"})],
+                stop_reason=StopReason.TOOL_USE,
+            ),
+            LLMResponse(content="Deployed.", stop_reason=StopReason.END_TURN),
+        ]
+        from agentsh.security.controller import SecurityController
+
+        llm = RecordingLLMClient(responses)
+        agent = AgentLoop(llm, tool_registry, security_controller=SecurityController())
+        await agent.invoke("deploy web")
+        calls = len(llm.received)
+
+        result = await agent.redo()
+
+        assert result.success
+        assert len(llm.received) == calls
+        assert tool_registry.runs == ["web", "web"]  # type: ignore[attr-defined]
+        assert "Step 1: deploy" in result.response
+        assert "deployed web" in result.response
+
+    @pytest.mark.asyncio
+    async def test_redo_without_plan(self, tool_registry: ToolRegistry) -> None:
+        """Should refuse when no plan has run."""
+        agent = AgentLoop(MockLLMClient(), tool_registry)
+
+        result = await agent.redo()
+
+        assert not result.success
+        assert result.error == "Nothing to redo"
+        assert tool_registry.runs == []  # type: ignore[attr-defined]
+
+    @pytest.mark.asyncio
+    async def test_answer_without_steps_keeps_plan(self, tool_registry: ToolRegistry) -> None:
+        """Should keep the last plan when a later request runs no steps."""
+        responses = [
+            LLMResponse(
+                content="Deploying.",
+                tool_calls=[ToolCall(id="c1", name="deploy", arguments={"target": "api"})],
+                stop_reason=StopReason.TOOL_USE,
+            ),
+            LLMResponse(content="Deployed.", stop_reason=StopReason.END_TURN),
+        ]
+        agent = AgentLoop(MockLLMClient(responses), tool_registry)
+        await agent.invoke("deploy api")
+        agent.llm_client = MockLLMClient()
+        await agent.invoke("what did you do?")
+
+        assert [step.arguments for step in agent.last_plan] == [{"target": "api"}]
+
+    @pytest.mark.asyncio
+    async def test_redo_refused_without_security(self, tool_registry: ToolRegistry) -> None:
+        """Should not run steps again when nothing can confirm them."""
+        responses = [
+            LLMResponse(
+                content="Deploying.",
+                tool_calls=[ToolCall(id="c1", name="deploy", arguments={"target": "web"})],
+                stop_reason=StopReason.TOOL_USE,
+            ),
+            LLMResponse(content="Deployed.", stop_reason=StopReason.END_TURN),
+        ]
+        agent = AgentLoop(MockLLMClient(responses), tool_registry)
+        await agent.invoke("deploy web")
+
+        result = await agent.redo()
+
+        assert not result.success
+        assert result.error == "Redo unavailable"
+        assert tool_registry.runs == ["web"]  # type: ignore[attr-defined]
+
+    @pytest.mark.asyncio
+    async def test_declined_steps_not_redone(self, tool_registry: ToolRegistry) -> None:
+        """Should only remember the steps that ran."""
+        from agentsh.security.controller import SecurityController, ValidationResult
+
+        tool_registry.register_tool(
+            name="wipe",
+            handler=lambda: "wiped",
+            description="Wipe",
+            parameters={},
+            requires_confirmation=True,
+        )
+        security = SecurityController()
+        security.confirm_tool = MagicMock(  # type: ignore[method-assign]
+            return_value=MagicMock(result=ValidationResult.BLOCKED, reason="Denied by user")
+        )
+        responses = [
+            LLMResponse(
+                content="Deploying.",
+                tool_calls=[
+                    ToolCall(id="c1", name="wipe", arguments={}),
+                    ToolCall(id="c2", name="deploy", arguments={"target": "web"}),
+                ],
+                stop_reason=StopReason.TOOL_USE,
+            ),
+            LLMResponse(content="Deployed.", stop_reason=StopReason.END_TURN),
+        ]
+        agent = AgentLoop(MockLLMClient(responses), tool_registry, security_controller=security)
+        await agent.invoke("wipe and deploy")
+
+        assert [step.name for step in agent.last_plan] == ["deploy"]
+
+    @pytest.mark.asyncio
+    async def test_redo_of_read_only_query_stays_read_only(self) -> None:
+        """Should redo a read-only query's steps as a read-only query."""
+        from agentsh.security.controller import SecurityController
+
+        registry = ToolRegistry()
+        registry.register_tool(
+            name="shell.run",
+            handler=lambda command: f"Executed: {command}",
+            description="Run a shell command",
+            parameters={"properties": {"command": {"type": "string"}}, "required": ["command"]},
+        )
+        responses = [
+            LLMResponse(
+                content="Looking.",
+                tool_calls=[ToolCall(id="c1", name="shell.run", arguments={"command": "ls"})],
+                stop_reason=StopReason.TOOL_USE,
+            ),
+            LLMResponse(content="Done.", stop_reason=StopReason.END_TURN),
+        ]
+        agent = AgentLoop(
+            MockLLMClient(responses), registry, security_controller=SecurityController()
+        )
+        await agent.invoke("what is here", AgentContext(read_only=True))
+        agent.last_plan[0].arguments["command"] = "touch x"
+
+        result = await agent.redo(AgentContext())
+
+        assert result.step_outcomes == [StepOutcome.CANCELLED]
+        assert "read-only query" in result.response
+
+
+class TestUnderreportedRisk:
+    """Tests for escalating shell steps the AI marked safe."""
+
+    @pytest.fixture
+    def tool_registry(self) -> ToolRegistry:
+        """Create tool registry with a shell.run tool that needs no confirmation."""
+        registry = ToolRegistry()
+        registry.register_tool(
+            name="shell.run",
+            handler=lambda command, is_destructive=False: f"Executed: {command}",
+            description="Run a shell command",
+            parameters={
+                "properties": {
+                    "command": {"type": "string"},
+                    "is_destructive": {"type": "boolean"},
+                },
+                "required": ["command"],
+            },
+        )
+        return registry
+
+    @staticmethod
+    def _run(command: str, **arguments: Any) -> list[LLMResponse]:
+        """Build a plan running one shell command."""
+        call = ToolCall(
+            id="call_1", name="shell.run", arguments={"command": command, **arguments}
+        )
+        return [
+            LLMResponse(content="Running.", tool_calls=[call], stop_reason=StopReason.TOOL_USE),
+            LLMResponse(content="Done.", stop_reason=StopReason.END_TURN),
+        ]
+
+    @pytest.mark.asyncio
+    async def test_rm_rf_marked_safe_escalated(self, tool_registry: ToolRegistry) -> None:
+        """Should flag rm -rf as destructive and confirm it before running."""
+        from agentsh.security.classifier import RiskLevel
+        from agentsh.security.controller import SecurityController, ValidationResult
+
+        plan = self._run("rm -rf build", is_destructive=False)
+        security = SecurityController()
+        # Policy lets the command run; only the escalation asks the user
+        security.validate_and_approve = MagicMock(  # type: ignore[method-assign]
+            return_value=MagicMock(
+                result=ValidationResult.ALLOW, approval=ApprovalKind.AUTO_APPROVED
+            )
+        )
+        security.confirm_tool = MagicMock(  # type: ignore[method-assign]
+            return_value=MagicMock(result=ValidationResult.BLOCKED, reason="Denied by user")
+        )
+        agent = AgentLoop(MockLLMClient(plan), tool_registry, security_controller=security)
+
+        result = await agent.invoke("clean up")
+
+        description, risk_level, _ = security.confirm_tool.call_args[0]
+        assert "rm -rf build" in description
+        assert risk_level == RiskLevel.HIGH
+        assert result.step_outcomes == [StepOutcome.CANCELLED]
+        assert plan[0].tool_calls[0].arguments["is_destructive"] is True
+
+    @pytest.mark.asyncio
+    async def test_step_marked_destructive_confirmed(self, tool_registry: ToolRegistry) -> None:
+        """Should confirm a step the AI marked destructive, whatever its risk."""
+        from agentsh.security.controller import SecurityController, ValidationResult
+
+        security = SecurityController()
+        security.confirm_tool = MagicMock(  # type: ignore[method-assign]
+            return_value=MagicMock(result=ValidationResult.BLOCKED, reason="Denied by user")
+        )
+        agent = AgentLoop(
+            MockLLMClient(self._run("ls -la", is_destructive=True)),
+            tool_registry,
+            security_controller=security,
+        )
+
+        result = await agent.invoke("list")
+
+        security.confirm_tool.assert_called_once()
+        assert result.step_outcomes == [StepOutcome.CANCELLED]
+
+    @pytest.mark.asyncio
+    async def test_safe_command_not_confirmed(self, tool_registry: ToolRegistry) -> None:
+        """Should run a harmless command without asking."""
+        from agentsh.security.controller import SecurityController
+
+        security = SecurityController()
+        security.confirm_tool = MagicMock()  # type: ignore[method-assign]
+        agent = AgentLoop(
+            MockLLMClient(self._run("ls -la")), tool_registry, security_controller=security
+        )
+
+        result = await agent.invoke("list")
+
+        security.confirm_tool.assert_not_called()
+        assert result.step_outcomes == [StepOutcome.SUCCESS]
+
+
+class TestEditedPlan:
+    """Tests for editing the rest of the plan when a step is approved."""
+
+    @pytest.mark.asyncio
+    async def test_edited_plan_replaces_and_removes_steps(self) -> None:
+        """Should run the edited command and skip the steps deleted in the editor."""
+        from agentsh.security.approval import ApprovalFlow
+        from agentsh.security.controller import SecurityController
+
+        ran: list[str] = []
+        registry = ToolRegistry()
+        registry.register_tool(
+            name="shell.run",
+            handler=lambda command: ran.append(command) or command,
+            description="Run a shell command",
+            parameters={"properties": {"command": {"type": "string"}}, "required": ["command"]},
+        )
+        editor = MagicMock(return_value="pip install --user requests\n")
+        flow = ApprovalFlow(
+            use_color=False,
+            input_func=MagicMock(side_effect=["E", "y"]),
+            output_func=MagicMock(),
+            editor_func=editor,
+        )
+        calls = [
+            ToolCall(id="c1", name="shell.run", arguments={"command": "pip install requests"}),
+            ToolCall(id="c2", name="shell.run", arguments={"command": "pip install flask"}),
+        ]
+        llm = MockLLMClient([
+            LLMResponse(content="Installing.", tool_calls=calls, stop_reason=StopReason.TOOL_USE),
+            LLMResponse(content="Done.", stop_reason=StopReason.END_TURN),
         ])
+        agent = AgentLoop(
+            llm, registry, security_controller=SecurityController(approval_flow=flow)
+        )
+
+        result = await agent.invoke("install")
+
+        assert editor.call_args.args[0].endswith("pip install requests\npip install flask\n")
+        assert ran == ["pip install --user requests"]
+        assert result.step_outcomes == [StepOutcome.SUCCESS, StepOutcome.CANCELLED]
+
+
+class TestConfirmAll:
+    """Tests for security.confirm_all (the paranoid preset)."""
+
+    @pytest.mark.asyncio
+    async def test_only_read_only_steps_run_unasked(self) -> None:
+        """Should confirm low-risk commands and tools, but not read-only ones."""
+        from agentsh.security.approval import ApprovalResponse, ApprovalResult
+        from agentsh.security.controller import SecurityController, ValidationResult
+
         registry = ToolRegistry()
-        return StreamingAgentLoop(mock_llm, registry)
+        registry.register_tool(
+            name="shell.run",
+            handler=lambda command: f"Executed: {command}",
+            description="Run a shell command",
+            parameters={"properties": {"command": {"type": "string"}}, "required": ["command"]},
+        )
+        registry.register_tool(
+            name="fs.write",
+            handler=lambda path, content: f"Wrote {path}",
+            description="Write a file",
+            parameters={"properties": {"path": {"type": "string"}}, "required": ["path"]},
+            risk_level=ToolRiskLevel.LOW,
+        )
+        security = SecurityController(confirm_all=True)
+        security.approval_flow.request_approval = MagicMock(  # type: ignore[method-assign]
+            side_effect=lambda request: ApprovalResponse(
+                result=ApprovalResult.DENIED,
+                command=request.command,
+                approver="user",
+                timestamp=datetime.now(),
+            )
+        )
+        security.confirm_tool = MagicMock(  # type: ignore[method-assign]
+            return_value=MagicMock(result=ValidationResult.BLOCKED, reason="Denied by user")
+        )
+        calls = [
+            ToolCall(id="c1", name="shell.run", arguments={"command": "ls -la"}),
+            ToolCall(id="c2", name="shell.run", arguments={"command": "mkdir build"}),
+            ToolCall(id="c3", name="fs.write", arguments={"path": "a.txt", "content": "x"}),
+        ]
+        agent = AgentLoop(
+            MockLLMClient([
+                LLMResponse(content="Plan.", tool_calls=calls, stop_reason=StopReason.TOOL_USE),
+                LLMResponse(content="Done.", stop_reason=StopReason.END_TURN),
+            ]),
+            registry,
+            security_controller=security,
+        )
+
+        result = await agent.invoke("set up")
+
+        assert result.step_outcomes == [
+            StepOutcome.SUCCESS,
+            StepOutcome.CANCELLED,
+            StepOutcome.CANCELLED,
+        ]
+        (request,) = [c.args[0] for c in security.approval_flow.request_approval.call_args_list]
+        assert request.command == "mkdir build"
+        security.confirm_tool.assert_called_once()
+
+
+class TestWorkspaceRoot:
+    """Tests for confirming steps that cd outside security.workspace_root."""
 
     @pytest.mark.asyncio
-    async def test_stream_with_callback(self, streaming_agent: StreamingAgentLoop) -> None:
-        """Should call token callback."""
-        tokens = []
+    async def test_cd_outside_workspace_confirmed(self, tmp_path: Path) -> None:
+        """Should confirm cd /etc with a warning, but run cd ./subdir unasked."""
+        from agentsh.security.controller import SecurityController, ValidationResult
 
-        def on_token(token: str) -> None:
-            tokens.append(token)
+        registry = ToolRegistry()
+        registry.register_tool(
+            name="shell.run",
+            handler=lambda command: f"Executed: {command}",
+            description="Run a shell command",
+            parameters={"properties": {"command": {"type": "string"}}},
+        )
+        security = SecurityController()
+        security.confirm_tool = MagicMock(  # type: ignore[method-assign]
+            return_value=MagicMock(result=ValidationResult.BLOCKED, reason="Denied by user")
+        )
+        agent = AgentLoop(
+            MockLLMClient(
+                [
+                    LLMResponse(
+                        content="Plan.",
+                        tool_calls=[
+                            ToolCall(id="c1", name="shell.run", arguments={"command": "cd /etc"}),
+                            ToolCall(
+                                id="c2", name="shell.run", arguments={"command": "cd ./subdir"}
+                            ),
+                        ],
+                        stop_reason=StopReason.TOOL_USE,
+                    ),
+                    LLMResponse(content="Done.", stop_reason=StopReason.END_TURN),
+                ]
+            ),
+            registry,
+            AgentConfig(workspace_root=str(tmp_path)),
+            security_controller=security,
+        )
 
-        result = await streaming_agent.stream("Test", on_token=on_token)
+        result = await agent.invoke("tidy up", AgentContext(cwd=str(tmp_path)))
 
-        assert len(tokens) > 0
-        assert result.response != ""
+        security.confirm_tool.assert_called_once()
+        assert "cd /etc" in security.confirm_tool.call_args[0][0]
+        (warning,) = security.confirm_tool.call_args.kwargs["reasons"]
+        assert "leaves the workspace" in warning
+        assert result.step_outcomes == [StepOutcome.CANCELLED, StepOutcome.SUCCESS]
 
     @pytest.mark.asyncio
-    async def test_stream_without_callback(self, streaming_agent: StreamingAgentLoop) -> None:
-        """Should work without callback."""
-        result = await streaming_agent.stream("Test")
+    async def test_confirmed_without_security_controller(self, tmp_path: Path) -> None:
+        """Should ask about a subshell cd or a cwd outside the workspace without a controller."""
+        from agentsh.security.approval import ApprovalResult
 
-        assert result.response != ""
-        assert result.total_steps >= 1
+        registry = ToolRegistry()
+        registry.register_tool(
+            name="shell.run",
+            handler=lambda command, cwd=None: f"Executed: {command}",
+            description="Run a shell command",
+            parameters={"properties": {"command": {"type": "string"}}},
+        )
+        flow = MagicMock()
+        flow.request_approval.return_value = MagicMock(result=ApprovalResult.DENIED)
+        calls = [
+            ToolCall(id="c1", name="shell.run", arguments={"command": "(cd /etc && rm -rf *)"}),
+            ToolCall(id="c2", name="shell.run", arguments={"command": "ls", "cwd": "/etc"}),
+            ToolCall(id="c3", name="shell.run", arguments={"command": "ls", "cwd": "src"}),
+        ]
+        agent = AgentLoop(
+            MockLLMClient(
+                [
+                    LLMResponse(content="Plan.", tool_calls=calls, stop_reason=StopReason.TOOL_USE),
+                    LLMResponse(content="Done.", stop_reason=StopReason.END_TURN),
+                ]
+            ),
+            registry,
+            AgentConfig(workspace_root=str(tmp_path)),
+            approval_flow=flow,
+        )
+
+        result = await agent.invoke("tidy up", AgentContext(cwd=str(tmp_path)))
+
+        assert flow.request_approval.call_count == 2
+        (warning,) = flow.request_approval.call_args.args[0].reasons
+        assert "runs in /etc, outside the workspace" in warning
+        assert result.step_outcomes == [
+            StepOutcome.CANCELLED,
+            StepOutcome.CANCELLED,
+            StepOutcome.SUCCESS,
+        ]
+
+
+class TestReadOnlyQuery:
+    """Tests for requests limited to read-only steps (ai ask)."""
+
+    @pytest.fixture
+    def tool_registry(self) -> ToolRegistry:
+        """Create tool registry with shell.run and a file-writing tool."""
+        registry = ToolRegistry()
+        registry.register_tool(
+            name="shell.run",
+            handler=lambda command: f"Executed: {command}",
+            description="Run a shell command",
+            parameters={"properties": {"command": {"type": "string"}}, "required": ["command"]},
+        )
+        registry.register_tool(
+            name="fs.write",
+            handler=lambda path, content: f"Wrote {path}",
+            description="Write a file",
+            parameters={"properties": {"path": {"type": "string"}}, "required": ["path"]},
+            risk_level=ToolRiskLevel.MEDIUM,
+        )
+        return registry
+
+    @staticmethod
+    def _steps(*calls: tuple[str, dict[str, Any]]) -> list[LLMResponse]:
+        """Build a plan of the given tool calls, then a final answer."""
+        tool_calls = [
+            ToolCall(id=f"call_{i}", name=name, arguments=arguments)
+            for i, (name, arguments) in enumerate(calls)
+        ]
+        return [
+            LLMResponse(
+                content="Checking.", tool_calls=tool_calls, stop_reason=StopReason.TOOL_USE
+            ),
+            LLMResponse(content="Done.", stop_reason=StopReason.END_TURN),
+        ]
+
+    @pytest.mark.asyncio
+    async def test_destructive_step_refused_before_confirmation(
+        self, tool_registry: ToolRegistry
+    ) -> None:
+        """Should refuse changing steps without asking the user, and run the rest."""
+        from agentsh.security.controller import SecurityController
+
+        security = SecurityController()
+        security.confirm_tool = MagicMock()  # type: ignore[method-assign]
+        plan = self._steps(
+            ("shell.run", {"command": "df -h"}),
+            ("shell.run", {"command": "rm -rf /var/log/old"}),
+            ("shell.run", {"command": "ps aux | grep nginx"}),
+            ("fs.write", {"path": "notes.txt", "content": "x"}),
+        )
+        agent = AgentLoop(MockLLMClient(plan), tool_registry, security_controller=security)
+
+        result = await agent.invoke("why is the disk full", AgentContext(read_only=True))
+
+        security.confirm_tool.assert_not_called()
+        assert result.step_outcomes == [
+            StepOutcome.SUCCESS,
+            StepOutcome.CANCELLED,
+            StepOutcome.SUCCESS,
+            StepOutcome.CANCELLED,
+        ]
+        assert "read-only query" in result.steps[1].output
+
+    @pytest.mark.asyncio
+    async def test_prompt_asks_for_read_only_commands(self, tool_registry: ToolRegistry) -> None:
+        """Should tell the AI about the restriction only for read-only queries."""
+        llm = RecordingLLMClient()
+        agent = AgentLoop(llm, tool_registry)
+
+        await agent.invoke("why is the disk full", AgentContext(read_only=True))
+        await agent.invoke("free up space")
+
+        assert "Read-Only Query" in llm.received[0][0].content
+        assert "Read-Only Query" not in llm.received[1][0].content
+
+
+class TestPreExecHook:
+    """Tests for the security.pre_exec_hook policy command."""
+
+    @pytest.mark.asyncio
+    async def test_hook_denies_and_rewrites_steps(self, tmp_path: Path) -> None:
+        """Should cancel denied steps and run modified commands in their place."""
+        import sys
+
+        script = tmp_path / "hook.py"
+        script.write_text(
+            "import json, sys\n"
+            "command = json.load(sys.stdin)['command']\n"
+            "if command.startswith('rm '):\n"
+            "    print(json.dumps({'action': 'deny', 'reason': 'no rm'}))\n"
+            "elif command == 'ls':\n"
+            "    print(json.dumps({'action': 'modify', 'command': 'ls -l'}))\n"
+        )
+        registry = ToolRegistry()
+        registry.register_tool(
+            name="shell.run",
+            handler=lambda command: f"Executed: {command}",
+            description="Run a shell command",
+            parameters={"properties": {"command": {"type": "string"}}, "required": ["command"]},
+        )
+        calls = [
+            ToolCall(id="call_1", name="shell.run", arguments={"command": "rm -rf build"}),
+            ToolCall(id="call_2", name="shell.run", arguments={"command": "ls"}),
+        ]
+        agent = AgentLoop(
+            MockLLMClient([
+                LLMResponse(content="Clean.", tool_calls=calls, stop_reason=StopReason.TOOL_USE),
+                LLMResponse(content="Done.", stop_reason=StopReason.END_TURN),
+            ]),
+            registry,
+            config=AgentConfig(pre_exec_hook=f"{sys.executable} {script}"),
+        )
+
+        result = await agent.invoke("clean up")
+
+        assert result.step_outcomes == [StepOutcome.CANCELLED, StepOutcome.SUCCESS]
+        assert result.steps[0].output == "Denied by pre-exec hook: no rm"
+        assert result.steps[1].output == "Executed: ls -l"
+
+    @pytest.mark.asyncio
+    async def test_rewritten_step_checked_in_read_only_query(self, tmp_path: Path) -> None:
+        """Should judge a read-only query's step by the command the hook left."""
+        import sys
+
+        script = tmp_path / "hook.py"
+        script.write_text(
+            "import json\n"
+            "print(json.dumps({'action': 'modify', 'command': 'rm -rf build'}))\n"
+        )
+        registry = ToolRegistry()
+        registry.register_tool(
+            name="shell.run",
+            handler=lambda command: f"Executed: {command}",
+            description="Run a shell command",
+            parameters={"properties": {"command": {"type": "string"}}, "required": ["command"]},
+        )
+        call = ToolCall(id="call_1", name="shell.run", arguments={"command": "ls"})
+        agent = AgentLoop(
+            MockLLMClient([
+                LLMResponse(content="Look.", tool_calls=[call], stop_reason=StopReason.TOOL_USE),
+                LLMResponse(content="Done.", stop_reason=StopReason.END_TURN),
+            ]),
+            registry,
+            config=AgentConfig(pre_exec_hook=f"{sys.executable} {script}"),
+        )
+
+        result = await agent.invoke("what is here", AgentContext(read_only=True))
+
+        assert result.step_outcomes == [StepOutcome.CANCELLED]
+        assert "read-only query" in result.steps[0].output
+
+
+class TestStepTiming:
+    """Tests for start time and duration of executed steps."""
+
+    @pytest.mark.asyncio
+    async def test_sleep_step_duration_audited(self, tmp_path: Path) -> None:
+        """Should log a plausible duration and start time for a slow step."""
+        from datetime import datetime, timezone
+
+        from agentsh.security.audit import AuditAction, AuditLogger
+        from agentsh.security.controller import SecurityController
+
+        registry = ToolRegistry()
+        registry.register_tool(
+            name="shell.run",
+            handler=lambda command: time.sleep(0.1) or ToolResult(success=True, exit_code=0),
+            description="Run a shell command",
+            parameters={"properties": {"command": {"type": "string"}}, "required": ["command"]},
+        )
+        audit = AuditLogger(log_path=tmp_path / "audit.log")
+        call = ToolCall(id="call_1", name="shell.run", arguments={"command": "sleep 0.1"})
+        agent = AgentLoop(
+            MockLLMClient([
+                LLMResponse(content="Waiting.", tool_calls=[call], stop_reason=StopReason.TOOL_USE),
+                LLMResponse(content="Done.", stop_reason=StopReason.END_TURN),
+            ]),
+            registry,
+            security_controller=SecurityController(audit_logger=audit),
+        )
+        before = datetime.now(timezone.utc)
+
+        await agent.invoke("wait a bit")
+
+        event = audit.get_by_action(AuditAction.COMMAND_EXECUTED)[0]
+        assert event.command == "sleep 0.1"
+        assert event.result == "success"
+        assert 100 <= event.metadata["duration_ms"] < 5000
+        assert datetime.fromisoformat(event.metadata["started_at"]) >= before
+
+    @pytest.mark.asyncio
+    async def test_safety_report_audited(self, tmp_path: Path) -> None:
+        """Should log why an approved destructive step was flagged."""
+        from agentsh.security.audit import AuditAction, AuditLogger
+        from agentsh.security.controller import SecurityController, ValidationResult
+
+        registry = ToolRegistry()
+        registry.register_tool(
+            name="shell.run",
+            handler=lambda command, **_: ToolResult(success=True, exit_code=0),
+            description="Run a shell command",
+            parameters={"properties": {"command": {"type": "string"}}, "required": ["command"]},
+        )
+        audit = AuditLogger(log_path=tmp_path / "audit.log")
+        security = SecurityController(audit_logger=audit)
+        security.validate_and_approve = MagicMock(  # type: ignore[method-assign]
+            return_value=MagicMock(result=ValidationResult.ALLOW, approval=ApprovalKind.CONFIRMED)
+        )
+        call = ToolCall(id="call_1", name="shell.run", arguments={"command": "rm -rf build"})
+        agent = AgentLoop(
+            MockLLMClient([
+                LLMResponse(content="Clean.", tool_calls=[call], stop_reason=StopReason.TOOL_USE),
+                LLMResponse(content="Done.", stop_reason=StopReason.END_TURN),
+            ]),
+            registry,
+            security_controller=security,
+        )
+
+        await agent.invoke("clean the build")
+
+        event = audit.get_by_action(AuditAction.COMMAND_EXECUTED)[0]
+        assert "DESTRUCTIVE" in event.metadata["safety_flags"]
+        assert event.metadata["warnings"] == ["Recursive/force delete"]
+
+    @pytest.mark.parametrize(
+        ("requires_confirmation", "expected"),
+        [(False, ApprovalKind.AUTO_APPROVED), (True, ApprovalKind.CONFIRMED)],
+    )
+    @pytest.mark.asyncio
+    async def test_approval_kind_audited(
+        self, tmp_path: Path, requires_confirmation: bool, expected: ApprovalKind
+    ) -> None:
+        """Should log whether a step ran unasked or after the user confirmed it."""
+        from datetime import datetime
+
+        from agentsh.security.approval import ApprovalResponse, ApprovalResult
+        from agentsh.security.audit import AuditAction, AuditLogger
+        from agentsh.security.controller import SecurityController
+
+        registry = ToolRegistry()
+        registry.register_tool(
+            name="shell.run",
+            handler=lambda command, **_: ToolResult(success=True, exit_code=0),
+            description="Run a shell command",
+            parameters={"properties": {"command": {"type": "string"}}, "required": ["command"]},
+            requires_confirmation=requires_confirmation,
+        )
+        approval_flow = MagicMock()
+        approval_flow.request_approval.return_value = ApprovalResponse(
+            result=ApprovalResult.APPROVED,
+            command="ls",
+            approver="alice",
+            timestamp=datetime.now(),
+        )
+        audit = AuditLogger(log_path=tmp_path / "audit.log")
+        security = SecurityController(audit_logger=audit, approval_flow=approval_flow)
+        call = ToolCall(id="call_1", name="shell.run", arguments={"command": "ls"})
+        agent = AgentLoop(
+            MockLLMClient([
+                LLMResponse(content="List.", tool_calls=[call], stop_reason=StopReason.TOOL_USE),
+                LLMResponse(content="Done.", stop_reason=StopReason.END_TURN),
+            ]),
+            registry,
+            security_controller=security,
+        )
+
+        await agent.invoke("list files")
+
+        event = audit.get_by_action(AuditAction.COMMAND_EXECUTED)[0]
+        assert event.metadata["approval"] == expected.value
+
+
+class TestToolCallLimits:
+    """Tests for capping and cleaning tool calls from one response."""
+
+    @pytest.fixture
+    def tool_registry(self) -> ToolRegistry:
+        """Create tool registry with an echo tool."""
+        registry = ToolRegistry()
+        registry.register_tool(
+            name="echo",
+            handler=lambda text: text,
+            description="Echo text",
+            parameters={
+                "properties": {"text": {"type": "string"}},
+                "required": ["text"],
+            },
+        )
+        return registry
+
+    @staticmethod
+    def _flood(count: int) -> list[LLMResponse]:
+        """Build a response with many tool calls followed by a final answer."""
+        return [
+            LLMResponse(
+                content="Running.",
+                tool_calls=[
+                    ToolCall(id=f"call_{i}", name="echo", arguments={"text": str(i)})
+                    for i in range(count)
+                ],
+                stop_reason=StopReason.TOOL_USE,
+            ),
+            LLMResponse(content="Done.", stop_reason=StopReason.END_TURN),
+        ]
+
+    @pytest.mark.asyncio
+    async def test_hundred_steps_truncated(self, tool_registry: ToolRegistry) -> None:
+        """Should run only the first max_tool_calls calls by default."""
+        agent = AgentLoop(
+            MockLLMClient(self._flood(100)),
+            tool_registry,
+            AgentConfig(max_tool_calls=20),
+        )
+
+        result = await agent.invoke("Do everything")
+
+        assert result.success
+        assert len(result.tool_calls_made) == 20
+
+    @pytest.mark.asyncio
+    async def test_hundred_steps_rejected(self, tool_registry: ToolRegistry) -> None:
+        """Should run nothing when configured to reject."""
+        agent = AgentLoop(
+            MockLLMClient(self._flood(100)),
+            tool_registry,
+            AgentConfig(max_tool_calls=20, reject_excess_tool_calls=True),
+        )
+
+        result = await agent.invoke("Do everything")
+
+        assert not result.success
+        assert result.error == "Too many tool calls"
+        assert "100 steps" in result.response
+        assert result.tool_calls_made == []
+        assert result.exit_status == 1
+
+    @pytest.mark.asyncio
+    async def test_duplicate_and_empty_calls_dropped(self, tool_registry: ToolRegistry) -> None:
+        """Should skip unnamed calls and exact repeats."""
+        responses = [
+            LLMResponse(
+                content="Running.",
+                tool_calls=[
+                    ToolCall(id="call_1", name="echo", arguments={"text": "a"}),
+                    ToolCall(id="call_2", name="echo", arguments={"text": "a"}),
+                    ToolCall(id="call_3", name="", arguments={}),
+                    ToolCall(id="call_4", name="echo", arguments={"text": "b"}),
+                ],
+                stop_reason=StopReason.TOOL_USE,
+            ),
+            LLMResponse(content="Done.", stop_reason=StopReason.END_TURN),
+        ]
+        llm = RecordingLLMClient(responses)
+        agent = AgentLoop(llm, tool_registry)
+
+        result = await agent.invoke("Echo")
+
+        assert result.tool_calls_made == ["echo", "echo"]
+        tool_messages = [m for m in llm.received[1] if m.role == MessageRole.TOOL]
+        assert [m.tool_call_id for m in tool_messages] == ["call_1", "call_4"]
+
+
+def _run_step(command: str) -> str:
+    """Echo a command, failing for commands containing "fail"."""
+    if "fail" in command:
+        raise RuntimeError(f"{command}: exit 1")
+    return command
+
+
+def _plan(*commands: str, prefix: str = "call") -> LLMResponse:
+    """Build an LLM response proposing one run step per command."""
+    return LLMResponse(
+        content="Plan.",
+        tool_calls=[
+            ToolCall(id=f"{prefix}_{i}", name="run", arguments={"command": command})
+            for i, command in enumerate(commands)
+        ],
+        stop_reason=StopReason.TOOL_USE,
+    )
+
+
+class TestAlternatives:
+    """Tests for picking one of several plans offered by the AI."""
+
+    @pytest.fixture
+    def tool_registry(self) -> ToolRegistry:
+        """Create tool registry with a shell.run tool recording what ran."""
+        registry = ToolRegistry()
+        registry.register_tool(
+            name="shell.run",
+            handler=lambda command: f"ran {command}",
+            description="Run a shell command",
+            parameters={"properties": {"command": {"type": "string"}}, "required": ["command"]},
+        )
+        return registry
+
+    @staticmethod
+    def _offer() -> list[LLMResponse]:
+        """Build responses offering two alternatives, then finishing."""
+        offer = ToolCall(
+            id="call_1",
+            name="offer_alternatives",
+            arguments={
+                "alternatives": [
+                    {"name": "Quick", "commands": ["make clean"]},
+                    {"name": "Thorough", "commands": ["git clean -xdn", "git clean -xdf"]},
+                ]
+            },
+        )
+        return [
+            LLMResponse(content="Options.", tool_calls=[offer], stop_reason=StopReason.TOOL_USE),
+            LLMResponse(content="Cleaned.", stop_reason=StopReason.END_TURN),
+        ]
+
+    @pytest.mark.asyncio
+    async def test_chosen_alternative_runs(self, tool_registry: ToolRegistry) -> None:
+        """Should run the chosen plan's commands as shell steps."""
+        llm = RecordingLLMClient(self._offer())
+        offered: list[list[str]] = []
+
+        def choose(alternatives: list[Any]) -> int:
+            offered.append([alt.name for alt in alternatives])
+            return 1
+
+        agent = AgentLoop(llm, tool_registry)
+        result = await agent.invoke("clean the repo", on_choose=choose)
+
+        assert offered == [["Quick", "Thorough"]]
+        assert "offer_alternatives" in llm.offered_tools[0]
+        assert result.tool_calls_made == ["offer_alternatives", "shell.run", "shell.run"]
+        assert result.step_outcomes == [StepOutcome.SUCCESS, StepOutcome.SUCCESS]
+        assert [step.arguments["command"] for step in agent.last_plan] == [
+            "git clean -xdn",
+            "git clean -xdf",
+        ]
+        fed_back = llm.received[1][-1].content
+        assert fed_back.startswith("The user chose: Thorough")
+        assert "ran git clean -xdf" in fed_back
+
+    @pytest.mark.asyncio
+    async def test_no_choice_runs_nothing(self, tool_registry: ToolRegistry) -> None:
+        """Should run nothing when the user picks no alternative."""
+        llm = RecordingLLMClient(self._offer())
+        agent = AgentLoop(llm, tool_registry)
+
+        result = await agent.invoke("clean the repo", on_choose=lambda alternatives: None)
+
+        assert result.step_outcomes == []
+        assert "Nothing was run" in llm.received[1][-1].content
+
+    @pytest.mark.asyncio
+    async def test_not_offered_without_picker(self, tool_registry: ToolRegistry) -> None:
+        """Should not offer alternatives when no one can choose."""
+        llm = RecordingLLMClient()
+        agent = AgentLoop(llm, tool_registry)
+
+        await agent.invoke("clean the repo")
+
+        assert "offer_alternatives" not in llm.offered_tools[0]
+        assert "## Alternatives" not in llm.received[0][0].content
+
+
+class TestParallelSteps:
+    """Tests for running independent steps concurrently."""
+
+    @staticmethod
+    def _registry(log: list[str]) -> ToolRegistry:
+        """Create a registry with an async tool that records start and end."""
+
+        async def fetch(name: str) -> str:
+            log.append(f"start {name}")
+            await asyncio.sleep(0.01)
+            log.append(f"end {name}")
+            if name == "broken":
+                raise OSError("not found")
+            return name
+
+        registry = ToolRegistry()
+        registry.register_tool(
+            name="fetch",
+            handler=fetch,
+            description="Fetch a file",
+            parameters={"properties": {"name": {"type": "string"}}, "required": ["name"]},
+        )
+        return registry
+
+    @staticmethod
+    def _plan() -> list[LLMResponse]:
+        """Build a plan of two downloads and a step depending on both."""
+        calls = [
+            ToolCall(id="c1", name="fetch", arguments={"name": "a", "step_id": "a"}),
+            ToolCall(id="c2", name="fetch", arguments={"name": "b", "step_id": "b"}),
+            ToolCall(
+                id="c3", name="fetch", arguments={"name": "c", "depends_on": ["a", "b"]}
+            ),
+        ]
+        return [
+            LLMResponse(content="Fetching.", tool_calls=calls, stop_reason=StopReason.TOOL_USE),
+            LLMResponse(content="Done.", stop_reason=StopReason.END_TURN),
+        ]
+
+    @pytest.mark.asyncio
+    async def test_independent_steps_overlap(self) -> None:
+        """Should start both downloads before either finishes, then the dependent step."""
+        log: list[str] = []
+        llm = RecordingLLMClient(self._plan())
+        agent = AgentLoop(llm, self._registry(log), AgentConfig(parallel=True))
+
+        result = await agent.invoke("fetch files")
+
+        assert result.success
+        assert log[:2] == ["start a", "start b"]
+        assert log[-2:] == ["start c", "end c"]
+        tool_results = [m.content for m in llm.received[1] if m.role == MessageRole.TOOL]
+        assert tool_results == ["a", "b", "c"]
+
+    @pytest.mark.asyncio
+    async def test_sequential_by_default(self) -> None:
+        """Should run one step at a time when parallel is off."""
+        log: list[str] = []
+        agent = AgentLoop(MockLLMClient(self._plan()), self._registry(log))
+
+        await agent.invoke("fetch files")
+
+        assert log[:2] == ["start a", "end a"]
+
+    @pytest.mark.asyncio
+    async def test_steps_confirmed_before_any_starts(self) -> None:
+        """Should check every step of a batch before running them together."""
+        log: list[str] = []
+        agent = AgentLoop(
+            MockLLMClient(self._plan()), self._registry(log), AgentConfig(parallel=True)
+        )
+
+        async def authorize(tool: Any, tool_call: ToolCall, context: Any) -> ApprovalKind:
+            log.append(f"check {tool_call.arguments['name']}")
+            return ApprovalKind.CONFIRMED
+
+        with patch.object(agent, "_authorize_tool", side_effect=authorize):
+            await agent.invoke("fetch files")
+
+        assert log[:4] == ["check a", "check b", "start a", "start b"]
+
+    @pytest.mark.parametrize("action", [FailureAction.ABORT, FailureAction.ADJUST])
+    @pytest.mark.asyncio
+    async def test_stop_records_steps_run_alongside(self, action: FailureAction) -> None:
+        """Should report the steps of the batch that ran with the failed one."""
+        calls = [
+            ToolCall(id="c1", name="fetch", arguments={"name": "broken", "step_id": "a"}),
+            ToolCall(id="c2", name="fetch", arguments={"name": "b", "step_id": "b"}),
+            ToolCall(id="c3", name="fetch", arguments={"name": "c", "depends_on": ["a"]}),
+        ]
+        llm = MockLLMClient([
+            LLMResponse(content="Fetching.", tool_calls=calls, stop_reason=StopReason.TOOL_USE),
+            LLMResponse(content="Gave up.", stop_reason=StopReason.END_TURN),
+        ])
+        agent = AgentLoop(llm, self._registry([]), AgentConfig(parallel=True))
+
+        result = await agent.invoke("fetch files", on_failure=lambda step, output: action)
+
+        assert result.tool_calls_made == ["fetch", "fetch"]
+        assert result.step_outcomes == [StepOutcome.FAILED, StepOutcome.SUCCESS]
+        assert [record.outcome for record in agent._current_steps] == result.step_outcomes
+
+
+class TestReplan:
+    """Tests for adjusting a plan after a failed step."""
+
+    @pytest.fixture
+    def tool_registry(self) -> ToolRegistry:
+        """Create tool registry with a step that can fail."""
+        registry = ToolRegistry()
+        registry.register_tool(
+            name="run",
+            handler=_run_step,
+            description="Run a step",
+            parameters={
+                "properties": {"command": {"type": "string"}},
+                "required": ["command"],
+            },
+        )
+        return registry
+
+    @pytest.mark.asyncio
+    async def test_adjust_requests_revised_plan(self, tool_registry: ToolRegistry) -> None:
+        """Should skip remaining steps and send them back with the failure."""
+        llm = RecordingLLMClient(
+            [
+                _plan("make fail", "make install"),
+                _plan("make clean", prefix="revised"),
+                LLMResponse(content="Done.", stop_reason=StopReason.END_TURN),
+            ]
+        )
+        failures: list[str] = []
+
+        def on_failure(step: str, output: str) -> FailureAction:
+            failures.append(step)
+            return FailureAction.ADJUST
+
+        agent = AgentLoop(llm, tool_registry)
+        result = await agent.invoke("Build", on_failure=on_failure)
+
+        assert result.success
+        assert failures == ["make fail"]
+        assert result.tool_calls_made == ["run", "run"]
+        replan_turn = llm.received[1]
+        skipped = [m for m in replan_turn if m.tool_call_id == "call_1"]
+        assert skipped[0].content == "Not run: the user asked for a revised plan."
+        assert replan_turn[-1].role == MessageRole.USER
+        assert "Failed step: make fail" in replan_turn[-1].content
+        assert "- make install" in replan_turn[-1].content
+
+    @pytest.mark.asyncio
+    async def test_expected_exit_code_not_a_failure(self) -> None:
+        """Should not ask about a step that exited with an expected code."""
+        from agentsh.plugins.builtin.shell import ShellToolset
+
+        registry = ToolRegistry()
+        ShellToolset().register_tools(registry)
+        call = ToolCall(
+            id="call_1",
+            name="shell.run",
+            arguments={"command": "exit 1", "expected_exit_codes": [0, 1]},
+        )
+        agent = AgentLoop(
+            MockLLMClient([
+                LLMResponse(content="Grep.", tool_calls=[call], stop_reason=StopReason.TOOL_USE),
+                LLMResponse(content="No matches.", stop_reason=StopReason.END_TURN),
+            ]),
+            registry,
+        )
+        on_failure = MagicMock(return_value=FailureAction.ABORT)
+
+        result = await agent.invoke("Find TODOs", on_failure=on_failure)
+
+        on_failure.assert_not_called()
+        assert result.success
+        assert result.step_outcomes == [StepOutcome.SUCCESS]
+
+    @pytest.mark.asyncio
+    async def test_abort_stops_plan(self, tool_registry: ToolRegistry) -> None:
+        """Should stop without running the remaining steps."""
+        agent = AgentLoop(MockLLMClient([_plan("make fail", "make install")]), tool_registry)
+
+        result = await agent.invoke("Build", on_failure=lambda step, output: FailureAction.ABORT)
+
+        assert not result.success
+        assert result.error == "Plan stopped"
+        assert "1 remaining step(s) were not run" in result.response
+        assert result.step_outcomes == [StepOutcome.FAILED]
+
+    @pytest.mark.asyncio
+    async def test_abort_logs_partial_steps(
+        self, tool_registry: ToolRegistry, tmp_path: Path
+    ) -> None:
+        """Should keep and audit the steps run before aborting on step 2 of 3."""
+        from agentsh.security.audit import AuditAction, AuditLogger
+        from agentsh.security.controller import SecurityController
+
+        audit = AuditLogger(log_path=tmp_path / "audit.log")
+        agent = AgentLoop(
+            MockLLMClient([_plan("make", "make fail", "make install")]),
+            tool_registry,
+            security_controller=SecurityController(audit_logger=audit),
+        )
+
+        result = await agent.invoke("Build", on_failure=lambda step, output: FailureAction.ABORT)
+
+        assert [(s.step, s.outcome) for s in result.steps] == [
+            ("make", StepOutcome.SUCCESS),
+            ("make fail", StepOutcome.FAILED),
+        ]
+        assert "- make [success]\n- make fail [failed]" in result.response
+        event = audit.get_by_action(AuditAction.PLAN_INCOMPLETE)[0]
+        assert event.command == "Build"
+        assert event.result == "incomplete"
+        assert event.metadata["reason"] == "Plan stopped"
+        assert [s["step"] for s in event.metadata["steps"]] == ["make", "make fail"]
+        assert "make fail: exit 1" in event.metadata["steps"][1]["output"]
+
+    @pytest.mark.asyncio
+    async def test_completed_plan_not_audited_incomplete(
+        self, tool_registry: ToolRegistry, tmp_path: Path
+    ) -> None:
+        """Should not log a plan that ran to the end as incomplete."""
+        from agentsh.security.audit import AuditAction, AuditLogger
+        from agentsh.security.controller import SecurityController
+
+        audit = AuditLogger(log_path=tmp_path / "audit.log")
+        agent = AgentLoop(
+            MockLLMClient([_plan("make"), LLMResponse(content="Built.")]),
+            tool_registry,
+            security_controller=SecurityController(audit_logger=audit),
+        )
+
+        result = await agent.invoke("Build")
+
+        assert [s.step for s in result.steps] == ["make"]
+        assert audit.get_by_action(AuditAction.PLAN_INCOMPLETE) == []
+
+    @pytest.mark.asyncio
+    async def test_turns_recorded_for_export(self, tool_registry: ToolRegistry) -> None:
+        """Should keep each request with its steps and answer for export-chat."""
+        llm = MockLLMClient(
+            [_plan("make"), LLMResponse(content="Built."), LLMResponse(content="Hi.")]
+        )
+        agent = AgentLoop(llm, tool_registry)
+
+        await agent.invoke("Build")
+        await agent.invoke("Hello")
+
+        assert [(t.request, t.response) for t in agent.turns] == [
+            ("Build", "Built."),
+            ("Hello", "Hi."),
+        ]
+        assert [s.step for s in agent.turns[0].steps] == ["make"]
+        assert agent.turns[1].steps == []
+
+    @pytest.mark.asyncio
+    async def test_replans_bounded(self, tool_registry: ToolRegistry) -> None:
+        """Should stop once max_replans revisions have failed too."""
+        llm = MockLLMClient([_plan("try fail", "next")])
+        agent = AgentLoop(llm, tool_registry, AgentConfig(max_replans=2))
+
+        result = await agent.invoke("Build", on_failure=lambda step, output: FailureAction.ADJUST)
+
+        assert not result.success
+        assert "revised 2 time(s)" in result.response
+        assert result.step_outcomes == [StepOutcome.FAILED] * 3
+
+    @pytest.mark.asyncio
+    async def test_continue_without_callback(self, tool_registry: ToolRegistry) -> None:
+        """Should run the remaining steps when nobody is asked."""
+        responses = [
+            _plan("make fail", "make install"),
+            LLMResponse(content="Done.", stop_reason=StopReason.END_TURN),
+        ]
+        agent = AgentLoop(MockLLMClient(responses), tool_registry)
+
+        result = await agent.invoke("Build")
+
+        assert result.step_outcomes == [StepOutcome.FAILED, StepOutcome.SUCCESS]
+
+
+class TestLastExchange:
+    """Tests for retaining the last prompt/response pair."""
+
+    @pytest.mark.asyncio
+    async def test_exchange_recorded(self) -> None:
+        """Should keep the system prompt, request and raw replies."""
+        responses = [
+            LLMResponse(
+                content="Checking.",
+                tool_calls=[ToolCall(id="c1", name="echo", arguments={"text": "hi"})],
+                stop_reason=StopReason.TOOL_USE,
+            ),
+            LLMResponse(content="All done.", stop_reason=StopReason.END_TURN),
+        ]
+        agent = AgentLoop(MockLLMClient(responses), ToolRegistry())
+        assert agent.last_exchange is None
+
+        await agent.invoke("say hi", AgentContext(cwd="/srv/app"))
+
+        exchange = agent.last_exchange
+        assert "Current directory: /srv/app" in exchange.system_prompt
+        assert exchange.request == "say hi"
+        assert exchange.responses == [
+            'Checking.\n[tool call] echo {"text": "hi"}',
+            "All done.",
+        ]
+
+    @pytest.mark.asyncio
+    async def test_recent_output_sent_for_fix(self) -> None:
+        """Should include the terminal output only for fix requests."""
+        context = AgentContext(recent_output="make: *** [build] Error 2")
+
+        for request, included in (("fix the build", True), ("list files", False)):
+            responses = [LLMResponse(content="Done.", stop_reason=StopReason.END_TURN)]
+            agent = AgentLoop(MockLLMClient(responses), ToolRegistry())
+
+            await agent.invoke(request, context)
+
+            prompt = agent.last_exchange.system_prompt
+            assert ("make: *** [build] Error 2" in prompt) is included, request
+
+
+class TestExplain:
+    """Tests for explaining a command without tools or history."""
+
+    @pytest.mark.asyncio
+    async def test_no_tools_and_no_history(self) -> None:
+        """Should send only the explain request, and not remember it."""
+        llm = RecordingLLMClient([LLMResponse(content="Done.", stop_reason=StopReason.END_TURN)])
+        registry = ToolRegistry()
+        registry.register_tool(
+            name="shell.run",
+            handler=lambda command: command,
+            description="Run a shell command",
+            parameters={"properties": {"command": {"type": "string"}}, "required": ["command"]},
+        )
+        agent = AgentLoop(llm, registry)
+        await agent.invoke("list files")
+        history = list(agent.conversation)
+
+        reply = await agent.explain("tar -xzf a.tgz")
+
+        assert reply == "Done."
+        assert llm.offered_tools[-1] == []
+        assert [m.role for m in llm.received[-1]] == [MessageRole.USER]
+        assert "Command: tar -xzf a.tgz" in llm.received[-1][0].content
+        assert agent.conversation == history
+
+
+class TestConversationHistory:
+    """Tests for sending earlier exchanges with a request."""
+
+    @pytest.mark.asyncio
+    async def test_previous_exchange_sent(self) -> None:
+        """Should send the previous request and answer before the new request."""
+        llm = RecordingLLMClient([LLMResponse(content="Done.", stop_reason=StopReason.END_TURN)])
+        agent = AgentLoop(llm, ToolRegistry())
+
+        await agent.invoke("list files")
+        await agent.invoke("now the hidden ones")
+
+        second = llm.received[1]
+        assert [m.role for m in second] == [
+            MessageRole.SYSTEM,
+            MessageRole.USER,
+            MessageRole.ASSISTANT,
+            MessageRole.USER,
+        ]
+        assert second[1].content == "list files"
+        assert second[2].content == "Done."
+
+    @pytest.mark.asyncio
+    async def test_history_window_limits_messages(self) -> None:
+        """Should send no more than history_window earlier messages."""
+        llm = RecordingLLMClient([LLMResponse(content="Done.", stop_reason=StopReason.END_TURN)])
+        agent = AgentLoop(llm, ToolRegistry(), AgentConfig(history_window=2))
+
+        for request in ("one", "two", "three"):
+            await agent.invoke(request)
+
+        last = llm.received[-1]
+        assert [m.content for m in last[1:]] == ["two", "Done.", "three"]
+
+    @pytest.mark.asyncio
+    async def test_step_results_sent_with_follow_up(self) -> None:
+        """Should remember a redacted summary of the steps run with the answer."""
+        registry = ToolRegistry()
+        registry.register_tool(
+            name="run",
+            handler=_run_step,
+            description="Run a step",
+            parameters={"properties": {"command": {"type": "string"}}},
+        )
+        llm = RecordingLLMClient(
+            [
+                _plan("make test", "make fail TOKEN=sk-abcdefghijklmnopqrstuvwx"),
+                LLMResponse(content="Done.", stop_reason=StopReason.END_TURN),
+                LLMResponse(content="Because of make.", stop_reason=StopReason.END_TURN),
+            ]
+        )
+        agent = AgentLoop(llm, registry)
+
+        await agent.invoke("run the tests")
+        await agent.invoke("now why is it failing?")
+
+        answer = llm.received[-1][2].content
+        assert answer.startswith("Done.\n\nResults of the steps run:")
+        assert "- make test [success]" in answer
+        assert "[failed]" in answer
+        assert "sk-abcdefghijklmnopqrstuvwx" not in answer
+
+    @pytest.mark.asyncio
+    async def test_failed_run_remembered(self) -> None:
+        """Should remember the steps of a stopped plan for the follow-up."""
+        registry = ToolRegistry()
+        registry.register_tool(
+            name="run",
+            handler=_run_step,
+            description="Run a step",
+            parameters={"properties": {"command": {"type": "string"}}},
+        )
+        llm = RecordingLLMClient(
+            [
+                _plan("make build", "make fail", "make install"),
+                LLMResponse(content="Because of make.", stop_reason=StopReason.END_TURN),
+            ]
+        )
+        agent = AgentLoop(llm, registry)
+
+        result = await agent.invoke(
+            "build and install", on_failure=lambda step, error: FailureAction.ABORT
+        )
+        await agent.invoke("why is it failing?")
+
+        assert result.error == "Plan stopped"
+        request, answer = llm.received[-1][1:3]
+        assert request.content == "build and install"
+        assert answer.content.startswith("Error: Plan stopped")
+        assert "- make build [success]" in answer.content
+        assert "- make fail [failed]" in answer.content
+
+    @pytest.mark.asyncio
+    async def test_step_results_bounded(self) -> None:
+        """Should not remember step results when history_results_chars is 0."""
+        registry = ToolRegistry()
+        registry.register_tool(
+            name="run", handler=_run_step, description="Run a step", parameters={}
+        )
+        llm = RecordingLLMClient(
+            [_plan("make"), LLMResponse(content="Done.", stop_reason=StopReason.END_TURN)]
+        )
+        agent = AgentLoop(llm, registry, AgentConfig(history_results_chars=0))
+
+        await agent.invoke("build")
+
+        assert agent.conversation[-1].content == "Done."
+
+
+class TestPipedInput:
+    """Tests for sending piped stdin with a request."""
+
+    @pytest.mark.asyncio
+    async def test_piped_input_in_user_message(self) -> None:
+        """Should include piped stdin in the message sent to the LLM."""
+        llm = RecordingLLMClient()
+        agent = AgentLoop(llm, ToolRegistry(), AgentConfig(max_piped_input_chars=100))
+
+        await agent.invoke(
+            "fix",
+            AgentContext(piped_input="error: linker `cc` not found\n"),
+        )
+
+        user_message = llm.received[0][1]
+        assert user_message.role == MessageRole.USER
+        assert user_message.content.startswith("fix\n\nInput piped from stdin:")
+        assert "error: linker `cc` not found" in user_message.content
+
+    @pytest.mark.asyncio
+    async def test_non_interactive_denies_approval(self) -> None:
+        """Should deny steps needing approval without prompting when nobody can answer."""
+        from agentsh.security.approval import ApprovalFlow
+        from agentsh.security.controller import SecurityController
+
+        ran: list[str] = []
+        registry = ToolRegistry()
+        registry.register_tool(
+            name="shell.run",
+            handler=lambda command: ran.append(command) or command,
+            description="Run a shell command",
+            parameters={"properties": {"command": {"type": "string"}}, "required": ["command"]},
+        )
+        answers = MagicMock(return_value="y")
+        flow = ApprovalFlow(use_color=False, input_func=answers, output_func=MagicMock())
+        call = ToolCall(id="c1", name="shell.run", arguments={"command": "pip install requests"})
+        llm = MockLLMClient([
+            LLMResponse(content="Installing.", tool_calls=[call], stop_reason=StopReason.TOOL_USE),
+            LLMResponse(content="Done.", stop_reason=StopReason.END_TURN),
+        ])
+        agent = AgentLoop(
+            llm, registry, security_controller=SecurityController(approval_flow=flow)
+        )
+
+        result = await agent.invoke(
+            "install", AgentContext(piped_input="y\ny\n", interactive=False)
+        )
+
+        answers.assert_not_called()
+        assert ran == []
+        assert result.step_outcomes == [StepOutcome.CANCELLED]
+
+
+class TestPlanOnly:
+    """Tests for requesting a plan without executing it."""
+
+    @pytest.mark.asyncio
+    async def test_plan_does_not_execute(self) -> None:
+        """Should return proposed tool calls without running them."""
+        executed: list[str] = []
+        registry = ToolRegistry()
+        registry.register_tool(
+            name="shell.run",
+            handler=lambda command: executed.append(command) or "ok",
+            description="Run a command",
+            parameters={"properties": {"command": {"type": "string"}}, "required": ["command"]},
+        )
+        response = LLMResponse(
+            content="Plan:",
+            tool_calls=[
+                ToolCall(id="c1", name="shell.run", arguments={"command": "make"}),
+                ToolCall(id="c2", name="shell.run", arguments={"command": "make"}),
+            ],
+            stop_reason=StopReason.TOOL_USE,
+        )
+        agent = AgentLoop(MockLLMClient([response]), registry)
+
+        plan = await agent.plan("build it")
+
+        assert [c.arguments["command"] for c in plan.tool_calls] == ["make"]
+        assert executed == []
+
+
+class FakeClock:
+    """Manually advanced monotonic clock."""
+
+    def __init__(self) -> None:
+        self.now = 100.0
+
+    def __call__(self) -> float:
+        return self.now
+
+
+class TestRequestInterval:
+    """Tests for the minimum interval between AI requests."""
+
+    @pytest.mark.asyncio
+    async def test_rapid_request_delayed(self) -> None:
+        """Should sleep for the rest of the interval before the second request."""
+        clock = FakeClock()
+        agent = AgentLoop(
+            MockLLMClient(),
+            ToolRegistry(),
+            AgentConfig(min_request_interval_ms=500),
+            clock=clock,
+        )
+
+        await agent.invoke("first")
+        clock.now += 0.2
+        assert agent.request_wait() == pytest.approx(0.3)
+
+        with patch("agentsh.agent.agent_loop.asyncio.sleep", new=AsyncMock()) as mock_sleep:
+            result = await agent.invoke("second")
+
+        assert result.success
+        mock_sleep.assert_awaited_once()
+        assert mock_sleep.await_args.args[0] == pytest.approx(0.3)
+
+    @pytest.mark.asyncio
+    async def test_rapid_request_rejected(self) -> None:
+        """Should reject without calling the LLM when configured to."""
+        clock = FakeClock()
+        llm = RecordingLLMClient()
+        agent = AgentLoop(
+            llm,
+            ToolRegistry(),
+            AgentConfig(min_request_interval_ms=1000, reject_rapid_requests=True),
+            clock=clock,
+        )
+
+        await agent.invoke("first")
+        clock.now += 0.25
+        result = await agent.invoke("second")
+
+        assert not result.success
+        assert result.error == "Rate limited"
+        assert "750 ms" in result.response
+        assert len(llm.received) == 1
+
+    @pytest.mark.asyncio
+    async def test_rapid_plan_rejected(self) -> None:
+        """Should not ask the LLM for a plan when a rapid request is rejected."""
+        clock = FakeClock()
+        llm = RecordingLLMClient([LLMResponse(content="Done.", stop_reason=StopReason.END_TURN)])
+        agent = AgentLoop(
+            llm,
+            ToolRegistry(),
+            AgentConfig(min_request_interval_ms=1000, reject_rapid_requests=True),
+            clock=clock,
+        )
+
+        await agent.plan("first")
+        clock.now += 0.25
+        with pytest.raises(LLMError, match="750 ms"):
+            await agent.plan("second")
+
+        assert len(llm.received) == 1
+
+    @pytest.mark.asyncio
+    async def test_request_after_interval_not_delayed(self) -> None:
+        """Should not wait once the interval has elapsed."""
+        clock = FakeClock()
+        agent = AgentLoop(
+            MockLLMClient(),
+            ToolRegistry(),
+            AgentConfig(min_request_interval_ms=500),
+            clock=clock,
+        )
+
+        await agent.invoke("first")
+        clock.now += 0.6
+
+        assert agent.request_wait() == 0.0
+
+    def test_disabled_by_default(self) -> None:
+        """Should never wait with the default configuration."""
+        agent = AgentLoop(MockLLMClient(), ToolRegistry())
+        agent._last_request_at = time.monotonic()
+
+        assert agent.request_wait() == 0.0
+
+
+class TestPlanBudget:
+    """Tests for the wall-clock budget across all steps of a request."""
+
+    @staticmethod
+    def _plan(clock: FakeClock, seconds_per_step: float) -> tuple[MockLLMClient, ToolRegistry]:
+        """Build a five-step plan whose steps each advance the clock."""
+        registry = ToolRegistry()
+        registry.register_tool(
+            name="work",
+            handler=lambda n: setattr(clock, "now", clock.now + seconds_per_step) or n,
+            description="Do some work",
+            parameters={"properties": {"n": {"type": "string"}}, "required": ["n"]},
+        )
+        client = MockLLMClient(
+            [
+                LLMResponse(
+                    content="Working.",
+                    tool_calls=[
+                        ToolCall(id=f"call_{i}", name="work", arguments={"n": str(i)})
+                        for i in range(5)
+                    ],
+                    stop_reason=StopReason.TOOL_USE,
+                ),
+                LLMResponse(content="Done.", stop_reason=StopReason.END_TURN),
+            ]
+        )
+        return client, registry
+
+    @pytest.mark.asyncio
+    async def test_plan_over_budget_aborts(self) -> None:
+        """Should stop before running every step once the budget is spent."""
+        clock = FakeClock()
+        client, registry = self._plan(clock, seconds_per_step=4.0)
+        agent = AgentLoop(client, registry, AgentConfig(total_timeout=10.0), clock=clock)
+
+        result = await agent.invoke("Do five things")
+
+        assert not result.success
+        assert result.error == "Plan timeout"
+        # Steps at t=0, 4 and 8 start within budget; the one at t=12 does not
+        assert result.tool_calls_made == ["work", "work", "work"]
+        assert "10s time budget" in result.response
+
+    @pytest.mark.asyncio
+    async def test_plan_within_budget_completes(self) -> None:
+        """Should run every step when the budget is not exceeded."""
+        clock = FakeClock()
+        client, registry = self._plan(clock, seconds_per_step=1.0)
+        agent = AgentLoop(client, registry, AgentConfig(total_timeout=10.0), clock=clock)
+
+        result = await agent.invoke("Do five things")
+
+        assert result.success
+        assert len(result.tool_calls_made) == 5
+
+    @pytest.mark.asyncio
+    async def test_remaining_budget_reported_between_steps(self) -> None:
+        """Should report the remaining budget after each step."""
+        clock = FakeClock()
+        client, registry = self._plan(clock, seconds_per_step=1.0)
+        agent = AgentLoop(client, registry, AgentConfig(total_timeout=10.0), clock=clock)
+        notes: list[str] = []
+
+        await agent.invoke("Do five things", on_step=notes.append)
+
+        assert notes[0] == "9.0s of 10s plan budget left"
+        assert notes[-1] == "5.0s of 10s plan budget left"
+
+    @pytest.mark.asyncio
+    async def test_no_budget_by_default(self) -> None:
+        """Should not limit or report anything without total_timeout."""
+        clock = FakeClock()
+        client, registry = self._plan(clock, seconds_per_step=1000.0)
+        agent = AgentLoop(client, registry, clock=clock)
+        notes: list[str] = []
+
+        result = await agent.invoke("Do five things", on_step=notes.append)
+
+        assert result.success
+        assert notes == []
+
+
+class TestPlanCost:
+    """Tests for reporting the cost of the request behind a plan."""
+
+    @staticmethod
+    def _run(config: AgentConfig) -> tuple[AgentLoop, list[str]]:
+        """Build an agent whose plan used 1,200 prompt and 80 completion tokens."""
+        log: list[str] = []
+        registry = ToolRegistry()
+        registry.register_tool(
+            name="run",
+            handler=lambda command: log.append(f"ran {command}") or "ok",
+            description="Run a command",
+            parameters={"properties": {"command": {"type": "string"}}, "required": ["command"]},
+        )
+        plan = _plan("ls")
+        plan.input_tokens, plan.output_tokens = 1200, 80
+        llm = MockLLMClient([plan, LLMResponse(content="Done.", input_tokens=1300)])
+        return AgentLoop(llm, registry, config), log
+
+    @pytest.mark.asyncio
+    async def test_cost_reported_before_steps(self) -> None:
+        """Should report the plan's tokens and cost before its steps run."""
+        agent, log = self._run(AgentConfig(show_plan_cost=True, pricing=(3.0, 15.0)))
+
+        await agent.invoke("list files", on_step=log.append)
+
+        assert log == [
+            "Plan used ~1,200 prompt + 80 completion tokens, about $0.0048",
+            "ran ls",
+        ]
+
+    @pytest.mark.asyncio
+    async def test_disabled(self) -> None:
+        """Should report nothing unless show_plan_cost is set."""
+        agent, log = self._run(AgentConfig())
+
+        await agent.invoke("list files", on_step=log.append)
+
+        assert log == ["ran ls"]
+
+    @pytest.mark.asyncio
+    async def test_plan_shown_before_steps(self) -> None:
+        """Should show the plan at plan_verbosity before its steps run."""
+        agent, log = self._run(AgentConfig(plan_verbosity="minimal"))
+
+        await agent.invoke("list files", on_step=log.append)
+
+        assert log == ["Plan:\n  1. ls", "ran ls"]
+
+
+class TestStreamingAgentLoop:
+    """Tests for StreamingAgentLoop class."""
+
+    @pytest.fixture
+    def streaming_agent(self) -> StreamingAgentLoop:
+        """Create streaming agent."""
+        mock_llm = MockLLMClient([
+            LLMResponse(content="This is a streaming response.")
+        ])
+        registry = ToolRegistry()
+        return StreamingAgentLoop(mock_llm, registry)
+
+    @pytest.mark.asyncio
+    async def test_stream_with_callback(self, streaming_agent: StreamingAgentLoop) -> None:
+        """Should call token callback."""
+        tokens = []
+
+        def on_token(token: str) -> None:
+            tokens.append(token)
+
+        result = await streaming_agent.stream("Test", on_token=on_token)
+
+        assert len(tokens) > 0
+        assert result.response != ""
+
+    @pytest.mark.asyncio
+    async def test_stream_without_callback(self, streaming_agent: StreamingAgentLoop) -> None:
+        """Should work without callback."""
+        result = await streaming_agent.stream("Test")
+
+        assert result.response != ""
+        assert result.total_steps >= 1
+
+
+class TruncatedStreamLLMClient(MockLLMClient):
+    """Mock LLM client whose stream drops before the JSON reply ends."""
+
+    def __init__(self, responses: Optional[list[LLMResponse]] = None) -> None:
+        super().__init__(responses)
+        self.invoked = 0
+
+    async def invoke(
+        self,
+        messages: list[Message],
+        tools: Optional[list[ToolDefinition]] = None,
+        temperature: float = 0.0,
+        max_tokens: int = 4096,
+    ) -> LLMResponse:
+        self.invoked += 1
+        return await super().invoke(messages, tools, temperature, max_tokens)
+
+    async def stream(
+        self,
+        messages: list[Message],
+        tools: Optional[list[ToolDefinition]] = None,
+        temperature: float = 0.0,
+        max_tokens: int = 4096,
+    ) -> AsyncIterator[str]:
+        yield '{"summary": "Lists files", '
+        yield '"flags": [{"flag": "-l"'
+
+
+class TestTruncatedStream:
+    """Tests for streamed JSON replies that are cut off."""
+
+    @pytest.mark.asyncio
+    async def test_refetched_without_streaming(self) -> None:
+        """Should request the reply once more without streaming."""
+        complete = '{"summary": "Lists files", "flags": []}'
+        llm = TruncatedStreamLLMClient([LLMResponse(content=complete)])
+        agent = StreamingAgentLoop(llm, ToolRegistry())
+
+        result = await agent.stream("explain ls -l")
+
+        assert llm.invoked == 1
+        assert result.success
+        assert result.response == complete
+
+    @pytest.mark.asyncio
+    async def test_truncated_again_reports_error(self) -> None:
+        """Should fail with a truncation error if the retry is cut off too."""
+        llm = TruncatedStreamLLMClient([LLMResponse(content='{"summary": "Lis')])
+        agent = StreamingAgentLoop(llm, ToolRegistry())
+
+        result = await agent.stream("explain ls -l")
+
+        assert llm.invoked == 1
+        assert not result.success
+        assert result.response == LLMTruncatedError.user_message
+        assert agent.last_exchange.responses[-1].endswith('[{"flag": "-l"')
+
+
+class RecordingLLMClient(MockLLMClient):
+    """Mock LLM client that records the tools offered on each call."""
+
+    def __init__(self, responses: Optional[list[LLMResponse]] = None) -> None:
+        super().__init__(responses)
+        self.offered_tools: list[list[str]] = []
+        self.received: list[list[Message]] = []
+
+    async def invoke(
+        self,
+        messages: list[Message],
+        tools: Optional[list[ToolDefinition]] = None,
+        temperature: float = 0.0,
+        max_tokens: int = 4096,
+    ) -> LLMResponse:
+        self.offered_tools.append([t.name for t in tools or []])
+        self.received.append(list(messages))
+        return await super().invoke(messages, tools, temperature, max_tokens)
+
+
+def _need_info_response(*commands: str, call_id: str = "info_1") -> LLMResponse:
+    """Build an LLM response requesting need_info probes."""
+    return LLMResponse(
+        content="Let me look first.",
+        tool_calls=[
+            ToolCall(id=call_id, name=NEED_INFO_TOOL, arguments={"commands": list(commands)}),
+        ],
+        stop_reason=StopReason.TOOL_USE,
+    )
+
+
+class TestNeedInfo:
+    """Tests for the need_info read-only probe action."""
+
+    @pytest.mark.asyncio
+    async def test_need_info_output_fed_back(self) -> None:
+        """Should run read-only probes and re-query with their output."""
+        mock_llm = RecordingLLMClient(
+            [
+                _need_info_response("ls", "cat setup.cfg"),
+                LLMResponse(content="Here is the plan.", stop_reason=StopReason.END_TURN),
+            ]
+        )
+        agent = AgentLoop(mock_llm, ToolRegistry())
+
+        with patch.object(
+            agent, "_run_probe", new_callable=AsyncMock, return_value="probe output"
+        ) as mock_probe:
+            result = await agent.invoke("Set up the project")
+
+        assert result.response == "Here is the plan."
+        assert mock_probe.call_count == 2
+        tool_message = mock_llm.received[1][-1]
+        assert tool_message.name == NEED_INFO_TOOL
+        assert "$ ls\nprobe output" in tool_message.content
+        assert "$ cat setup.cfg\nprobe output" in tool_message.content
+        # Probes are context gathering, not plan steps
+        assert result.step_outcomes == []
+
+    @pytest.mark.asyncio
+    async def test_need_info_refuses_non_read_only(self) -> None:
+        """Should refuse probes that fail the read-only check."""
+        mock_llm = RecordingLLMClient(
+            [
+                _need_info_response("rm -rf build"),
+                LLMResponse(content="Done.", stop_reason=StopReason.END_TURN),
+            ]
+        )
+        agent = AgentLoop(mock_llm, ToolRegistry())
+
+        with patch.object(agent, "_run_probe", new_callable=AsyncMock) as mock_probe:
+            await agent.invoke("Clean up")
+
+        mock_probe.assert_not_called()
+        assert "Refused" in mock_llm.received[1][-1].content
+
+    @pytest.mark.asyncio
+    async def test_need_info_rounds_bounded(self) -> None:
+        """Should stop offering and running probes after max_info_rounds."""
+        responses = [_need_info_response("ls", call_id=f"info_{i}") for i in range(3)]
+        responses.append(LLMResponse(content="Final.", stop_reason=StopReason.END_TURN))
+        mock_llm = RecordingLLMClient(responses)
+        agent = AgentLoop(mock_llm, ToolRegistry(), AgentConfig(max_info_rounds=2))
+
+        with patch.object(
+            agent, "_run_probe", new_callable=AsyncMock, return_value="out"
+        ) as mock_probe:
+            result = await agent.invoke("Investigate")
+
+        assert result.response == "Final."
+        assert mock_probe.call_count == 2
+        assert [NEED_INFO_TOOL in tools for tools in mock_llm.offered_tools] == [
+            True,
+            True,
+            False,
+            False,
+        ]
+        assert "limit reached" in mock_llm.received[3][-1].content
+
+    @pytest.mark.asyncio
+    async def test_need_info_disabled(self) -> None:
+        """Should not offer need_info when max_info_rounds is 0."""
+        mock_llm = RecordingLLMClient()
+        agent = AgentLoop(mock_llm, ToolRegistry(), AgentConfig(max_info_rounds=0))
+
+        await agent.invoke("Hello")
+
+        assert mock_llm.offered_tools == [[]]
+
+    @pytest.mark.asyncio
+    async def test_run_probe_captures_output(self) -> None:
+        """Should capture probe stdout."""
+        agent = AgentLoop(MockLLMClient(), ToolRegistry())
+
+        output = await agent._run_probe("echo probe-ok", AgentContext())
+
+        assert output == "probe-ok"
 
 
 class TestAgentContext:
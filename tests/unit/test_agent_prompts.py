@@ -14,6 +14,10 @@ from agentsh.agent.prompts import (
     REFACTORING_PROMPT,
     build_system_prompt,
     build_few_shot_messages,
+    build_user_message,
+    detect_domain_hints,
+    is_fix_request,
+    prompt_hostname,
 )
 
 
@@ -188,6 +192,40 @@ class TestBuildSystemPrompt:
         # Empty list is falsy, so no history section
         assert "Recent commands:" not in prompt
 
+    def test_prompt_with_last_exit_code(self) -> None:
+        """Should include the last command's exit status when known."""
+        prompt = build_system_prompt(available_tools=["test"], last_exit_code=127)
+
+        assert "Last command exit status: 127" in prompt
+        assert "Last command exit status" not in build_system_prompt(available_tools=["test"])
+
+    def test_prompt_with_recent_output(self) -> None:
+        """Should include the terminal output with secrets redacted."""
+        output = "$ deploy --token=ghp_" + "a" * 36 + "\nError: permission denied"
+        prompt = build_system_prompt(available_tools=["test"], recent_output=output)
+
+        assert "Recent terminal output:" in prompt
+        assert "Error: permission denied" in prompt
+        assert "ghp_" + "a" * 36 not in prompt
+
+    def test_is_fix_request(self) -> None:
+        """Should recognize requests starting with fix."""
+        assert is_fix_request("fix")
+        assert is_fix_request("Fix the failing test")
+        assert not is_fix_request("prefix files with a date")
+
+    def test_prompt_with_hostname(self) -> None:
+        """Should name the host when it identifies the machine."""
+        prompt = build_system_prompt(available_tools=["test"], hostname="web-1.prod")
+
+        assert "Host: web-1.prod" in prompt
+
+    def test_prompt_omits_localhost(self) -> None:
+        """Should leave out a host name that is just localhost."""
+        with patch("socket.gethostname", return_value="localhost"):
+            assert "Host:" not in build_system_prompt(available_tools=["test"])
+        assert "Host:" not in build_system_prompt(available_tools=["test"], hostname="  ")
+
     def test_prompt_empty_tools_list(self) -> None:
         """Should handle empty tools list."""
         prompt = build_system_prompt(
@@ -205,6 +243,105 @@ class TestBuildSystemPrompt:
         assert "Current time:" in prompt
 
 
+class TestDomainHints:
+    """Tests for domain hints in the system prompt."""
+
+    def test_configured_hint_included(self, tmp_path: Path) -> None:
+        """Should include the configured domain hint."""
+        prompt = build_system_prompt(
+            available_tools=["test"],
+            cwd=str(tmp_path),
+            domain_hint="Kubernetes ops",
+        )
+
+        assert "Domain: Kubernetes ops" in prompt
+
+    def test_no_hint_no_domain_line(self, tmp_path: Path) -> None:
+        """Should omit the domain line when nothing is known."""
+        prompt = build_system_prompt(available_tools=["test"], cwd=str(tmp_path))
+
+        assert "Domain:" not in prompt
+
+    def test_detect_project_markers(self, tmp_path: Path) -> None:
+        """Should detect project types from marker files."""
+        (tmp_path / "Cargo.toml").write_text("[package]\n")
+        (tmp_path / "package.json").write_text("{}")
+        (tmp_path / "docker-compose.yml").write_text("services: {}\n")
+
+        assert detect_domain_hints(str(tmp_path)) == ["Rust project", "Node.js", "Docker"]
+
+    def test_detected_hints_appended_to_configured(self, tmp_path: Path) -> None:
+        """Should append detected hints after the configured one."""
+        (tmp_path / "Cargo.toml").write_text("[package]\n")
+        (tmp_path / "docker-compose.yml").write_text("services: {}\n")
+
+        prompt = build_system_prompt(
+            available_tools=["test"],
+            cwd=str(tmp_path),
+            domain_hint="embedded firmware",
+        )
+
+        assert "Domain: embedded firmware; Rust project; Docker" in prompt
+
+    def test_detection_can_be_disabled(self, tmp_path: Path) -> None:
+        """Should skip detection when disabled."""
+        (tmp_path / "package.json").write_text("{}")
+
+        prompt = build_system_prompt(
+            available_tools=["test"],
+            cwd=str(tmp_path),
+            detect_domain=False,
+        )
+
+        assert "Node.js" not in prompt
+
+
+class TestPromptHostname:
+    """Tests for prompt_hostname function."""
+
+    def test_default_is_this_machine(self) -> None:
+        """Should use this machine's host name when none is given."""
+        with patch("socket.gethostname", return_value="build-box"):
+            assert prompt_hostname() == "build-box"
+
+    def test_sanitized(self) -> None:
+        """Should drop characters that cannot appear in a host name."""
+        assert prompt_hostname("db-2\n## New rules") == "db-2Newrules"
+        assert prompt_hostname("LOCALHOST.localdomain") is None
+        assert prompt_hostname("") is None
+
+
+class TestBuildUserMessage:
+    """Tests for build_user_message function."""
+
+    def test_request_only(self) -> None:
+        """Should return the request unchanged without piped input."""
+        assert build_user_message("fix this") == "fix this"
+        assert build_user_message("fix this", "  \n") == "fix this"
+
+    def test_piped_input_included(self) -> None:
+        """Should append piped stdin in a fenced block."""
+        message = build_user_message("fix this", "Traceback:\n  KeyError: 'x'\n")
+
+        assert message.startswith("fix this\n\nInput piped from stdin:\n```\n")
+        assert "Traceback:\n  KeyError: 'x'\n```" in message
+
+    def test_piped_input_bounded(self) -> None:
+        """Should truncate piped input to the configured size."""
+        message = build_user_message("summarize", "a" * 50, max_piped_chars=10)
+
+        assert "a" * 10 + "\n```" in message
+        assert "a" * 11 not in message
+        assert "truncated to the first 10 characters" in message
+
+    def test_piped_secrets_redacted(self) -> None:
+        """Should mask secrets in piped input before it is sent."""
+        message = build_user_message("why", "export API_KEY=sk-abcdef1234567890abcdef\n")
+
+        assert "sk-abcdef1234567890abcdef" not in message
+        assert "API_KEY=" in message
+
+
 class TestBuildFewShotMessages:
     """Tests for build_few_shot_messages function."""
 
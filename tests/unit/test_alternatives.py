@@ -0,0 +1,101 @@
+"""Tests for alternative plans offered by the AI."""
+
+from agentsh.agent.alternatives import (
+    ALTERNATIVES_TOOL,
+    Alternative,
+    alternative_steps,
+    format_alternatives,
+    parse_alternatives,
+    prompt_alternative,
+)
+from agentsh.agent.llm_client import ToolCall
+
+
+def _offer(*entries: object) -> ToolCall:
+    """Build an offer_alternatives call with the given entries."""
+    return ToolCall(id="call_1", name=ALTERNATIVES_TOOL, arguments={"alternatives": list(entries)})
+
+
+class TestParseAlternatives:
+    """Tests for parse_alternatives."""
+
+    def test_parses_entries(self) -> None:
+        """Should read name, description and commands of each entry."""
+        call = _offer(
+            {"name": "Quick", "description": "Delete caches", "commands": ["rm -rf .cache"]},
+            {"name": "Thorough", "commands": ["git clean -xdn", "git clean -xdf"]},
+        )
+
+        alternatives = parse_alternatives(call)
+
+        assert alternatives == [
+            Alternative(name="Quick", description="Delete caches", commands=["rm -rf .cache"]),
+            Alternative(name="Thorough", commands=["git clean -xdn", "git clean -xdf"]),
+        ]
+
+    def test_skips_malformed(self) -> None:
+        """Should skip entries without a name or commands, and accept one command string."""
+        call = _offer(
+            "not a dict",
+            {"name": "", "commands": ["ls"]},
+            {"name": "Empty", "commands": [" "]},
+            {"name": "Single", "commands": "make clean"},
+        )
+
+        assert [alt.commands for alt in parse_alternatives(call)] == [["make clean"]]
+
+    def test_missing_alternatives(self) -> None:
+        """Should return nothing when the argument is missing."""
+        call = ToolCall(id="call_1", name=ALTERNATIVES_TOOL, arguments={})
+        assert parse_alternatives(call) == []
+
+
+class TestAlternativeSteps:
+    """Tests for turning the chosen alternative into steps."""
+
+    def test_one_shell_step_per_command(self) -> None:
+        """Should create a shell.run call for each command with a unique id."""
+        chosen = Alternative(name="Build", commands=["make", "make install"])
+
+        steps = alternative_steps(_offer(), chosen)
+
+        assert [(s.id, s.name, s.arguments) for s in steps] == [
+            ("call_1-1", "shell.run", {"command": "make"}),
+            ("call_1-2", "shell.run", {"command": "make install"}),
+        ]
+
+
+class TestPromptAlternative:
+    """Tests for the numbered picker."""
+
+    ALTERNATIVES = [
+        Alternative(name="Quick", commands=["a"]),
+        Alternative(name="Thorough", description="Slower", commands=["b", "c"]),
+    ]
+
+    def test_format(self) -> None:
+        """Should number alternatives and list their commands."""
+        text = format_alternatives(self.ALTERNATIVES)
+
+        assert text.splitlines() == [
+            "1. Quick",
+            "   $ a",
+            "2. Thorough",
+            "   Slower",
+            "   $ b",
+            "   $ c",
+        ]
+
+    def test_choice(self) -> None:
+        """Should return the index of the chosen number, asking again when invalid."""
+        answers = iter(["3", "x", "2"])
+        assert prompt_alternative(self.ALTERNATIVES, lambda _: next(answers)) == 1
+
+    def test_cancel(self) -> None:
+        """Should pick nothing on an empty answer or EOF."""
+
+        def eof(_: str) -> str:
+            raise EOFError
+
+        assert prompt_alternative(self.ALTERNATIVES, lambda _: "") is None
+        assert prompt_alternative(self.ALTERNATIVES, eof) is None
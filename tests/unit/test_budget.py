@@ -0,0 +1,59 @@
+"""Tests for the execution time budget."""
+
+from agentsh.agent.budget import ExecutionBudget
+
+
+class FakeClock:
+    """Manually advanced monotonic clock."""
+
+    def __init__(self) -> None:
+        self.now = 50.0
+
+    def __call__(self) -> float:
+        return self.now
+
+
+class TestExecutionBudget:
+    """Tests for ExecutionBudget."""
+
+    def test_remaining_counts_down(self) -> None:
+        """Should report elapsed and remaining time from the clock."""
+        clock = FakeClock()
+        budget = ExecutionBudget(10.0, clock=clock)
+
+        clock.now += 4.0
+
+        assert budget.elapsed() == 4.0
+        assert budget.remaining() == 6.0
+        assert not budget.exceeded
+
+    def test_exceeded_at_limit(self) -> None:
+        """Should be exceeded once the whole budget is spent."""
+        clock = FakeClock()
+        budget = ExecutionBudget(10.0, clock=clock)
+
+        clock.now += 10.0
+
+        assert budget.exceeded
+        assert budget.remaining() == 0.0
+
+    def test_unlimited(self) -> None:
+        """Should never be exceeded without a limit."""
+        clock = FakeClock()
+        budget = ExecutionBudget(0, clock=clock)
+
+        clock.now += 10_000.0
+
+        assert not budget.limited
+        assert not budget.exceeded
+        assert budget.remaining() is None
+
+    def test_describe(self) -> None:
+        """Should describe the time left."""
+        clock = FakeClock()
+        budget = ExecutionBudget(60, clock=clock)
+
+        clock.now += 17.5
+
+        assert budget.describe() == "42.5s of 60s plan budget left"
+        assert ExecutionBudget(0, clock=clock).describe().endswith("(no plan budget)")
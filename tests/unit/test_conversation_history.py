@@ -0,0 +1,104 @@
+"""Tests for trimming the conversation history sent with a request."""
+
+from agentsh.agent.agent_loop import StepOutcome, StepRecord
+from agentsh.agent.history import (
+    WINDOW_TOKENS,
+    estimate_tokens,
+    summarize_step_results,
+    trim_history,
+)
+from agentsh.agent.llm_client import Message, MessageRole
+
+
+def _conversation(turns: int) -> list[Message]:
+    """Build a history of user/assistant pairs."""
+    messages = []
+    for turn in range(turns):
+        messages.append(Message.user(f"question {turn}"))
+        messages.append(Message.assistant(f"answer {turn}"))
+    return messages
+
+
+class TestTrimHistory:
+    """Tests for trim_history."""
+
+    def test_count_window(self) -> None:
+        """Should keep the newest messages up to the window."""
+        trimmed = trim_history(_conversation(20), 4)
+
+        assert [m.content for m in trimmed] == [
+            "question 18",
+            "answer 18",
+            "question 19",
+            "answer 19",
+        ]
+
+    def test_starts_with_user_message(self) -> None:
+        """Should not send an answer without its question."""
+        trimmed = trim_history(_conversation(20), 3)
+
+        assert [m.content for m in trimmed] == ["question 19", "answer 19"]
+
+    def test_zero_window_disables(self) -> None:
+        """Should send no history when the window is 0."""
+        assert trim_history(_conversation(3), 0) == []
+
+    def test_token_window(self) -> None:
+        """Should keep as many recent messages as fit the token budget."""
+        history = _conversation(10)
+        history[-1] = Message.assistant("x" * 400)
+
+        # The last answer is 101 tokens and each short message 3
+        trimmed = trim_history(history, 110, WINDOW_TOKENS)
+
+        assert [m.content for m in trimmed][:3] == ["question 8", "answer 8", "question 9"]
+        assert len(trimmed) == 4
+
+    def test_token_window_too_small(self) -> None:
+        """Should send nothing when the newest message exceeds the budget."""
+        history = [Message.user("hi"), Message.assistant("y" * 4000)]
+
+        assert trim_history(history, 50, WINDOW_TOKENS, count_tokens=estimate_tokens) == []
+
+    def test_system_messages_excluded(self) -> None:
+        """Should never include system messages."""
+        history = [Message.system("You are helpful"), *_conversation(2)]
+
+        trimmed = trim_history(history, 10)
+
+        assert all(m.role != MessageRole.SYSTEM for m in trimmed)
+        assert len(trimmed) == 4
+
+
+class TestSummarizeStepResults:
+    """Tests for summarize_step_results."""
+
+    def test_lists_steps_with_output_tail(self) -> None:
+        """Should list each step's outcome and the last lines of its output."""
+        output = "\n".join(f"line {n}" for n in range(20))
+        steps = [
+            StepRecord("make test", "run", StepOutcome.FAILED, output),
+            StepRecord("rm -rf /", "run", StepOutcome.CANCELLED),
+        ]
+
+        summary = summarize_step_results(steps, 2000)
+
+        assert summary.splitlines() == [
+            "Results of the steps run:",
+            "- make test [failed]",
+            "    line 15",
+            "    line 16",
+            "    line 17",
+            "    line 18",
+            "    line 19",
+            "- rm -rf / [cancelled]",
+        ]
+
+    def test_bounded_by_size(self) -> None:
+        """Should cut the summary to max_chars and return nothing when disabled."""
+        steps = [StepRecord(f"step {n}", "run", StepOutcome.SUCCESS, "x" * 80) for n in range(50)]
+
+        assert len(summarize_step_results(steps, 300)) <= 300
+        assert summarize_step_results(steps, 300).endswith("...")
+        assert summarize_step_results(steps, 0) == ""
+        assert summarize_step_results([], 2000) == ""
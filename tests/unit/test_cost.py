@@ -0,0 +1,45 @@
+"""Tests for plan token usage and cost estimates."""
+
+from agentsh.agent.cost import estimate_cost, format_usage, parse_usage
+
+
+class TestParseUsage:
+    """Tests for parse_usage."""
+
+    def test_openai_usage(self) -> None:
+        """Should read prompt_tokens and completion_tokens."""
+        usage = {"prompt_tokens": 1200, "completion_tokens": 80, "total_tokens": 1280}
+
+        assert parse_usage(usage) == (1200, 80)
+
+    def test_other_providers(self) -> None:
+        """Should read Anthropic and Gemini usage blocks."""
+        assert parse_usage({"input_tokens": 10, "output_tokens": 2}) == (10, 2)
+        assert parse_usage({"promptTokenCount": 7, "candidatesTokenCount": 3}) == (7, 3)
+
+    def test_missing_or_malformed(self) -> None:
+        """Should count missing or malformed values as 0."""
+        assert parse_usage(None) == (0, 0)
+        assert parse_usage({}) == (0, 0)
+        assert parse_usage({"prompt_tokens": "many", "completion_tokens": 5}) == (0, 5)
+
+
+class TestEstimateCost:
+    """Tests for estimate_cost and format_usage."""
+
+    def test_cost_from_pricing(self) -> None:
+        """Should price tokens per million."""
+        cost = estimate_cost(1200, 80, (3.0, 15.0))
+
+        assert cost is not None
+        assert round(cost, 6) == 0.0048
+
+    def test_no_pricing(self) -> None:
+        """Should not estimate without a price."""
+        assert estimate_cost(1200, 80, None) is None
+
+    def test_format(self) -> None:
+        """Should show tokens, and cost when known."""
+        assert format_usage(1200, 80) == "~1,200 prompt + 80 completion tokens"
+        assert format_usage(1200, 80, 0.0048).endswith(", about $0.0048")
+        assert format_usage(100_000, 5000, 0.375).endswith(", about $0.38")
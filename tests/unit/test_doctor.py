@@ -0,0 +1,92 @@
+"""Tests for the agentsh doctor environment report."""
+
+from pathlib import Path
+
+import pytest
+
+from agentsh import __version__
+from agentsh.config.schemas import AgentSHConfig, LLMConfig, LLMProvider
+from agentsh.telemetry.doctor import build_report, format_report
+
+
+@pytest.fixture
+def config() -> AgentSHConfig:
+    """Create a config using OpenAI."""
+    return AgentSHConfig(
+        llm=LLMConfig(provider=LLMProvider.OPENAI, model="gpt-4o", api_key_env="OPENAI_API_KEY")
+    )
+
+
+class TestBuildReport:
+    """Tests for gathering the report."""
+
+    def test_report_fields(self, config: AgentSHConfig, tmp_path: Path) -> None:
+        """Should report the effective setup without the key itself."""
+        plugins_dir = tmp_path / "plugins"
+        plugins_dir.mkdir()
+        (plugins_dir / "weather.py").write_text("")
+        config_file = tmp_path / "config.yaml"
+
+        report = build_report(
+            config,
+            [config_file],
+            env={"OPENAI_API_KEY": "sk-secret", "SHELL": "/bin/bash"},
+            plugins_dir=plugins_dir,
+        )
+
+        assert report.version == __version__
+        assert report.config_paths == [config_file]
+        assert report.provider == "openai"
+        assert report.model == "gpt-4o"
+        assert report.endpoint == "https://api.openai.com/v1"
+        assert report.api_key_set is True
+        assert report.plugins == ["weather.py"]
+        assert report.login_shell == "/bin/bash"
+        assert report.ai_available
+        assert "sk-secret" not in format_report(report)
+
+    def test_missing_key_makes_ai_unavailable(
+        self, config: AgentSHConfig, tmp_path: Path
+    ) -> None:
+        """Should flag a missing API key."""
+        report = build_report(config, [], env={}, plugins_dir=tmp_path / "none")
+
+        assert report.api_key_set is False
+        assert not report.ai_available
+        assert report.problems == ["API key not set (export OPENAI_API_KEY)"]
+        assert report.plugins == []
+
+    def test_keyless_provider(self, tmp_path: Path) -> None:
+        """Should not require a key for Ollama and honor OLLAMA_HOST."""
+        config = AgentSHConfig(llm=LLMConfig(provider=LLMProvider.OLLAMA, model="llama3.2"))
+
+        report = build_report(
+            config, [], env={"OLLAMA_HOST": "http://gpu:11434"}, plugins_dir=tmp_path
+        )
+
+        assert report.api_key_set is None
+        assert report.endpoint == "http://gpu:11434"
+        assert report.ai_available
+
+    def test_azure_without_endpoint(self, tmp_path: Path) -> None:
+        """Should flag Azure without a configured endpoint."""
+        config = AgentSHConfig(llm=LLMConfig(provider=LLMProvider.AZURE, api_key_env="AZ_KEY"))
+
+        report = build_report(config, [], env={"AZ_KEY": "k"}, plugins_dir=tmp_path)
+
+        assert report.problems == ["llm.azure_endpoint is not set"]
+
+
+class TestFormatReport:
+    """Tests for rendering the report."""
+
+    def test_unavailable_lists_problems(self, config: AgentSHConfig, tmp_path: Path) -> None:
+        """Should show why the AI is unavailable."""
+        report = build_report(config, [], env={}, plugins_dir=tmp_path / "none")
+
+        text = format_report(report)
+
+        assert "API key:      missing ($OPENAI_API_KEY)" in text
+        assert "(none, using defaults)" in text
+        assert "(missing)" in text
+        assert "AI:           unavailable\n  - API key not set" in text
@@ -0,0 +1,91 @@
+"""Tests for command side-effect previews."""
+
+from pathlib import Path
+
+import pytest
+
+from agentsh.security.effects import preview_effects
+
+
+@pytest.fixture
+def workdir(tmp_path: Path) -> Path:
+    """Create a directory with a few files."""
+    for name in ("file1", "file2", "a.log", "b.log", "c.log"):
+        (tmp_path / name).write_text("x")
+    (tmp_path / "build").mkdir()
+    (tmp_path / "build" / "out.o").write_text("x")
+    return tmp_path
+
+
+class TestPreviewRm:
+    """Tests for rm previews."""
+
+    def test_rm_two_files(self, workdir: Path) -> None:
+        """Should count explicit operands."""
+        assert preview_effects("rm file1 file2", cwd=str(workdir)) == ["will delete 2 paths"]
+
+    def test_rm_glob_counts_matches(self, workdir: Path) -> None:
+        """Should expand globs against the filesystem."""
+        assert preview_effects("rm -f *.log", cwd=str(workdir)) == ["will delete 3 paths"]
+        assert (workdir / "a.log").exists()
+
+    def test_rm_recursive_directory(self, workdir: Path) -> None:
+        """Should call out recursive directory deletion."""
+        effects = preview_effects("rm -rf build", cwd=str(workdir))
+        assert effects == [
+            "will delete 1 path",
+            "will recursively delete the contents of 1 directory",
+        ]
+
+    def test_rm_missing_paths(self, workdir: Path) -> None:
+        """Should not describe deletes of paths that do not exist."""
+        assert preview_effects("rm nothing-here", cwd=str(workdir)) == []
+        assert preview_effects("rm *.tmp", cwd=str(workdir)) == []
+
+    def test_sudo_prefix(self, workdir: Path) -> None:
+        """Should see through sudo."""
+        assert preview_effects("sudo rm file1", cwd=str(workdir)) == ["will delete 1 path"]
+
+
+class TestPreviewOtherCommands:
+    """Tests for mv, cp and chmod previews."""
+
+    def test_mv_overwrite(self, workdir: Path) -> None:
+        """Should warn when a single move overwrites a file."""
+        assert preview_effects("mv file1 file2", cwd=str(workdir)) == [
+            "will move 1 path to file2",
+            "will overwrite file2",
+        ]
+
+    def test_cp_glob(self, workdir: Path) -> None:
+        """Should count copied paths."""
+        assert preview_effects("cp *.log build/", cwd=str(workdir)) == [
+            "will copy 3 paths to build/"
+        ]
+
+    def test_chmod_recursive(self, workdir: Path) -> None:
+        """Should describe the new mode."""
+        assert preview_effects("chmod -R 755 build", cwd=str(workdir)) == [
+            "will change permissions of 1 path to 755 recursively"
+        ]
+
+
+class TestPreviewConservative:
+    """Tests for commands that are left undescribed."""
+
+    @pytest.mark.parametrize(
+        "command",
+        [
+            "rm $(cat list.txt)",
+            "rm file1 && rm file2",
+            "find . -name '*.log' | xargs rm",
+            "rm $TARGET",
+            "rm '*.log'",
+            "echo hi > file1",
+            "ls -la",
+            "rm 'unterminated",
+        ],
+    )
+    def test_unpredictable_commands(self, workdir: Path, command: str) -> None:
+        """Should return no effects rather than guess."""
+        assert preview_effects(command, cwd=str(workdir)) == []
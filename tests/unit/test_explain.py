@@ -0,0 +1,92 @@
+"""Tests for structured command explanations."""
+
+from agentsh.agent.explain import (
+    Explanation,
+    build_explain_request,
+    format_explanation,
+    parse_explanation,
+)
+
+
+class TestParseExplanation:
+    """Tests for parsing the model's explanation."""
+
+    def test_structured_reply(self) -> None:
+        """Should read the summary, flags and risk."""
+        explanation = parse_explanation(
+            '{"summary": "Deletes the build directory", '
+            '"flags": [{"flag": "-r", "meaning": "recurse"}, '
+            '{"flag": "-f", "meaning": "no prompts"}], '
+            '"risk": "Removes files permanently"}'
+        )
+
+        assert explanation.structured
+        assert explanation.summary == "Deletes the build directory"
+        assert explanation.flags == [("-r", "recurse"), ("-f", "no prompts")]
+        assert explanation.risk == "Removes files permanently"
+
+    def test_fenced_json(self) -> None:
+        """Should find the JSON inside a code fence with surrounding text."""
+        reply = (
+            'Here you go:\n```json\n{"summary": "Lists files", "flags": [], "risk": "none"}\n```'
+        )
+
+        explanation = parse_explanation(reply)
+
+        assert explanation.structured
+        assert explanation.summary == "Lists files"
+        assert explanation.risk is None
+
+    def test_prose_falls_back(self) -> None:
+        """Should keep a prose reply as the summary."""
+        explanation = parse_explanation("  `ls -la` lists all files in long format.\n")
+
+        assert not explanation.structured
+        assert explanation.summary == "`ls -la` lists all files in long format."
+
+    def test_wrong_shape_falls_back(self) -> None:
+        """Should fall back when the JSON has the wrong shape."""
+        for reply in (
+            '{"summary": "x", "flags": "-l"}',
+            '{"flags": []}',
+            '{"summary": "x", "flags": [{"flag": "-l"}]}',
+            '{"summary": "x", broken',
+        ):
+            explanation = parse_explanation(reply)
+            assert not explanation.structured
+            assert explanation.summary == reply
+
+
+class TestFormatExplanation:
+    """Tests for rendering explanations."""
+
+    def test_sections(self) -> None:
+        """Should render each part as its own section with aligned flags."""
+        explanation = Explanation(
+            summary="Extracts an archive",
+            flags=[("-x", "extract"), ("--gzip", "decompress with gzip")],
+            risk=None,
+            structured=True,
+        )
+
+        assert format_explanation(explanation, use_color=False) == (
+            "What it does\n"
+            "  Extracts an archive\n"
+            "\n"
+            "Flags\n"
+            "  -x      extract\n"
+            "  --gzip  decompress with gzip\n"
+            "\n"
+            "Risk\n"
+            "  None noted"
+        )
+
+    def test_plain_summary(self) -> None:
+        """Should show an unstructured reply as-is."""
+        assert format_explanation(Explanation(summary="It lists files.")) == "It lists files."
+
+    def test_request_names_command(self) -> None:
+        """Should ask for JSON about the given command."""
+        request = build_explain_request("du -sh *")
+        assert "Command: du -sh *" in request
+        assert '"summary"' in request
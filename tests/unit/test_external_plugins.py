@@ -0,0 +1,144 @@
+"""Tests for external plugins run as separate programs."""
+
+import json
+import sys
+from pathlib import Path
+from unittest.mock import patch
+
+import pytest
+
+from agentsh.plugins.external import (
+    MANIFEST_FILE,
+    PluginExecutor,
+    PluginManifest,
+    PluginProtocol,
+    decode_response,
+    encode_request,
+)
+from agentsh.plugins.loader import discover_directory_plugins
+from agentsh.tools.registry import ToolRegistry
+
+# Plugin answering JSON-RPC requests: echoes params, fails on method "fail"
+JSONRPC_PLUGIN = """
+import json, sys
+request = json.loads(sys.stdin.readline())
+if request["method"] == "fail":
+    reply = {"code": -32000, "message": "no such city"}
+    print(json.dumps({"jsonrpc": "2.0", "id": request["id"], "error": reply}))
+else:
+    result = {"output": "echo " + request["params"]["text"]}
+    print(json.dumps({"jsonrpc": "2.0", "id": request["id"], "result": result}))
+"""
+
+
+class TestJsonRpcFraming:
+    """Tests for encoding and decoding plugin messages."""
+
+    def test_encode_request(self) -> None:
+        """Test that JSON-RPC requests carry the tool as method."""
+        request = encode_request(PluginProtocol.JSONRPC, "7", "forecast", {"city": "Oslo"})
+
+        assert request == {
+            "jsonrpc": "2.0",
+            "id": "7",
+            "method": "forecast",
+            "params": {"city": "Oslo"},
+        }
+        assert encode_request(PluginProtocol.AGENTSH, "7", "forecast", {}) == {
+            "id": "7",
+            "tool": "forecast",
+            "params": {},
+        }
+
+    def test_decode_result(self) -> None:
+        """Test that a result becomes the tool output."""
+        response = {"jsonrpc": "2.0", "id": "7", "result": {"output": "Sunny"}}
+
+        result = decode_response(PluginProtocol.JSONRPC, "7", response)
+
+        assert result.success
+        assert result.output == "Sunny"
+
+    def test_decode_error(self) -> None:
+        """Test that a JSON-RPC error object becomes a failed result."""
+        response = {
+            "jsonrpc": "2.0",
+            "id": "7",
+            "error": {"code": -32602, "message": "Invalid params", "data": "city"},
+        }
+
+        result = decode_response(PluginProtocol.JSONRPC, "7", response)
+
+        assert not result.success
+        assert result.error == "Plugin error -32602: Invalid params"
+        assert result.metadata == {"data": "city"}
+
+    def test_decode_rejects_mismatched_reply(self) -> None:
+        """Test that replies for another request or without a version are rejected."""
+        other = decode_response(PluginProtocol.JSONRPC, "7", {"jsonrpc": "2.0", "id": "8"})
+        unversioned = decode_response(PluginProtocol.JSONRPC, "7", {"id": "7", "result": 1})
+
+        assert "request id" in other.error
+        assert "JSON-RPC 2.0" in unversioned.error
+
+
+class TestExternalPlugin:
+    """Tests for running and discovering external plugins."""
+
+    @pytest.fixture
+    def plugin_dir(self, tmp_path: Path) -> Path:
+        """Create a JSON-RPC plugin in a plugins directory."""
+        directory = tmp_path / "plugins" / "echo"
+        directory.mkdir(parents=True)
+        (directory / "plugin.py").write_text(JSONRPC_PLUGIN)
+        manifest = {
+            "name": "echo",
+            "command": [sys.executable, "plugin.py"],
+            "protocol": "jsonrpc",
+            "tools": [
+                {"name": "say", "description": "Echo text", "risk_level": "safe"},
+                {"name": "fail"},
+            ],
+        }
+        (directory / MANIFEST_FILE).write_text(json.dumps(manifest))
+        return directory
+
+    def test_executor_round_trip(self, plugin_dir: Path) -> None:
+        """Test a call and an error through a real plugin process."""
+        manifest = PluginManifest.load(plugin_dir / MANIFEST_FILE)
+        executor = PluginExecutor(manifest.command, manifest.protocol, cwd=plugin_dir)
+
+        assert executor.call("say", {"text": "hi"}).output == "echo hi"
+        assert executor.call("fail", {}).error == "Plugin error -32000: no such city"
+
+    def test_discovered_tools_registered(self, plugin_dir: Path) -> None:
+        """Test that a manifest directory is discovered as a toolset."""
+        (toolset_class,) = discover_directory_plugins(plugin_dir.parent)
+        registry = ToolRegistry()
+
+        toolset_class().register_tools(registry)
+
+        assert registry.get_tool("echo.say").handler(text="hi").output == "echo hi"
+        assert registry.get_tool("echo.fail") is not None
+
+    def test_arguments_cannot_pick_the_tool(self, plugin_dir: Path) -> None:
+        """Test that a _tool argument is passed on, not used as the tool name."""
+        (toolset_class,) = discover_directory_plugins(plugin_dir.parent)
+        toolset = toolset_class()
+        registry = ToolRegistry()
+        toolset.register_tools(registry)
+
+        with patch.object(toolset.executor, "call") as call:
+            registry.get_tool("echo.say").handler(text="hi", _tool="fail")
+
+        call.assert_called_once_with("say", {"text": "hi", "_tool": "fail"})
+
+    def test_manifest_defaults_to_agentsh_framing(self, tmp_path: Path) -> None:
+        """Test that the existing framing stays the default."""
+        path = tmp_path / MANIFEST_FILE
+        path.write_text(json.dumps({"name": "x", "command": "./x --serve"}))
+
+        manifest = PluginManifest.load(path)
+
+        assert manifest.protocol == PluginProtocol.AGENTSH
+        assert manifest.command == ["./x", "--serve"]
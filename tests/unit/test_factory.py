@@ -1,6 +1,8 @@
 """Tests for agent factory module."""
 
 import asyncio
+import ssl
+from pathlib import Path
 from unittest.mock import AsyncMock, MagicMock, patch
 
 import pytest
@@ -12,27 +14,41 @@ from agentsh.agent.factory import (
     create_async_workflow_handler,
     create_llm_client,
     create_memory_manager,
+    create_security_controller,
     create_workflow_executor,
     create_workflow_handler,
 )
+from agentsh.agent.resilient import FailoverLLMClient
 from agentsh.config.schemas import (
     AgentSHConfig,
     LLMConfig,
     LLMProvider,
+    ModelPricing,
     ShellConfig,
 )
 
 
 @pytest.fixture
 def mock_llm_config() -> MagicMock:
-    """Create mock LLM config with api_key attribute."""
+    """Create mock LLM config reading its key from TEST_LLM_KEY."""
     config = MagicMock()
     config.provider = LLMProvider.ANTHROPIC
-    config.api_key = "test-key"
+    config.fallback = None
+    config.api_key_env = "TEST_LLM_KEY"
     config.model = "claude-3-sonnet"
     config.temperature = 0.7
     config.max_tokens = 4096
-    config.timeout = 60
+    config.timeout_seconds = 60
+    config.max_info_rounds = 3
+    config.max_steps = 20
+    config.reject_excess_steps = False
+    config.domain_hint = None
+    config.max_context_size = 20000
+    config.min_request_interval_ms = 0
+    config.reject_rapid_requests = False
+    config.total_timeout_seconds = 0
+    config.socks_proxy = None
+    config.ca_cert_path = None
     return config
 
 
@@ -52,11 +68,22 @@ def openai_config() -> MagicMock:
     config = MagicMock()
     config.llm = MagicMock()
     config.llm.provider = LLMProvider.OPENAI
-    config.llm.api_key = "test-key"
+    config.llm.fallback = None
+    config.llm.api_key_env = "TEST_LLM_KEY"
     config.llm.model = "gpt-4"
     config.llm.temperature = 0.7
     config.llm.max_tokens = 4096
-    config.llm.timeout = 60
+    config.llm.timeout_seconds = 60
+    config.llm.max_info_rounds = 3
+    config.llm.max_steps = 20
+    config.llm.reject_excess_steps = False
+    config.llm.domain_hint = None
+    config.llm.max_context_size = 20000
+    config.llm.min_request_interval_ms = 0
+    config.llm.reject_rapid_requests = False
+    config.llm.total_timeout_seconds = 0
+    config.llm.socks_proxy = None
+    config.llm.ca_cert_path = None
     config.shell = MagicMock()
     return config
 
@@ -64,8 +91,11 @@ def openai_config() -> MagicMock:
 class TestCreateLLMClient:
     """Tests for create_llm_client function."""
 
-    def test_create_anthropic_client(self, anthropic_config: AgentSHConfig) -> None:
+    def test_create_anthropic_client(
+        self, anthropic_config: AgentSHConfig, monkeypatch: pytest.MonkeyPatch
+    ) -> None:
         """Should create Anthropic client."""
+        monkeypatch.setenv("TEST_LLM_KEY", "test-key")
         with patch("agentsh.agent.factory.AnthropicClient") as mock_client:
             mock_client.return_value = MagicMock()
             client = create_llm_client(anthropic_config)
@@ -74,10 +104,14 @@ class TestCreateLLMClient:
                 api_key="test-key",
                 model="claude-3-sonnet",
                 timeout=60,
+                client_options={},
             )
 
-    def test_create_openai_client(self, openai_config: AgentSHConfig) -> None:
+    def test_create_openai_client(
+        self, openai_config: AgentSHConfig, monkeypatch: pytest.MonkeyPatch
+    ) -> None:
         """Should create OpenAI client."""
+        monkeypatch.setenv("TEST_LLM_KEY", "test-key")
         with patch("agentsh.agent.factory.OpenAIClient") as mock_client:
             mock_client.return_value = MagicMock()
             client = create_llm_client(openai_config)
@@ -86,13 +120,146 @@ class TestCreateLLMClient:
                 api_key="test-key",
                 model="gpt-4",
                 timeout=60,
+                client_options={},
+            )
+
+    def test_real_config_key_and_timeout(self, monkeypatch: pytest.MonkeyPatch) -> None:
+        """Should pass llm.timeout_seconds, which the timeout error points to."""
+        monkeypatch.setenv("ANTHROPIC_API_KEY", "sk-ant-test")
+        config = AgentSHConfig(llm=LLMConfig(timeout_seconds=5))
+
+        with patch("agentsh.agent.factory.AnthropicClient") as mock_client:
+            create_llm_client(config)
+
+        assert mock_client.call_args.kwargs["api_key"] == "sk-ant-test"
+        assert mock_client.call_args.kwargs["timeout"] == 5
+
+    def test_create_azure_client(self, monkeypatch: pytest.MonkeyPatch) -> None:
+        """Should create Azure client from azure config fields."""
+        monkeypatch.setenv("MY_AZURE_KEY", "azure-key")
+        config = AgentSHConfig(
+            llm=LLMConfig(
+                provider=LLMProvider.AZURE,
+                api_key_env="MY_AZURE_KEY",
+                model="gpt-4o",
+                azure_endpoint="https://res.openai.azure.com",
+                azure_deployment="gpt4o-prod",
+                azure_api_version="2024-06-01",
+                timeout_seconds=45,
+            )
+        )
+
+        with patch("agentsh.agent.factory.AzureOpenAIClient") as mock_client:
+            mock_client.return_value = MagicMock()
+            create_llm_client(config)
+
+            mock_client.assert_called_once_with(
+                api_key="azure-key",
+                endpoint="https://res.openai.azure.com",
+                deployment="gpt4o-prod",
+                api_version="2024-06-01",
+                model="gpt-4o",
+                timeout=45,
+                client_options={},
             )
 
+    def test_azure_key_falls_back_to_provider_variable(
+        self, monkeypatch: pytest.MonkeyPatch
+    ) -> None:
+        """Should leave the key to the client when llm.api_key_env is unset."""
+        monkeypatch.delenv("ANTHROPIC_API_KEY", raising=False)
+        config = AgentSHConfig(
+            llm=LLMConfig(
+                provider=LLMProvider.AZURE,
+                azure_endpoint="https://res.openai.azure.com",
+                azure_deployment="gpt4o-prod",
+            )
+        )
+
+        with patch("agentsh.agent.factory.AzureOpenAIClient") as mock_client:
+            create_llm_client(config)
+
+        assert mock_client.call_args.kwargs["api_key"] is None
+
+    def test_create_gemini_client(self, monkeypatch: pytest.MonkeyPatch) -> None:
+        """Should create Gemini client."""
+        monkeypatch.setenv("GEMINI_API_KEY", "gemini-key")
+        config = AgentSHConfig(
+            llm=LLMConfig(
+                provider=LLMProvider.GEMINI,
+                api_key_env="GEMINI_API_KEY",
+                model="gemini-1.5-pro",
+                timeout_seconds=30,
+            )
+        )
+
+        with patch("agentsh.agent.factory.GeminiClient") as mock_client:
+            mock_client.return_value = MagicMock()
+            create_llm_client(config)
+
+            mock_client.assert_called_once_with(
+                api_key="gemini-key",
+                model="gemini-1.5-pro",
+                timeout=30,
+                client_options={},
+            )
+
+    def test_network_options_passed(
+        self, anthropic_config: AgentSHConfig, ca_bundle: Path
+    ) -> None:
+        """Should give the client the SOCKS proxy and CA bundle."""
+        anthropic_config.llm.socks_proxy = "socks5://127.0.0.1:1080"
+        anthropic_config.llm.ca_cert_path = ca_bundle
+
+        with patch("agentsh.agent.factory.AnthropicClient") as mock_client:
+            create_llm_client(anthropic_config)
+
+        options = mock_client.call_args.kwargs["client_options"]
+        assert options["proxy"] == "socks5://127.0.0.1:1080"
+        assert isinstance(options["verify"], ssl.SSLContext)
+
+    def test_create_mock_client(self, tmp_path) -> None:
+        """Should create the offline mock client from the rules file."""
+        rules = tmp_path / "mock_responses.json"
+        rules.write_text('[{"match": "hello", "answer": "hi"}]')
+        config = MagicMock()
+        config.llm.provider = LLMProvider.MOCK
+        config.llm.fallback = None
+        config.llm.mock_responses_path = rules
+
+        client = create_llm_client(config)
+
+        assert client.provider == "mock"
+
+    def test_create_ollama_client(self) -> None:
+        """Should create the local Ollama client."""
+        config = AgentSHConfig(llm={"provider": "ollama", "model": "llama3"})
+
+        client = create_llm_client(config)
+
+        assert client.provider == "ollama"
+        assert client.model == "llama3"
+
+    def test_openai_with_ollama_fallback(self) -> None:
+        """Should build a failover client from llm.fallback."""
+        config = AgentSHConfig(
+            llm={"provider": "openai", "fallback": {"provider": "ollama", "model": "llama3"}}
+        )
+
+        with patch("agentsh.agent.factory.OpenAIClient") as openai_client:
+            client = create_llm_client(config)
+
+        assert isinstance(client, FailoverLLMClient)
+        assert client._primary is openai_client.return_value
+        assert client._fallback.provider == "ollama"
+        assert client._fallback.model == "llama3"
+
     def test_unsupported_provider(self) -> None:
         """Should raise error for unsupported provider."""
         config = MagicMock()
         config.llm = MagicMock()
         config.llm.provider = "unsupported"
+        config.llm.fallback = None
 
         with pytest.raises(ValueError, match="Unsupported LLM provider"):
             create_llm_client(config)
@@ -132,6 +299,48 @@ class TestCreateAgentLoop:
 
             assert agent_loop.tool_registry is custom_registry
 
+    def test_pricing_for_configured_model(self, anthropic_config: AgentSHConfig) -> None:
+        """Should pass the llm.pricing entry of llm.model to the agent."""
+        anthropic_config.llm.pricing = {
+            anthropic_config.llm.model: ModelPricing(input=3.0, output=15.0),
+            "other-model": ModelPricing(input=1.0, output=1.0),
+        }
+
+        with patch("agentsh.agent.factory.AnthropicClient") as mock_client:
+            mock_client.return_value = MagicMock()
+            agent_loop = create_agent_loop(anthropic_config)
+
+        assert agent_loop.config.show_plan_cost
+        assert agent_loop.config.pricing == (3.0, 15.0)
+
+    def test_default_registry_has_plugin_tools(self) -> None:
+        """Should register the enabled plugins' tools for every agent built."""
+        config = AgentSHConfig()
+
+        with patch("agentsh.agent.factory.create_llm_client", return_value=MagicMock()):
+            first = create_agent_loop(config)
+            second = create_agent_loop(config)
+
+        for agent_loop in (first, second):
+            tool = agent_loop.tool_registry.get_tool("shell.run")
+            assert tool is not None
+            assert tool.handler.__self__._confirm_cwd is not None
+
+    def test_security_controller_built_from_config(self, tmp_path: Path) -> None:
+        """Should check the agent's steps with a controller following security.*."""
+        from agentsh.security.controller import SecurityController
+
+        config = AgentSHConfig()
+        config.security.audit_log_path = tmp_path / "audit.log"
+        config.security.critical_services = ["postgresql"]
+
+        with patch("agentsh.agent.factory.create_llm_client", return_value=MagicMock()):
+            agent_loop = create_agent_loop(config)
+
+        assert isinstance(agent_loop.security_controller, SecurityController)
+        assert "postgresql" in agent_loop.security_controller.classifier.critical_services
+        assert agent_loop.security_controller.audit.log_path == tmp_path / "audit.log"
+
 
 class TestCreateAIHandler:
     """Tests for create_ai_handler function."""
@@ -159,6 +368,43 @@ class TestCreateAIHandler:
 
             assert result == "Hello!"
 
+    def test_handler_exposes_audit_session_id(self, anthropic_config: AgentSHConfig) -> None:
+        """Should expose the audit session id for naming the recording."""
+        with patch("agentsh.agent.factory.create_agent_loop") as mock_loop:
+            mock_loop.return_value.security_controller.audit.session_id = "a1b2c3d4"
+
+            handler = create_ai_handler(anthropic_config)
+
+        assert handler.session_id == "a1b2c3d4"
+
+    def test_handler_passes_shell_state(self, anthropic_config: AgentSHConfig) -> None:
+        """Should put the shell's directory and last status in the context."""
+        with patch("agentsh.agent.factory.create_agent_loop") as mock_loop:
+            mock_agent = MagicMock()
+            mock_agent.invoke = AsyncMock(return_value=MagicMock(success=True, response="ok"))
+            mock_loop.return_value = mock_agent
+
+            handler = create_ai_handler(anthropic_config)
+            handler("fix it", cwd="/srv", last_exit_code=2)
+
+            context = mock_agent.invoke.call_args.args[1]
+            assert context.cwd == "/srv"
+            assert context.last_exit_code == 2
+
+    def test_explain_skips_agent_loop(self, anthropic_config: AgentSHConfig) -> None:
+        """Should explain through the agent's tool-less call, not a request."""
+        with patch("agentsh.agent.factory.create_agent_loop") as mock_loop:
+            mock_agent = MagicMock()
+            mock_agent.explain = AsyncMock(return_value='{"summary": "Lists files"}')
+            mock_loop.return_value = mock_agent
+
+            handler = create_ai_handler(anthropic_config)
+            reply = handler.explain("ls -la")
+
+            assert reply == '{"summary": "Lists files"}'
+            mock_agent.explain.assert_awaited_once_with("ls -la")
+            mock_agent.invoke.assert_not_called()
+
     def test_handler_returns_error_response(
         self, anthropic_config: AgentSHConfig
     ) -> None:
@@ -251,6 +497,25 @@ class TestCreateMemoryManager:
             mock_manager.assert_called_once_with(db_path="/tmp/memory.db")
 
 
+class TestCreateSecurityController:
+    """Tests for create_security_controller function."""
+
+    def test_approval_flow_follows_ui_config(self) -> None:
+        """Should pass ui.color and ui.redact_display to the approval flow."""
+        config = AgentSHConfig(shell=ShellConfig(color=False, redact_display=False))
+
+        controller = create_security_controller(config)
+
+        assert controller.approval_flow.use_color is False
+        assert controller.approval_flow.redact_display is False
+
+    def test_redacts_by_default(self) -> None:
+        """Should redact displayed commands by default."""
+        controller = create_security_controller(AgentSHConfig())
+
+        assert controller.approval_flow.redact_display is True
+
+
 class TestCreateWorkflowExecutor:
     """Tests for create_workflow_executor function."""
 
@@ -0,0 +1,118 @@
+"""Tests for project files included with AI requests."""
+
+from pathlib import Path
+
+from agentsh.agent.file_context import (
+    MAX_CONTEXT_LINE_CHARS,
+    IgnoreRules,
+    read_context_file,
+    read_file_context,
+)
+from agentsh.agent.prompts import build_system_prompt
+
+
+class TestReadContextFile:
+    """Tests for reading a single context file."""
+
+    def test_binary_file_skipped(self, tmp_path: Path) -> None:
+        """Should not decode a binary file into the prompt."""
+        path = tmp_path / "logo.png"
+        path.write_bytes(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR")
+
+        assert read_context_file(path) is None
+
+    def test_huge_file_line_capped(self, tmp_path: Path) -> None:
+        """Should keep only the first max_lines lines."""
+        path = tmp_path / "data.csv"
+        path.write_text("".join(f"row {i}\n" for i in range(10000)))
+
+        text = read_context_file(path, max_lines=50)
+
+        lines = text.splitlines()
+        assert lines[0] == "row 0"
+        assert lines[49] == "row 49"
+        assert lines[-1] == "(truncated to the first 50 lines)"
+        assert len(lines) == 51
+
+    def test_long_line_shortened(self, tmp_path: Path) -> None:
+        """Should shorten a minified single-line file."""
+        path = tmp_path / "app.min.js"
+        path.write_text("x" * (MAX_CONTEXT_LINE_CHARS * 10))
+
+        text = read_context_file(path)
+
+        assert len(text) == MAX_CONTEXT_LINE_CHARS + 3
+        assert text.endswith("...")
+
+
+class TestReadFileContext:
+    """Tests for reading the configured context files."""
+
+    def test_sections_per_file(self, tmp_path: Path) -> None:
+        """Should include each readable file under its name."""
+        (tmp_path / "README.md").write_text("# Demo\n")
+        (tmp_path / "tool.bin").write_bytes(b"\0\1\2")
+
+        text = read_file_context(str(tmp_path), ["README.md", "tool.bin", "missing.txt"])
+
+        assert text == "--- README.md ---\n# Demo\n\n--- tool.bin ---\n(binary file, not included)"
+
+    def test_extension_allowlist(self, tmp_path: Path) -> None:
+        """Should only read files with an allowed extension."""
+        (tmp_path / "README.md").write_text("docs\n")
+        (tmp_path / "Makefile").write_text("all:\n")
+
+        text = read_file_context(str(tmp_path), ["README.md", "Makefile"], ["md"])
+
+        assert text == "--- README.md ---\ndocs"
+
+    def test_nothing_readable(self, tmp_path: Path) -> None:
+        """Should return None when no file could be read."""
+        assert read_file_context(str(tmp_path), ["missing.txt"]) is None
+
+    def test_ignore_file_excludes_listed_files(self, tmp_path: Path) -> None:
+        """Should never read a file matched by .agentshignore, even if configured."""
+        (tmp_path / ".git").mkdir()
+        (tmp_path / ".agentshignore").write_text("# keep secrets out\n.env\nsecrets.yaml\n")
+        app = tmp_path / "app"
+        app.mkdir()
+        (app / ".env").write_text("API_KEY=hunter2\n")
+        (app / "secrets.yaml").write_text("password: hunter2\n")
+        (app / "README.md").write_text("docs\n")
+
+        text = read_file_context(str(app), [".env", "secrets.yaml", "README.md"])
+
+        assert text == "--- README.md ---\ndocs"
+
+    def test_in_system_prompt(self, tmp_path: Path) -> None:
+        """Should add the files to the system prompt."""
+        prompt = build_system_prompt([], cwd=str(tmp_path), file_context="--- a.md ---\nhi")
+
+        assert "Project files:\n--- a.md ---\nhi" in prompt
+
+
+class TestIgnoreRules:
+    """Tests for .agentshignore pattern matching."""
+
+    def test_gitignore_semantics(self) -> None:
+        """Should follow gitignore anchoring, directory and negation rules."""
+        rules = IgnoreRules(["*.pem", "/local.yaml", "secrets/", "!keys/public.pem", "# x"])
+
+        assert rules.ignored("certs/server.pem")
+        assert not rules.ignored("keys/public.pem")
+        assert rules.ignored("local.yaml")
+        assert not rules.ignored("config/local.yaml")
+        assert rules.ignored("deploy/secrets/db.txt")
+        assert not rules.ignored("secrets")
+
+    def test_double_star(self) -> None:
+        """Should match any number of directories with **."""
+        rules = IgnoreRules(["config/**/prod.env"])
+
+        assert rules.ignored("config/prod.env")
+        assert rules.ignored("config/a/b/prod.env")
+        assert not rules.ignored("other/prod.env")
+
+    def test_empty(self) -> None:
+        """Should ignore nothing without patterns."""
+        assert not IgnoreRules(["", "# comment"])
@@ -7,6 +7,8 @@ from unittest.mock import MagicMock, patch
 import pytest
 
 from agentsh.plugins.builtin.filesystem import FilesystemToolset
+from agentsh.tools.base import RiskLevel
+from agentsh.tools.registry import ToolRegistry
 
 
 class TestFilesystemToolsetProperties:
@@ -39,11 +41,13 @@ class TestFilesystemToolsetRegistration:
         ]
         assert "fs.read" in registered_names
         assert "fs.write" in registered_names
+        assert "fs.apply_patch" in registered_names
         assert "fs.list" in registered_names
         assert "fs.delete" in registered_names
         assert "fs.copy" in registered_names
         assert "fs.move" in registered_names
         assert "fs.search" in registered_names
+        assert "fs.grep" in registered_names
         assert "fs.info" in registered_names
 
 
@@ -109,7 +113,44 @@ class TestReadFile:
 
         assert result.success
         assert "truncated" in result.output.lower()
-        assert "200 bytes" in result.output
+        assert "read 50 bytes, file is 200 bytes" in result.output
+        assert result.metadata == {"size": 200, "bytes_read": 50, "truncated": True}
+
+    def test_read_binary_file_rejected(self, toolset: FilesystemToolset, tmp_path: Path) -> None:
+        """Should refuse binary files and suggest a hex dump."""
+        test_file = tmp_path / "image.png"
+        test_file.write_bytes(b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR")
+
+        result = toolset.read_file(str(test_file))
+
+        assert not result.success
+        assert "Binary file" in result.error
+        assert "xxd" in result.error
+        assert result.metadata["binary"] is True
+
+    def test_read_utf16_not_binary(self, toolset: FilesystemToolset, tmp_path: Path) -> None:
+        """Should not mistake UTF-16 text for binary data."""
+        test_file = tmp_path / "wide.txt"
+        test_file.write_text("hello", encoding="utf-16")
+
+        result = toolset.read_file(str(test_file), encoding="utf-16")
+
+        assert result.success
+        assert result.output == "hello"
+
+    def test_read_truncates_on_char_boundary(
+        self, toolset: FilesystemToolset, tmp_path: Path
+    ) -> None:
+        """Should cut before a character split by the byte limit."""
+        test_file = tmp_path / "emoji.txt"
+        test_file.write_text("🚀" * 200, encoding="utf-8")
+
+        result = toolset.read_file(str(test_file), max_bytes=50)
+
+        assert result.success
+        content = result.output.split("\n\n")[0]
+        assert content == "🚀" * 12
+        assert "file is 800 bytes" in result.output
 
     def test_read_expands_user_path(self, toolset: FilesystemToolset, tmp_path: Path) -> None:
         """Should expand ~ in path."""
@@ -182,6 +223,216 @@ class TestWriteFile:
         assert test_file.read_text(encoding="utf-8") == "Héllo"
 
 
+class TestApplyPatch:
+    """Tests for apply_patch method."""
+
+    @pytest.fixture
+    def toolset(self, tmp_path: Path) -> FilesystemToolset:
+        """Create a filesystem toolset sandboxed to the test directory."""
+        return FilesystemToolset(root=tmp_path)
+
+    @pytest.fixture
+    def config_file(self, tmp_path: Path) -> Path:
+        """Create a file to patch."""
+        test_file = tmp_path / "app.conf"
+        test_file.write_text("host = localhost\nport = 8080\ndebug = false\n")
+        return test_file
+
+    def test_apply_valid_patch(self, toolset: FilesystemToolset, config_file: Path) -> None:
+        """Should apply a patch whose context matches."""
+        patch = (
+            f"--- a/{config_file}\n"
+            f"+++ b/{config_file}\n"
+            "@@ -1,3 +1,3 @@\n"
+            " host = localhost\n"
+            "-port = 8080\n"
+            "+port = 9090\n"
+            " debug = false\n"
+        )
+
+        result = toolset.apply_patch(patch, path=str(config_file))
+
+        assert result.success
+        assert config_file.read_text() == "host = localhost\nport = 9090\ndebug = false\n"
+        assert "1 hunk(s)" in result.output
+
+    def test_reject_drifted_file(self, toolset: FilesystemToolset, config_file: Path) -> None:
+        """Should reject a patch whose context no longer matches."""
+        patch = (
+            "--- app.conf\n"
+            "+++ app.conf\n"
+            "@@ -1,3 +1,3 @@\n"
+            " host = example.com\n"
+            "-port = 8080\n"
+            "+port = 9090\n"
+            " debug = false\n"
+        )
+
+        result = toolset.apply_patch(patch, path=str(config_file))
+
+        assert not result.success
+        assert "does not match at line 1" in result.error
+        assert "host = example.com" in result.error
+        assert config_file.read_text() == "host = localhost\nport = 8080\ndebug = false\n"
+
+    def test_mismatch_leaves_all_files_untouched(
+        self, toolset: FilesystemToolset, tmp_path: Path
+    ) -> None:
+        """Should not write any file if one file in the patch fails."""
+        first = tmp_path / "first.txt"
+        second = tmp_path / "second.txt"
+        first.write_text("a\n")
+        second.write_text("b\n")
+        patch = (
+            f"--- {first}\n+++ {first}\n@@ -1 +1 @@\n-a\n+A\n"
+            f"--- {second}\n+++ {second}\n@@ -1 +1 @@\n-x\n+X\n"
+        )
+
+        result = toolset.apply_patch(patch)
+
+        assert not result.success
+        assert first.read_text() == "a\n"
+        assert second.read_text() == "b\n"
+
+    def test_create_new_file(self, toolset: FilesystemToolset, tmp_path: Path) -> None:
+        """Should create a file from a /dev/null patch."""
+        target = tmp_path / "new.txt"
+        patch = f"--- /dev/null\n+++ b/{target}\n@@ -0,0 +1,2 @@\n+one\n+two\n"
+
+        result = toolset.apply_patch(patch)
+
+        assert result.success
+        assert target.read_text() == "one\ntwo\n"
+
+    def test_preserves_missing_final_newline(
+        self, toolset: FilesystemToolset, tmp_path: Path
+    ) -> None:
+        """Should honor no-newline-at-end-of-file markers."""
+        target = tmp_path / "raw.txt"
+        target.write_text("a\nb")
+        patch = (
+            f"--- {target}\n+++ {target}\n@@ -1,2 +1,2 @@\n a\n-b\n"
+            "\\ No newline at end of file\n+c\n\\ No newline at end of file\n"
+        )
+
+        result = toolset.apply_patch(patch)
+
+        assert result.success
+        assert target.read_text() == "a\nc"
+
+    def test_write_failure_rolls_back(
+        self, toolset: FilesystemToolset, tmp_path: Path
+    ) -> None:
+        """Should restore files already written when a later write fails."""
+        first = tmp_path / "first.txt"
+        second = tmp_path / "second.txt"
+        first.write_text("a\n")
+        second.write_text("b\n")
+        diff = (
+            f"--- {first}\n+++ {first}\n@@ -1 +1 @@\n-a\n+A\n"
+            f"--- {second}\n+++ {second}\n@@ -1 +1 @@\n-b\n+B\n"
+        )
+        write_text = Path.write_text
+
+        def failing_write(self: Path, data: str, *args, **kwargs) -> int:
+            if self == second:
+                raise OSError("disk full")
+            return write_text(self, data, *args, **kwargs)
+
+        with patch.object(Path, "write_text", failing_write):
+            result = toolset.apply_patch(diff)
+
+        assert not result.success
+        assert "disk full" in result.error
+        assert first.read_text() == "a\n"
+        assert second.read_text() == "b\n"
+
+    def test_rollback_removes_created_directories(
+        self, toolset: FilesystemToolset, tmp_path: Path
+    ) -> None:
+        """Should remove the directories created for new files on a rollback."""
+        first = tmp_path / "new" / "pkg" / "mod.py"
+        second = tmp_path / "second.txt"
+        second.write_text("b\n")
+        diff = (
+            f"--- /dev/null\n+++ b/{first}\n@@ -0,0 +1 @@\n+x = 1\n"
+            f"--- {second}\n+++ {second}\n@@ -1 +1 @@\n-b\n+B\n"
+        )
+        write_text = Path.write_text
+
+        def failing_write(self: Path, data: str, *args, **kwargs) -> int:
+            if self == second:
+                raise OSError("disk full")
+            return write_text(self, data, *args, **kwargs)
+
+        with patch.object(Path, "write_text", failing_write):
+            result = toolset.apply_patch(diff)
+
+        assert not result.success
+        assert not (tmp_path / "new").exists()
+        assert second.read_text() == "b\n"
+
+    def test_defaults_to_working_directory(
+        self, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+    ) -> None:
+        """Should confine patches to the working directory without a root."""
+        (tmp_path / "project").mkdir()
+        outside = tmp_path / "secret.txt"
+        outside.write_text("b\n")
+        monkeypatch.chdir(tmp_path / "project")
+        toolset = FilesystemToolset()
+
+        result = toolset.apply_patch(f"--- {outside}\n+++ {outside}\n@@ -1 +1 @@\n-b\n+B\n")
+
+        assert toolset.root == (tmp_path / "project").resolve()
+        assert not result.success
+        assert "outside the sandbox root" in result.error
+        assert outside.read_text() == "b\n"
+
+    def test_sandbox_root_resolves_relative_paths(self, tmp_path: Path) -> None:
+        """Should apply relative diff paths under the sandbox root."""
+        (tmp_path / "src").mkdir()
+        target = tmp_path / "src" / "main.py"
+        target.write_text("x = 1\n")
+        toolset = FilesystemToolset()
+        toolset.configure({"root": str(tmp_path)})
+
+        result = toolset.apply_patch(
+            "--- a/src/main.py\n+++ b/src/main.py\n@@ -1 +1 @@\n-x = 1\n+x = 2\n"
+        )
+
+        assert result.success
+        assert target.read_text() == "x = 2\n"
+
+    def test_outside_sandbox_root_rejected(self, tmp_path: Path) -> None:
+        """Should reject a patch touching a file outside the sandbox root."""
+        root = tmp_path / "project"
+        root.mkdir()
+        inside = root / "ok.txt"
+        inside.write_text("a\n")
+        outside = tmp_path / "secret.txt"
+        outside.write_text("b\n")
+        toolset = FilesystemToolset(root=root)
+        patch = (
+            "--- a/ok.txt\n+++ b/ok.txt\n@@ -1 +1 @@\n-a\n+A\n"
+            "--- a/../secret.txt\n+++ b/../secret.txt\n@@ -1 +1 @@\n-b\n+B\n"
+        )
+
+        result = toolset.apply_patch(patch)
+
+        assert not result.success
+        assert "outside the sandbox root" in result.error
+        assert inside.read_text() == "a\n"
+        assert outside.read_text() == "b\n"
+
+    def test_invalid_patch(self, toolset: FilesystemToolset, config_file: Path) -> None:
+        """Should reject text that is not a unified diff."""
+        result = toolset.apply_patch("port = 9090", path=str(config_file))
+
+        assert not result.success
+        assert "Invalid patch" in result.error
+
+
 class TestListDirectory:
     """Tests for list_directory method."""
 
@@ -282,6 +533,91 @@ class TestListDirectory:
         assert ".hidden_dir" not in result.output
 
 
+    @pytest.fixture
+    def mixed_case_dir(self, tmp_path: Path) -> Path:
+        """Create a directory with mixed-case files and subdirectories."""
+        (tmp_path / "Zebra.txt").write_text("z" * 10)
+        (tmp_path / "apple.txt").write_text("a" * 30)
+        (tmp_path / "Mango.txt").write_text("m" * 20)
+        (tmp_path / "beta").mkdir()
+        (tmp_path / "Alpha").mkdir()
+
+        os.utime(tmp_path / "Zebra.txt", (1000, 1000))
+        os.utime(tmp_path / "apple.txt", (2000, 2000))
+        os.utime(tmp_path / "Mango.txt", (3000, 3000))
+        return tmp_path
+
+    def test_default_sort_case_insensitive_dirs_first(
+        self, toolset: FilesystemToolset, mixed_case_dir: Path
+    ) -> None:
+        """Should sort case-insensitively with directories first by default."""
+        result = toolset.list_directory(str(mixed_case_dir))
+
+        assert result.output.splitlines() == [
+            "[d] Alpha",
+            "[d] beta",
+            "[f] apple.txt",
+            "[f] Mango.txt",
+            "[f] Zebra.txt",
+        ]
+
+    def test_sort_name_case_sensitive(
+        self, toolset: FilesystemToolset, mixed_case_dir: Path
+    ) -> None:
+        """Should sort by exact name in name mode."""
+        result = toolset.list_directory(str(mixed_case_dir), sort="name")
+
+        assert result.output.splitlines() == [
+            "[d] Alpha",
+            "[d] beta",
+            "[f] Mango.txt",
+            "[f] Zebra.txt",
+            "[f] apple.txt",
+        ]
+
+    def test_sort_name_ci_without_dirs_first(
+        self, toolset: FilesystemToolset, mixed_case_dir: Path
+    ) -> None:
+        """Should interleave directories and files when dirs_first is false."""
+        result = toolset.list_directory(
+            str(mixed_case_dir), sort="name_ci", dirs_first=False
+        )
+
+        assert result.output.splitlines() == [
+            "[d] Alpha",
+            "[f] apple.txt",
+            "[d] beta",
+            "[f] Mango.txt",
+            "[f] Zebra.txt",
+        ]
+
+    def test_sort_size(self, toolset: FilesystemToolset, mixed_case_dir: Path) -> None:
+        """Should sort files largest first."""
+        result = toolset.list_directory(str(mixed_case_dir), sort="size")
+
+        files = [line for line in result.output.splitlines() if line.startswith("[f]")]
+        assert files == ["[f] apple.txt", "[f] Mango.txt", "[f] Zebra.txt"]
+
+    def test_sort_mtime(self, toolset: FilesystemToolset, mixed_case_dir: Path) -> None:
+        """Should sort files newest first."""
+        result = toolset.list_directory(
+            str(mixed_case_dir), sort="mtime", pattern="*.txt"
+        )
+
+        assert result.output.splitlines() == [
+            "[f] Mango.txt",
+            "[f] apple.txt",
+            "[f] Zebra.txt",
+        ]
+
+    def test_invalid_sort_mode(self, toolset: FilesystemToolset, tmp_path: Path) -> None:
+        """Should reject unknown sort modes."""
+        result = toolset.list_directory(str(tmp_path), sort="random")
+
+        assert not result.success
+        assert "Invalid sort mode" in result.error
+
+
 class TestDeleteFile:
     """Tests for delete_file method."""
 
@@ -507,6 +843,123 @@ class TestSearchFiles:
         assert result.success or "not found" in result.error.lower()
 
 
+class TestGrepFiles:
+    """Tests for grep_files method."""
+
+    @pytest.fixture
+    def toolset(self, tmp_path: Path) -> FilesystemToolset:
+        """Create a filesystem toolset rooted at the test directory."""
+        return FilesystemToolset(root=tmp_path)
+
+    @pytest.fixture
+    def tree(self, tmp_path: Path) -> Path:
+        """Create a small source tree."""
+        (tmp_path / "src").mkdir()
+        (tmp_path / "src" / "app.py").write_text("import os\n\ndef main():\n    TODO = 1\n")
+        (tmp_path / "src" / "util.py").write_text("# todo: tidy\nx = 2\n")
+        (tmp_path / "notes.txt").write_text("TODO list\n")
+        (tmp_path / "node_modules").mkdir()
+        (tmp_path / "node_modules" / "dep.js").write_text("// TODO upstream\n")
+        (tmp_path / "image.bin").write_bytes(b"TODO\0\x01")
+        return tmp_path
+
+    def test_matches(self, toolset: FilesystemToolset, tree: Path) -> None:
+        """Should return file:line: text for each matching line."""
+        result = toolset.grep_files("TODO", str(tree))
+
+        assert result.success
+        assert result.output.splitlines() == [
+            "notes.txt:1: TODO list",
+            "node_modules/dep.js:1: // TODO upstream",
+            "src/app.py:4:     TODO = 1",
+        ]
+        assert result.metadata["matches"][0] == ("notes.txt", 1, "TODO list")
+
+    def test_ignore_case(self, toolset: FilesystemToolset, tree: Path) -> None:
+        """Should match regardless of case when asked."""
+        result = toolset.grep_files("todo", str(tree / "src"), ignore_case=True)
+
+        assert [m[0] for m in result.metadata["matches"]] == ["app.py", "util.py"]
+
+    def test_include_exclude(self, toolset: FilesystemToolset, tree: Path) -> None:
+        """Should filter files and directories by glob."""
+        result = toolset.grep_files(
+            "TODO", str(tree), include=["*.py", "*.js"], exclude=["node_modules"]
+        )
+
+        assert result.output == "src/app.py:4:     TODO = 1"
+
+    def test_max_matches(self, toolset: FilesystemToolset, tmp_path: Path) -> None:
+        """Should stop after max_matches lines."""
+        (tmp_path / "a.txt").write_text("hit\n" * 5)
+        (tmp_path / "b.txt").write_text("hit\n")
+
+        result = toolset.grep_files("hit", str(tmp_path), max_matches=3)
+
+        assert len(result.metadata["matches"]) == 3
+        assert result.metadata["limited"]
+        assert "limited to 3 matches" in result.output
+
+    def test_large_files_skipped(self, toolset: FilesystemToolset, tmp_path: Path) -> None:
+        """Should skip files over the size cap."""
+        (tmp_path / "big.log").write_text("needle\n")
+
+        with patch("agentsh.plugins.builtin.filesystem.GREP_MAX_FILE_SIZE", 3):
+            result = toolset.grep_files("needle", str(tmp_path))
+
+        assert "No matches" in result.output
+        assert result.metadata["skipped"] == 1
+
+    def test_single_file(self, toolset: FilesystemToolset, tree: Path) -> None:
+        """Should search a single file."""
+        result = toolset.grep_files(r"^x = \d", str(tree / "src" / "util.py"))
+
+        assert result.output == "util.py:2: x = 2"
+
+    def test_invalid_pattern(self, toolset: FilesystemToolset, tmp_path: Path) -> None:
+        """Should report an invalid regex."""
+        result = toolset.grep_files("(", str(tmp_path))
+
+        assert not result.success
+        assert "Invalid pattern" in result.error
+
+    def test_max_matches_at_least_one(self, toolset: FilesystemToolset, tree: Path) -> None:
+        """Should reject a limit below one instead of returning a match."""
+        for limit in (0, -1):
+            result = toolset.grep_files("TODO", str(tree), max_matches=limit)
+
+            assert not result.success
+            assert "at least 1" in result.error
+
+    def test_outside_sandbox_root_rejected(self, tmp_path: Path) -> None:
+        """Should not search outside the sandbox root, directly or through links."""
+        root = tmp_path / "project"
+        root.mkdir()
+        (root / "ok.txt").write_text("needle\n")
+        secret = tmp_path / "secret.txt"
+        secret.write_text("needle\n")
+        (root / "link.txt").symlink_to(secret)
+        toolset = FilesystemToolset(root=root)
+
+        for path in (str(tmp_path), "..", str(secret)):
+            result = toolset.grep_files("needle", path)
+            assert not result.success
+            assert "outside the sandbox root" in result.error
+
+        result = toolset.grep_files("needle")
+        assert result.output == "ok.txt:1: needle"
+        assert result.metadata["skipped"] == 1
+
+    def test_registered_safe(self, toolset: FilesystemToolset) -> None:
+        """Should be read-only so it never needs confirmation."""
+        registry = ToolRegistry()
+        toolset.register_tools(registry)
+
+        tool = registry.get_tool("fs.grep")
+        assert tool.risk_level == RiskLevel.SAFE
+        assert not tool.requires_confirmation
+
+
 class TestGetInfo:
     """Tests for get_info method."""
 
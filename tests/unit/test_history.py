@@ -165,6 +165,56 @@ class TestHistoryManager:
         assert entries[0].command == "cmd5"
         assert entries[-1].command == "cmd9"
 
+    def test_max_entries_persisted(self, temp_history_path: Path) -> None:
+        """Test that trimmed history is what gets written to disk."""
+        manager = HistoryManager(path=temp_history_path, max_entries=3)
+        for i in range(5):
+            manager.add(f"ask {i}", is_ai_request=True)
+        manager.save()
+
+        reloaded = HistoryManager(path=temp_history_path)
+        reloaded.load()
+        assert [e.command for e in reloaded] == ["ask 2", "ask 3", "ask 4"]
+
+    # Filtering
+    def test_ignore_space(self, temp_history_path: Path) -> None:
+        """Test that space-prefixed input is not recorded."""
+        manager = HistoryManager(path=temp_history_path, ignore_space=True)
+        manager.add(" export TOKEN=abc")
+        manager.add("ask about disk usage", is_ai_request=True, original=" ai ask about disk usage")
+        manager.add("ls")
+        assert [e.command for e in manager] == ["ls"]
+
+    def test_leading_space_kept_by_default(self, manager: HistoryManager) -> None:
+        """Test that space-prefixed input is recorded unless configured."""
+        manager.add(" ls")
+        assert len(manager) == 1
+
+    def test_ignore_patterns(self, temp_history_path: Path) -> None:
+        """Test that commands matching ignore patterns are skipped."""
+        manager = HistoryManager(
+            path=temp_history_path,
+            ignore_patterns=[r"^export ", r"password"],
+        )
+        manager.add("export AWS_SECRET=xyz")
+        manager.add("reset my password", is_ai_request=True)
+        manager.add("git status")
+        assert [e.command for e in manager] == ["git status"]
+
+    def test_invalid_ignore_pattern_skipped(self, temp_history_path: Path) -> None:
+        """Test that an invalid regex does not break history."""
+        manager = HistoryManager(path=temp_history_path, ignore_patterns=["(", "^ls"])
+        manager.add("ls")
+        manager.add("pwd")
+        assert [e.command for e in manager] == ["pwd"]
+
+    def test_redact_secrets(self, temp_history_path: Path) -> None:
+        """Test that secrets are redacted before storing."""
+        manager = HistoryManager(path=temp_history_path, redact=True)
+        manager.add("deploy with api_key=sk_live_1234567890abcdef", is_ai_request=True)
+        assert "sk_live" not in manager.ai_requests[0].command
+        assert "REDACTED" in manager.ai_requests[0].command
+
     # Get recent
     def test_get_recent(self, manager: HistoryManager) -> None:
         """Test getting recent entries."""
@@ -399,3 +449,28 @@ class TestReadlineHistory:
         result = adapter.save()
         # Result depends on readline availability
         assert isinstance(result, bool)
+
+    def test_save_filters_readline_lines(self, temp_history_path: Path) -> None:
+        """Test that ignored lines and secrets do not reach the readline file."""
+        readline = pytest.importorskip("readline")
+        manager = HistoryManager(
+            path=temp_history_path, ignore_space=True, ignore_patterns=["^vault "], redact=True
+        )
+        adapter = ReadlineHistory(manager)
+        readline.clear_history()
+        for line in (
+            "ls -la",
+            " export SECRET_TOKEN=abc",
+            "vault login s3cr3t",
+            "curl -H 'token: abcdefgh12345678' https://example.com",
+        ):
+            readline.add_history(line)
+
+        assert adapter.save()
+
+        saved = temp_history_path.with_suffix(".readline").read_text()
+        readline.clear_history()
+        assert "ls -la" in saved
+        assert "SECRET_TOKEN" not in saved
+        assert "vault" not in saved
+        assert "abcdefgh12345678" not in saved
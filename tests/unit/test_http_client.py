@@ -1,14 +1,177 @@
 """Tests for HTTP client management module."""
 
+import logging
+import ssl
+from pathlib import Path
+from unittest.mock import patch
+
+import httpx
 import pytest
 
 from agentsh.agent.http_client import (
     ClientStats,
     HTTPClientConfig,
     HTTPClientManager,
+    classify_http_error,
     get_http_client_manager,
     cleanup_http_clients,
+    load_ca_bundle,
+    network_options,
+    parse_json_response,
+    redact_headers,
+    redact_log_text,
+    trace_llm_request,
 )
+from agentsh.agent.llm_client import LLMNetworkError, LLMParseError, LLMTimeoutError
+
+
+class TestClassifyHTTPError:
+    """Tests for classify_http_error."""
+
+    def test_timeout_classified_as_timeout(self) -> None:
+        """Should map httpx timeouts to LLMTimeoutError."""
+        error = classify_http_error(httpx.ReadTimeout("timed out"))
+
+        assert isinstance(error, LLMTimeoutError)
+        assert "timeout" in error.user_message.lower()
+
+    def test_connect_timeout_classified_as_timeout(self) -> None:
+        """Should treat connect timeouts as timeouts, not network errors."""
+        error = classify_http_error(httpx.ConnectTimeout("connect timed out"))
+
+        assert isinstance(error, LLMTimeoutError)
+        assert not isinstance(error, LLMNetworkError)
+
+    def test_connect_error_classified_as_network(self) -> None:
+        """Should map other transport errors to LLMNetworkError."""
+        error = classify_http_error(httpx.ConnectError("connection refused"))
+
+        assert isinstance(error, LLMNetworkError)
+
+    def test_status_error_unchanged(self) -> None:
+        """Should leave HTTP status errors as they are."""
+        original = httpx.HTTPStatusError("500", request=None, response=None)
+
+        assert classify_http_error(original) is original
+
+    def test_timeout_is_retryable_builtin(self) -> None:
+        """Should remain a TimeoutError for generic retry handling."""
+        error = classify_http_error(httpx.ReadTimeout("timed out"))
+
+        assert isinstance(error, TimeoutError)
+
+
+class TestParseJSONResponse:
+    """Tests for parse_json_response."""
+
+    def test_json_decoded(self) -> None:
+        """Should return the decoded body."""
+        response = httpx.Response(200, json={"message": {"content": "Hi"}})
+
+        assert parse_json_response(response) == {"message": {"content": "Hi"}}
+
+    def test_html_body_quoted(self) -> None:
+        """Should report an HTML page and quote the start of the body."""
+        body = "<!DOCTYPE html>\n<html><head><title>Proxy Login</title></head>" + "x" * 500
+        response = httpx.Response(200, text=body)
+
+        with pytest.raises(LLMParseError) as exc_info:
+            parse_json_response(response, "ollama")
+
+        message = exc_info.value.user_message
+        assert "ollama endpoint returned an HTML page" in message
+        assert "<title>Proxy Login</title>" in message
+        assert exc_info.value.snippet.endswith("...")
+        assert len(exc_info.value.snippet) < len(body)
+
+    def test_empty_body(self) -> None:
+        """Should say the body was empty."""
+        with pytest.raises(LLMParseError, match="empty response"):
+            parse_json_response(httpx.Response(200, text=""))
+
+    def test_plain_text_body(self) -> None:
+        """Should quote other non-JSON bodies."""
+        with pytest.raises(LLMParseError, match="not JSON: Service Unavailable"):
+            parse_json_response(httpx.Response(200, text="Service Unavailable"))
+
+
+class TestNetworkOptions:
+    """Tests for SOCKS proxy and CA bundle client options."""
+
+    def test_valid_ca_bundle(self, ca_bundle: Path) -> None:
+        """Should trust the certificates of a PEM bundle."""
+        context = load_ca_bundle(ca_bundle)
+
+        assert isinstance(context, ssl.SSLContext)
+        assert context.verify_mode == ssl.CERT_REQUIRED
+
+    def test_malformed_ca_bundle(self, tmp_path: Path) -> None:
+        """Should reject a file without a valid certificate."""
+        bad = tmp_path / "bad.pem"
+        bad.write_text("-----BEGIN CERTIFICATE-----\nnot base64\n-----END CERTIFICATE-----\n")
+
+        with pytest.raises(ValueError, match="is not a PEM certificate bundle"):
+            load_ca_bundle(bad)
+        with pytest.raises(ValueError, match="cannot be read"):
+            load_ca_bundle(tmp_path / "missing.pem")
+
+    def test_options(self, ca_bundle: Path) -> None:
+        """Should only set the options that are configured."""
+        options = network_options("socks5://proxy:1080", ca_bundle)
+
+        assert options["proxy"] == "socks5://proxy:1080"
+        assert isinstance(options["verify"], ssl.SSLContext)
+        assert network_options() == {}
+
+
+class TestLLMTracing:
+    """Tests for debug logging of LLM request and response bodies."""
+
+    def test_strips_bearer_token(self) -> None:
+        """Should remove the bearer token from a logged request body."""
+        body = '{"headers": {"Authorization": "Bearer sk-live-0123456789"}, "model": "gpt-4o"}'
+
+        redacted = redact_log_text(body)
+
+        assert "sk-live-0123456789" not in redacted
+        assert "Bearer ***REDACTED***" in redacted
+        assert '"model": "gpt-4o"' in redacted
+
+    def test_strips_known_secrets(self) -> None:
+        """Should mask the client's API key wherever it appears."""
+        redacted = redact_log_text("url=/v1?key=shortkey", secrets=["shortkey"])
+        assert "shortkey" not in redacted
+
+    def test_redacts_credential_headers(self) -> None:
+        """Should mask credential headers and keep the rest."""
+        headers = redact_headers({"Authorization": "Bearer abc", "api-key": "k", "X-Title": "t"})
+        assert headers == {
+            "Authorization": "***REDACTED***",
+            "api-key": "***REDACTED***",
+            "X-Title": "t",
+        }
+
+    def test_trace_only_when_debugging(self) -> None:
+        """Should log request bodies only at DEBUG level, without the key."""
+        std_logger = logging.getLogger("agentsh.agent.http_client")
+        previous = std_logger.level
+        with patch("agentsh.agent.http_client.logger") as mock_logger:
+            std_logger.setLevel(logging.INFO)
+            trace_llm_request("openai", {"model": "gpt-4o"}, secrets=["sk-secret"])
+            mock_logger.debug.assert_not_called()
+
+            std_logger.setLevel(logging.DEBUG)
+            trace_llm_request(
+                "openai",
+                {"model": "gpt-4o", "note": "sk-secret"},
+                {"Authorization": "Bearer sk-secret"},
+                secrets=["sk-secret"],
+            )
+        std_logger.setLevel(previous)
+
+        kwargs = mock_logger.debug.call_args.kwargs
+        assert "sk-secret" not in str(kwargs)
+        assert '"model": "gpt-4o"' in kwargs["body"]
 
 
 class TestHTTPClientConfig:
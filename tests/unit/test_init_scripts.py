@@ -0,0 +1,52 @@
+"""Tests for shell integration snippet generation."""
+
+import pytest
+
+from agentsh.shell.init_scripts import (
+    HOOK_NAME,
+    SUPPORTED_SHELLS,
+    get_bash_init,
+    get_fish_init,
+    get_init_script,
+    get_zsh_init,
+)
+from agentsh import __version__
+
+
+class TestInitScripts:
+    """Tests for per-shell snippets."""
+
+    @pytest.mark.parametrize("shell", SUPPORTED_SHELLS)
+    def test_snippet_not_empty(self, shell: str) -> None:
+        """Should emit a non-empty snippet for every supported shell."""
+        script = get_init_script(shell)
+        assert script.strip()
+        assert __version__ in script
+
+    @pytest.mark.parametrize("shell", SUPPORTED_SHELLS)
+    def test_snippet_defines_hook(self, shell: str) -> None:
+        """Should define the hook and emit cwd and prompt markers."""
+        script = get_init_script(shell)
+        assert HOOK_NAME in script
+        assert "\\033]7;file://" in script
+        assert "\\033]133;A" in script
+
+    def test_bash_uses_prompt_command(self) -> None:
+        """Should install the hook via PROMPT_COMMAND."""
+        script = get_bash_init()
+        assert f'PROMPT_COMMAND="{HOOK_NAME}' in script
+
+    def test_zsh_uses_precmd_hook(self) -> None:
+        """Should install the hook via add-zsh-hook precmd."""
+        script = get_zsh_init()
+        assert f"add-zsh-hook precmd {HOOK_NAME}" in script
+
+    def test_fish_uses_prompt_event(self) -> None:
+        """Should install the hook on the fish_prompt event."""
+        script = get_fish_init()
+        assert f"function {HOOK_NAME} --on-event fish_prompt" in script
+
+    def test_unsupported_shell(self) -> None:
+        """Should raise for unknown shells."""
+        with pytest.raises(ValueError, match="Unsupported shell"):
+            get_init_script("tcsh")
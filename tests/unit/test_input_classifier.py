@@ -65,6 +65,71 @@ class TestInputClassifier:
         # Should not match 'ai ' prefix, will be heuristic classified
         assert result.input_type != InputType.AI_REQUEST or "Forced" not in result.reason
 
+    # Safety check route tests
+    def test_ai_check_quoted_command(self, classifier: InputClassifier) -> None:
+        """Test that ai check '<command>' routes to a safety check."""
+        result = classifier.classify("ai check 'rm -rf /var/log/*.gz'")
+        assert result.input_type == InputType.SAFETY_CHECK
+        assert result.content == "rm -rf /var/log/*.gz"
+
+    def test_ai_check_double_quoted(self, classifier: InputClassifier) -> None:
+        """Test that double quotes are accepted and nested quotes kept."""
+        result = classifier.classify("ai check \"echo 'hi' > out.txt\"")
+        assert result.input_type == InputType.SAFETY_CHECK
+        assert result.content == "echo 'hi' > out.txt"
+
+    def test_ai_check_unquoted_is_ai_request(self, classifier: InputClassifier) -> None:
+        """Test that natural language starting with 'check' still goes to the AI."""
+        result = classifier.classify("ai check disk usage on /home")
+        assert result.input_type == InputType.AI_REQUEST
+        assert result.content == "check disk usage on /home"
+
+    def test_ai_explain_quoted_command(self, classifier: InputClassifier) -> None:
+        """Test that ai explain '<command>' routes to a structured explanation."""
+        result = classifier.classify("ai explain 'tar -xzf a.tgz'")
+        assert result.input_type == InputType.EXPLAIN
+        assert result.content == "tar -xzf a.tgz"
+
+        result = classifier.classify("ai explain this code")
+        assert result.input_type == InputType.AI_REQUEST
+
+    def test_ai_redo(self, classifier: InputClassifier) -> None:
+        """Test that ai redo re-runs the last plan but longer requests go to the AI."""
+        assert classifier.classify("ai redo").input_type == InputType.REDO
+        assert classifier.classify("  ai redo  ").input_type == InputType.REDO
+
+        result = classifier.classify("ai redo the migration with --force")
+        assert result.input_type == InputType.AI_REQUEST
+
+    def test_ai_reload(self, classifier: InputClassifier) -> None:
+        """Test that ai reload reloads the configuration."""
+        assert classifier.classify("ai reload").input_type == InputType.RELOAD
+
+        result = classifier.classify("ai reload nginx")
+        assert result.input_type == InputType.AI_REQUEST
+
+    def test_ai_export_chat(self, classifier: InputClassifier) -> None:
+        """Test that ai export-chat takes the file to write as its argument."""
+        result = classifier.classify("ai export-chat  ~/notes/chat.md ")
+        assert result.input_type == InputType.EXPORT_CHAT
+        assert result.content == "~/notes/chat.md"
+
+        assert classifier.classify("ai export-chat").content == ""
+
+    def test_ai_ask(self, classifier: InputClassifier) -> None:
+        """Test that ai ask makes a read-only query of the rest of the line."""
+        result = classifier.classify("ai ask why is /var full?")
+        assert result.input_type == InputType.READ_ONLY_QUERY
+        assert result.content == "why is /var full?"
+
+        assert classifier.classify("ai ask").input_type == InputType.AI_REQUEST
+
+    def test_ai_check_malformed_is_ai_request(self, classifier: InputClassifier) -> None:
+        """Test that unbalanced or extra words fall back to an AI request."""
+        assert classifier.classify("ai check 'rm -rf x").input_type == InputType.AI_REQUEST
+        assert classifier.classify("ai check 'ls' twice").input_type == InputType.AI_REQUEST
+        assert classifier.classify("ai check ''").input_type == InputType.AI_REQUEST
+
     # Special command tests
     def test_special_command_help(self, classifier: InputClassifier) -> None:
         """Test that :help is classified as special command."""
@@ -248,7 +313,7 @@ class TestSpecialCommands:
     def test_all_commands_documented(self) -> None:
         """Test that all expected commands are in registry."""
         expected = {
-            "help", "h", "config", "history", "clear", "reset", "status",
+            "help", "h", "config", "history", "clear", "reset", "status", "why",
             "remember", "recall", "forget",  # Memory commands
             "quit", "exit", "q",
         }
@@ -11,6 +11,10 @@ from agentsh.agent.llm_client import (
     StopReason,
     ToolCall,
     ToolDefinition,
+    json_response,
+    json_response_requested,
+    json_truncated,
+    split_system_prompt,
 )
 
 
@@ -126,6 +130,68 @@ class TestMessage:
         assert d["name"] == "ls"
 
 
+class TestSplitSystemPrompt:
+    """Tests for split_system_prompt."""
+
+    def test_joins_system_messages(self) -> None:
+        """Should combine every system message and keep the rest in order."""
+        messages = [
+            Message.system("Be brief."),
+            Message.user("Hi"),
+            Message.system("Answer in English."),
+            Message.assistant("Hello"),
+        ]
+
+        system, rest = split_system_prompt(messages)
+
+        assert system == "Be brief.\n\nAnswer in English."
+        assert [m.content for m in rest] == ["Hi", "Hello"]
+
+    def test_no_system_message(self) -> None:
+        """Should return an empty prompt when there is none."""
+        system, rest = split_system_prompt([Message.user("Hi")])
+
+        assert system == ""
+        assert len(rest) == 1
+
+
+class TestJsonResponse:
+    """Tests for the json_response context."""
+
+    def test_requested_inside_block(self) -> None:
+        """Should request JSON only inside the block."""
+        assert not json_response_requested()
+
+        with json_response():
+            assert json_response_requested()
+
+        assert not json_response_requested()
+
+    def test_disabled(self) -> None:
+        """Should not request JSON when disabled by config."""
+        with json_response(False):
+            assert not json_response_requested()
+
+
+class TestJsonTruncated:
+    """Tests for json_truncated."""
+
+    def test_complete_object(self) -> None:
+        """Should accept a closed object, braces in strings included."""
+        assert not json_truncated('{"command": "echo \'}\'", "args": [1, {"a": 2}]}')
+        assert not json_truncated('```json\n{"summary": "ok"}\n```')
+
+    def test_cut_off_object(self) -> None:
+        """Should flag an object that never closes."""
+        assert json_truncated('{"summary": "Lists files", "flags": [{"flag": "-l"')
+        assert json_truncated('```json\n{"summary": "a \\"quoted\\" }')
+
+    def test_prose_not_checked(self) -> None:
+        """Should ignore replies that do not start with JSON."""
+        assert not json_truncated("Use awk '{print $1' to get the first column")
+        assert not json_truncated("")
+
+
 class TestToolDefinition:
     """Tests for ToolDefinition dataclass."""
 
@@ -460,6 +460,83 @@ class TestLoadPlugins:
 
                     mock_registry.load_toolset.assert_not_called()
 
+    def test_shell_settings_passed_to_configure(self) -> None:
+        """Should configure the shell toolset from shell.*, over its plugin section."""
+        from agentsh.config.schemas import AgentSHConfig, PluginConfig
+        from agentsh.plugins.builtin.shell import ShellToolset
+        from agentsh.tools.registry import ToolRegistry
+
+        config = AgentSHConfig(
+            shell={"backend": "bash", "exec_shell": "dash"},
+            plugins=[PluginConfig(name="shell", config={"exec_shell": "./evil.sh"})],
+        )
+
+        with patch(
+            "agentsh.plugins.loader.get_toolset_registry", return_value=ToolsetRegistry()
+        ):
+            with patch(
+                "agentsh.plugins.loader.discover_builtin_plugins", return_value=[ShellToolset]
+            ):
+                with patch(
+                    "agentsh.plugins.loader.discover_entry_point_plugins", return_value=[]
+                ):
+                    registry = load_plugins(config, ToolRegistry(), plugins_dir=Path("/none"))
+
+        toolset = registry.get("shell")
+        assert isinstance(toolset, ShellToolset)
+        assert toolset.exec_shell == "dash"
+        assert toolset.backend == "bash"
+
+    def test_security_settings_passed_to_configure(self) -> None:
+        """Should pass rm_to_trash, output_encoding and confirm_cwd through configure()."""
+        from agentsh.config.schemas import AgentSHConfig
+        from agentsh.plugins.builtin.shell import ShellToolset
+        from agentsh.tools.registry import ToolRegistry
+
+        config = AgentSHConfig(
+            security={"rm_to_trash": True}, shell={"output_encoding": "strict"}
+        )
+        confirm_cwd = MagicMock(return_value=True)
+
+        with patch(
+            "agentsh.plugins.loader.discover_builtin_plugins", return_value=[ShellToolset]
+        ):
+            with patch("agentsh.plugins.loader.discover_entry_point_plugins", return_value=[]):
+                registry = load_plugins(
+                    config,
+                    ToolRegistry(),
+                    plugins_dir=Path("/none"),
+                    registry=ToolsetRegistry(),
+                    confirm_cwd=confirm_cwd,
+                )
+
+        toolset = registry.get("shell")
+        assert toolset.trash_dir is not None
+        assert toolset.strict_output is True
+        assert toolset._check_working_dir("/etc") is None
+        confirm_cwd.assert_called_once_with("/etc")
+
+    def test_workspace_root_sandboxes_filesystem(self, tmp_path: Path) -> None:
+        """Should confine the filesystem toolset to security.workspace_root."""
+        from agentsh.config.schemas import AgentSHConfig, PluginConfig
+        from agentsh.plugins.builtin.filesystem import FilesystemToolset
+        from agentsh.tools.registry import ToolRegistry
+
+        config = AgentSHConfig(
+            security={"workspace_root": str(tmp_path)},
+            plugins=[PluginConfig(name="filesystem", config={"root": "/"})],
+        )
+
+        with patch(
+            "agentsh.plugins.loader.discover_builtin_plugins", return_value=[FilesystemToolset]
+        ):
+            with patch("agentsh.plugins.loader.discover_entry_point_plugins", return_value=[]):
+                registry = load_plugins(
+                    config, ToolRegistry(), plugins_dir=Path("/none"), registry=ToolsetRegistry()
+                )
+
+        assert registry.get("filesystem").root == tmp_path.resolve()
+
 
 class TestToolsetRegistry:
     """Tests for ToolsetRegistry class."""
@@ -13,6 +13,7 @@ from agentsh.telemetry.logger import (
     unbind_context,
     clear_context,
     LoggerMixin,
+    truncate_preview,
 )
 
 # structlog returns lazy proxies, not direct BoundLogger instances
@@ -94,3 +95,37 @@ class TestLoggerMixin:
         logger2 = instance.logger
 
         assert logger1 is logger2
+
+
+class TestTruncatePreview:
+    """Tests for head and tail previews of long text."""
+
+    def test_short_text_unchanged(self) -> None:
+        """Should return text within the budget as is."""
+        assert truncate_preview("short output", 600) == "short output"
+
+    def test_keeps_head_and_tail(self) -> None:
+        """Should keep the start and the final error of long output."""
+        text = "START " + "x" * 5000 + " error: disk full"
+
+        preview = truncate_preview(text, 100)
+
+        assert preview.startswith("START ")
+        assert preview.endswith(" error: disk full")
+        assert "[... 4923 bytes elided ...]" in preview
+
+    def test_multibyte_boundaries(self) -> None:
+        """Should cut between characters, never inside one."""
+        text = "é" * 1000 + "日本語" * 500
+
+        preview = truncate_preview(text, 103)
+
+        head, tail = preview.split("\n[... ")[0], preview.split(" ...]\n")[1]
+        assert head == "é" * 25
+        assert tail == ("日本語" * 6)[1:]
+        elided = len(text.encode("utf-8")) - len(head.encode()) - len(tail.encode())
+        assert f"[... {elided} bytes elided ...]" in preview
+
+    def test_zero_budget_keeps_everything(self) -> None:
+        """Should not truncate when the budget is disabled."""
+        assert truncate_preview("x" * 1000, 0) == "x" * 1000
@@ -124,6 +124,13 @@ class TestLoginShellManager:
         manager.setup_environment()
         assert os.environ.get("LOGIN_SHELL") == "1"
 
+    def test_setup_environment_ps1(self) -> None:
+        """Should export the computed prompt for child shells."""
+        manager = LoginShellManager()
+        with patch.dict(os.environ, {}):
+            manager.setup_environment(ps1="\\u@\\h:\\w\\$ ")
+            assert os.environ["PS1"] == "\\u@\\h:\\w\\$ "
+
     def test_get_sourced_files_empty(self) -> None:
         """Should return empty list initially."""
         manager = LoginShellManager()
@@ -693,6 +693,33 @@ block 2
         assert "**not bold**" in output
         assert "# not header" in output
 
+    def test_globs_in_code_kept(self) -> None:
+        """Should not treat glob stars inside code as emphasis."""
+        for use_color in (True, False):
+            output = render_markdown(
+                "Use `rm *.log` or:\n\n```bash\nls *.py *.txt\n```", use_color=use_color
+            )
+            assert "rm *.log" in output
+            assert "ls *.py *.txt" in output
+
+        output = strip_markdown("Use `rm *.log` or:\n\n```bash\nls *.py *.txt\n```")
+        assert "rm *.log" in output
+        assert "ls *.py *.txt" in output
+
+    def test_unmatched_and_intraword_markers_kept(self) -> None:
+        """Should leave stars and underscores that do not mark emphasis."""
+        text = "Run rm *.log and rm *.tmp, then set my_var_name in a_b_c."
+        assert strip_markdown(text) == text
+        assert render_markdown(text, use_color=True) == text
+        assert strip_markdown("*italic* and _also_ and **bold**") == "italic and also and bold"
+
+    def test_code_block_restored_with_color(self) -> None:
+        """Should restore code blocks when italic rendering is active."""
+        renderer = MarkdownRenderer(RenderConfig(use_color=True))
+        output = renderer.render("```bash\nls -la\n```")
+        assert "ls -la" in output
+        assert "\x00" not in output
+
 
 class TestMarkdownMaxWidth:
     """Tests for max width handling."""
@@ -0,0 +1,102 @@
+"""Tests for validating the configured model against the provider's list."""
+
+import asyncio
+from typing import Any
+
+import pytest
+
+from agentsh.agent.model_check import check_model, is_known_model, model_ids, model_warning
+
+# Trimmed response from OpenAI's GET /v1/models
+OPENAI_MODELS_RESPONSE = {
+    "object": "list",
+    "data": [
+        {"id": "gpt-4o", "object": "model", "created": 1715367049, "owned_by": "system"},
+        {"id": "gpt-4", "object": "model", "created": 1687882411, "owned_by": "openai"},
+        {"id": "gpt-4o-mini", "object": "model", "created": 1721172741, "owned_by": "system"},
+    ],
+}
+
+
+class FakeClient:
+    """LLM client stand-in whose model list is scripted."""
+
+    provider = "fake"
+
+    def __init__(self, model: str, models: Any = None, error: Exception = None) -> None:
+        self.model = model
+        self._models = models or []
+        self._error = error
+
+    async def list_models(self) -> list[Any]:
+        if self._error:
+            raise self._error
+        if self._models == "hang":
+            await asyncio.sleep(10)
+        return self._models
+
+
+class TestModelIds:
+    """Tests for parsing models-list responses."""
+
+    def test_openai_response(self) -> None:
+        """Should read ids from an OpenAI models list."""
+        assert model_ids(OPENAI_MODELS_RESPONSE["data"]) == ["gpt-4o", "gpt-4", "gpt-4o-mini"]
+
+    def test_names_and_strings(self) -> None:
+        """Should accept name-keyed entries and plain names."""
+        assert model_ids([{"name": "llama3.2:latest"}, "mistral", {"object": "model"}]) == [
+            "llama3.2:latest",
+            "mistral",
+        ]
+
+
+class TestModelMembership:
+    """Tests for checking the model against the list."""
+
+    def test_listed_model(self) -> None:
+        """Should accept a listed model."""
+        ids = model_ids(OPENAI_MODELS_RESPONSE["data"])
+        assert is_known_model("gpt-4", ids)
+        assert model_warning("gpt-4", ids) is None
+
+    def test_untagged_matches_latest(self) -> None:
+        """Should match an untagged name to its :latest variant."""
+        assert is_known_model("llama3.2", ["llama3.2:latest"])
+        assert not is_known_model("llama3", ["llama3.2:latest"])
+
+    def test_typo_warns_with_suggestion(self) -> None:
+        """Should warn about a missing model and suggest close names."""
+        warning = model_warning("gpt4", model_ids(OPENAI_MODELS_RESPONSE["data"]))
+
+        assert warning.startswith("Model 'gpt4' is not offered by the provider")
+        assert "did you mean gpt-4" in warning
+
+    def test_empty_list_not_checked(self) -> None:
+        """Should not warn when the provider listed nothing."""
+        assert model_warning("anything", []) is None
+
+
+class TestCheckModel:
+    """Tests for the startup check."""
+
+    @pytest.mark.asyncio
+    async def test_warns_for_unknown_model(self) -> None:
+        """Should warn when the model is not listed."""
+        client = FakeClient("gpt-5-turbo-ultra", OPENAI_MODELS_RESPONSE["data"])
+
+        assert "gpt-5-turbo-ultra" in await check_model(client)
+
+    @pytest.mark.asyncio
+    async def test_no_network(self) -> None:
+        """Should skip the check when listing fails."""
+        client = FakeClient("gpt4", error=ConnectionError("Network is unreachable"))
+
+        assert await check_model(client) is None
+
+    @pytest.mark.asyncio
+    async def test_timeout(self) -> None:
+        """Should give up on a slow models endpoint."""
+        client = FakeClient("gpt4", models="hang")
+
+        assert await check_model(client, timeout=0.01) is None
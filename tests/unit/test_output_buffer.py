@@ -0,0 +1,57 @@
+"""Tests for the recent terminal output buffer."""
+
+import pytest
+
+from agentsh.shell.output_buffer import OutputRingBuffer
+
+
+class TestOutputRingBuffer:
+    """Tests for OutputRingBuffer."""
+
+    def test_size_bounded(self) -> None:
+        """Should keep only the newest max_bytes bytes."""
+        buffer = OutputRingBuffer(max_bytes=10)
+
+        buffer.write(b"0123456789")
+        buffer.write(b"abc")
+
+        assert len(buffer) == 10
+        assert buffer.tail() == "3456789abc"
+
+    def test_single_write_larger_than_buffer(self) -> None:
+        """Should keep the end of a write bigger than the buffer."""
+        buffer = OutputRingBuffer(max_bytes=4)
+
+        buffer.write(b"x" * 100 + b"tail")
+
+        assert buffer.tail() == "tail"
+
+    def test_tail_strips_escapes(self) -> None:
+        """Should remove colors and shell integration markers."""
+        buffer = OutputRingBuffer()
+
+        buffer.write(b"\x1b]133;D;1\x07\x1b[31mError:\x1b[0m disk full\r\n$ ")
+
+        assert buffer.tail() == "Error: disk full\n$ "
+
+    def test_tail_starts_at_line_boundary(self) -> None:
+        """Should drop a line cut by the character limit."""
+        buffer = OutputRingBuffer()
+        buffer.write(b"first line\nsecond line\nthird\n")
+
+        assert buffer.tail(max_chars=15) == "third"
+        assert buffer.tail(max_chars=1000) == "first line\nsecond line\nthird"
+
+    def test_clear(self) -> None:
+        """Should drop all output."""
+        buffer = OutputRingBuffer()
+        buffer.write(b"output")
+
+        buffer.clear()
+
+        assert buffer.tail() == ""
+
+    def test_invalid_size(self) -> None:
+        """Should reject a non-positive size."""
+        with pytest.raises(ValueError):
+            OutputRingBuffer(max_bytes=0)
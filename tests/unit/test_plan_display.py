@@ -0,0 +1,67 @@
+"""Tests for showing a plan's steps before they run."""
+
+from pathlib import Path
+
+from agentsh.agent.llm_client import ToolCall
+from agentsh.agent.plan_display import format_plan
+
+
+def _shell_call(command: str, description: str = "") -> ToolCall:
+    """Build a shell.run tool call."""
+    arguments = {"command": command}
+    if description:
+        arguments["description"] = description
+    return ToolCall(id=f"call_{command[:8]}", name="shell.run", arguments=arguments)
+
+
+def _plan(cwd: Path) -> list[ToolCall]:
+    """Build a sample plan deleting a build directory with two files."""
+    build = cwd / "build"
+    build.mkdir()
+    (build / "a.o").write_text("")
+    (build / "b.o").write_text("")
+    return [
+        _shell_call("git status", "Check for uncommitted changes"),
+        _shell_call("rm -rf build", "Remove the old build output"),
+    ]
+
+
+class TestFormatPlan:
+    """Tests for format_plan at each verbosity."""
+
+    def test_minimal(self, tmp_path: Path) -> None:
+        """Should show just the numbered commands."""
+        plan = format_plan(_plan(tmp_path), "minimal", cwd=str(tmp_path))
+
+        assert plan.splitlines() == ["Plan:", "  1. git status", "  2. rm -rf build"]
+
+    def test_normal(self, tmp_path: Path) -> None:
+        """Should add descriptions and flags, without warnings or effects."""
+        plan = format_plan(_plan(tmp_path), "normal", cwd=str(tmp_path))
+
+        lines = plan.splitlines()
+        assert lines[:4] == [
+            "Plan:",
+            "  1. git status",
+            "     Check for uncommitted changes",
+            "  2. rm -rf build",
+        ]
+        assert lines[4] == "     Remove the old build output"
+        assert lines[5].startswith("     Flags: ") and "DESTRUCTIVE" in lines[5]
+        assert len(lines) == 6
+
+    def test_verbose(self, tmp_path: Path) -> None:
+        """Should add the risk warnings and previewed effects of each step."""
+        plan = format_plan(_plan(tmp_path), "verbose", cwd=str(tmp_path))
+
+        assert "     Check for uncommitted changes" in plan
+        assert "     Flags: " in plan
+        assert "     Warning: Recursive/force delete" in plan
+        assert "     Effect: will delete" in plan
+        assert "No known risk patterns" not in plan
+
+    def test_secrets_masked(self) -> None:
+        """Should never show secrets in commands."""
+        call = _shell_call("make deploy TOKEN=sk-abcdefghijklmnopqrstuvwx")
+
+        assert "sk-abcdefghijklmnopqrstuvwx" not in format_plan([call], "minimal")
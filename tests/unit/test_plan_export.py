@@ -0,0 +1,98 @@
+"""Tests for exporting AI plans as shell scripts."""
+
+import shutil
+import subprocess
+from pathlib import Path
+
+import pytest
+
+from agentsh.agent.llm_client import ToolCall
+from agentsh.agent.plan_export import PlanStep, export_script, steps_from_tool_calls
+
+
+def _shell_call(command: str, description: str = "", **extra: str) -> ToolCall:
+    """Build a shell.run tool call."""
+    arguments = {"command": command, **extra}
+    if description:
+        arguments["description"] = description
+    return ToolCall(id=f"call_{command[:8]}", name="shell.run", arguments=arguments)
+
+
+PLAN = [
+    _shell_call("git status", "Check for uncommitted changes"),
+    _shell_call("rm -rf build", "Remove the old build output"),
+    _shell_call("make -j4", "Rebuild the project"),
+]
+
+
+class TestStepsFromToolCalls:
+    """Tests for converting tool calls to plan steps."""
+
+    def test_shell_calls_become_steps(self) -> None:
+        """Should keep commands and descriptions in order."""
+        steps = steps_from_tool_calls(PLAN)
+
+        assert [s.command for s in steps] == ["git status", "rm -rf build", "make -j4"]
+        assert steps[0].description == "Check for uncommitted changes"
+
+    def test_high_risk_steps_guarded(self) -> None:
+        """Should guard destructive steps only."""
+        steps = steps_from_tool_calls(PLAN)
+
+        assert [s.guarded for s in steps] == [False, True, False]
+
+    def test_cwd_folded_into_command(self) -> None:
+        """Should run steps with a cwd in a subshell."""
+        steps = steps_from_tool_calls([_shell_call("ls", cwd="/tmp/my dir")])
+
+        assert steps[0].command == "(cd '/tmp/my dir' && ls)"
+
+    def test_non_shell_tool_skipped(self) -> None:
+        """Should record non-shell tools as comment-only steps."""
+        call = ToolCall(id="c1", name="fs.read", arguments={"path": "README.md"})
+
+        steps = steps_from_tool_calls([call])
+
+        assert steps[0].command is None
+        assert "fs.read(path='README.md')" in steps[0].description
+
+
+class TestExportScript:
+    """Tests for rendering the script."""
+
+    def test_contains_every_command_and_description(self) -> None:
+        """Should include each command with its description as a comment."""
+        script = export_script(steps_from_tool_calls(PLAN), "rebuild the project")
+
+        assert script.startswith("#!/usr/bin/env bash\n")
+        assert "# Request: rebuild the project" in script
+        assert "set -euo pipefail" in script
+        for call in PLAN:
+            assert call.arguments["command"] in script
+            assert call.arguments["description"] in script
+
+    def test_destructive_step_behind_prompt(self) -> None:
+        """Should ask before running a guarded step."""
+        script = export_script(steps_from_tool_calls(PLAN), "rebuild")
+
+        lines = script.splitlines()
+        index = lines.index("    rm -rf build")
+        assert lines[index - 2].startswith("read -r -p 'Run high-risk step 2?")
+        assert lines[index - 1] == 'if [[ "$reply" =~ ^[Yy]$ ]]; then'
+
+    def test_multiline_description(self) -> None:
+        """Should comment every line of a multi-line description."""
+        script = export_script([PlanStep("true", "first line\nsecond line")], "r")
+
+        assert "# Step 1: first line\n#   second line\ntrue" in script
+
+    @pytest.mark.skipif(shutil.which("bash") is None, reason="bash not available")
+    def test_script_is_valid_bash(self, tmp_path: Path) -> None:
+        """Should produce a script bash can parse."""
+        steps = steps_from_tool_calls(PLAN + [_shell_call("echo 'it''s done'", cwd="/tmp")])
+        path = tmp_path / "plan.sh"
+        path.write_text(export_script(steps, "rebuild\nand report"))
+
+        result = subprocess.run(["bash", "-n", str(path)], capture_output=True, text=True)
+
+        assert result.returncode == 0, result.stderr
@@ -0,0 +1,68 @@
+"""Tests for the pre-execution policy hook."""
+
+import sys
+from pathlib import Path
+
+from agentsh.security.pre_exec import HookAction, parse_verdict, run_pre_exec_hook
+
+# Denies any rm, rewrites curl to go through a proxy wrapper, allows the rest
+POLICY_HOOK = """
+import json, sys
+step = json.load(sys.stdin)
+command = step["command"] or ""
+if command.startswith("rm "):
+    print(json.dumps({"action": "deny", "reason": "rm is not allowed here"}))
+elif command.startswith("curl "):
+    print(json.dumps({"action": "modify", "command": "proxied " + command}))
+"""
+
+
+def write_hook(tmp_path: Path, source: str) -> str:
+    """Write a Python hook script and return the command running it."""
+    script = tmp_path / "hook.py"
+    script.write_text(source)
+    return f"{sys.executable} {script}"
+
+
+class TestParseVerdict:
+    """Tests for parse_verdict."""
+
+    def test_actions(self) -> None:
+        """Test that each action is read, and empty output allows."""
+        assert parse_verdict("").action == HookAction.ALLOW
+        assert parse_verdict('{"action": "ALLOW"}').action == HookAction.ALLOW
+        denied = parse_verdict('{"action": "deny", "reason": "no"}')
+        assert (denied.action, denied.reason) == (HookAction.DENY, "no")
+        modified = parse_verdict('{"action": "modify", "command": "ls -l"}')
+        assert (modified.action, modified.command) == (HookAction.MODIFY, "ls -l")
+
+    def test_unreadable_answers_deny(self) -> None:
+        """Test that garbage, unknown actions and empty modifications deny."""
+        for output in ("yes", "[]", '{"action": "maybe"}', '{"action": "modify"}'):
+            assert parse_verdict(output).action == HookAction.DENY, output
+
+
+class TestRunPreExecHook:
+    """Tests for run_pre_exec_hook."""
+
+    def test_deny_and_rewrite(self, tmp_path: Path) -> None:
+        """Test a hook that denies one command and rewrites another."""
+        hook = write_hook(tmp_path, POLICY_HOOK)
+
+        denied = run_pre_exec_hook(hook, {"command": "rm -rf build"}, timeout=10)
+        modified = run_pre_exec_hook(hook, {"command": "curl example.com"}, timeout=10)
+        allowed = run_pre_exec_hook(hook, {"command": "ls"}, timeout=10)
+
+        assert (denied.action, denied.reason) == (HookAction.DENY, "rm is not allowed here")
+        assert modified.action == HookAction.MODIFY
+        assert modified.command == "proxied curl example.com"
+        assert allowed.action == HookAction.ALLOW
+
+    def test_fails_closed(self, tmp_path: Path) -> None:
+        """Test that a failing, slow or missing hook denies the step."""
+        failing = write_hook(tmp_path, "import sys; sys.exit('policy error')")
+        slow = f"{sys.executable} -c 'import time; time.sleep(5)'"
+
+        assert "policy error" in run_pre_exec_hook(failing, {}, timeout=10).reason
+        assert "timed out" in run_pre_exec_hook(slow, {}, timeout=0.2).reason
+        assert run_pre_exec_hook(str(tmp_path / "missing"), {}, 10).action == HookAction.DENY
@@ -0,0 +1,94 @@
+"""Tests for per-project notes."""
+
+from pathlib import Path
+
+import pytest
+
+from agentsh.agent.project_notes import append_note, find_project_root, read_notes
+from agentsh.agent.prompts import build_system_prompt
+from agentsh.plugins.builtin.notes import NotesToolset
+from agentsh.tools.registry import ToolRegistry
+
+
+@pytest.fixture
+def project(tmp_path: Path) -> Path:
+    """Create a project with a nested source directory."""
+    (tmp_path / ".git").mkdir()
+    (tmp_path / "src" / "app").mkdir(parents=True)
+    return tmp_path
+
+
+class TestProjectNotes:
+    """Tests for reading and appending notes."""
+
+    def test_root_found_from_subdirectory(self, project: Path) -> None:
+        """Should resolve the project root from a nested directory."""
+        assert find_project_root(str(project / "src" / "app")) == project.resolve()
+
+    def test_search_stops_below_home(self, tmp_path: Path, monkeypatch: pytest.MonkeyPatch) -> None:
+        """Should not take $HOME, whose .agentsh is the user config, as the root."""
+        monkeypatch.setenv("HOME", str(tmp_path))
+        (tmp_path / ".agentsh").mkdir()
+        work = tmp_path / "scratch" / "logs"
+        work.mkdir(parents=True)
+
+        assert find_project_root(str(work)) == work.resolve()
+        assert find_project_root(str(tmp_path)) == tmp_path.resolve()
+
+    def test_append_writes_notes_file(self, project: Path) -> None:
+        """Should create the notes file and append one line per note."""
+        path = append_note(str(project / "src"), "Deploys go through make ship")
+        append_note(str(project), "  db is a read replica\non port 5433 ")
+
+        assert path == project.resolve() / ".agentsh" / "notes.md"
+        lines = path.read_text().splitlines()
+        assert lines[0] == "# Project notes"
+        assert lines[2].startswith("- Deploys go through make ship (")
+        assert lines[3].startswith("- db is a read replica on port 5433 (")
+
+    def test_append_rejects_empty_note(self, project: Path) -> None:
+        """Should refuse to record an empty note."""
+        with pytest.raises(ValueError):
+            append_note(str(project), "   ")
+
+    def test_read_missing_notes(self, project: Path) -> None:
+        """Should return None when the project has no notes."""
+        assert read_notes(str(project)) is None
+
+    def test_read_is_bounded(self, project: Path) -> None:
+        """Should keep only the newest notes when the file is long."""
+        for i in range(100):
+            append_note(str(project), f"fact number {i}")
+
+        notes = read_notes(str(project), max_chars=200)
+
+        assert notes.startswith("(older notes omitted)")
+        assert "fact number 99" in notes
+        assert "fact number 0 " not in notes
+        assert read_notes(str(project), max_chars=0) is None
+
+    def test_notes_in_system_prompt(self, project: Path) -> None:
+        """Should include the notes in the prompt context."""
+        append_note(str(project), "Deploys go through make ship")
+
+        prompt = build_system_prompt(
+            available_tools=[],
+            cwd=str(project),
+            project_notes=read_notes(str(project / "src")),
+        )
+
+        assert "Project notes:\n# Project notes" in prompt
+        assert "- Deploys go through make ship" in prompt
+
+    def test_notes_append_tool(self, project: Path) -> None:
+        """Should record notes through the notes.append tool."""
+        registry = ToolRegistry()
+        toolset = NotesToolset()
+        toolset.register_tools(registry)
+
+        result = toolset.append("Tests need docker running", cwd=str(project))
+
+        assert registry.get_tool("notes.append") is not None
+        assert result.success
+        assert "Tests need docker running" in read_notes(str(project))
+        assert not toolset.append("", cwd=str(project)).success
@@ -13,6 +13,7 @@ from agentsh.shell.prompt import (
     PromptRenderer,
     PromptStyle,
     strip_ansi,
+    template_to_ps1,
 )
 
 
@@ -386,3 +387,32 @@ class TestStripAnsi:
         """Test empty string."""
         result = strip_ansi("")
         assert result == ""
+
+
+class TestTemplateToPS1:
+    """Test cases for translating prompt templates into PS1."""
+
+    DEFAULT = "{user}@{host}:{cwd} [{mode}]$ "
+
+    def test_default_template_bash(self) -> None:
+        """Test the default template with bash escapes."""
+        assert template_to_ps1(self.DEFAULT, "bash", "normal") == "\\u@\\h:\\w [normal]\\$ "
+
+    def test_default_template_zsh(self) -> None:
+        """Test the default template with zsh escapes."""
+        assert template_to_ps1(self.DEFAULT, "/usr/bin/zsh", "strict") == "%n@%m:%~ [strict]$ "
+
+    def test_custom_template(self) -> None:
+        """Test a custom template with repeated and unknown placeholders."""
+        template = "({mode}) {cwd} {branch} {cwd}> "
+        assert template_to_ps1(template, "bash", "ai") == "(ai) \\w {branch} \\w> "
+        assert template_to_ps1(template, "zsh", "ai") == "(ai) %~ {branch} %~> "
+
+    def test_literal_text_escaped(self) -> None:
+        """Test that prompt metacharacters in literal text are escaped."""
+        assert template_to_ps1("{mode} `x` 50% ", "bash", "a\\b") == "a\\\\b \\`x\\` 50% "
+        assert template_to_ps1("{mode} 50% ", "zsh", "100%") == "100%% 50%% "
+
+    def test_unsupported_shell(self) -> None:
+        """Test that shells without PS1 get no prompt."""
+        assert template_to_ps1(self.DEFAULT, "fish", "normal") is None
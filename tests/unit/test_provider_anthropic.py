@@ -204,6 +204,27 @@ class TestAnthropicClientInvoke:
         with pytest.raises(anthropic.APIError):
             asyncio.run(client.invoke([Message.user("Test")]))
 
+    def test_invoke_maps_timeout_and_connection_errors(self, mock_anthropic) -> None:
+        """Should raise the typed LLM errors for SDK timeouts and connection failures."""
+        import anthropic
+
+        from agentsh.agent.llm_client import LLMNetworkError, LLMTimeoutError
+        from agentsh.agent.providers.anthropic import AnthropicClient
+
+        client = AnthropicClient(api_key="test")
+
+        mock_anthropic.messages.create.side_effect = anthropic.APITimeoutError(
+            request=MagicMock()
+        )
+        with pytest.raises(LLMTimeoutError):
+            asyncio.run(client.invoke([Message.user("Test")]))
+
+        mock_anthropic.messages.create.side_effect = anthropic.APIConnectionError(
+            request=MagicMock()
+        )
+        with pytest.raises(LLMNetworkError):
+            asyncio.run(client.invoke([Message.user("Test")]))
+
     def test_invoke_max_tokens_stop(self, mock_anthropic) -> None:
         """Should handle max_tokens stop reason."""
         from agentsh.agent.providers.anthropic import AnthropicClient
@@ -272,6 +293,22 @@ class TestAnthropicClientMessageConversion:
             mock.return_value = mock_instance
             yield mock_instance
 
+    def test_system_prompt_top_level(self, mock_anthropic) -> None:
+        """Should move system messages out of the messages array."""
+        from agentsh.agent.providers.anthropic import AnthropicClient
+
+        client = AnthropicClient(api_key="test")
+        messages = [
+            Message.system("Be helpful"),
+            Message.user("Hello"),
+            Message.system("Be brief"),
+        ]
+
+        system, converted = client._convert_messages(messages)
+
+        assert system == "Be helpful\n\nBe brief"
+        assert converted == [{"role": "user", "content": "Hello"}]
+
     def test_convert_user_message(self, mock_anthropic) -> None:
         """Should convert user message."""
         from agentsh.agent.providers.anthropic import AnthropicClient
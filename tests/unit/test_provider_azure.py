@@ -0,0 +1,133 @@
+"""Tests for Azure OpenAI LLM provider."""
+
+import pytest
+from unittest.mock import AsyncMock, MagicMock, patch
+
+from agentsh.agent.llm_client import Message
+from agentsh.agent.providers.azure import AzureOpenAIClient
+from agentsh.config.schemas import LLMConfig, LLMProvider
+
+
+class TestAzureOpenAIClientInit:
+    """Tests for AzureOpenAIClient initialization."""
+
+    def test_provider_name(self) -> None:
+        """Should report azure as provider."""
+        client = AzureOpenAIClient(
+            api_key="test-key",
+            endpoint="https://res.openai.azure.com",
+            deployment="gpt4o-prod",
+        )
+        assert client.provider == "azure"
+        assert client.model == "gpt4o-prod"
+
+    def test_deployment_defaults_to_model(self) -> None:
+        """Should use model name as deployment when none given."""
+        client = AzureOpenAIClient(
+            api_key="test-key",
+            endpoint="https://res.openai.azure.com",
+            model="gpt-4o",
+        )
+        assert client.deployment_url.endswith("/openai/deployments/gpt-4o")
+
+    def test_missing_endpoint_raises(self) -> None:
+        """Should require an endpoint."""
+        with patch.dict("os.environ", {}, clear=True):
+            with pytest.raises(ValueError, match="endpoint"):
+                AzureOpenAIClient(api_key="test-key", deployment="gpt-4o")
+
+    def test_missing_deployment_raises(self) -> None:
+        """Should require a deployment name."""
+        with pytest.raises(ValueError, match="deployment"):
+            AzureOpenAIClient(api_key="test-key", endpoint="https://res.openai.azure.com")
+
+    def test_env_vars(self) -> None:
+        """Should read key and endpoint from environment."""
+        env = {
+            "AZURE_OPENAI_API_KEY": "env-key",
+            "AZURE_OPENAI_ENDPOINT": "https://env.openai.azure.com/",
+        }
+        with patch.dict("os.environ", env):
+            client = AzureOpenAIClient(deployment="gpt-4o")
+        assert client._build_headers()["api-key"] == "env-key"
+        assert client.deployment_url == "https://env.openai.azure.com/openai/deployments/gpt-4o"
+
+
+class TestAzureOpenAIRequest:
+    """Tests for Azure request assembly from config."""
+
+    @pytest.fixture
+    def llm_config(self) -> LLMConfig:
+        """Create an Azure LLM config."""
+        return LLMConfig(
+            provider=LLMProvider.AZURE,
+            model="gpt-4o",
+            azure_endpoint="https://res.openai.azure.com/",
+            azure_deployment="gpt4o-prod",
+            azure_api_version="2024-02-15-preview",
+        )
+
+    @pytest.fixture
+    def client(self, llm_config: LLMConfig) -> AzureOpenAIClient:
+        """Create client from config."""
+        return AzureOpenAIClient(
+            api_key="azure-key",
+            endpoint=llm_config.azure_endpoint,
+            deployment=llm_config.azure_deployment,
+            api_version=llm_config.azure_api_version,
+            model=llm_config.model,
+        )
+
+    def test_url_from_config(self, client: AzureOpenAIClient) -> None:
+        """Should put deployment in path and api-version in query."""
+        assert client.chat_completions_url == (
+            "https://res.openai.azure.com/openai/deployments/gpt4o-prod"
+            "/chat/completions?api-version=2024-02-15-preview"
+        )
+
+    def test_headers_from_config(self, client: AzureOpenAIClient) -> None:
+        """Should send key in api-key header, not Authorization."""
+        headers = client._build_headers()
+        assert headers["api-key"] == "azure-key"
+        assert "Authorization" not in headers
+
+    def test_http_client_configured(self, llm_config: LLMConfig) -> None:
+        """Should configure HTTP client with base URL and api-version param."""
+        with patch("agentsh.agent.providers.azure.httpx.AsyncClient") as mock_client:
+            AzureOpenAIClient(
+                api_key="azure-key",
+                endpoint=llm_config.azure_endpoint,
+                deployment=llm_config.azure_deployment,
+                api_version=llm_config.azure_api_version,
+            )
+
+        kwargs = mock_client.call_args.kwargs
+        assert kwargs["base_url"] == (
+            "https://res.openai.azure.com/openai/deployments/gpt4o-prod"
+        )
+        assert kwargs["params"] == {"api-version": "2024-02-15-preview"}
+        assert kwargs["headers"]["api-key"] == "azure-key"
+
+    @pytest.mark.asyncio
+    async def test_invoke_shares_openai_parsing(self, client: AzureOpenAIClient) -> None:
+        """Should parse OpenAI-shaped responses."""
+        mock_response = MagicMock()
+        mock_response.json.return_value = {
+            "choices": [
+                {
+                    "message": {"content": "Hello from Azure"},
+                    "finish_reason": "stop",
+                }
+            ],
+            "usage": {"prompt_tokens": 5, "completion_tokens": 3},
+        }
+        mock_response.raise_for_status = MagicMock()
+
+        with patch.object(client._client, "post", new_callable=AsyncMock) as mock_post:
+            mock_post.return_value = mock_response
+            response = await client.invoke([Message.user("Hi")])
+
+        assert response.content == "Hello from Azure"
+        assert response.input_tokens == 5
+        mock_post.assert_called_once()
+        assert mock_post.call_args.args[0] == "/chat/completions"
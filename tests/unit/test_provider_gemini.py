@@ -0,0 +1,170 @@
+"""Tests for Gemini LLM provider."""
+
+import pytest
+from unittest.mock import AsyncMock, MagicMock, patch
+
+from agentsh.agent.llm_client import (
+    LLMAPIError,
+    Message,
+    StopReason,
+    ToolCall,
+    ToolDefinition,
+)
+from agentsh.agent.providers.gemini import GeminiClient
+
+# Captured from generativelanguage.googleapis.com v1beta generateContent
+GEMINI_RESPONSE = {
+    "candidates": [
+        {
+            "content": {
+                "parts": [{"text": "Use `du -sh *` to see directory sizes."}],
+                "role": "model",
+            },
+            "finishReason": "STOP",
+            "index": 0,
+            "safetyRatings": [
+                {"category": "HARM_CATEGORY_DANGEROUS_CONTENT", "probability": "NEGLIGIBLE"}
+            ],
+        }
+    ],
+    "usageMetadata": {
+        "promptTokenCount": 12,
+        "candidatesTokenCount": 11,
+        "totalTokenCount": 23,
+    },
+    "modelVersion": "gemini-1.5-flash-002",
+}
+
+BLOCKED_RESPONSE = {
+    "promptFeedback": {
+        "blockReason": "SAFETY",
+        "safetyRatings": [
+            {"category": "HARM_CATEGORY_DANGEROUS_CONTENT", "probability": "HIGH"}
+        ],
+    },
+    "usageMetadata": {"promptTokenCount": 9, "totalTokenCount": 9},
+}
+
+
+@pytest.fixture
+def client() -> GeminiClient:
+    """Create a Gemini client."""
+    return GeminiClient(api_key="gemini-key", model="gemini-1.5-flash")
+
+
+class TestGeminiClientInit:
+    """Tests for GeminiClient initialization."""
+
+    def test_provider_name(self, client: GeminiClient) -> None:
+        """Should report gemini as provider."""
+        assert client.provider == "gemini"
+        assert client.model == "gemini-1.5-flash"
+
+    def test_env_var_key(self) -> None:
+        """Should read key from GEMINI_API_KEY."""
+        with patch.dict("os.environ", {"GEMINI_API_KEY": "env-key"}):
+            client = GeminiClient()
+        assert client._api_key == "env-key"
+
+    def test_key_in_header(self) -> None:
+        """Should send key in the x-goog-api-key header, not the URL."""
+        with patch("agentsh.agent.providers.gemini.httpx.AsyncClient") as mock_client:
+            GeminiClient(api_key="gemini-key")
+
+        kwargs = mock_client.call_args.kwargs
+        assert kwargs["headers"]["x-goog-api-key"] == "gemini-key"
+        assert "params" not in kwargs
+        assert "Authorization" not in kwargs["headers"]
+
+
+class TestGeminiMessageConversion:
+    """Tests for converting messages to contents/parts."""
+
+    def test_system_is_separate(self, client: GeminiClient) -> None:
+        """Should lift the system message into systemInstruction."""
+        payload = client._build_payload(
+            [Message.system("Be brief."), Message.user("Hi")], None, 0.0, 100
+        )
+        assert payload["systemInstruction"] == {"parts": [{"text": "Be brief."}]}
+        assert payload["contents"] == [{"role": "user", "parts": [{"text": "Hi"}]}]
+        assert payload["generationConfig"]["maxOutputTokens"] == 100
+
+    def test_tool_round_trip(self, client: GeminiClient) -> None:
+        """Should map tool calls and results to function parts."""
+        messages = [
+            Message.user("List files"),
+            Message.assistant("", [ToolCall(id="c1", name="shell", arguments={"cmd": "ls"})]),
+            Message.tool_result("c1", "shell", "a.txt"),
+        ]
+        _, contents = client._convert_messages(messages)
+
+        assert contents[1] == {
+            "role": "model",
+            "parts": [{"functionCall": {"name": "shell", "args": {"cmd": "ls"}}}],
+        }
+        assert contents[2]["parts"][0]["functionResponse"]["name"] == "shell"
+
+    def test_tools_as_function_declarations(self, client: GeminiClient) -> None:
+        """Should wrap tools in functionDeclarations."""
+        tool = ToolDefinition(
+            name="shell",
+            description="Run a command",
+            parameters={"cmd": {"type": "string"}},
+            required=["cmd"],
+        )
+        payload = client._build_payload([Message.user("Hi")], [tool], 0.0, 100)
+        declaration = payload["tools"][0]["functionDeclarations"][0]
+        assert declaration["name"] == "shell"
+        assert declaration["parameters"]["required"] == ["cmd"]
+
+
+class TestGeminiResponseParsing:
+    """Tests for parsing generateContent responses."""
+
+    def test_parse_captured_response(self, client: GeminiClient) -> None:
+        """Should read text from candidates[0].content.parts[0].text."""
+        response = client._parse_response(GEMINI_RESPONSE)
+
+        assert response.content == "Use `du -sh *` to see directory sizes."
+        assert response.stop_reason == StopReason.END_TURN
+        assert response.input_tokens == 12
+        assert response.output_tokens == 11
+        assert response.model == "gemini-1.5-flash-002"
+
+    def test_parse_function_call(self, client: GeminiClient) -> None:
+        """Should parse functionCall parts as tool calls."""
+        data = {
+            "candidates": [
+                {
+                    "content": {
+                        "parts": [{"functionCall": {"name": "shell", "args": {"cmd": "ls"}}}]
+                    },
+                    "finishReason": "STOP",
+                }
+            ]
+        }
+        response = client._parse_response(data)
+
+        assert response.stop_reason == StopReason.TOOL_USE
+        assert response.tool_calls[0].name == "shell"
+        assert response.tool_calls[0].arguments == {"cmd": "ls"}
+
+    def test_empty_candidates_raises_api_error(self, client: GeminiClient) -> None:
+        """Should surface a safety block as an API error."""
+        with pytest.raises(LLMAPIError, match="SAFETY") as exc_info:
+            client._parse_response(BLOCKED_RESPONSE)
+        assert "SAFETY" in exc_info.value.user_message
+
+    @pytest.mark.asyncio
+    async def test_invoke_posts_generate_content(self, client: GeminiClient) -> None:
+        """Should POST to the model's generateContent endpoint."""
+        mock_response = MagicMock()
+        mock_response.json.return_value = GEMINI_RESPONSE
+        mock_response.raise_for_status = MagicMock()
+
+        with patch.object(client._client, "post", new_callable=AsyncMock) as mock_post:
+            mock_post.return_value = mock_response
+            response = await client.invoke([Message.user("How big are my dirs?")])
+
+        assert mock_post.call_args.args[0] == "/models/gemini-1.5-flash:generateContent"
+        assert response.content.startswith("Use `du -sh *`")
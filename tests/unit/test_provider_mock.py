@@ -0,0 +1,154 @@
+"""Tests for the offline mock LLM provider."""
+
+import json
+from pathlib import Path
+
+import pytest
+
+from agentsh.agent.agent_loop import AgentLoop
+from agentsh.agent.llm_client import Message, StopReason, ToolCall
+from agentsh.agent.providers import get_client
+from agentsh.agent.providers.mock import MockClient, load_mock_rules
+from agentsh.tools.registry import ToolRegistry
+
+RULES = [
+    {
+        "match": "list files",
+        "content": "Listing the current directory.",
+        "tool_calls": [{"name": "shell.run", "arguments": {"command": "ls -la"}}],
+        "answer": "Here are your files.",
+    },
+    {"match": "hello", "answer": "Hi there!"},
+]
+
+
+@pytest.fixture
+def rules_file(tmp_path: Path) -> Path:
+    """Write the rules to a temporary mock responses file."""
+    path = tmp_path / "mock_responses.json"
+    path.write_text(json.dumps(RULES))
+    return path
+
+
+class TestLoadMockRules:
+    """Tests for loading the rules file."""
+
+    def test_load_rules(self, rules_file: Path) -> None:
+        """Should load rules from JSON."""
+        assert load_mock_rules(rules_file) == RULES
+
+    def test_missing_file(self, tmp_path: Path) -> None:
+        """Should return no rules when the file does not exist."""
+        assert load_mock_rules(tmp_path / "missing.json") == []
+
+    def test_invalid_file(self, tmp_path: Path) -> None:
+        """Should reject files that are not a list of rules."""
+        path = tmp_path / "bad.json"
+        path.write_text('{"match": "x"}')
+
+        with pytest.raises(ValueError, match="expected a list"):
+            load_mock_rules(path)
+
+
+class TestMockClient:
+    """Tests for MockClient responses."""
+
+    def test_provider_info(self) -> None:
+        """Should report the mock provider."""
+        client = MockClient(rules=[])
+        assert client.provider == "mock"
+        assert client.model == "mock"
+
+    def test_get_client(self, rules_file: Path) -> None:
+        """Should be available through get_client."""
+        client = get_client("mock", rules_path=rules_file)
+        assert isinstance(client, MockClient)
+
+    @pytest.mark.asyncio
+    async def test_list_files_returns_ls(self, rules_file: Path) -> None:
+        """Should return the configured ls command for 'list files'."""
+        client = MockClient(rules_path=rules_file)
+
+        response = await client.invoke([Message.user("Please list files here")])
+
+        assert response.stop_reason == StopReason.TOOL_USE
+        assert response.content == "Listing the current directory."
+        assert response.tool_calls == [
+            ToolCall(id="mock_0", name="shell.run", arguments={"command": "ls -la"})
+        ]
+
+    @pytest.mark.asyncio
+    async def test_answer_after_tool_results(self, rules_file: Path) -> None:
+        """Should end the turn with the rule's answer once tools have run."""
+        client = MockClient(rules_path=rules_file)
+        messages = [
+            Message.user("list files"),
+            Message.assistant(
+                "Listing.",
+                tool_calls=[
+                    ToolCall(id="mock_0", name="shell.run", arguments={"command": "ls -la"})
+                ],
+            ),
+            Message.tool_result("mock_0", "shell.run", "a.txt\nb.txt"),
+        ]
+
+        response = await client.invoke(messages)
+
+        assert response.stop_reason == StopReason.END_TURN
+        assert response.content == "Here are your files."
+
+    @pytest.mark.asyncio
+    async def test_answer_only_rule(self, rules_file: Path) -> None:
+        """Should answer directly for rules without tool calls."""
+        client = MockClient(rules_path=rules_file)
+
+        response = await client.invoke([Message.user("HELLO")])
+
+        assert response.content == "Hi there!"
+        assert not response.tool_calls
+
+    @pytest.mark.asyncio
+    async def test_unmatched_request_echoed(self) -> None:
+        """Should echo requests that match no rule."""
+        client = MockClient(rules=[])
+
+        response = await client.invoke([Message.user("what time is it")])
+
+        assert response.content == "(mock) what time is it"
+        assert response.stop_reason == StopReason.END_TURN
+
+    @pytest.mark.asyncio
+    async def test_stream(self) -> None:
+        """Should stream the response content."""
+        client = MockClient(rules=RULES)
+
+        chunks = [c async for c in client.stream([Message.user("hello")])]
+
+        assert chunks == ["Hi there!"]
+
+
+class TestMockClientEndToEnd:
+    """Tests running the agent loop against the mock provider."""
+
+    @pytest.mark.asyncio
+    async def test_agent_runs_mock_command(self, rules_file: Path) -> None:
+        """Should execute the canned command and return the final answer."""
+        executed: list[str] = []
+        registry = ToolRegistry()
+        registry.register_tool(
+            name="shell.run",
+            handler=lambda command: executed.append(command) or "a.txt",
+            description="Run a shell command",
+            parameters={
+                "properties": {"command": {"type": "string"}},
+                "required": ["command"],
+            },
+        )
+        agent = AgentLoop(MockClient(rules_path=rules_file), registry)
+
+        result = await agent.invoke("list files")
+
+        assert result.success
+        assert executed == ["ls -la"]
+        assert result.tool_calls_made == ["shell.run"]
+        assert result.response == "Here are your files."
@@ -209,6 +209,37 @@ class TestOllamaClientInvoke:
         assert response.content == "Hello!"
         mock_post.assert_called_once()
 
+    @pytest.mark.asyncio
+    async def test_invoke_html_body(self, client: OllamaClient) -> None:
+        """Should raise LLMParseError quoting an HTML body instead of a JSON error."""
+        from agentsh.agent.llm_client import LLMParseError
+
+        mock_response = MagicMock()
+        mock_response.text = "<html><body>Please sign in to the proxy</body></html>"
+        mock_response.json.side_effect = json.JSONDecodeError("Expecting value", "<html>", 0)
+        mock_response.raise_for_status = MagicMock()
+
+        with patch.object(client._client, "post", new_callable=AsyncMock) as mock_post:
+            mock_post.return_value = mock_response
+            with pytest.raises(LLMParseError, match="Please sign in to the proxy"):
+                await client.invoke([Message.user("Hi")])
+
+    @pytest.mark.asyncio
+    async def test_invoke_sends_request_id(self, client: OllamaClient) -> None:
+        """Should send the bound request id as X-Request-Id."""
+        from agentsh.telemetry.logger import request_context
+
+        mock_response = MagicMock()
+        mock_response.json.return_value = {"message": {"content": "Hi"}, "done_reason": "stop"}
+        mock_response.raise_for_status = MagicMock()
+
+        with patch.object(client._client, "post", new_callable=AsyncMock) as mock_post:
+            mock_post.return_value = mock_response
+            with request_context("req-123"):
+                await client.invoke([Message.user("Hi")])
+
+        assert mock_post.call_args.kwargs["headers"] == {"X-Request-Id": "req-123"}
+
     @pytest.mark.asyncio
     async def test_invoke_with_tools(self, client: OllamaClient) -> None:
         """Should include tools in request."""
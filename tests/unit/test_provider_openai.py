@@ -106,6 +106,30 @@ class TestOpenAIClientInvoke:
             mock.return_value = mock_instance
             yield mock_instance
 
+    def test_list_models(self, mock_openai) -> None:
+        """Should list models from the models endpoint."""
+        from agentsh.agent.providers.openai import OpenAIClient
+
+        model = MagicMock()
+        model.model_dump.return_value = {"id": "gpt-4o", "object": "model"}
+        mock_openai.models.list = AsyncMock(return_value=MagicMock(data=[model]))
+
+        client = OpenAIClient(api_key="test")
+
+        assert asyncio.run(client.list_models()) == [{"id": "gpt-4o", "object": "model"}]
+
+    def test_list_models_error(self, mock_openai) -> None:
+        """Should return an empty list when listing fails."""
+        import openai
+
+        from agentsh.agent.providers.openai import OpenAIClient
+
+        mock_openai.models.list = AsyncMock(side_effect=openai.OpenAIError("offline"))
+
+        client = OpenAIClient(api_key="test")
+
+        assert asyncio.run(client.list_models()) == []
+
     def test_invoke_simple_message(self, mock_openai) -> None:
         """Should invoke with simple message."""
         from agentsh.agent.providers.openai import OpenAIClient
@@ -130,6 +154,31 @@ class TestOpenAIClientInvoke:
         assert result.stop_reason == StopReason.END_TURN
         mock_openai.chat.completions.create.assert_called_once()
 
+    def test_invoke_json_mode(self, mock_openai) -> None:
+        """Should request a JSON object only when JSON mode is on."""
+        from agentsh.agent.llm_client import json_response
+        from agentsh.agent.providers.openai import OpenAIClient
+
+        mock_choice = MagicMock()
+        mock_choice.message = MagicMock(content='{"summary": "ok"}', tool_calls=None)
+        mock_choice.finish_reason = "stop"
+        mock_response = MagicMock()
+        mock_response.choices = [mock_choice]
+        mock_response.usage = MagicMock(prompt_tokens=10, completion_tokens=5)
+        mock_openai.chat.completions.create.return_value = mock_response
+
+        client = OpenAIClient(api_key="test")
+        messages = [Message.user("Explain ls as JSON")]
+
+        with json_response():
+            asyncio.run(client.invoke(messages))
+        call_kwargs = mock_openai.chat.completions.create.call_args[1]
+        assert call_kwargs["response_format"] == {"type": "json_object"}
+
+        asyncio.run(client.invoke(messages))
+        call_kwargs = mock_openai.chat.completions.create.call_args[1]
+        assert "response_format" not in call_kwargs
+
     def test_invoke_with_system_prompt(self, mock_openai) -> None:
         """Should include system message in messages."""
         from agentsh.agent.providers.openai import OpenAIClient
@@ -269,6 +318,27 @@ class TestOpenAIClientInvoke:
         with pytest.raises(openai.APIError):
             asyncio.run(client.invoke([Message.user("Test")]))
 
+    def test_invoke_maps_timeout_and_connection_errors(self, mock_openai) -> None:
+        """Should raise the typed LLM errors for SDK timeouts and connection failures."""
+        import openai
+
+        from agentsh.agent.llm_client import LLMNetworkError, LLMTimeoutError
+        from agentsh.agent.providers.openai import OpenAIClient
+
+        client = OpenAIClient(api_key="test")
+
+        mock_openai.chat.completions.create.side_effect = openai.APITimeoutError(
+            request=MagicMock()
+        )
+        with pytest.raises(LLMTimeoutError):
+            asyncio.run(client.invoke([Message.user("Test")]))
+
+        mock_openai.chat.completions.create.side_effect = openai.APIConnectionError(
+            request=MagicMock()
+        )
+        with pytest.raises(LLMNetworkError):
+            asyncio.run(client.invoke([Message.user("Test")]))
+
     def test_invoke_length_stop(self, mock_openai) -> None:
         """Should handle length stop reason."""
         from agentsh.agent.providers.openai import OpenAIClient
@@ -426,6 +496,22 @@ class TestOpenAIClientMessageConversion:
         assert converted[0]["role"] == "system"
         assert converted[0]["content"] == "Be helpful"
 
+    def test_system_message_first(self, mock_openai) -> None:
+        """Should send a single system message ahead of the conversation."""
+        from agentsh.agent.providers.openai import OpenAIClient
+
+        client = OpenAIClient(api_key="test")
+        messages = [
+            Message.user("Hello"),
+            Message.system("Be helpful"),
+            Message.assistant("Hi"),
+        ]
+
+        converted = client._convert_messages(messages)
+
+        assert [m["role"] for m in converted] == ["system", "user", "assistant"]
+        assert converted[0]["content"] == "Be helpful"
+
     def test_convert_user_message(self, mock_openai) -> None:
         """Should convert user message."""
         from agentsh.agent.providers.openai import OpenAIClient
@@ -206,6 +206,20 @@ class TestOpenRouterClientInvoke:
         assert response.content == "Hello!"
         mock_post.assert_called_once()
 
+    @pytest.mark.asyncio
+    async def test_invoke_timeout_raises_timeout_error(
+        self, client: OpenRouterClient
+    ) -> None:
+        """Should raise LLMTimeoutError when the request times out."""
+        import httpx
+
+        from agentsh.agent.llm_client import LLMTimeoutError
+
+        with patch.object(client._client, "post", new_callable=AsyncMock) as mock_post:
+            mock_post.side_effect = httpx.ReadTimeout("timed out")
+            with pytest.raises(LLMTimeoutError):
+                await client.invoke([Message.user("Hi")])
+
     @pytest.mark.asyncio
     async def test_invoke_with_tools(self, client: OpenRouterClient) -> None:
         """Should include tools in request."""
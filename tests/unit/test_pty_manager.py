@@ -7,7 +7,7 @@ from unittest.mock import MagicMock, patch, PropertyMock
 
 import pytest
 
-from agentsh.shell.pty_manager import PTYManager
+from agentsh.shell.pty_manager import PTYManager, detect_terminal_size, size_from_env
 
 
 class TestPTYManagerInit:
@@ -122,13 +122,50 @@ class TestGetTerminalSize:
             assert len(size) == 2
 
     def test_returns_default_on_error(self) -> None:
-        """Should return default size on OSError."""
-        with patch("os.get_terminal_size", side_effect=OSError()):
+        """Should return default size when no source reports one."""
+        with patch("os.get_terminal_size", side_effect=OSError()), patch(
+            "agentsh.shell.pty_manager._size_from_controlling_tty", return_value=None
+        ), patch.dict(os.environ, {}, clear=True):
             with patch.object(PTYManager, "_detect_shell", return_value="/bin/zsh"):
-                pty = PTYManager(dimensions=(24, 80))
+                pty = PTYManager(dimensions=(24, 80), default_size=(40, 120))
                 size = pty._get_terminal_size()
 
-                assert size == (24, 80)
+                assert size == (40, 120)
+
+    def test_falls_back_to_environment(self) -> None:
+        """Should use LINES and COLUMNS when the terminal cannot be asked."""
+        with patch("os.get_terminal_size", side_effect=OSError()), patch(
+            "agentsh.shell.pty_manager._size_from_controlling_tty", return_value=None
+        ):
+            size = detect_terminal_size(env={"LINES": "50", "COLUMNS": "200"})
+
+        assert size == (50, 200)
+
+    def test_zero_size_ignored(self) -> None:
+        """Should not trust a 0x0 size reported inside a multiplexer."""
+        with patch("os.get_terminal_size", return_value=os.terminal_size((0, 0))), patch(
+            "agentsh.shell.pty_manager._size_from_controlling_tty", return_value=(30, 100)
+        ):
+            assert detect_terminal_size(env={}) == (30, 100)
+
+
+class TestSizeFromEnv:
+    """Tests for size_from_env."""
+
+    def test_parses_lines_and_columns(self) -> None:
+        """Should read rows from LINES and columns from COLUMNS."""
+        assert size_from_env({"LINES": " 40 ", "COLUMNS": "132"}) == (40, 132)
+
+    def test_invalid_values_rejected(self) -> None:
+        """Should ignore missing, non-numeric and non-positive values."""
+        for env in (
+            {},
+            {"LINES": "40"},
+            {"LINES": "forty", "COLUMNS": "132"},
+            {"LINES": "0", "COLUMNS": "132"},
+            {"LINES": "40", "COLUMNS": "-1"},
+        ):
+            assert size_from_env(env) is None, env
 
 
 class TestSpawn:
@@ -160,6 +197,21 @@ class TestSpawn:
                         assert pty._process is mock_process
                         mock_spawn.assert_called_once()
 
+    def test_spawns_command(self) -> None:
+        """Should run a command with -c, reporting its status."""
+        with patch.object(PTYManager, "_get_terminal_size", return_value=(24, 80)):
+            with patch("ptyprocess.PtyProcess.spawn") as mock_spawn:
+                pty = PTYManager(shell_path="/bin/bash", command="make test")
+                assert pty.env.get("PROMPT_COMMAND") == os.environ.get("PROMPT_COMMAND")
+                with patch.object(pty, "_setup_sigwinch_handler"):
+                    pty.spawn()
+
+                argv = mock_spawn.call_args.args[0]
+                assert argv[:2] == ["/bin/bash", "-c"]
+                assert argv[2].startswith("make test\n")
+                # The command's child is reaped even if close() never runs
+                assert pty._guard is not None and pty._guard.active
+
     def test_raises_on_spawn_failure(self) -> None:
         """Should raise RuntimeError on spawn failure."""
         with patch.object(PTYManager, "_detect_shell", return_value="/bin/zsh"):
@@ -252,6 +304,35 @@ class TestRead:
 
                 assert result == b"hello world"
 
+    def test_read_tees_to_recorder(self) -> None:
+        """Should pass read data to the session recorder."""
+        with patch.object(PTYManager, "_detect_shell", return_value="/bin/zsh"):
+            with patch.object(PTYManager, "_get_terminal_size", return_value=(24, 80)):
+                recorder = MagicMock()
+                pty = PTYManager(recorder=recorder)
+                mock_process = MagicMock()
+                mock_process.isalive.return_value = True
+                mock_process.read.return_value = b"hello world"
+                pty._process = mock_process
+
+                pty.read()
+
+                recorder.write.assert_called_once_with(b"hello world")
+
+    def test_read_kept_in_output_buffer(self) -> None:
+        """Should keep read data as recent output for ai fix."""
+        with patch.object(PTYManager, "_detect_shell", return_value="/bin/zsh"):
+            with patch.object(PTYManager, "_get_terminal_size", return_value=(24, 80)):
+                pty = PTYManager()
+                mock_process = MagicMock()
+                mock_process.isalive.return_value = True
+                mock_process.read.return_value = b"\x1b[31mcommand not found\x1b[0m\r\n"
+                pty._process = mock_process
+
+                pty.read()
+
+                assert pty.recent_output() == "command not found"
+
     def test_read_with_timeout(self) -> None:
         """Should read with timeout."""
         with patch.object(PTYManager, "_detect_shell", return_value="/bin/zsh"):
@@ -570,6 +651,25 @@ class TestClose:
 
                     mock_signal.assert_called_once_with(signal.SIGWINCH, signal.SIG_DFL)
 
+    def test_reaps_guarded_child(self) -> None:
+        """Should reap the shell through its guard."""
+        from agentsh.shell.cleanup import ChildGuard
+
+        with patch.object(PTYManager, "_detect_shell", return_value="/bin/zsh"):
+            with patch.object(PTYManager, "_get_terminal_size", return_value=(24, 80)):
+                pty = PTYManager()
+                mock_process = MagicMock()
+                mock_process.isalive.return_value = False
+                pty._process = mock_process
+                guard = ChildGuard(mock_process)
+                pty._guard = guard
+
+                pty.close()
+
+                mock_process.wait.assert_called_once()
+                assert not guard.active
+                assert pty._guard is None
+
 
 class TestContextManager:
     """Tests for context manager interface."""
@@ -634,3 +734,49 @@ class TestSigwinchHandler:
                 pty._handle_sigwinch(signal.SIGWINCH, None)
 
                 mock_process.setwinsize.assert_called_once_with(40, 120)
+
+
+class TestInteract:
+    """Tests for interact method."""
+
+    def test_raises_if_not_spawned(self) -> None:
+        """Should raise if PTY not spawned."""
+        with patch.object(PTYManager, "_get_terminal_size", return_value=(24, 80)):
+            pty = PTYManager(shell_path="/bin/sh", command="true")
+
+            with pytest.raises(RuntimeError, match="not spawned"):
+                pty.interact()
+
+    def test_copies_output_and_tracks_status(self, tmp_path: Path) -> None:
+        """Should copy the output and follow the command's status and directory."""
+        read_fd, write_fd = os.pipe()
+        with patch.object(PTYManager, "_get_terminal_size", return_value=(24, 80)):
+            pty = PTYManager(
+                shell_path="/bin/sh", command=f"echo hello; cd '{tmp_path}'; exit 3"
+            )
+            pty.spawn()
+            try:
+                status = pty.interact(output_fd=write_fd)
+            finally:
+                pty.close()
+                os.close(write_fd)
+
+        with os.fdopen(read_fd, "rb") as output:
+            assert output.read().startswith(b"hello")
+        assert status == 3
+        # exit skips the report, so the directory is not followed
+        assert pty.current_directory == os.getcwd()
+
+        with patch.object(PTYManager, "_get_terminal_size", return_value=(24, 80)):
+            pty = PTYManager(shell_path="/bin/sh", command=f"cd '{tmp_path}' && false")
+            pty.spawn()
+            devnull = os.open(os.devnull, os.O_WRONLY)
+            try:
+                status = pty.interact(output_fd=devnull)
+            finally:
+                pty.close()
+                os.close(devnull)
+
+        assert status == 1
+        assert pty.last_command_status == 1
+        assert pty.current_directory == str(tmp_path)
@@ -0,0 +1,80 @@
+"""Tests for session recorder module."""
+
+import json
+from pathlib import Path
+
+from agentsh.shell.recorder import SessionRecorder
+
+
+def _read_cast(path: Path) -> tuple[dict, list[list]]:
+    """Read a cast file into its header and frames."""
+    lines = path.read_text().splitlines()
+    return json.loads(lines[0]), [json.loads(line) for line in lines[1:]]
+
+
+class TestSessionRecorder:
+    """Tests for SessionRecorder."""
+
+    def test_path_uses_session_id(self, tmp_path: Path) -> None:
+        """Should store casts as <session_id>.cast."""
+        recorder = SessionRecorder(session_id="abc123", sessions_dir=tmp_path)
+        assert recorder.path == tmp_path / "abc123.cast"
+
+    def test_writes_header(self, tmp_path: Path) -> None:
+        """Should write an asciicast v2 header."""
+        with SessionRecorder(
+            session_id="s1", sessions_dir=tmp_path, dimensions=(30, 100)
+        ) as recorder:
+            pass
+
+        header, frames = _read_cast(recorder.path)
+        assert header["version"] == 2
+        assert header["width"] == 100
+        assert header["height"] == 30
+        assert frames == []
+
+    def test_frames_have_monotonic_timestamps(self, tmp_path: Path) -> None:
+        """Should write frames with non-decreasing timestamps."""
+        ticks = iter([10.0, 10.5, 10.5, 12.25, 11.0])
+        recorder = SessionRecorder(
+            session_id="s2", sessions_dir=tmp_path, clock=lambda: next(ticks)
+        )
+        recorder.start()
+        recorder.write(b"$ ls\r\n")
+        recorder.write("file.txt\r\n")
+        recorder.write(b"$ ")
+        recorder.write(b"exit\r\n")  # clock stepped backwards
+        recorder.close()
+
+        _, frames = _read_cast(recorder.path)
+        times = [frame[0] for frame in frames]
+        assert times == [0.5, 0.5, 2.25, 2.25]
+        assert [frame[1] for frame in frames] == ["o"] * 4
+        assert frames[0][2] == "$ ls\r\n"
+        assert frames[1][2] == "file.txt\r\n"
+
+    def test_invalid_utf8_is_replaced(self, tmp_path: Path) -> None:
+        """Should record undecodable bytes with replacement characters."""
+        with SessionRecorder(session_id="s3", sessions_dir=tmp_path) as recorder:
+            recorder.write(b"ok \xff\r\n")
+
+        _, frames = _read_cast(recorder.path)
+        assert frames[0][2] == "ok �\r\n"
+
+    def test_split_multibyte_character_kept(self, tmp_path: Path) -> None:
+        """Should decode a character split across two reads intact."""
+        data = "héllo ✓\r\n".encode()
+        with SessionRecorder(session_id="s5", sessions_dir=tmp_path) as recorder:
+            recorder.write(data[:2])
+            recorder.write(data[2:9])
+            recorder.write(data[9:])
+
+        _, frames = _read_cast(recorder.path)
+        assert "".join(frame[2] for frame in frames) == "héllo ✓\r\n"
+
+    def test_write_before_start_is_ignored(self, tmp_path: Path) -> None:
+        """Should not create a file until started."""
+        recorder = SessionRecorder(session_id="s4", sessions_dir=tmp_path)
+        recorder.write(b"data")
+        assert not recorder.is_recording
+        assert not recorder.path.exists()
@@ -0,0 +1,71 @@
+"""Tests for replanning after a failed step."""
+
+from agentsh.agent.llm_client import ToolCall
+from agentsh.agent.replan import (
+    FailureAction,
+    build_replan_message,
+    describe_step,
+    prompt_failure_action,
+)
+
+
+class TestDescribeStep:
+    """Tests for describing plan steps."""
+
+    def test_command_step(self) -> None:
+        """Should show the command of a shell step."""
+        call = ToolCall(id="1", name="shell.run", arguments={"command": "make test"})
+        assert describe_step(call) == "make test"
+
+    def test_other_tool(self) -> None:
+        """Should show the tool name and arguments otherwise."""
+        call = ToolCall(id="1", name="fs.read", arguments={"path": "a.txt"})
+        assert describe_step(call) == 'fs.read {"path": "a.txt"}'
+
+
+class TestBuildReplanMessage:
+    """Tests for the revised-plan request."""
+
+    def test_includes_failure_and_remaining(self) -> None:
+        """Should name the failed step, its output and the skipped steps."""
+        failed = ToolCall(id="1", name="run", arguments={"command": "make"})
+        remaining = [ToolCall(id="2", name="run", arguments={"command": "make install"})]
+
+        message = build_replan_message(failed, "No rule to make target", remaining)
+
+        assert "Failed step: make" in message
+        assert "No rule to make target" in message
+        assert "- make install" in message
+
+    def test_long_output_keeps_tail(self) -> None:
+        """Should keep the end of long failure output."""
+        failed = ToolCall(id="1", name="run", arguments={"command": "build"})
+
+        message = build_replan_message(failed, "x" * 10000 + "final error", [])
+
+        assert "final error" in message
+        assert "bytes elided" in message
+        assert "(none)" in message
+
+
+class TestPromptFailureAction:
+    """Tests for the failure prompt."""
+
+    def test_choices(self) -> None:
+        """Should map keys to actions."""
+        assert prompt_failure_action("s", "o", lambda _: "a") == FailureAction.ADJUST
+        assert prompt_failure_action("s", "o", lambda _: "stop") == FailureAction.ABORT
+        assert prompt_failure_action("s", "o", lambda _: "") == FailureAction.CONTINUE
+
+    def test_reprompts_on_unknown(self) -> None:
+        """Should ask again after an unknown answer."""
+        answers = iter(["x", "c"])
+        assert prompt_failure_action("s", "o", lambda _: next(answers)) == FailureAction.CONTINUE
+
+    def test_eof_stops(self) -> None:
+        """Should stop the plan on EOF."""
+
+        def eof(_: str) -> str:
+            raise EOFError
+
+        assert prompt_failure_action("s", "o", eof) == FailureAction.ABORT
@@ -17,10 +17,12 @@ from agentsh.agent.resilient import (
     CircuitBreakerConfig,
     CircuitBreakerState,
     CircuitState,
+    FailoverLLMClient,
     ResilienceConfig,
     ResilientLLMClient,
     RetryConfig,
     create_resilient_client,
+    is_availability_error,
 )
 
 
@@ -427,3 +429,82 @@ class TestCreateResilientClient:
         assert client._config.retry.max_retries == 5
         assert client._config.use_cache_fallback is False
         assert client._config.fallback_response == "Custom fallback"
+
+
+class TestFailoverLLMClient:
+    """Tests for FailoverLLMClient."""
+
+    @staticmethod
+    def _server_error(status: int) -> Exception:
+        """Build an HTTP status error as raised by httpx-based providers."""
+        import httpx
+
+        request = httpx.Request("POST", "https://api.example.com/v1/chat")
+        response = httpx.Response(status, request=request)
+        return httpx.HTTPStatusError("server error", request=request, response=response)
+
+    def test_primary_500_routes_to_fallback(self) -> None:
+        """Should send the same conversation to the fallback after a 500."""
+        primary = MockLLMClient(errors=[self._server_error(500)])
+        fallback = MockLLMClient(
+            responses=[LLMResponse(content="From fallback", stop_reason=StopReason.END_TURN)]
+        )
+        fallback.invoke = AsyncMock(wraps=fallback.invoke)
+        notes: list[str] = []
+        client = FailoverLLMClient(primary, fallback, on_switch=notes.append)
+        messages = [Message.user("list files"), Message.assistant("ls"), Message.user("again")]
+
+        response = asyncio.run(client.invoke(messages))
+
+        assert response.content == "From fallback"
+        assert fallback.invoke.call_args[0][0] == messages
+        assert len(notes) == 1
+        assert "using fallback mock/mock-model" in notes[0]
+
+    def test_primary_success_skips_fallback(self) -> None:
+        """Should not touch the fallback when the primary answers."""
+        fallback = MockLLMClient()
+        client = FailoverLLMClient(MockLLMClient(), fallback)
+
+        asyncio.run(client.invoke([Message.user("hi")]))
+
+        assert fallback._call_count == 0
+
+    def test_request_error_not_failed_over(self) -> None:
+        """Should raise errors about the request itself."""
+        primary = MockLLMClient(errors=[self._server_error(400)])
+        client = FailoverLLMClient(primary, MockLLMClient())
+
+        with pytest.raises(Exception, match="server error"):
+            asyncio.run(client.invoke([Message.user("hi")]))
+
+    def test_stream_fails_over_before_output(self) -> None:
+        """Should stream from the fallback when the primary fails to start."""
+        primary = MockLLMClient(errors=[ConnectionError("refused"), None])
+        client = FailoverLLMClient(primary, MockLLMClient())
+
+        async def collect() -> str:
+            return "".join([chunk async for chunk in client.stream([Message.user("hi")])])
+
+        assert asyncio.run(collect()) == "Mock streaming response"
+
+    def test_is_availability_error(self) -> None:
+        """Should treat outages, rate limits and 5xx as availability errors."""
+        assert is_availability_error(ConnectionError())
+        assert is_availability_error(asyncio.TimeoutError())
+        assert is_availability_error(self._server_error(503))
+        assert is_availability_error(self._server_error(429))
+        assert not is_availability_error(self._server_error(401))
+        assert not is_availability_error(ValueError("bad arguments"))
+
+    def test_sdk_connection_errors_are_availability_errors(self) -> None:
+        """Should fail over on the SDKs' connection and timeout errors."""
+        import anthropic
+        import httpx
+        import openai
+
+        request = httpx.Request("POST", "https://api.example.com/v1/chat")
+        assert is_availability_error(anthropic.APIConnectionError(request=request))
+        assert is_availability_error(anthropic.APITimeoutError(request=request))
+        assert is_availability_error(openai.APIConnectionError(request=request))
+        assert is_availability_error(openai.APITimeoutError(request=request))
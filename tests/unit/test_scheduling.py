@@ -0,0 +1,98 @@
+"""Tests for scheduling plan steps into parallel batches."""
+
+from agentsh.agent.llm_client import ToolCall
+from agentsh.agent.scheduling import extract_dependencies, schedule_batches
+
+
+def _step(step_id: str, *depends_on: str) -> ToolCall:
+    """Build a step with dependencies."""
+    return ToolCall(
+        id=f"call_{step_id}",
+        name="shell.run",
+        arguments={},
+        step_id=step_id,
+        depends_on=list(depends_on),
+    )
+
+
+def _ids(batches: list[list[ToolCall]]) -> list[list[str]]:
+    """Get the step ids of each batch."""
+    return [[step.key for step in batch] for batch in batches]
+
+
+class TestScheduleBatches:
+    """Tests for schedule_batches."""
+
+    def test_no_dependencies_sequential(self) -> None:
+        """Should run steps one at a time without dependency info."""
+        steps = [_step("a"), _step("b"), _step("c")]
+
+        assert _ids(schedule_batches(steps)) == [["a"], ["b"], ["c"]]
+
+    def test_independent_downloads_then_extract(self) -> None:
+        """Should run independent steps together and dependents after them."""
+        steps = [
+            _step("get1"),
+            _step("get2"),
+            _step("get3"),
+            _step("unpack", "get1", "get2", "get3"),
+        ]
+
+        assert _ids(schedule_batches(steps)) == [["get1", "get2", "get3"], ["unpack"]]
+
+    def test_chain_and_diamond(self) -> None:
+        """Should follow transitive dependencies."""
+        steps = [
+            _step("build", "fetch"),
+            _step("fetch"),
+            _step("lint", "fetch"),
+            _step("deploy", "build", "lint"),
+        ]
+
+        assert _ids(schedule_batches(steps)) == [["fetch"], ["build", "lint"], ["deploy"]]
+
+    def test_unknown_dependency_ignored(self) -> None:
+        """Should treat dependencies on unknown steps as satisfied."""
+        steps = [_step("a", "missing"), _step("b", "a")]
+
+        assert _ids(schedule_batches(steps)) == [["a"], ["b"]]
+
+    def test_cycle_falls_back_to_sequential(self) -> None:
+        """Should run steps in order when dependencies form a cycle."""
+        steps = [_step("a", "b"), _step("b", "a"), _step("c")]
+
+        assert _ids(schedule_batches(steps)) == [["a"], ["b"], ["c"]]
+
+    def test_tool_call_id_as_key(self) -> None:
+        """Should reference steps without a step_id by their call id."""
+        first = ToolCall(id="call_1", name="shell.run", arguments={})
+        second = ToolCall(id="call_2", name="shell.run", arguments={}, depends_on=["call_1"])
+
+        assert schedule_batches([second, first]) == [[first], [second]]
+
+
+class TestExtractDependencies:
+    """Tests for extract_dependencies."""
+
+    def test_moves_arguments_to_fields(self) -> None:
+        """Should strip dependency arguments before the tool runs."""
+        call = ToolCall(
+            id="call_1",
+            name="shell.run",
+            arguments={"command": "tar xf a.tgz", "step_id": "x", "depends_on": ["d1", "d2"]},
+        )
+
+        extract_dependencies(call)
+
+        assert call.arguments == {"command": "tar xf a.tgz"}
+        assert call.step_id == "x"
+        assert call.depends_on == ["d1", "d2"]
+
+    def test_single_dependency_string(self) -> None:
+        """Should accept a single id instead of a list."""
+        call = ToolCall(id="call_1", name="shell.run", arguments={"depends_on": "d1"})
+
+        extract_dependencies(call)
+
+        assert call.depends_on == ["d1"]
+        assert call.key == "call_1"
@@ -4,11 +4,14 @@ from unittest.mock import MagicMock, patch
 
 import pytest
 
+from agentsh.security.approval import ApprovalKind
+from agentsh.security.classifier import RiskClassifier
 from agentsh.security.controller import (
     SecurityContext,
     SecurityController,
     SecurityDecision,
     ValidationResult,
+    auto_runnable,
 )
 
 
@@ -27,8 +30,14 @@ class MockRiskAssessment:
         # Make risk_level comparable
         level_order = {"SAFE": 0, "LOW": 1, "MEDIUM": 2, "HIGH": 3, "CRITICAL": 4}
         self.risk_level.__le__ = lambda self, other: level_order.get(self.name, 1) <= level_order.get(other.name, 1)
+        self.risk_level.__lt__ = lambda self, other: (
+            level_order.get(self.name, 1) < level_order.get(other.name, 1)
+        )
         self.is_blocked = is_blocked
         self.reasons = reasons or []
+        self.possible_exfiltration = False
+        self.too_long = False
+        self.opens_root_shell = False
 
 
 class MockUser:
@@ -260,6 +269,25 @@ class TestSecurityControllerCheck:
 
         assert decision.result == ValidationResult.NEED_APPROVAL
 
+    def test_needs_approval_when_too_long(
+        self, controller: SecurityController, context: SecurityContext
+    ) -> None:
+        """Should require approval for a command too long to review."""
+        risk_assessment = MockRiskAssessment(is_blocked=False)
+        risk_assessment.too_long = True
+        controller.classifier.classify.return_value = risk_assessment
+
+        mock_policy = MagicMock()
+        mock_policy.is_blocked_by_mode.return_value = False
+        mock_policy.requires_approval.return_value = False
+        controller.policy_manager.get_policy.return_value = mock_policy
+
+        controller.rbac.check_access.return_value = (True, False, "")
+
+        decision = controller.check("echo " + "a" * 9000, context)
+
+        assert decision.result == ValidationResult.NEED_APPROVAL
+
     def test_allowed(
         self, controller: SecurityController, context: SecurityContext
     ) -> None:
@@ -365,6 +393,243 @@ class TestSecurityControllerValidateAndApprove:
         controller.approval_flow.request_approval.assert_called_once()
         assert decision.result == ValidationResult.ALLOW
 
+    def test_always_this_session(self, controller: SecurityController) -> None:
+        """Should not re-prompt for the exact command approved for the session."""
+        from agentsh.security.approval import ApprovalResult
+
+        context = SecurityContext(user=MockUser(), interactive=True)
+        controller.classifier.classify.return_value = MockRiskAssessment(is_blocked=False)
+        mock_policy = MagicMock()
+        mock_policy.is_blocked_by_mode.return_value = False
+        mock_policy.requires_approval.return_value = True
+        controller.policy_manager.get_policy.return_value = mock_policy
+        controller.rbac.check_access.return_value = (True, False, "")
+        response = MockApprovalResponse(command="chown -R me project/")
+        response.result = ApprovalResult.APPROVED_SESSION
+        controller.approval_flow.request_approval.return_value = response
+
+        first = controller.validate_and_approve("chown -R me project/", context)
+        again = controller.validate_and_approve("chown -R me project/", context)
+
+        assert first.result == ValidationResult.ALLOW
+        assert again.result == ValidationResult.ALLOW
+        assert again.reason == "Allowed for this session"
+        controller.approval_flow.request_approval.assert_called_once()
+
+        controller.validate_and_approve("chown -R me other/", context)
+        assert controller.approval_flow.request_approval.call_count == 2
+
+
+class TestConfirmTool:
+    """Tests for SecurityController.confirm_tool method."""
+
+    @pytest.fixture
+    def controller(self) -> SecurityController:
+        """Create controller with mocks."""
+        return SecurityController(approval_flow=MagicMock(), audit_logger=MagicMock())
+
+    def test_approved(self, controller: SecurityController) -> None:
+        """Should allow the tool once the user approves."""
+        from agentsh.security.approval import ApprovalResult
+        from agentsh.security.classifier import RiskLevel
+
+        response = MockApprovalResponse()
+        response.result = ApprovalResult.APPROVED
+        controller.approval_flow.request_approval.return_value = response
+        context = SecurityContext(user=MockUser(), interactive=True)
+
+        decision = controller.confirm_tool('fs.delete {"path": "a"}', RiskLevel.HIGH, context)
+
+        assert decision.result == ValidationResult.ALLOW
+        request = controller.approval_flow.request_approval.call_args.args[0]
+        assert request.command == 'fs.delete {"path": "a"}'
+        assert request.reasons == ["Tool requires confirmation"]
+
+    def test_denied(self, controller: SecurityController) -> None:
+        """Should block the tool when the user declines."""
+        from agentsh.security.approval import ApprovalResult
+        from agentsh.security.classifier import RiskLevel
+
+        response = MockApprovalResponse()
+        response.result = ApprovalResult.DENIED
+        controller.approval_flow.request_approval.return_value = response
+        context = SecurityContext(user=MockUser(), interactive=True)
+
+        decision = controller.confirm_tool("fs.delete", RiskLevel.HIGH, context)
+
+        assert decision.result == ValidationResult.BLOCKED
+
+    def test_always_this_session(self, controller: SecurityController) -> None:
+        """Should not re-prompt for a tool call approved for the session."""
+        from agentsh.security.approval import ApprovalResult
+        from agentsh.security.classifier import RiskLevel
+
+        response = MockApprovalResponse()
+        response.result = ApprovalResult.APPROVED_SESSION
+        controller.approval_flow.request_approval.return_value = response
+        context = SecurityContext(user=MockUser(), interactive=True)
+
+        controller.confirm_tool('fs.delete {"path": "a"}', RiskLevel.MEDIUM, context)
+        decision = controller.confirm_tool('fs.delete {"path": "a"}', RiskLevel.MEDIUM, context)
+
+        assert decision.result == ValidationResult.ALLOW
+        controller.approval_flow.request_approval.assert_called_once()
+
+        controller.confirm_tool('fs.delete {"path": "b"}', RiskLevel.MEDIUM, context)
+        assert controller.approval_flow.request_approval.call_count == 2
+
+    def test_high_risk_not_trusted_for_session(self, controller: SecurityController) -> None:
+        """Should ask again for a HIGH risk tool call, even if approved for the session."""
+        from agentsh.security.approval import ApprovalResult
+        from agentsh.security.classifier import RiskLevel
+
+        response = MockApprovalResponse()
+        response.result = ApprovalResult.APPROVED_SESSION
+        controller.approval_flow.request_approval.return_value = response
+        context = SecurityContext(user=MockUser(), interactive=True)
+
+        controller.confirm_tool('fs.delete {"path": "a"}', RiskLevel.HIGH, context)
+        controller.confirm_tool('fs.delete {"path": "a"}', RiskLevel.HIGH, context)
+
+        assert controller.approval_flow.request_approval.call_count == 2
+
+    def test_non_interactive_blocks(self, controller: SecurityController) -> None:
+        """Should block without prompting when nobody can answer."""
+        from agentsh.security.classifier import RiskLevel
+
+        context = SecurityContext(user=MockUser(), interactive=False)
+
+        decision = controller.confirm_tool("fs.delete", RiskLevel.HIGH, context)
+
+        assert decision.result == ValidationResult.BLOCKED
+        assert "non-interactively" in decision.reason
+        controller.approval_flow.request_approval.assert_not_called()
+
+
+class TestRedactedApproval:
+    """Tests for masking secrets in approval prompts."""
+
+    def test_shown_redacted_but_run_unchanged(self) -> None:
+        """Should show the secret masked and return the real command to run."""
+        from agentsh.security.approval import ApprovalFlow
+
+        output = MagicMock()
+        rbac = MagicMock()
+        rbac.check_access.return_value = (True, False, "")
+        controller = SecurityController(
+            rbac=rbac,
+            approval_flow=ApprovalFlow(
+                use_color=False,
+                input_func=MagicMock(return_value="y"),
+                output_func=output,
+            ),
+            audit_logger=MagicMock(),
+        )
+        command = "rm -rf ./cache && deploy --api-key=9f8e7d6c5b4a3210fedc"
+        context = SecurityContext(user=MockUser(), interactive=True)
+
+        decision = controller.validate_and_approve(command, context)
+
+        shown = "\n".join(call[0][0] for call in output.call_args_list)
+        assert "9f8e7d6c5b4a3210fedc" not in shown
+        assert "api-key=***REDACTED***" in shown
+        assert decision.result == ValidationResult.ALLOW
+        assert decision.command == command
+
+
+class TestAutoRunAllowlist:
+    """Tests for the autonomous-mode allowlist."""
+
+    @pytest.fixture
+    def controller(self) -> SecurityController:
+        """Create a controller allowing only ls to run unasked."""
+        rbac = MagicMock()
+        rbac.check_access.return_value = (True, False, "")
+        return SecurityController(
+            rbac=rbac,
+            approval_flow=MagicMock(),
+            audit_logger=MagicMock(),
+            auto_run_allowlist=[r"ls(\s.*)?"],
+        )
+
+    def test_allowlisted_command_auto_runs(self, controller: SecurityController) -> None:
+        """Should allow an allowlisted command without concerns."""
+        decision = controller.check("ls -la", SecurityContext(user=MockUser()))
+
+        assert decision.result == ValidationResult.ALLOW
+
+    def test_allowlist_matches_each_command_in_full(self) -> None:
+        """Should require every simple command to match a pattern in full."""
+        assessment = RiskClassifier().classify("ls")
+        assert auto_runnable("ls -la | wc -l", assessment, [r"ls(\s.*)?", "wc -l"])
+        assert not auto_runnable("cols", assessment, ["ls"])
+        assert not auto_runnable("ls; pwd", assessment, ["ls"])
+        assert not auto_runnable("ls -la && pwd", assessment, [r"ls(\s.*)?"])
+
+    def test_safe_command_not_allowlisted_prompts(self, controller: SecurityController) -> None:
+        """Should require approval for a safe command missing from the allowlist."""
+        decision = controller.check("pwd", SecurityContext(user=MockUser()))
+
+        assert decision.result == ValidationResult.NEED_APPROVAL
+        assert "auto_run_allowlist" in decision.reason
+
+    def test_allowlisted_command_with_concern_prompts(
+        self, controller: SecurityController
+    ) -> None:
+        """Should still require approval when an allowlisted command raises a concern."""
+        decision = controller.check("ls $(cat targets)", SecurityContext(user=MockUser()))
+
+        assert decision.result == ValidationResult.NEED_APPROVAL
+
+    def test_without_allowlist_policy_decides(self) -> None:
+        """Should leave safe commands to the policy when no allowlist is set."""
+        rbac = MagicMock()
+        rbac.check_access.return_value = (True, False, "")
+        controller = SecurityController(rbac=rbac, audit_logger=MagicMock())
+
+        decision = controller.check("pwd", SecurityContext(user=MockUser()))
+
+        assert decision.result == ValidationResult.ALLOW
+
+
+class TestRetypeForCritical:
+    """Tests for security.require_retype_for_critical."""
+
+    def _controller(self, require_retype: bool) -> SecurityController:
+        """Create a controller with real classification and a mock approval flow."""
+        rbac = MagicMock()
+        rbac.check_access.return_value = (True, False, "")
+        approval_flow = MagicMock()
+        approval_flow.request_approval.return_value = MockApprovalResponse(result="DENIED")
+        return SecurityController(
+            rbac=rbac,
+            approval_flow=approval_flow,
+            audit_logger=MagicMock(),
+            require_retype_for_critical=require_retype,
+        )
+
+    def test_critical_service_requires_retype(self) -> None:
+        """Should ask for a retype when a critical service is affected."""
+        controller = self._controller(require_retype=True)
+        context = SecurityContext(user=MockUser(), interactive=True)
+
+        controller.validate_and_approve("systemctl stop sshd", context)
+
+        request = controller.approval_flow.request_approval.call_args[0][0]
+        assert request.require_retype
+
+    def test_other_commands_not_retyped(self) -> None:
+        """Should keep the usual prompt for other risky commands, or when disabled."""
+        context = SecurityContext(user=MockUser(), interactive=True)
+        enabled = self._controller(require_retype=True)
+        disabled = self._controller(require_retype=False)
+
+        enabled.validate_and_approve("rm -rf build", context)
+        disabled.validate_and_approve("systemctl stop sshd", context)
+
+        assert not enabled.approval_flow.request_approval.call_args[0][0].require_retype
+        assert not disabled.approval_flow.request_approval.call_args[0][0].require_retype
+
 
 class TestHandleApprovalResponse:
     """Tests for _handle_approval_response method."""
@@ -416,6 +681,27 @@ class TestHandleApprovalResponse:
         assert decision.result == ValidationResult.ALLOW
         assert decision.approved_by == "testuser"
         controller.audit.log_command_approved.assert_called_once()
+        assert decision.approval == ApprovalKind.CONFIRMED
+
+    def test_retyped(
+        self,
+        controller: SecurityController,
+        original_decision: SecurityDecision,
+        context: SecurityContext,
+    ) -> None:
+        """Should record approvals that took retyping the command."""
+        from agentsh.security.approval import ApprovalResult
+
+        response = MagicMock()
+        response.result = ApprovalResult.APPROVED
+        response.command = "rm -rf /srv/old"
+        response.approver = "testuser"
+
+        decision = controller._handle_approval_response(
+            response, original_decision, context, retyped=True
+        )
+
+        assert decision.approval == ApprovalKind.RETYPED
 
     def test_edited_and_allowed(
         self,
@@ -447,6 +733,7 @@ class TestHandleApprovalResponse:
 
         assert decision.result == ValidationResult.ALLOW
         assert decision.command == "ls -la"
+        assert decision.approval == ApprovalKind.EDITED
 
     def test_edited_but_blocked(
         self,
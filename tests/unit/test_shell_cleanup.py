@@ -0,0 +1,165 @@
+"""Tests for crash cleanup of child shells."""
+
+import gc
+import signal
+import sys
+from unittest.mock import MagicMock, patch
+
+import pytest
+
+from agentsh.shell.cleanup import (
+    ChildGuard,
+    install_crash_hook,
+    reap_children,
+    restore_terminal,
+    save_terminal,
+)
+
+
+class StubChild:
+    """Child process stub recording kill and wait calls."""
+
+    def __init__(self, alive: bool = True) -> None:
+        self.alive = alive
+        self.calls: list[str] = []
+
+    def isalive(self) -> bool:
+        return self.alive
+
+    def kill(self, sig: int) -> None:
+        self.calls.append(f"kill {sig}")
+        self.alive = False
+
+    def wait(self) -> int:
+        self.calls.append("wait")
+        return 0
+
+
+class TestChildGuard:
+    """Tests for ChildGuard."""
+
+    def test_drop_kills_and_waits(self) -> None:
+        """Should kill and reap a running child when the guard is dropped."""
+        child = StubChild()
+        guard = ChildGuard(child)
+
+        del guard
+        gc.collect()
+
+        assert child.calls == [f"kill {signal.SIGKILL}", "wait"]
+
+    def test_exited_child_only_waited(self) -> None:
+        """Should only reap a child that already exited."""
+        child = StubChild(alive=False)
+
+        with ChildGuard(child):
+            pass
+
+        assert child.calls == ["wait"]
+
+    def test_reap_once(self) -> None:
+        """Should not touch the child again after reaping."""
+        child = StubChild()
+        guard = ChildGuard(child)
+
+        guard.reap()
+        guard.reap()
+
+        assert child.calls == [f"kill {signal.SIGKILL}", "wait"]
+        assert not guard.active
+
+    def test_reap_errors_swallowed(self) -> None:
+        """Should not raise when the child cannot be reaped."""
+        child = MagicMock()
+        child.isalive.side_effect = OSError("gone")
+
+        ChildGuard(child).reap()
+
+    def test_reap_children(self) -> None:
+        """Should reap every guarded child still alive."""
+        children = [StubChild(), StubChild()]
+        guards = [ChildGuard(child) for child in children]
+
+        assert reap_children() == 2
+        assert all(child.calls[-1] == "wait" for child in children)
+        assert not any(guard.active for guard in guards)
+
+
+class TestSaveTerminal:
+    """Tests for save_terminal and restore_terminal."""
+
+    def test_round_trip(self) -> None:
+        """Should put back the settings saved from the terminal."""
+        stream = MagicMock()
+        stream.isatty.return_value = True
+        stream.fileno.return_value = 5
+
+        with patch("agentsh.shell.cleanup.termios") as mock_termios:
+            mock_termios.tcgetattr.return_value = ["saved"]
+            saved = save_terminal(stream)
+            restore_terminal(saved)
+
+        assert saved == (5, ["saved"])
+        mock_termios.tcsetattr.assert_called_once_with(
+            5, mock_termios.TCSADRAIN, ["saved"]
+        )
+
+    def test_not_a_terminal(self) -> None:
+        """Should save nothing, and restore nothing, without a terminal."""
+        stream = MagicMock()
+        stream.isatty.return_value = False
+
+        with patch("agentsh.shell.cleanup.termios") as mock_termios:
+            saved = save_terminal(stream)
+            restore_terminal(saved)
+
+        assert saved is None
+        mock_termios.tcsetattr.assert_not_called()
+
+
+class TestInstallCrashHook:
+    """Tests for install_crash_hook."""
+
+    @pytest.fixture(autouse=True)
+    def restore_excepthook(self):
+        """Put the original exception hook back after each test."""
+        original = sys.excepthook
+        yield
+        sys.excepthook = original
+
+    def test_hook_restores_terminal_and_reaps(self) -> None:
+        """Should restore terminal settings and reap children before the old hook."""
+        previous = MagicMock()
+        sys.excepthook = previous
+        stream = MagicMock()
+        stream.isatty.return_value = True
+        stream.fileno.return_value = 7
+        child = StubChild()
+        guard = ChildGuard(child)
+
+        with patch("agentsh.shell.cleanup.termios") as mock_termios:
+            mock_termios.tcgetattr.return_value = ["saved"]
+            install_crash_hook(stream)
+            error = RuntimeError("boom")
+            sys.excepthook(RuntimeError, error, None)
+
+        mock_termios.tcsetattr.assert_called_once_with(
+            7, mock_termios.TCSADRAIN, ["saved"]
+        )
+        assert child.calls == [f"kill {signal.SIGKILL}", "wait"]
+        assert not guard.active
+        previous.assert_called_once_with(RuntimeError, error, None)
+
+    def test_hook_without_terminal(self) -> None:
+        """Should skip terminal restore when not attached to a terminal."""
+        previous = MagicMock()
+        sys.excepthook = previous
+        stream = MagicMock()
+        stream.isatty.return_value = False
+
+        with patch("agentsh.shell.cleanup.termios") as mock_termios:
+            install_crash_hook(stream)
+            sys.excepthook(ValueError, ValueError(), None)
+
+        mock_termios.tcsetattr.assert_not_called()
+        previous.assert_called_once()
@@ -0,0 +1,211 @@
+"""Tests for shell exit status hooks."""
+
+import shutil
+import time
+from pathlib import Path
+
+import pytest
+
+from agentsh.shell.hooks import (
+    BASH_PROMPT_COMMAND,
+    ShellStatusTracker,
+    hook_environment,
+    parse_cwd,
+    parse_exit_status,
+    report_command,
+)
+
+
+class TestParseExitStatus:
+    """Tests for extracting statuses from hook lines."""
+
+    def test_bel_terminated(self) -> None:
+        """Should parse a BEL-terminated marker."""
+        assert parse_exit_status(b"\x1b]133;D;1\x07") == 1
+
+    def test_st_terminated(self) -> None:
+        """Should parse an ST-terminated marker."""
+        assert parse_exit_status(b"\x1b]133;D;127\x1b\\") == 127
+
+    def test_last_marker_wins(self) -> None:
+        """Should report the newest status in a chunk."""
+        data = b"\x1b]133;D;0\x07out\r\n\x1b]133;A\x07\x1b]133;D;2\x07"
+        assert parse_exit_status(data) == 2
+
+    def test_surrounded_by_output(self) -> None:
+        """Should find the marker amid other escape sequences."""
+        data = b"ls: cannot access 'x'\r\n\x1b]133;D;2\x07\x1b]7;file://host/tmp\x07$ "
+        assert parse_exit_status(data) == 2
+
+    def test_no_marker(self) -> None:
+        """Should return None without a marker."""
+        assert parse_exit_status(b"plain output\r\n") is None
+        assert parse_exit_status(b"\x1b]133;A\x07") is None
+
+    def test_incomplete_marker(self) -> None:
+        """Should ignore an unterminated marker."""
+        assert parse_exit_status(b"\x1b]133;D;1") is None
+
+
+class TestParseCwd:
+    """Tests for extracting the working directory from OSC 7 markers."""
+
+    def test_bel_terminated(self) -> None:
+        """Should parse the path of a BEL-terminated marker."""
+        assert parse_cwd(b"\x1b]7;file://host/home/me/src\x07") == "/home/me/src"
+
+    def test_st_terminated_without_host(self) -> None:
+        """Should parse an ST-terminated marker with an empty host."""
+        assert parse_cwd(b"\x1b]7;file:///tmp\x1b\\") == "/tmp"
+
+    def test_percent_decoded(self) -> None:
+        """Should decode percent-escaped characters in the path."""
+        assert parse_cwd(b"\x1b]7;file://host/tmp/my%20dir\x07") == "/tmp/my dir"
+
+    def test_last_marker_wins(self) -> None:
+        """Should report the newest directory amid other output."""
+        data = b"\x1b]7;file://h/a\x07$ cd /b\r\n\x1b]133;D;0\x07\x1b]7;file://h/b\x07$ "
+        assert parse_cwd(data) == "/b"
+
+    def test_no_or_incomplete_marker(self) -> None:
+        """Should return None without a complete marker."""
+        assert parse_cwd(b"plain output\r\n") is None
+        assert parse_cwd(b"\x1b]7;file://host/tmp") is None
+
+
+class TestShellStatusTracker:
+    """Tests for following statuses across reads."""
+
+    def test_tracks_latest(self) -> None:
+        """Should remember the latest status."""
+        tracker = ShellStatusTracker()
+        assert tracker.last_exit_code is None
+
+        tracker.feed(b"\x1b]133;D;1\x07")
+        tracker.feed(b"more output")
+
+        assert tracker.last_exit_code == 1
+
+    def test_marker_split_across_reads(self) -> None:
+        """Should reassemble a marker split between chunks."""
+        tracker = ShellStatusTracker()
+
+        assert tracker.feed(b"output\x1b]13") is None
+        assert tracker.feed(b"3;D;12") is None
+        assert tracker.feed(b"7\x07$ ") == 127
+        assert tracker.last_exit_code == 127
+
+    def test_split_terminator(self) -> None:
+        """Should handle an ST terminator split between chunks."""
+        tracker = ShellStatusTracker()
+
+        tracker.feed(b"\x1b]133;D;0\x1b")
+        tracker.feed(b"\\")
+
+        assert tracker.last_exit_code == 0
+
+
+    def test_cwd_split_across_reads(self) -> None:
+        """Should reassemble a directory report split between chunks."""
+        tracker = ShellStatusTracker()
+
+        tracker.feed(b"out\x1b]7;file://host/home/")
+        assert tracker.cwd is None
+        tracker.feed(b"me/project\x07$ ")
+
+        assert tracker.cwd == "/home/me/project"
+
+
+class TestReportCommand:
+    """Tests for the report appended to commands run with -c."""
+
+    def test_posix_shell_reports(self) -> None:
+        """Should run the report on its own line after the command."""
+        assert report_command("/bin/sh", "ls # note") == f"ls # note\n{BASH_PROMPT_COMMAND}"
+
+    def test_other_shells_unchanged(self) -> None:
+        """Should leave commands for non-POSIX shells alone."""
+        assert report_command("/usr/bin/fish", "ls") == "ls"
+
+    def test_reports_status_and_cwd(self, tmp_path: Path) -> None:
+        """Should report the command's status and directory and keep its status."""
+        import subprocess
+
+        result = subprocess.run(
+            ["/bin/sh", "-c", report_command("/bin/sh", f"cd '{tmp_path}'; (exit 4)")],
+            capture_output=True,
+        )
+
+        assert result.returncode == 4
+        tracker = ShellStatusTracker()
+        tracker.feed(result.stdout)
+        assert tracker.last_exit_code == 4
+        assert tracker.cwd == str(tmp_path)
+
+
+class TestHookEnvironment:
+    """Tests for injecting the hook into a shell's environment."""
+
+    def test_bash_prompt_command(self) -> None:
+        """Should set PROMPT_COMMAND for bash."""
+        env = hook_environment("/bin/bash", {"PATH": "/bin"})
+        assert env["PROMPT_COMMAND"] == BASH_PROMPT_COMMAND
+
+    def test_existing_prompt_command_kept(self) -> None:
+        """Should run the hook before an existing PROMPT_COMMAND."""
+        env = hook_environment("/bin/bash", {"PROMPT_COMMAND": "history -a"})
+        assert env["PROMPT_COMMAND"] == f"{BASH_PROMPT_COMMAND};history -a"
+
+    def test_not_added_twice(self) -> None:
+        """Should not duplicate an installed hook."""
+        once = hook_environment("/bin/bash", {})
+        assert hook_environment("/bin/bash", once) == once
+
+    def test_other_shells_untouched(self) -> None:
+        """Should leave shells that ignore PROMPT_COMMAND alone."""
+        env = {"PATH": "/bin"}
+        assert hook_environment("/usr/bin/zsh", env) == env
+
+    @pytest.mark.skipif(shutil.which("bash") is None, reason="bash not available")
+    def test_bash_reports_status(self, tmp_path: Path) -> None:
+        """Should capture the status of a command typed into bash."""
+        from agentsh.shell.pty_manager import PTYManager
+
+        pty = PTYManager(
+            shell_path=shutil.which("bash"),
+            env={"PATH": "/usr/bin:/bin", "HOME": str(tmp_path), "PS1": "$ "},
+            cwd=tmp_path,
+        )
+        pty.spawn()
+        try:
+            pty.write_line("(exit 3)")
+            deadline = time.time() + 5
+            while pty.last_command_status != 3 and time.time() < deadline:
+                pty.read(timeout=0.2)
+        finally:
+            pty.close(force=True)
+
+        assert pty.last_command_status == 3
+
+    @pytest.mark.skipif(shutil.which("bash") is None, reason="bash not available")
+    def test_bash_reports_cwd(self, tmp_path: Path) -> None:
+        """Should follow a cd typed into bash."""
+        from agentsh.shell.pty_manager import PTYManager
+
+        target = tmp_path / "sub dir"
+        target.mkdir()
+        pty = PTYManager(
+            shell_path=shutil.which("bash"),
+            env={"PATH": "/usr/bin:/bin", "HOME": str(tmp_path), "PS1": "$ "},
+            cwd=tmp_path,
+        )
+        pty.spawn()
+        try:
+            pty.write_line(f"cd '{target}'")
+            deadline = time.time() + 5
+            while pty.current_directory != str(target) and time.time() < deadline:
+                pty.read(timeout=0.2)
+        finally:
+            pty.close(force=True)
+
+        assert pty.current_directory == str(target)
@@ -0,0 +1,131 @@
+"""Tests for system information helpers."""
+
+import sys
+import time
+from unittest.mock import patch
+
+import pytest
+
+from agentsh.utils.sysinfo import (
+    UNKNOWN,
+    SystemContext,
+    collect_system_context,
+    get_command_output,
+    get_hostname,
+    get_os_version,
+)
+
+
+class TestGetCommandOutput:
+    """Tests for get_command_output function."""
+
+    def test_returns_stripped_stdout(self) -> None:
+        """Should return the command's stdout without surrounding whitespace."""
+        result = get_command_output([sys.executable, "-c", "print('  hello  ')"])
+        assert result == "hello"
+
+    def test_nonzero_exit_returns_none(self) -> None:
+        """Should return None when the command fails."""
+        result = get_command_output([sys.executable, "-c", "import sys; sys.exit(3)"])
+        assert result is None
+
+    def test_missing_command_returns_none(self) -> None:
+        """Should return None when the command does not exist."""
+        assert get_command_output(["definitely-not-a-real-command-xyz"]) is None
+
+    def test_timeout_returns_none(self) -> None:
+        """Should kill a hung command and return None after the timeout."""
+        start = time.monotonic()
+        result = get_command_output(
+            [sys.executable, "-c", "import time; time.sleep(10)"],
+            timeout=0.2,
+        )
+        elapsed = time.monotonic() - start
+
+        assert result is None
+        assert elapsed < 5
+
+
+class TestGetHostname:
+    """Tests for get_hostname function."""
+
+    def test_returns_nodename(self) -> None:
+        """Should return a non-empty hostname."""
+        assert get_hostname()
+
+    def test_falls_back_to_command(self) -> None:
+        """Should use the hostname command if uname has no nodename."""
+        with patch("agentsh.utils.sysinfo.os.uname") as mock_uname, patch(
+            "agentsh.utils.sysinfo.get_command_output", return_value="box"
+        ):
+            mock_uname.return_value.nodename = ""
+            assert get_hostname() == "box"
+
+
+class TestGetOsVersion:
+    """Tests for get_os_version function."""
+
+    def test_returns_description(self) -> None:
+        """Should return a non-empty OS description."""
+        assert get_os_version()
+
+    def test_fallback_to_platform(self) -> None:
+        """Should fall back to system and release for other platforms."""
+        with patch("agentsh.utils.sysinfo.platform.system", return_value="Plan9"), patch(
+            "agentsh.utils.sysinfo.platform.release", return_value="4"
+        ):
+            assert get_os_version() == "Plan9 4"
+
+
+class TestCollectSystemContext:
+    """Tests for the concurrent system context collector."""
+
+    @pytest.mark.asyncio
+    async def test_collects_probes(self) -> None:
+        """Should fill fields from the probes' output."""
+        context = await collect_system_context(
+            probes={
+                "hostname": [sys.executable, "-c", "print('box')"],
+                "kernel": [sys.executable, "-c", "print('Linux 6.1')"],
+            }
+        )
+
+        assert context.hostname == "box"
+        assert context.kernel == "Linux 6.1"
+        assert context.timed_out == []
+
+    @pytest.mark.asyncio
+    async def test_slow_probe_bounded_by_timeout(self) -> None:
+        """Should stop waiting for a hung probe and return partial context."""
+        start = time.monotonic()
+        context = await collect_system_context(
+            timeout=0.5,
+            probes={
+                "hostname": [sys.executable, "-c", "import time; time.sleep(10)"],
+                "kernel": [sys.executable, "-c", "print('Linux 6.1')"],
+            },
+        )
+        elapsed = time.monotonic() - start
+
+        assert elapsed < 5
+        assert context.hostname == UNKNOWN
+        assert context.kernel == "Linux 6.1"
+        assert context.timed_out == ["hostname"]
+
+    @pytest.mark.asyncio
+    async def test_failed_probe_unknown(self) -> None:
+        """Should leave the field unknown when a probe cannot run."""
+        context = await collect_system_context(
+            probes={"kernel": ["definitely-not-a-real-command-xyz"]}
+        )
+
+        assert context.kernel == UNKNOWN
+        assert context.timed_out == []
+
+    def test_os_info(self) -> None:
+        """Should describe the OS, leaving out unknown parts."""
+        assert SystemContext(os_version="Ubuntu 22.04", kernel="Linux 6.1").os_info() == (
+            "Ubuntu 22.04 (Linux 6.1)"
+        )
+        assert SystemContext(kernel="Linux 6.1").os_info() == "Linux 6.1"
+        assert SystemContext().os_info() is None
@@ -378,16 +378,20 @@ class TestEventFactories:
         assert event.data["success"] is False
 
     def test_tool_completed_event_long_result(self) -> None:
-        """Should truncate long result preview."""
-        long_result = "x" * 500
+        """Should keep the head and tail of a long result preview."""
+        long_result = "first line\n" + "x" * 5000 + "\nError: failed"
         event = tool_completed_event(
             tool_name="test",
             success=True,
             duration_ms=10,
             result_preview=long_result,
+            preview_bytes=200,
         )
 
-        assert len(event.data["result_preview"]) == 200
+        preview = event.data["result_preview"]
+        assert preview.startswith("first line")
+        assert preview.endswith("Error: failed")
+        assert "bytes elided" in preview
 
     def test_workflow_event(self) -> None:
         """Should create workflow event."""
@@ -255,6 +255,39 @@ class TestToolRegistryExtended:
         assert len(tool.examples) == 2
         assert tool.plugin_name == "test_plugin"
 
+    def test_confirmation_override(self, tool_registry: ToolRegistry) -> None:
+        """Should let an override replace the tool's confirmation default."""
+        tool_registry.register_tool(
+            name="fs.delete",
+            handler=lambda path: path,
+            description="Delete a file",
+            parameters={"type": "object", "properties": {}},
+            requires_confirmation=True,
+        )
+        assert tool_registry.requires_confirmation("fs.delete") is True
+
+        tool_registry.set_confirmation_override("fs.delete", False)
+        assert tool_registry.requires_confirmation("fs.delete") is False
+
+        tool_registry.set_confirmation_override("fs.delete", None)
+        assert tool_registry.requires_confirmation("fs.delete") is True
+
+    def test_confirmation_override_before_registration(
+        self, tool_registry: ToolRegistry
+    ) -> None:
+        """Should apply an override to a tool registered later."""
+        tool_registry.set_confirmation_override("net.fetch", True)
+        assert tool_registry.requires_confirmation("unknown.tool") is False
+
+        tool_registry.register_tool(
+            name="net.fetch",
+            handler=lambda url: url,
+            description="Fetch a URL",
+            parameters={"type": "object", "properties": {}},
+        )
+
+        assert tool_registry.requires_confirmation("net.fetch") is True
+
 
 class TestGetToolRegistry:
     """Tests for get_tool_registry function."""
@@ -263,6 +263,8 @@ class TestToolRunner:
 
         assert result.duration_ms is not None
         assert result.duration_ms >= 0
+        assert result.started_at is not None
+        assert result.started_at.tzinfo is not None
 
 
 class TestToolRunnerTypeValidation:
@@ -598,6 +600,41 @@ class TestToolRunnerSecurity:
 
         assert result.success
 
+    def test_tool_policy_requires_confirmation(
+        self,
+        tool_registry: ToolRegistry,
+        mock_security_controller: MagicMock,
+        mock_risk_assessment: MagicMock,
+    ) -> None:
+        """Should ask for confirmation when a policy requires it."""
+        from agentsh.security.controller import SecurityDecision, ValidationResult
+
+        mock_security_controller.confirm_tool.return_value = SecurityDecision(
+            result=ValidationResult.BLOCKED,
+            command="math.add",
+            risk_assessment=mock_risk_assessment,
+            reason="User denied",
+        )
+        runner = ToolRunner(tool_registry, security_controller=mock_security_controller)
+        tool_registry.register_tool(
+            name="math.add",
+            handler=lambda a, b: ToolResult(success=True, output=str(a + b)),
+            description="Add numbers",
+            parameters={"type": "object", "properties": {}},
+        )
+
+        result = asyncio.run(runner.execute("math.add", {"a": 1, "b": 2}))
+        assert result.success
+        mock_security_controller.confirm_tool.assert_not_called()
+
+        tool_registry.set_confirmation_override("math.add", True)
+        result = asyncio.run(runner.execute("math.add", {"a": 1, "b": 2}))
+
+        assert not result.success
+        assert result.error == "Security: User denied"
+        description = mock_security_controller.confirm_tool.call_args.args[0]
+        assert description == 'math.add {"a": 1, "b": 2}'
+
 
 class TestToolRunnerRetries:
     """Test retry logic in tool runner."""
@@ -2,13 +2,14 @@
 
 import asyncio
 import os
+import shutil
 import pytest
 import tempfile
 from pathlib import Path
 
 from agentsh.tools.base import RiskLevel, ToolResult
 from agentsh.tools.registry import ToolRegistry
-from agentsh.plugins.builtin.shell import ShellToolset
+from agentsh.plugins.builtin.shell import DEFAULT_EXEC_SHELL, ShellToolset, resolve_exec_shell
 from agentsh.plugins.builtin.filesystem import FilesystemToolset
 from agentsh.plugins.builtin.process import ProcessToolset
 from agentsh.plugins.builtin.code import CodeToolset
@@ -49,6 +50,27 @@ class TestShellToolset:
         assert not result.success
         assert result.exit_code == 1
 
+    def test_run_command_expected_exit_code(self, shell_toolset: ShellToolset) -> None:
+        """Should treat an expected nonzero exit code as success."""
+        result = asyncio.run(
+            shell_toolset.run_command("grep -q missing /dev/null", expected_exit_codes=[0, 1])
+        )
+
+        assert result.success
+        assert result.exit_code == 1
+        assert "exit code 1, expected" in result.output
+
+        result = asyncio.run(shell_toolset.run_command("exit 2", expected_exit_codes=[0, 1]))
+        assert not result.success
+
+    def test_run_command_expected_codes_exact(self, shell_toolset: ShellToolset) -> None:
+        """Should fail on exit code 0 when it is not among the expected codes."""
+        result = asyncio.run(shell_toolset.run_command("true", expected_exit_codes=[1]))
+
+        assert not result.success
+        assert result.exit_code == 0
+        assert result.error == "Command exited with code 0, expected 1"
+
     def test_run_command_timeout(self, shell_toolset: ShellToolset) -> None:
         """Should handle command timeout."""
         result = asyncio.run(
@@ -67,6 +89,51 @@ class TestShellToolset:
         assert not result.success
         assert "does not exist" in result.error.lower()
 
+    def test_run_command_missing_cwd_fails_step(self, shell_toolset: ShellToolset) -> None:
+        """Should fail with an exit code instead of running elsewhere."""
+        result = asyncio.run(
+            shell_toolset.run_command("touch marker", cwd="/nonexistent/path")
+        )
+
+        assert not result.success
+        assert result.exit_code == 1
+        assert "working directory not found" in result.error.lower()
+        assert not os.path.exists("marker")
+
+    def test_run_command_cwd_is_file(self, shell_toolset: ShellToolset, tmp_path) -> None:
+        """Should reject a working directory that is a file."""
+        target = tmp_path / "file.txt"
+        target.write_text("x")
+
+        result = asyncio.run(shell_toolset.run_command("pwd", cwd=str(target)))
+
+        assert not result.success
+        assert "not a directory" in result.error
+
+    def test_run_command_valid_cwd(self, shell_toolset: ShellToolset, tmp_path) -> None:
+        """Should run the command in the requested directory."""
+        result = asyncio.run(shell_toolset.run_command("pwd", cwd=str(tmp_path)))
+
+        assert result.success
+        assert result.output == os.path.realpath(tmp_path)
+
+    def test_run_command_protected_cwd_refused(self, shell_toolset: ShellToolset) -> None:
+        """Should refuse protected working directories without confirmation."""
+        result = asyncio.run(shell_toolset.run_command("pwd", cwd="/etc"))
+
+        assert not result.success
+        assert "protected path /etc" in result.error
+
+    def test_run_command_protected_cwd_confirmed(self) -> None:
+        """Should run in a protected directory once confirmed."""
+        asked: list[str] = []
+        toolset = ShellToolset(confirm_cwd=lambda path: asked.append(path) or True)
+
+        result = asyncio.run(toolset.run_command("pwd", cwd="/etc"))
+
+        assert result.success
+        assert asked == ["/etc"]
+
     def test_explain_command(self, shell_toolset: ShellToolset) -> None:
         """Should explain command."""
         result = shell_toolset.explain_command("ls -la /tmp")
@@ -513,6 +580,59 @@ class TestShellToolsetExtended:
         assert result.success
         assert "/tmp" in result.output
 
+    def test_run_command_invalid_utf8_lossy(self, shell_toolset: ShellToolset) -> None:
+        """Should replace invalid bytes by default."""
+        result = asyncio.run(shell_toolset.run_command(r"printf 'ok\377\376'"))
+        assert result.success
+        assert result.output == "ok\ufffd\ufffd"
+
+    @pytest.mark.skipif(shutil.which("bash") is None, reason="bash not installed")
+    def test_run_command_bashism_in_bash(self) -> None:
+        """Should run bash-only syntax when the shell is bash."""
+        toolset = ShellToolset(exec_shell="bash")
+        result = asyncio.run(
+            toolset.run_command("arr=(a b c); [[ ${#arr[@]} -eq 3 ]] && echo ${arr[1]}")
+        )
+        assert result.success
+        assert result.output == "b"
+
+    def test_run_command_exec_shell_override(self) -> None:
+        """Should run commands with shell.exec_shell rather than shell.backend."""
+        toolset = ShellToolset(exec_shell="sh", backend="bash")
+        result = asyncio.run(toolset.run_command("echo $0"))
+        assert result.success
+        assert os.path.basename(result.output) == "sh"
+
+    def test_resolve_exec_shell(self, tmp_path: Path) -> None:
+        """Should prefer exec_shell, then the backend, then fall back to sh."""
+        sh = shutil.which("sh")
+        assert resolve_exec_shell("sh", "/no/such/zsh") == sh
+        assert resolve_exec_shell(None, sh) == sh
+        assert resolve_exec_shell(str(tmp_path / "missing"), None) == DEFAULT_EXEC_SHELL
+        assert resolve_exec_shell(None, None) == DEFAULT_EXEC_SHELL
+
+    def test_resolve_exec_shell_ignores_shell_variable(
+        self, monkeypatch: pytest.MonkeyPatch
+    ) -> None:
+        """Should not run commands with $SHELL, which may be agentsh itself."""
+        monkeypatch.setenv("SHELL", shutil.which("sh") or "/bin/sh")
+        assert resolve_exec_shell(None, None) == DEFAULT_EXEC_SHELL
+
+    def test_resolve_exec_shell_skips_non_posix_shells(self, tmp_path: Path) -> None:
+        """Should fall back to sh for fish, csh, tcsh and agentsh."""
+        for name in ("fish", "csh", "tcsh", "agentsh"):
+            shell = tmp_path / name
+            shell.write_text("#!/bin/sh\n")
+            shell.chmod(0o755)
+            assert resolve_exec_shell(str(shell), str(shell)) == DEFAULT_EXEC_SHELL, name
+
+    def test_run_command_invalid_utf8_strict(self) -> None:
+        """Should show a placeholder for invalid UTF-8 in strict mode."""
+        toolset = ShellToolset(strict_output=True)
+        result = asyncio.run(toolset.run_command(r"printf 'ok\377\376'; echo fine >&2"))
+        assert result.success
+        assert result.output == "[binary output: 4 bytes]\n\nSTDERR:\nfine"
+
     def test_explain_empty_command(self, shell_toolset: ShellToolset) -> None:
         """Should reject empty command for explain."""
         result = shell_toolset.explain_command("")
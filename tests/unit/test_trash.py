@@ -0,0 +1,141 @@
+"""Tests for moving rm targets to the trash."""
+
+import asyncio
+import subprocess
+from pathlib import Path
+
+import pytest
+
+from agentsh.plugins.builtin.shell import ShellToolset
+from agentsh.security import trash
+from agentsh.security.trash import parse_rm, rewrite_rm_to_trash
+
+
+@pytest.fixture
+def no_temp_roots(monkeypatch: pytest.MonkeyPatch) -> None:
+    """Treat pytest's tmp_path like any other directory."""
+    monkeypatch.setattr(trash, "temp_roots", lambda: set())
+
+
+class TestParseRm:
+    """Tests for parsing rm arguments."""
+
+    def test_flags_and_targets(self) -> None:
+        """Should split flags from targets."""
+        invocation = parse_rm("rm -rf foo bar")
+        assert invocation.flags == ["-rf"]
+        assert invocation.targets == ["foo", "bar"]
+        assert invocation.force
+
+    def test_double_dash(self) -> None:
+        """Should treat everything after -- as targets."""
+        invocation = parse_rm("rm -v -- -weird 'with space'")
+        assert invocation.flags == ["-v"]
+        assert invocation.targets == ["-weird", "with space"]
+        assert not invocation.force
+
+    def test_unsupported_flags(self) -> None:
+        """Should report flags a move cannot reproduce."""
+        invocation = parse_rm("rm -r --one-file-system --force -x dir")
+        assert invocation.unsupported_flags() == ["--one-file-system", "-x"]
+
+    def test_interactive(self) -> None:
+        """Should let the last of -f and -i decide whether rm asks."""
+        assert parse_rm("rm -i foo").interactive
+        assert parse_rm("rm -rI foo").interactive
+        assert parse_rm("rm -f -i foo").interactive
+        assert not parse_rm("rm -i -f foo").interactive
+        assert not parse_rm("rm --interactive=never foo").interactive
+
+    def test_not_rm(self) -> None:
+        """Should ignore other commands and compound commands."""
+        assert parse_rm("ls -la") is None
+        assert parse_rm("rm foo && ls") is None
+        assert parse_rm("rm foo; echo done") is None
+        assert parse_rm("rm 'unclosed") is None
+
+
+class TestRewriteRmToTrash:
+    """Tests for rewriting rm into a move."""
+
+    def test_rewrite_rm_rf(self, tmp_path: Path, no_temp_roots: None) -> None:
+        """Should move both targets into a numbered trash directory."""
+        (tmp_path / "foo").mkdir()
+        (tmp_path / "bar").write_text("x")
+        trash_dir = tmp_path / "trash"
+
+        rewrite = rewrite_rm_to_trash("rm -rf foo bar", trash_dir, str(tmp_path))
+
+        assert rewrite.trash_dir == trash_dir / "1"
+        assert rewrite.trashed == ["foo", "bar"]
+        assert rewrite.command == f"mkdir -p {trash_dir}/1 && mv -- foo bar {trash_dir}/1/"
+        assert rewrite.warning is None
+
+        subprocess.run(rewrite.command, shell=True, cwd=tmp_path, check=True)
+        assert not (tmp_path / "foo").exists()
+        assert (trash_dir / "1" / "bar").read_text() == "x"
+
+    def test_missing_targets_with_force(self, tmp_path: Path, no_temp_roots: None) -> None:
+        """Should drop targets rm -f would ignore."""
+        (tmp_path / "foo").write_text("x")
+
+        rewrite = rewrite_rm_to_trash("rm -f foo gone", tmp_path / "trash", str(tmp_path))
+
+        assert rewrite.trashed == ["foo"]
+
+    def test_temp_targets_deleted(self, tmp_path: Path) -> None:
+        """Should leave targets under the temp directory to rm."""
+        rewrite = rewrite_rm_to_trash("rm -rf /tmp/build", tmp_path, "/")
+        assert rewrite.command == "rm -rf /tmp/build"
+        assert rewrite.trash_dir is None
+
+    def test_infeasible_falls_back(self, tmp_path: Path, no_temp_roots: None) -> None:
+        """Should delete for real with a warning when a move is not possible."""
+        for command in ("rm --one-file-system -r foo", "rm *.log", "rm a/x b/x"):
+            rewrite = rewrite_rm_to_trash(command, tmp_path, str(tmp_path))
+            assert rewrite.command == command
+            assert rewrite.warning
+
+    def test_interactive_left_to_rm(self, tmp_path: Path, no_temp_roots: None) -> None:
+        """Should not rewrite rm -i, which asks before removing."""
+        (tmp_path / "foo").write_text("x")
+        for command in ("rm -i foo", "rm -I foo", "rm --interactive foo"):
+            rewrite = rewrite_rm_to_trash(command, tmp_path / "trash", str(tmp_path))
+            assert rewrite.command == command
+            assert rewrite.trash_dir is None
+
+    def test_directory_without_recursive(self, tmp_path: Path, no_temp_roots: None) -> None:
+        """Should fail for a directory like rm does, keeping it in place."""
+        (tmp_path / "dir").mkdir()
+        (tmp_path / "file").write_text("x")
+        trash_dir = tmp_path / "trash"
+
+        only_dir = rewrite_rm_to_trash("rm dir", trash_dir, str(tmp_path))
+        assert only_dir.command == "rm dir"
+
+        rewrite = rewrite_rm_to_trash("rm file dir", trash_dir, str(tmp_path))
+        assert rewrite.trashed == ["file"]
+        result = subprocess.run(
+            rewrite.command, shell=True, cwd=tmp_path, capture_output=True, text=True
+        )
+        assert result.returncode != 0
+        assert "Is a directory" in result.stderr
+        assert (tmp_path / "dir").is_dir()
+        assert (trash_dir / "1" / "file").exists()
+
+    def test_empty_directory_with_dir_flag(self, tmp_path: Path, no_temp_roots: None) -> None:
+        """Should move an empty directory under -d."""
+        (tmp_path / "empty").mkdir()
+        rewrite = rewrite_rm_to_trash("rm -d empty", tmp_path / "trash", str(tmp_path))
+        assert rewrite.trashed == ["empty"]
+
+    def test_shell_toolset_moves_to_trash(self, tmp_path: Path, no_temp_roots: None) -> None:
+        """Should keep deleted files in the trash when enabled."""
+        (tmp_path / "notes.txt").write_text("keep me")
+        toolset = ShellToolset(trash_dir=tmp_path / "trash")
+
+        result = asyncio.run(toolset.run_command("rm notes.txt", cwd=str(tmp_path)))
+
+        assert result.success
+        assert "Moved to trash" in result.output
+        assert (tmp_path / "trash" / "1" / "notes.txt").read_text() == "keep me"
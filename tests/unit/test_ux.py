@@ -9,13 +9,16 @@ from agentsh.utils.ux import (
     ErrorContext,
     ErrorFormatter,
     OutputBuffer,
+    Painter,
     ProgressBar,
     Spinner,
     SpinnerStyle,
     Table,
     TableColumn,
+    color_enabled,
     colorize,
     get_error_suggestion,
+    parse_color,
     print_error,
     print_info,
     print_success,
@@ -65,6 +68,63 @@ class TestColorize:
         assert result == "test"
 
 
+class TestParseColor:
+    """Tests for parse_color function."""
+
+    def test_named_colors(self) -> None:
+        """Should accept Color names in any case, with - or _."""
+        assert parse_color("green") == Color.GREEN.value
+        assert parse_color("Bright-Cyan") == Color.BRIGHT_CYAN.value
+        assert parse_color("bright_red") == Color.BRIGHT_RED.value
+        assert parse_color("bold") == Color.BOLD.value
+
+    def test_hex_colors(self) -> None:
+        """Should map #rrggbb to a 24-bit color."""
+        assert parse_color("#ff8800") == "\033[38;2;255;136;0m"
+        assert parse_color("#FF8800") == "\033[38;2;255;136;0m"
+
+    def test_palette_colors(self) -> None:
+        """Should map ansi(N) to the 256-color palette."""
+        assert parse_color("ansi(208)") == "\033[38;5;208m"
+        assert parse_color("ansi(0)") == "\033[38;5;0m"
+        assert parse_color("ansi(256)") is None
+
+    def test_unknown_colors(self) -> None:
+        """Should return None for anything else."""
+        for name in ("", "chartreuse", "#ff88", "#gg0000", "ansi()", "ansi(-1)", "reset-ish"):
+            assert parse_color(name) is None, name
+
+
+class TestPainter:
+    """Tests for Painter and color_enabled."""
+
+    def test_paint_applies_styles(self, monkeypatch: pytest.MonkeyPatch) -> None:
+        """Should apply named and Color styles, ignoring unknown names."""
+        monkeypatch.delenv("NO_COLOR", raising=False)
+        painter = Painter()
+
+        result = painter.paint("ok", "green", Color.BOLD, "nonsense")
+
+        assert result == f"{Color.GREEN.value}{Color.BOLD.value}ok{Color.RESET.value}"
+        assert painter.paint("ok", "nonsense") == "ok"
+
+    def test_disabled_painter_is_noop(self, monkeypatch: pytest.MonkeyPatch) -> None:
+        """Should leave text unchanged when coloring is off."""
+        monkeypatch.delenv("NO_COLOR", raising=False)
+
+        assert Painter(use_color=False).paint("ok", "green", "#ff0000") == "ok"
+
+    def test_no_color_env(self, monkeypatch: pytest.MonkeyPatch) -> None:
+        """Should honor a non-empty NO_COLOR."""
+        monkeypatch.setenv("NO_COLOR", "1")
+        assert not color_enabled(True)
+        assert Painter().paint("ok", "red") == "ok"
+
+        monkeypatch.setenv("NO_COLOR", "")
+        assert color_enabled(True)
+        assert not color_enabled(False)
+
+
 class TestStripAnsi:
     """Tests for strip_ansi function."""
 
@@ -121,6 +181,47 @@ class TestSpinner:
         output = stream.getvalue()
         assert "Done" in output or len(output) > 0
 
+    def test_none_style_parses(self) -> None:
+        """Should parse "none" as the disabled style."""
+        assert SpinnerStyle("none") is SpinnerStyle.NONE
+        assert not Spinner("Test", style=SpinnerStyle.NONE).enabled
+
+    def test_disabled_spinner_starts_no_thread(self) -> None:
+        """Should neither write nor start a thread when disabled."""
+        stream = io.StringIO()
+        spinner = Spinner("Test", style=SpinnerStyle.NONE, stream=stream)
+
+        spinner.start()
+        assert spinner._thread is None
+        spinner.stop("Done")
+
+        assert stream.getvalue() == ""
+
+    def test_non_tty_prints_static_line(self) -> None:
+        """Should write the message once, without escapes, when not a TTY."""
+        stream = io.StringIO()
+        spinner = Spinner("Thinking...", stream=stream)
+
+        spinner.start()
+        assert spinner._thread is None
+        spinner.stop("Done")
+
+        assert stream.getvalue() == "Thinking...\nDone\n"
+
+    def test_tty_animates(self) -> None:
+        """Should animate in a thread when the stream is a terminal."""
+        stream = io.StringIO()
+        stream.isatty = lambda: True  # type: ignore[method-assign]
+        spinner = Spinner("Working", stream=stream)
+
+        spinner.start()
+        assert spinner._thread is not None
+        time.sleep(0.15)
+        spinner.stop()
+
+        assert "\r" in stream.getvalue()
+        assert stream.getvalue().endswith("\r\033[K")
+
     def test_spinner_update_message(self) -> None:
         """Should update message."""
         spinner = Spinner("Initial")
@@ -9,6 +9,7 @@ from agentsh.utils.validators import (
     PathValidator,
     ValidationError,
     ValidationResult,
+    redact_network_identifiers,
     redact_secrets,
     validate_and_sanitize,
 )
@@ -461,3 +462,44 @@ class TestRedactSecretsExtended:
         """Should handle whitespace input."""
         redacted = redact_secrets("   ")
         assert redacted == "   "
+
+
+class TestRedactNetworkIdentifiers:
+    """Tests for redact_network_identifiers."""
+
+    def test_ipv4_and_ipv6(self) -> None:
+        """Should redact IPv4 and IPv6 literals."""
+        text = "ssh 10.1.2.3 || ping -6 fd12:3456:789a::1"
+
+        redacted = redact_network_identifiers(text)
+
+        assert redacted == "ssh ***REDACTED*** || ping -6 ***REDACTED***"
+
+    def test_internal_fqdn(self) -> None:
+        """Should redact fully qualified and internal hostnames."""
+        text = "psql -h db01.prod.example.com; curl http://build.internal/status"
+
+        redacted = redact_network_identifiers(text)
+
+        assert "db01" not in redacted
+        assert "build.internal" not in redacted
+
+    def test_files_and_times_kept(self) -> None:
+        """Should not mistake file names, versions or times for hosts."""
+        text = "tar xzf app-1.2.3.tar.gz config.yaml at 12:30:45"
+
+        assert redact_network_identifiers(text) == text
+
+    def test_loopback_kept_when_private_only(self) -> None:
+        """Should keep loopback and public addresses in private-only mode."""
+        text = "curl 127.0.0.1 ::1 localhost 8.8.8.8 10.0.0.7 fe80::1 git.corp"
+
+        redacted = redact_network_identifiers(text, private_only=True)
+
+        assert redacted == (
+            "curl 127.0.0.1 ::1 localhost 8.8.8.8 ***REDACTED*** ***REDACTED*** ***REDACTED***"
+        )
+
+    def test_loopback_redacted_by_default(self) -> None:
+        """Should redact loopback addresses unless private-only is set."""
+        assert redact_network_identifiers("curl 127.0.0.1") == "curl ***REDACTED***"
@@ -1,5 +1,9 @@
 """Tests for the shell wrapper."""
 
+import io
+import os
+import shutil
+from pathlib import Path
 from unittest.mock import MagicMock, patch
 
 import pytest
@@ -40,6 +44,26 @@ class TestShellWrapper:
         assert wrapper._classifier.ai_prefix == "ask "
         assert wrapper._classifier.shell_prefix == "$"
 
+    def test_non_tty_uses_line_mode(self, wrapper: ShellWrapper) -> None:
+        """Test that piped stdin skips the prompt, readline and completion."""
+        stdin = io.StringIO(":quit\n!echo unreachable\n")
+
+        with (
+            patch("sys.stdin", stdin),
+            patch.object(wrapper._history, "load"),
+            patch.object(wrapper._history, "save"),
+            patch.object(wrapper, "_run_repl") as run_repl,
+            patch.object(wrapper, "_setup_completion") as setup_completion,
+            patch.object(wrapper, "_process_input", wraps=wrapper._process_input) as process,
+        ):
+            assert wrapper.interactive is False
+            wrapper.run()
+
+        run_repl.assert_not_called()
+        setup_completion.assert_not_called()
+        process.assert_called_once_with(":quit")
+        assert wrapper.is_running is False
+
     # AI handler tests
     def test_set_ai_handler(self, wrapper: ShellWrapper) -> None:
         """Test setting AI handler."""
@@ -150,37 +174,114 @@ class TestShellWrapper:
         assert wrapper._agent_status == AgentStatus.ERROR
         assert wrapper._last_exit_code == 1
 
+    def test_process_ai_request_propagates_status(self, wrapper: ShellWrapper) -> None:
+        """Test that the handler's aggregate status is exported."""
+
+        def handler(request: str) -> str:
+            return "Cancelled"
+
+        handler.last_status = 130
+        wrapper.set_ai_handler(handler)
+
+        with patch.dict("os.environ", {}), patch("builtins.print"):
+            wrapper._process_input("ai remove old logs")
+            assert os.environ["AGENTSH_LAST_STATUS"] == "130"
+
+        assert wrapper._last_exit_code == 130
+
     # Shell command processing tests
     def test_process_shell_command(self, wrapper: ShellWrapper) -> None:
         """Test processing shell command."""
-        with patch("subprocess.run") as mock_run:
-            mock_run.return_value.returncode = 0
+        with patch("agentsh.shell.wrapper.PTYManager") as mock_pty:
+            mock_pty.return_value.interact.return_value = 0
             wrapper._process_input("!ls -la")
 
-        mock_run.assert_called_once()
+        assert mock_pty.call_args.kwargs["command"] == "ls -la"
+        mock_pty.return_value.close.assert_called_once()
         assert wrapper._last_exit_code == 0
 
     def test_process_shell_command_with_exit_code(self, wrapper: ShellWrapper) -> None:
         """Test processing shell command with non-zero exit code."""
-        with patch("subprocess.run") as mock_run:
-            mock_run.return_value.returncode = 1
+        with patch("agentsh.shell.wrapper.PTYManager") as mock_pty:
+            mock_pty.return_value.interact.return_value = 1
             wrapper._process_input("!false")
 
         assert wrapper._last_exit_code == 1
 
     def test_process_shell_command_error(self, wrapper: ShellWrapper) -> None:
-        """Test processing shell command when subprocess raises error."""
-        with patch("subprocess.run", side_effect=Exception("Command failed")):
+        """Test processing shell command when the shell cannot be spawned."""
+        with patch("agentsh.shell.wrapper.PTYManager") as mock_pty:
+            mock_pty.return_value.spawn.side_effect = RuntimeError("Failed to spawn shell")
             with patch("builtins.print"):
                 wrapper._process_input("!bad-command")
 
         assert wrapper._last_exit_code == 1
 
+    @pytest.mark.skipif(shutil.which("bash") is None, reason="bash not available")
+    def test_shell_cd_followed(self, wrapper: ShellWrapper, tmp_path: Path) -> None:
+        """Test that a cd carries over to later commands and the AI context."""
+        target = tmp_path / "sub dir"
+        target.mkdir()
+        wrapper.config.shell.backend = "bash"
+        handler = MagicMock(return_value="ok")
+        wrapper.set_ai_handler(handler)
+
+        with patch("sys.stdin", io.StringIO()), patch("builtins.print"):
+            wrapper._process_input(f"!cd '{target}' && false")
+            assert wrapper._last_exit_code == 1
+            wrapper._process_input("!cd ..")
+            wrapper._process_input("ai what is here")
+
+        assert wrapper.cwd == str(tmp_path)
+        handler.assert_called_once_with("what is here", cwd=str(tmp_path), last_exit_code=0)
+
+    def test_shell_command_output_recorded(self, config: AgentSHConfig, tmp_path: Path) -> None:
+        """Test that the output of shell commands goes to the session recording."""
+        from agentsh.shell.recorder import SessionRecorder
+
+        config.shell.backend = "sh"
+        config.shell.record_session = True
+        wrapper = ShellWrapper(config)
+        wrapper._recorder = SessionRecorder(session_id="test", sessions_dir=tmp_path)
+        wrapper._recorder.start()
+
+        with patch("sys.stdin", io.StringIO()):
+            wrapper._process_input("!echo recorded")
+        wrapper._recorder.close()
+
+        assert "recorded" in (tmp_path / "test.cast").read_text()
+
+    def test_recording_uses_audit_session_id(self, config: AgentSHConfig) -> None:
+        """Test that the recording is named after the AI handler's audit session."""
+        config.shell.record_session = True
+        wrapper = ShellWrapper(config)
+        handler = MagicMock(return_value="ok")
+        handler.session_id = "a1b2c3d4"
+
+        wrapper.set_ai_handler(handler)
+
+        assert wrapper._recorder.session_id == "a1b2c3d4"
+
+    def test_fix_request_gets_recent_output(self, wrapper: ShellWrapper) -> None:
+        """Test that ai fix is sent the shell command's output and exit status."""
+        wrapper.config.shell.backend = "sh"
+        handler = MagicMock(return_value="ok")
+        wrapper.set_ai_handler(handler)
+
+        with patch("sys.stdin", io.StringIO()), patch("builtins.print"):
+            wrapper._process_input("ai why is it slow")
+            wrapper._process_input("!/bin/sh -c 'echo no such file >&2; exit 2'")
+            wrapper._process_input("ai fix it")
+
+        assert handler.call_args_list[0].kwargs == {}
+        assert handler.call_args_list[1].kwargs["recent_output"] == "no such file"
+        assert handler.call_args_list[1].kwargs["last_exit_code"] == 2
+
     # History integration tests
     def test_shell_command_added_to_history(self, wrapper: ShellWrapper) -> None:
         """Test that shell commands are added to history."""
-        with patch("subprocess.run") as mock_run:
-            mock_run.return_value.returncode = 0
+        with patch("agentsh.shell.wrapper.PTYManager") as mock_pty:
+            mock_pty.return_value.interact.return_value = 0
             wrapper._process_input("!ls")
 
         # Check history contains the command
@@ -221,6 +322,31 @@ class TestShellWrapperHelpers:
         calls = [str(call) for call in mock_print.call_args_list]
         assert any("AgentSH" in str(call) for call in calls)
 
+    def test_motd_unset(self, wrapper: ShellWrapper) -> None:
+        """Test that no MOTD is shown by default."""
+        assert wrapper._get_motd() is None
+
+    def test_motd_command_output_captured(self, config: AgentSHConfig) -> None:
+        """Test that the configured MOTD command's output is captured."""
+        config.shell.motd_command = "echo 'Welcome to the cluster'"
+        wrapper = ShellWrapper(config)
+
+        assert wrapper._get_motd() == "Welcome to the cluster"
+
+    def test_motd_printed_after_banner(self, config: AgentSHConfig) -> None:
+        """Test that the MOTD is printed as part of the welcome message."""
+        config.shell.motd_command = "echo tip-of-the-day"
+        wrapper = ShellWrapper(config)
+
+        with patch("builtins.print") as mock_print:
+            wrapper._print_welcome()
+
+        printed = [call.args[0] for call in mock_print.call_args_list if call.args]
+        assert printed[-1] == "tip-of-the-day"
+        assert printed.index("tip-of-the-day") > printed.index(
+            "  AgentSH - AI-Enhanced Terminal Shell"
+        )
+
     def test_show_help(self, wrapper: ShellWrapper) -> None:
         """Test help display via show_help function."""
         from agentsh.shell.help import show_help
@@ -296,6 +422,263 @@ class TestShellWrapperHelpers:
         calls = [str(call) for call in mock_print.call_args_list]
         assert any("Phase 2" in str(call) for call in calls)
 
+    def test_why_before_any_request(self, wrapper: ShellWrapper) -> None:
+        """Test :why when no AI request has been made."""
+        assert wrapper._format_why() == "No AI request has been made yet."
+
+    def test_why_shows_last_exchange(self, wrapper: ShellWrapper) -> None:
+        """Test that :why prints the stored prompt and raw response."""
+        from agentsh.agent.agent_loop import Exchange
+
+        def handler(request: str) -> str:
+            handler.last_exchange = Exchange(
+                system_prompt="You are AgentSH.\nCurrent directory: /srv",
+                request=request,
+                responses=["Try `df -h`."],
+            )
+            return "Try `df -h`."
+
+        wrapper.set_ai_handler(handler)
+
+        with patch("builtins.print") as mock_print:
+            wrapper._process_input("ai check disk space")
+            wrapper._process_input(":why")
+
+        output = mock_print.call_args_list[-1].args[0]
+        assert "Current directory: /srv" in output
+        assert "=== User message ===\ncheck disk space" in output
+        assert "=== AI response (step 1) ===\nTry `df -h`." in output
+
+    def test_why_redacts_secrets(self, config: AgentSHConfig) -> None:
+        """Test that :why respects security.redact_secrets."""
+        from agentsh.agent.agent_loop import Exchange
+
+        handler = MagicMock()
+        handler.last_exchange = Exchange(
+            system_prompt="prompt",
+            request="use token=abcdef1234567890",
+        )
+        wrapper = ShellWrapper(config)
+        wrapper.set_ai_handler(handler)
+        assert "abcdef1234567890" not in wrapper._format_why()
+
+        config.security.redact_secrets = False
+        assert "abcdef1234567890" in wrapper._format_why()
+
+    def test_ai_check_prints_classification(self, wrapper: ShellWrapper) -> None:
+        """Test that ai check reports the classification without calling the AI."""
+        handler = MagicMock(return_value="unused")
+        wrapper.set_ai_handler(handler)
+
+        with patch("builtins.print") as mock_print:
+            wrapper._process_input("ai check 'rm -rf ./build'")
+
+        handler.assert_not_called()
+        report = mock_print.call_args[0][0]
+        assert "Risk level:           HIGH" in report
+        assert "needs confirmation" in report
+
+    def test_ai_explain_prints_sections(self, wrapper: ShellWrapper) -> None:
+        """Test that ai explain renders a structured answer as sections."""
+        handler = MagicMock()
+        handler.explain.return_value = (
+            '{"summary": "Extracts a.tgz", '
+            '"flags": [{"flag": "-x", "meaning": "extract"}], "risk": "none"}'
+        )
+        wrapper.set_ai_handler(handler)
+        wrapper.config.shell.color = False
+
+        with patch("builtins.print") as mock_print:
+            wrapper._process_input("ai explain 'tar -xzf a.tgz'")
+
+        handler.explain.assert_called_once_with("tar -xzf a.tgz")
+        handler.assert_not_called()
+        output = mock_print.call_args[0][0]
+        assert output.startswith("What it does\n  Extracts a.tgz")
+        assert "  -x  extract" in output
+
+    def test_ai_explain_json_mode(self, wrapper: ShellWrapper) -> None:
+        """Test that ai explain asks for JSON when llm.json_mode is on."""
+        from agentsh.agent.llm_client import json_response_requested
+
+        requested = []
+        handler = MagicMock()
+        handler.explain = lambda _: requested.append(json_response_requested()) or "{}"
+        wrapper.set_ai_handler(handler)
+        wrapper.config.llm.json_mode = True
+
+        with patch("builtins.print"):
+            wrapper._process_input("ai explain 'ls'")
+
+        assert requested == [True]
+        assert not json_response_requested()
+
+    def test_ai_reload_swaps_config(self, wrapper: ShellWrapper) -> None:
+        """Test that ai reload applies the new config and keeps the conversation."""
+        old_handler = MagicMock()
+        old_handler.conversation = ["earlier request", "earlier answer"]
+        new_handler = MagicMock()
+        new_handler.conversation = []
+        factory = MagicMock(return_value=new_handler)
+        wrapper.set_ai_handler(old_handler, factory=factory)
+        new_config = AgentSHConfig(security={"mode": "strict"})
+
+        with patch("agentsh.config.loader.load_config", return_value=new_config), patch(
+            "builtins.print"
+        ) as mock_print:
+            wrapper._process_input("ai reload")
+
+        factory.assert_called_once_with(new_config)
+        assert wrapper.config is new_config
+        assert wrapper._ai_handler is new_handler
+        assert new_handler.conversation == ["earlier request", "earlier answer"]
+        printed = [call[0][0] for call in mock_print.call_args_list]
+        assert "  security.mode: normal -> strict" in printed
+
+    def test_ai_reload_failure_keeps_config(self, wrapper: ShellWrapper) -> None:
+        """Test that a failed reload keeps the current config and handler."""
+        handler = MagicMock()
+        wrapper.set_ai_handler(handler)
+        config = wrapper.config
+
+        with patch(
+            "agentsh.config.loader.load_config", side_effect=ValueError("bad yaml")
+        ), patch("builtins.print") as mock_print:
+            wrapper._process_input("ai reload")
+
+        assert wrapper.config is config
+        assert wrapper._ai_handler is handler
+        assert "bad yaml" in mock_print.call_args[0][0]
+
+    def test_ai_ask_is_read_only(self, wrapper: ShellWrapper) -> None:
+        """Test that ai ask sends a read-only request to the AI handler."""
+        handler = MagicMock(return_value="Logs use most of the space.")
+        handler.last_status = 0
+        wrapper.set_ai_handler(handler)
+
+        with patch("builtins.print"):
+            wrapper._process_input("ai ask why is the disk full")
+            wrapper._process_input("ai clean the logs")
+
+        assert handler.call_args_list[0] == (("why is the disk full",), {"read_only": True})
+        assert handler.call_args_list[1] == (("clean the logs",), {})
+
+    def test_ai_export_chat_writes_file(self, wrapper: ShellWrapper, tmp_path) -> None:
+        """Test that ai export-chat hands the file to the AI handler."""
+        handler = MagicMock()
+        handler.export_chat = MagicMock(side_effect=lambda path: path)
+        wrapper.set_ai_handler(handler)
+        target = tmp_path / "chat.md"
+
+        with patch("builtins.print") as mock_print:
+            wrapper._process_input(f"ai export-chat {target}")
+
+        handler.export_chat.assert_called_once_with(target)
+        handler.assert_not_called()
+        assert mock_print.call_args[0][0] == f"Conversation exported to {target}"
+
+    def test_ai_export_chat_without_file(self, wrapper: ShellWrapper) -> None:
+        """Test that ai export-chat without a file prints its usage."""
+        handler = MagicMock()
+        wrapper.set_ai_handler(handler)
+
+        with patch("builtins.print") as mock_print:
+            wrapper._process_input("ai export-chat")
+
+        handler.export_chat.assert_not_called()
+        assert "Usage" in mock_print.call_args[0][0]
+
+    def test_ai_redo_reuses_stored_plan(self, wrapper: ShellWrapper) -> None:
+        """Test that ai redo re-runs the stored plan instead of asking the AI."""
+        handler = MagicMock(return_value="unused")
+        handler.redo = MagicMock(return_value="Step 1: make deploy [success]")
+        handler.last_status = 0
+        wrapper.set_ai_handler(handler)
+
+        with patch("builtins.print") as mock_print:
+            wrapper._process_input("ai redo")
+
+        handler.assert_not_called()
+        handler.redo.assert_called_once_with()
+        assert "make deploy" in mock_print.call_args[0][0]
+
+    def test_ai_redo_without_ai(self, wrapper: ShellWrapper) -> None:
+        """Test that ai redo explains when no AI handler is set."""
+        with patch("builtins.print") as mock_print:
+            wrapper._process_input("ai redo")
+
+        assert "not available" in mock_print.call_args[0][0]
+
+    def test_space_prefixed_ai_request_not_in_history(self, wrapper: ShellWrapper) -> None:
+        """Test that ui.history_ignore_space applies to AI requests."""
+        with patch("builtins.print"):
+            wrapper._process_input(" ai find large files")
+            wrapper._process_input("ai find small files")
+
+        assert [e.command for e in wrapper._history.ai_requests] == ["find small files"]
+
+    def test_format_response_renders_code_block(self, wrapper: ShellWrapper) -> None:
+        """Test that code blocks are rendered when output is a terminal."""
+        answer = "Run this:\n\n```bash\nls -la\n```\n"
+
+        with patch("sys.stdout.isatty", return_value=True):
+            output = wrapper._format_response(answer)
+
+        assert "```" not in output
+        assert "ls -la" in output
+        assert "┌─ bash" in output
+        assert "\x1b[" in output
+
+    def test_format_response_renders_bullets(self, wrapper: ShellWrapper) -> None:
+        """Test that bullet lists are rendered with styled markers."""
+        with patch("sys.stdout.isatty", return_value=True):
+            output = wrapper._format_response("- one\n- two\n")
+
+        assert "\x1b[36m•\x1b[0m one" in output
+        assert "\x1b[36m•\x1b[0m two" in output
+
+    def test_format_response_without_color(self, config: AgentSHConfig) -> None:
+        """Test that rendering honours ui.color."""
+        config.shell.color = False
+        wrapper = ShellWrapper(config)
+
+        with patch("sys.stdout.isatty", return_value=True):
+            output = wrapper._format_response("- **one**\n")
+
+        assert output == "• one"
+
+    def test_format_response_strips_when_not_tty(self, wrapper: ShellWrapper) -> None:
+        """Test that markdown is stripped to plain text for pipes."""
+        with patch("sys.stdout.isatty", return_value=False):
+            output = wrapper._format_response("**Use** `ls`:\n\n```bash\nls -la\n```\n")
+
+        assert "**" not in output
+        assert "```" not in output
+        assert "ls -la" in output
+
+    def test_format_response_keeps_globs_and_identifiers(self, wrapper: ShellWrapper) -> None:
+        """Test that commands with globs and snake_case names are printed intact."""
+        answer = "Run `rm *.log`, then rm *.log and rm *.tmp for my_var_name.\n"
+
+        for tty in (True, False):
+            with patch("sys.stdout.isatty", return_value=tty):
+                output = wrapper._format_response(answer)
+
+            assert "rm *.log" in output.split("then")[0]
+            assert "rm *.log and rm *.tmp" in output
+            assert "my_var_name" in output
+
+    def test_format_response_strips_when_disabled(self, config: AgentSHConfig) -> None:
+        """Test that ui.render_markdown=false yields plain text."""
+        config.shell.render_markdown = False
+        wrapper = ShellWrapper(config)
+
+        with patch("sys.stdout.isatty", return_value=True):
+            output = wrapper._format_response("# Title\n\n- item\n")
+
+        assert "\x1b[" not in output
+        assert "Title" in output
+
     def test_show_status(self, wrapper: ShellWrapper) -> None:
         """Test status display."""
         from agentsh.telemetry.health import HealthChecker, HealthResult, HealthStatus